@@ -0,0 +1,327 @@
+//! Generic Analog-to-Digital Converter.
+//!
+//! The converter samples one analog pin at a time against an internal reference, either
+//! once on request or continuously. Pins are configured with `into_analog` and checked
+//! against their converter channel at compile time.
+//!
+//! Analog control registers of the converter are located in the always-on domain at
+//! offset `0x90c`; the register block here starts at that offset, clear of hibernation
+//! control registers sharing the domain.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use bouffalo_hal::adc::{Adc, Config};
+//! # use bouffalo_hal::gpio::{Alternate, Analog};
+//! # fn example(adc: &'static bouffalo_hal::adc::RegisterBlock, pot: Alternate<'static, 17, Analog>) {
+//! let mut adc = Adc::new(adc, Config::default());
+//! let raw = adc.convert(&pot);
+//! let millivolts = adc.millivolts(raw);
+//! # }
+//! ```
+use crate::gpio::{self, Alternate};
+use crate::gpip::{
+    AdcConverationSequence1, AdcConverationSequence2, AdcConverationSequence3,
+    AdcConverationSequence4, GpadcCommand, GpadcConfig1, GpadcConfig2, GpadcDefine,
+    GpadcInterruptState, GpadcRawResult, GpadcResult, GpadcStatus, Reference, Resolution,
+};
+use core::convert::Infallible;
+use core::ops::Deref;
+use volatile_register::RW;
+
+/// Analog-to-Digital Converter registers in the always-on domain.
+#[repr(C)]
+pub struct RegisterBlock {
+    /// Conversion command register.
+    pub command: RW<GpadcCommand>,
+    /// Conversion configuration register.
+    pub config_1: RW<GpadcConfig1>,
+    /// Analog configuration register.
+    pub config_2: RW<GpadcConfig2>,
+    /// Scan sequence register 1.
+    pub sequence_1: RW<AdcConverationSequence1>,
+    /// Scan sequence register 2.
+    pub sequence_2: RW<AdcConverationSequence2>,
+    /// Scan sequence register 3.
+    pub sequence_3: RW<AdcConverationSequence3>,
+    /// Scan sequence register 4.
+    pub sequence_4: RW<AdcConverationSequence4>,
+    /// Conversion status register.
+    pub status: RW<GpadcStatus>,
+    /// Interrupt state register.
+    pub interrupt_state: RW<GpadcInterruptState>,
+    /// Conversion result register.
+    pub result: RW<GpadcResult>,
+    /// Raw conversion result register.
+    pub raw_result: RW<GpadcRawResult>,
+    /// Converter definition register.
+    pub define: RW<GpadcDefine>,
+}
+
+/// Internal channel connected to analog ground.
+const GROUND_CHANNEL: u8 = 23;
+
+/// Analog clock divide factor giving a conversion clock within specification.
+const CLOCK_DIVIDE: u8 = 3;
+
+/// Analog-to-Digital Converter configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Config {
+    /// Conversion resolution.
+    pub resolution: Resolution,
+    /// Reference voltage.
+    pub reference: Reference,
+}
+
+impl Config {
+    /// Set conversion resolution.
+    #[inline]
+    pub const fn set_resolution(self, resolution: Resolution) -> Self {
+        Self { resolution, ..self }
+    }
+    /// Set reference voltage.
+    #[inline]
+    pub const fn set_reference(self, reference: Reference) -> Self {
+        Self { reference, ..self }
+    }
+}
+
+impl Default for Config {
+    /// 12-bit resolution with internal 3.2 V reference.
+    #[inline]
+    fn default() -> Self {
+        Self {
+            resolution: Resolution::Bits12,
+            reference: Reference::Internal3V2,
+        }
+    }
+}
+
+/// Managed Analog-to-Digital Converter peripheral.
+pub struct Adc<ADC> {
+    adc: ADC,
+    config: Config,
+    channel: Option<u8>,
+}
+
+impl<ADC: Deref<Target = RegisterBlock>> Adc<ADC> {
+    /// Create a converter instance with given configuration.
+    #[inline]
+    pub fn new(adc: ADC, config: Config) -> Self {
+        unsafe {
+            adc.command
+                .modify(|v| v.enable_global().enable_software_reset());
+            adc.command.modify(|v| v.disable_software_reset());
+            adc.config_1.modify(|v| {
+                v.disable_continuous()
+                    .set_resolution(config.resolution)
+                    .set_clock_divide(CLOCK_DIVIDE)
+            });
+            adc.config_2
+                .modify(|v| v.disable_differential().set_reference(config.reference));
+        }
+        Self {
+            adc,
+            config,
+            channel: None,
+        }
+    }
+    /// Start a single conversion on the given pin.
+    ///
+    /// The result is fetched with [`read`](Self::read).
+    #[inline]
+    pub fn start<P: HasAdcChannel>(&mut self, _pin: &P) {
+        self.start_channel(P::CHANNEL, false)
+    }
+    /// Start continuous conversions on the given pin.
+    ///
+    /// Each [`read`](Self::read) returns the latest result until [`stop`](Self::stop).
+    #[inline]
+    pub fn start_continuous<P: HasAdcChannel>(&mut self, _pin: &P) {
+        self.start_channel(P::CHANNEL, true)
+    }
+    /// Stop ongoing conversions.
+    #[inline]
+    pub fn stop(&mut self) {
+        unsafe {
+            self.adc.command.modify(|v| v.stop_conversion());
+            self.adc.config_1.modify(|v| v.disable_continuous());
+        }
+        self.channel = None;
+    }
+    /// Read conversion result in raw counts of configured resolution.
+    ///
+    /// Returns `WouldBlock` if no conversion is started, or the first conversion on the
+    /// started channel has not completed yet.
+    #[inline]
+    pub fn read(&mut self) -> nb::Result<u16, Infallible> {
+        let Some(channel) = self.channel else {
+            return Err(nb::Error::WouldBlock);
+        };
+        if !self.adc.status.read().is_data_ready() {
+            return Err(nb::Error::WouldBlock);
+        }
+        let result = self.adc.result.read();
+        // Result register keeps the last result of a previously selected channel.
+        if result.positive_channel() != channel {
+            return Err(nb::Error::WouldBlock);
+        }
+        Ok(result.value() >> (16 - self.config.resolution.bits()))
+    }
+    /// Convert the given pin once, blocking until result is ready.
+    #[inline]
+    pub fn convert<P: HasAdcChannel>(&mut self, pin: &P) -> u16 {
+        self.start(pin);
+        let Ok(raw) = nb::block!(self.read());
+        self.stop();
+        raw
+    }
+    /// Convert raw counts into millivolts under current configuration.
+    #[inline]
+    pub fn millivolts(&self, raw: u16) -> u32 {
+        raw_to_millivolts(
+            raw,
+            self.config.resolution,
+            self.config.reference.millivolts(),
+        )
+    }
+    /// Release the converter instance and return its peripheral.
+    #[inline]
+    pub fn free(self) -> ADC {
+        unsafe {
+            self.adc
+                .command
+                .modify(|v| v.stop_conversion().disable_global())
+        };
+        self.adc
+    }
+    #[inline]
+    fn start_channel(&mut self, channel: u8, continuous: bool) {
+        unsafe {
+            self.adc.command.modify(|v| v.stop_conversion());
+            // Software reset clears data ready flag and result of previous conversions,
+            // while keeping configuration registers.
+            self.adc.command.modify(|v| v.enable_software_reset());
+            self.adc.command.modify(|v| v.disable_software_reset());
+            self.adc.config_1.modify(|v| {
+                if continuous {
+                    v.enable_continuous()
+                } else {
+                    v.disable_continuous()
+                }
+            });
+            self.adc.command.modify(|v| {
+                v.set_positive_channel(channel)
+                    .set_negative_channel(GROUND_CHANNEL)
+                    .enable_negative_ground()
+                    .start_conversion()
+            });
+        }
+        self.channel = Some(channel);
+    }
+}
+
+/// Convert raw counts of `resolution` into millivolts against reference `reference_mv`.
+#[inline]
+pub const fn raw_to_millivolts(raw: u16, resolution: Resolution, reference_mv: u32) -> u32 {
+    (raw as u32 * reference_mv) >> resolution.bits()
+}
+
+/// Check if target pad is internally connected to an ADC channel.
+#[diagnostic::on_unimplemented(
+    message = "this I/O Alternate has no hardware connection to any ADC channel"
+)]
+pub trait HasAdcChannel {
+    /// Converter channel of this pad.
+    const CHANNEL: u8;
+}
+
+macro_rules! adc_channels {
+    ($($pad: expr => $channel: expr),+ $(,)?) => {
+        $(
+            impl<'a> HasAdcChannel for Alternate<'a, $pad, gpio::Analog> {
+                const CHANNEL: u8 = $channel;
+            }
+        )+
+    };
+}
+
+adc_channels! {
+    17 => 0,
+    5 => 1,
+    4 => 2,
+    11 => 3,
+    6 => 4,
+    40 => 5,
+    12 => 6,
+    13 => 7,
+    16 => 8,
+    18 => 9,
+    19 => 10,
+    34 => 11,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Adc, Config, HasAdcChannel, RegisterBlock, raw_to_millivolts};
+    use crate::gpip::{Reference, Resolution};
+    use crate::mock::MockRegisters;
+    use memoffset::offset_of;
+
+    struct MockPin;
+
+    impl HasAdcChannel for MockPin {
+        const CHANNEL: u8 = 0;
+    }
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, command), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, config_1), 0x04);
+        assert_eq!(offset_of!(RegisterBlock, config_2), 0x08);
+        assert_eq!(offset_of!(RegisterBlock, sequence_1), 0x0c);
+        assert_eq!(offset_of!(RegisterBlock, status), 0x1c);
+        assert_eq!(offset_of!(RegisterBlock, result), 0x24);
+        assert_eq!(offset_of!(RegisterBlock, define), 0x2c);
+    }
+
+    #[test]
+    fn function_adc_read() {
+        let mock = MockRegisters::<RegisterBlock>::new();
+        let config = Config::default().set_reference(Reference::Internal2V0);
+        let mut adc = Adc::new(mock.registers(), config);
+        assert_eq!(mock.raw(0x00), 0x1);
+        assert_eq!(mock.raw(0x08), 0x8);
+        // Nothing started yet.
+        mock.set_raw(0x1c, 0x1);
+        assert_eq!(adc.read(), Err(nb::Error::WouldBlock));
+
+        adc.start_continuous(&MockPin);
+        assert_eq!(mock.raw(0x00), 0x0000_20bb);
+        assert!(mock.registers().config_1.read().is_continuous_enabled());
+        // Result of another channel left from earlier conversions.
+        mock.set_raw(0x24, (5 << 21) | (23 << 16) | 0xfff0);
+        assert_eq!(adc.read(), Err(nb::Error::WouldBlock));
+        // First conversion not completed.
+        mock.set_raw(0x24, (23 << 16) | 0x8000);
+        mock.set_raw(0x1c, 0x0);
+        assert_eq!(adc.read(), Err(nb::Error::WouldBlock));
+        mock.set_raw(0x1c, 0x1);
+        assert_eq!(adc.read(), Ok(0x800));
+        assert_eq!(adc.millivolts(0x800), 1000);
+
+        adc.stop();
+        assert_eq!(mock.raw(0x00) & 0x2, 0);
+        assert!(!mock.registers().config_1.read().is_continuous_enabled());
+        assert_eq!(adc.read(), Err(nb::Error::WouldBlock));
+    }
+
+    #[test]
+    fn function_raw_to_millivolts() {
+        assert_eq!(raw_to_millivolts(0, Resolution::Bits12, 3200), 0);
+        assert_eq!(raw_to_millivolts(2048, Resolution::Bits12, 3200), 1600);
+        assert_eq!(raw_to_millivolts(4095, Resolution::Bits12, 3200), 3199);
+        assert_eq!(raw_to_millivolts(8192, Resolution::Bits14, 2000), 1000);
+        assert_eq!(raw_to_millivolts(u16::MAX, Resolution::Bits16, 3200), 3199);
+    }
+}
@@ -0,0 +1,790 @@
+//! Parallel camera interface.
+//!
+//! The camera interface samples an 8-bit parallel sensor bus on its pixel clock, cropping
+//! each frame to the lines and pixels framed by vertical and horizontal sync, and writes the
+//! pixels into memory through its own bus master. [`Camera::capture`] takes a frame into a
+//! buffer and returns a [`Capture`] which completes on the end of frame.
+//!
+//! A frame longer than the buffer is cut at the buffer boundary; the peripheral stops
+//! writing there and the returned [`Frame`] is marked as truncated.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use bouffalo_hal::cam::{Camera, Config, Pads, PixelFormat, RegisterBlock};
+//! # fn example(cam: &'static RegisterBlock, pads: impl Pads, buffer: &'static mut [u8]) {
+//! let config = Config::new(320, 240, PixelFormat::Rgb565);
+//! let camera = Camera::new(cam, pads, config);
+//! let (camera, buffer, frame) = camera.capture(buffer).wait();
+//! assert!(!frame.truncated);
+//! # }
+//! ```
+use crate::gpio::{self, Alternate};
+use core::{
+    future::Future,
+    ops::Deref,
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::{Context, Poll},
+};
+use volatile_register::{RW, WO};
+
+/// Camera interface registers.
+#[repr(C)]
+pub struct RegisterBlock {
+    /// Capture configuration.
+    pub config: RW<CaptureConfig>,
+    /// Start address of frame buffer.
+    pub address_start: RW<u32>,
+    /// Size of frame buffer in bursts.
+    pub memory_bursts: RW<u32>,
+    /// Interrupt enable and state, and capture status.
+    pub status: RW<Status>,
+    /// Size of each frame in bursts.
+    pub frame_bursts: RW<u32>,
+    /// Frame queue pop and interrupt clear.
+    pub frame_pop: WO<FramePop>,
+    /// Valid frames in each frame period.
+    pub frame_valid: RW<u32>,
+    /// Frame period of frame dropping.
+    pub frame_period: RW<u32>,
+    /// Pixel format conversion.
+    pub misc: RW<u32>,
+    /// Horizontal crop window in pixel clocks.
+    pub horizontal_crop: RW<Crop>,
+    /// Vertical crop window in lines.
+    pub vertical_crop: RW<Crop>,
+    /// Expected pixel clocks in each line and lines in each frame.
+    pub frame_size: RW<u32>,
+}
+
+/// Capture configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct CaptureConfig(u32);
+
+pub use crate::dpi::Polarity;
+
+/// Pixel format of sensor output.
+///
+/// Both formats carry 16 bits per pixel in two bytes, which are stored into memory in
+/// the order they are received.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// 16-bit RGB, 5 bits red, 6 bits green and 5 bits blue.
+    Rgb565,
+    /// 16-bit YUV 4:2:2, luma and alternating chroma per pixel.
+    Yuv422,
+}
+
+impl PixelFormat {
+    /// Bytes per pixel.
+    #[inline]
+    pub const fn bytes_per_pixel(self) -> usize {
+        match self {
+            PixelFormat::Rgb565 | PixelFormat::Yuv422 => 2,
+        }
+    }
+}
+
+/// Length of bus bursts writing to memory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BurstLength {
+    /// One beat of 8 bytes.
+    Single = 0,
+    /// Four beats of 8 bytes.
+    Incr4 = 1,
+    /// Eight beats of 8 bytes.
+    Incr8 = 2,
+    /// Sixteen beats of 8 bytes.
+    Incr16 = 3,
+}
+
+impl CaptureConfig {
+    const ENABLE: u32 = 1 << 0;
+    const VSYNC_HIGH: u32 = 1 << 2;
+    const HSYNC_HIGH: u32 = 1 << 3;
+    const BURST_LENGTH: u32 = 0x7 << 4;
+    const FRAME_WRAP: u32 = 1 << 11;
+
+    /// Enable capture.
+    #[inline]
+    pub const fn enable(self) -> Self {
+        Self(self.0 | Self::ENABLE)
+    }
+    /// Disable capture.
+    #[inline]
+    pub const fn disable(self) -> Self {
+        Self(self.0 & !Self::ENABLE)
+    }
+    /// Check if capture is enabled.
+    #[inline]
+    pub const fn is_enabled(self) -> bool {
+        self.0 & Self::ENABLE != 0
+    }
+    /// Set vertical sync polarity.
+    #[inline]
+    pub const fn set_vsync_polarity(self, val: Polarity) -> Self {
+        match val {
+            Polarity::ActiveHigh => Self(self.0 | Self::VSYNC_HIGH),
+            Polarity::ActiveLow => Self(self.0 & !Self::VSYNC_HIGH),
+        }
+    }
+    /// Get vertical sync polarity.
+    #[inline]
+    pub const fn vsync_polarity(self) -> Polarity {
+        if self.0 & Self::VSYNC_HIGH != 0 {
+            Polarity::ActiveHigh
+        } else {
+            Polarity::ActiveLow
+        }
+    }
+    /// Set horizontal sync polarity.
+    #[inline]
+    pub const fn set_hsync_polarity(self, val: Polarity) -> Self {
+        match val {
+            Polarity::ActiveHigh => Self(self.0 | Self::HSYNC_HIGH),
+            Polarity::ActiveLow => Self(self.0 & !Self::HSYNC_HIGH),
+        }
+    }
+    /// Get horizontal sync polarity.
+    #[inline]
+    pub const fn hsync_polarity(self) -> Polarity {
+        if self.0 & Self::HSYNC_HIGH != 0 {
+            Polarity::ActiveHigh
+        } else {
+            Polarity::ActiveLow
+        }
+    }
+    /// Set length of bus bursts.
+    #[inline]
+    pub const fn set_burst_length(self, val: BurstLength) -> Self {
+        Self((self.0 & !Self::BURST_LENGTH) | ((val as u32) << 4))
+    }
+    /// Get length of bus bursts.
+    #[inline]
+    pub const fn burst_length(self) -> BurstLength {
+        match (self.0 & Self::BURST_LENGTH) >> 4 {
+            0 => BurstLength::Single,
+            1 => BurstLength::Incr4,
+            2 => BurstLength::Incr8,
+            _ => BurstLength::Incr16,
+        }
+    }
+    /// Wrap around to the start of frame buffer once it is full.
+    #[inline]
+    pub const fn enable_frame_wrap(self) -> Self {
+        Self(self.0 | Self::FRAME_WRAP)
+    }
+    /// Stop writing once frame buffer is full.
+    #[inline]
+    pub const fn disable_frame_wrap(self) -> Self {
+        Self(self.0 & !Self::FRAME_WRAP)
+    }
+    /// Check if writing wraps around at the end of frame buffer.
+    #[inline]
+    pub const fn is_frame_wrap_enabled(self) -> bool {
+        self.0 & Self::FRAME_WRAP != 0
+    }
+}
+
+/// Camera interrupt event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Interrupt {
+    /// A whole frame is written to memory.
+    FrameComplete = 0,
+    /// Frame buffer is full before the end of frame.
+    MemoryFull = 1,
+}
+
+/// Interrupt enable and state, and capture status register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct Status(u32);
+
+impl Status {
+    const INTERRUPT_ENABLE: u32 = 0x3f;
+    const FRAME_TRIGGER: u32 = 0x1f << 6;
+    const FRAME_COUNT: u32 = 0x1f << 16;
+
+    /// Enable an interrupt event.
+    #[inline]
+    pub const fn enable_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 | (1 << val as u32))
+    }
+    /// Disable an interrupt event.
+    #[inline]
+    pub const fn disable_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 & !(1 << val as u32))
+    }
+    /// Disable all interrupt events.
+    #[inline]
+    pub const fn disable_all_interrupts(self) -> Self {
+        Self(self.0 & !Self::INTERRUPT_ENABLE)
+    }
+    /// Check if an interrupt event is enabled.
+    #[inline]
+    pub const fn is_interrupt_enabled(self, val: Interrupt) -> bool {
+        self.0 & (1 << val as u32) != 0
+    }
+    /// Set number of frames in queue which trigger frame complete interrupt.
+    #[inline]
+    pub const fn set_frame_trigger(self, val: u8) -> Self {
+        Self((self.0 & !Self::FRAME_TRIGGER) | (((val as u32) << 6) & Self::FRAME_TRIGGER))
+    }
+    /// Get number of frames in queue which trigger frame complete interrupt.
+    #[inline]
+    pub const fn frame_trigger(self) -> u8 {
+        ((self.0 & Self::FRAME_TRIGGER) >> 6) as u8
+    }
+    /// Check if an interrupt event has occurred.
+    #[inline]
+    pub const fn has_interrupt(self, val: Interrupt) -> bool {
+        self.0 & (1 << (val as u32 + 12)) != 0
+    }
+    /// Get number of captured frames in queue.
+    #[inline]
+    pub const fn frame_count(self) -> u8 {
+        ((self.0 & Self::FRAME_COUNT) >> 16) as u8
+    }
+}
+
+/// Frame queue pop and interrupt clear register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct FramePop(u32);
+
+impl FramePop {
+    const POP: u32 = 1 << 0;
+
+    /// Remove the oldest captured frame from queue.
+    #[inline]
+    pub const fn pop_frame(self) -> Self {
+        Self(self.0 | Self::POP)
+    }
+    /// Clear an interrupt event.
+    #[inline]
+    pub const fn clear_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 | (1 << (val as u32 + 4)))
+    }
+}
+
+/// Crop window register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct Crop(u32);
+
+impl Crop {
+    /// Set first pixel clock or line inside the window.
+    #[inline]
+    pub const fn set_start(self, val: u16) -> Self {
+        Self((self.0 & 0xffff) | ((val as u32) << 16))
+    }
+    /// Get first pixel clock or line inside the window.
+    #[inline]
+    pub const fn start(self) -> u16 {
+        (self.0 >> 16) as u16
+    }
+    /// Set first pixel clock or line after the window.
+    #[inline]
+    pub const fn set_end(self, val: u16) -> Self {
+        Self((self.0 & !0xffff) | val as u32)
+    }
+    /// Get first pixel clock or line after the window.
+    #[inline]
+    pub const fn end(self) -> u16 {
+        self.0 as u16
+    }
+}
+
+/// Bytes written to memory in each burst; frame buffers are aligned to this size.
+///
+/// Camera driver writes in bursts of [`BurstLength::Incr4`].
+pub const BURST_BYTES: usize = 32;
+
+/// Camera capture configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Config {
+    /// Pixels in each line.
+    pub width: u16,
+    /// Lines in each frame.
+    pub height: u16,
+    /// Pixel format of sensor output.
+    pub format: PixelFormat,
+    /// Vertical sync polarity.
+    pub vsync: Polarity,
+    /// Horizontal sync polarity.
+    pub hsync: Polarity,
+}
+
+impl Config {
+    /// Configuration of given resolution and format, with active high sync signals.
+    #[inline]
+    pub const fn new(width: u16, height: u16, format: PixelFormat) -> Self {
+        Self {
+            width,
+            height,
+            format,
+            vsync: Polarity::ActiveHigh,
+            hsync: Polarity::ActiveHigh,
+        }
+    }
+    /// Set vertical sync polarity.
+    #[inline]
+    pub const fn set_vsync(self, vsync: Polarity) -> Self {
+        Self { vsync, ..self }
+    }
+    /// Set horizontal sync polarity.
+    #[inline]
+    pub const fn set_hsync(self, hsync: Polarity) -> Self {
+        Self { hsync, ..self }
+    }
+    /// Bytes in each frame.
+    #[inline]
+    pub const fn frame_bytes(&self) -> usize {
+        self.width as usize * self.height as usize * self.format.bytes_per_pixel()
+    }
+}
+
+/// Captured frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Frame {
+    /// Pixels in each line.
+    pub width: u16,
+    /// Lines in the frame.
+    pub height: u16,
+    /// Pixel format.
+    pub format: PixelFormat,
+    /// Bytes written at the start of the buffer.
+    pub len: usize,
+    /// Frame is cut at the end of the buffer.
+    pub truncated: bool,
+}
+
+/// Managed camera interface peripheral.
+pub struct Camera<CAM, PADS> {
+    cam: CAM,
+    pads: PADS,
+    config: Config,
+}
+
+impl<CAM: Deref<Target = RegisterBlock>, PADS> Camera<CAM, PADS> {
+    /// Create a camera interface instance with given configuration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a line of the configured resolution is longer than the crop window allows.
+    #[inline]
+    pub fn new(cam: CAM, pads: PADS, config: Config) -> Self
+    where
+        PADS: Pads,
+    {
+        let line_bytes = config.width as usize * config.format.bytes_per_pixel();
+        assert!(
+            line_bytes <= u16::MAX as usize,
+            "camera line too long for crop window"
+        );
+        unsafe {
+            cam.config.write(
+                CaptureConfig(0)
+                    .set_vsync_polarity(config.vsync)
+                    .set_hsync_polarity(config.hsync)
+                    .set_burst_length(BurstLength::Incr4)
+                    .disable_frame_wrap(),
+            );
+            // One byte is sampled on each pixel clock.
+            cam.horizontal_crop
+                .write(Crop(0).set_start(0).set_end(line_bytes as u16));
+            cam.vertical_crop
+                .write(Crop(0).set_start(0).set_end(config.height));
+            cam.frame_bursts
+                .write(config.frame_bytes().div_ceil(BURST_BYTES) as u32);
+            cam.status
+                .modify(|v| v.disable_all_interrupts().set_frame_trigger(1));
+        }
+        Self { cam, pads, config }
+    }
+    /// Start capturing the next frame into `buffer`.
+    ///
+    /// Only whole bursts at the start of `buffer` are written; a frame longer than that is
+    /// truncated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffer` is not aligned to [`BURST_BYTES`].
+    #[inline]
+    pub fn capture(self, buffer: &'static mut [u8]) -> Capture<CAM, PADS> {
+        assert!(
+            (buffer.as_ptr() as usize).is_multiple_of(BURST_BYTES),
+            "camera buffer must be aligned to burst size"
+        );
+        let bursts = buffer.len() / BURST_BYTES;
+        unsafe {
+            self.cam.config.modify(|v| v.disable());
+            self.cam.frame_pop.write(
+                FramePop(0)
+                    .clear_interrupt(Interrupt::FrameComplete)
+                    .clear_interrupt(Interrupt::MemoryFull),
+            );
+            self.cam.address_start.write(buffer.as_ptr() as u32);
+            self.cam.memory_bursts.write(bursts as u32);
+            self.cam.config.modify(|v| v.enable());
+        }
+        Capture {
+            camera: self,
+            buffer,
+            capacity: bursts * BURST_BYTES,
+        }
+    }
+    /// Current capture configuration.
+    #[inline]
+    pub fn config(&self) -> Config {
+        self.config
+    }
+    /// Release the camera interface and return its peripheral and pads.
+    #[inline]
+    pub fn free(self) -> (CAM, PADS) {
+        (self.cam, self.pads)
+    }
+}
+
+/// Ongoing frame capture.
+pub struct Capture<CAM, PADS> {
+    camera: Camera<CAM, PADS>,
+    buffer: &'static mut [u8],
+    capacity: usize,
+}
+
+impl<CAM: Deref<Target = RegisterBlock>, PADS> Capture<CAM, PADS> {
+    /// Check if the frame is complete, or the buffer is full.
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        is_done(&self.camera.cam)
+    }
+    /// Wait for the frame to complete, returning the camera, buffer and frame.
+    #[inline]
+    pub fn wait(self) -> (Camera<CAM, PADS>, &'static mut [u8], Frame) {
+        while !self.is_done() {
+            core::hint::spin_loop();
+        }
+        let Capture {
+            camera,
+            buffer,
+            capacity,
+        } = self;
+        let state = camera.cam.status.read();
+        unsafe {
+            camera.cam.config.modify(|v| v.disable());
+            camera.cam.frame_pop.write(
+                FramePop(0)
+                    .pop_frame()
+                    .clear_interrupt(Interrupt::FrameComplete)
+                    .clear_interrupt(Interrupt::MemoryFull),
+            );
+        }
+        let config = camera.config;
+        let frame_bytes = config.frame_bytes();
+        let truncated = state.has_interrupt(Interrupt::MemoryFull) || frame_bytes > capacity;
+        let frame = Frame {
+            width: config.width,
+            height: config.height,
+            format: config.format,
+            len: if truncated { capacity } else { frame_bytes },
+            truncated,
+        };
+        (camera, buffer, frame)
+    }
+    /// Future resolving once the frame is complete, woken by the camera interrupt.
+    ///
+    /// The interrupt handler must call [`CameraState::on_interrupt`] on `state`.
+    #[inline]
+    pub fn frame_complete<'a>(&'a mut self, state: &'static CameraState) -> FrameComplete<'a> {
+        state
+            .ref_to_camera
+            .store(&*self.camera.cam as *const _ as usize, Ordering::Release);
+        FrameComplete {
+            cam: &self.camera.cam,
+            state,
+        }
+    }
+}
+
+#[inline]
+fn is_done(cam: &RegisterBlock) -> bool {
+    let state = cam.status.read();
+    state.has_interrupt(Interrupt::FrameComplete) || state.has_interrupt(Interrupt::MemoryFull)
+}
+
+/// Set of wakers for camera frame completion.
+pub struct CameraState {
+    frame_complete: atomic_waker::AtomicWaker,
+    ref_to_camera: AtomicUsize,
+}
+
+impl CameraState {
+    /// Creates the waker set for a camera interface.
+    #[inline]
+    pub const fn new() -> CameraState {
+        CameraState {
+            frame_complete: atomic_waker::AtomicWaker::new(),
+            ref_to_camera: AtomicUsize::new(0),
+        }
+    }
+    /// Use this waker set to handle interrupt.
+    ///
+    /// Frame interrupts are disabled here after waking the waiting task; events stay
+    /// pending until [`Capture::wait`] collects the frame.
+    #[inline]
+    pub fn on_interrupt(&self) {
+        let cam = self.ref_to_camera.load(Ordering::Acquire) as *const RegisterBlock;
+        if cam.is_null() {
+            return;
+        }
+        let cam = unsafe { &*cam };
+        if is_done(cam) {
+            unsafe { cam.status.modify(|v| v.disable_all_interrupts()) };
+            self.frame_complete.wake();
+        }
+    }
+}
+
+impl Default for CameraState {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Future of frame completion.
+pub struct FrameComplete<'a> {
+    cam: &'a RegisterBlock,
+    state: &'static CameraState,
+}
+
+impl Future for FrameComplete<'_> {
+    type Output = ();
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if is_done(self.cam) {
+            return Poll::Ready(());
+        }
+        self.state.frame_complete.register(cx.waker());
+        unsafe {
+            self.cam.status.modify(|v| {
+                v.enable_interrupt(Interrupt::FrameComplete)
+                    .enable_interrupt(Interrupt::MemoryFull)
+            })
+        };
+        // Frame may end before the interrupt is enabled.
+        if is_done(self.cam) {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl Drop for FrameComplete<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { self.cam.status.modify(|v| v.disable_all_interrupts()) };
+    }
+}
+
+/// Valid parallel camera pads.
+#[diagnostic::on_unimplemented(
+    message = "the camera interface needs pixel clock, horizontal sync, vertical sync and eight data pads in CAM mode"
+)]
+pub trait Pads {}
+
+/// Pixel clock, horizontal sync, vertical sync, and data 0 to 7.
+impl<
+    'a,
+    const C: usize,
+    const H: usize,
+    const V: usize,
+    const D0: usize,
+    const D1: usize,
+    const D2: usize,
+    const D3: usize,
+    const D4: usize,
+    const D5: usize,
+    const D6: usize,
+    const D7: usize,
+> Pads
+    for (
+        Alternate<'a, C, gpio::Cam>,
+        Alternate<'a, H, gpio::Cam>,
+        Alternate<'a, V, gpio::Cam>,
+        (
+            Alternate<'a, D0, gpio::Cam>,
+            Alternate<'a, D1, gpio::Cam>,
+            Alternate<'a, D2, gpio::Cam>,
+            Alternate<'a, D3, gpio::Cam>,
+            Alternate<'a, D4, gpio::Cam>,
+            Alternate<'a, D5, gpio::Cam>,
+            Alternate<'a, D6, gpio::Cam>,
+            Alternate<'a, D7, gpio::Cam>,
+        ),
+    )
+where
+    Alternate<'a, C, gpio::Cam>: HasPixelClockSignal,
+    Alternate<'a, H, gpio::Cam>: HasHsyncSignal,
+    Alternate<'a, V, gpio::Cam>: HasVsyncSignal,
+    Alternate<'a, D0, gpio::Cam>: HasDataSignal<0>,
+    Alternate<'a, D1, gpio::Cam>: HasDataSignal<1>,
+    Alternate<'a, D2, gpio::Cam>: HasDataSignal<2>,
+    Alternate<'a, D3, gpio::Cam>: HasDataSignal<3>,
+    Alternate<'a, D4, gpio::Cam>: HasDataSignal<4>,
+    Alternate<'a, D5, gpio::Cam>: HasDataSignal<5>,
+    Alternate<'a, D6, gpio::Cam>: HasDataSignal<6>,
+    Alternate<'a, D7, gpio::Cam>: HasDataSignal<7>,
+{
+}
+
+/// Check if target gpio `Pin` is internally connected to camera pixel clock signal.
+pub trait HasPixelClockSignal {}
+
+/// Check if target gpio `Pin` is internally connected to camera horizontal sync signal.
+pub trait HasHsyncSignal {}
+
+/// Check if target gpio `Pin` is internally connected to camera vertical sync signal.
+pub trait HasVsyncSignal {}
+
+/// Check if target gpio `Pin` is internally connected to camera data signal `I`.
+pub trait HasDataSignal<const I: usize> {}
+
+impl<'a> HasPixelClockSignal for Alternate<'a, 16, gpio::Cam> {}
+impl<'a> HasHsyncSignal for Alternate<'a, 21, gpio::Cam> {}
+impl<'a> HasVsyncSignal for Alternate<'a, 22, gpio::Cam> {}
+impl<'a> HasDataSignal<0> for Alternate<'a, 24, gpio::Cam> {}
+impl<'a> HasDataSignal<1> for Alternate<'a, 25, gpio::Cam> {}
+impl<'a> HasDataSignal<2> for Alternate<'a, 26, gpio::Cam> {}
+impl<'a> HasDataSignal<3> for Alternate<'a, 27, gpio::Cam> {}
+impl<'a> HasDataSignal<4> for Alternate<'a, 28, gpio::Cam> {}
+impl<'a> HasDataSignal<5> for Alternate<'a, 29, gpio::Cam> {}
+impl<'a> HasDataSignal<6> for Alternate<'a, 30, gpio::Cam> {}
+impl<'a> HasDataSignal<7> for Alternate<'a, 31, gpio::Cam> {}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        BURST_BYTES, BurstLength, Camera, CameraState, CaptureConfig, Config, Crop, FramePop,
+        Interrupt, Pads, PixelFormat, Polarity, RegisterBlock, Status,
+    };
+    use crate::mock::MockRegisters;
+    use core::{
+        future::Future,
+        pin::pin,
+        task::{Context, Poll, Waker},
+    };
+    use memoffset::offset_of;
+
+    struct MockPads;
+
+    impl Pads for MockPads {}
+
+    #[repr(C, align(32))]
+    struct MockBuffer([u8; 256]);
+
+    fn buffer() -> &'static mut [u8] {
+        extern crate std;
+        &mut std::boxed::Box::leak(std::boxed::Box::new(MockBuffer([0; 256]))).0
+    }
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, config), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, address_start), 0x04);
+        assert_eq!(offset_of!(RegisterBlock, memory_bursts), 0x08);
+        assert_eq!(offset_of!(RegisterBlock, status), 0x0c);
+        assert_eq!(offset_of!(RegisterBlock, frame_bursts), 0x10);
+        assert_eq!(offset_of!(RegisterBlock, frame_pop), 0x14);
+        assert_eq!(offset_of!(RegisterBlock, frame_valid), 0x18);
+        assert_eq!(offset_of!(RegisterBlock, frame_period), 0x1c);
+        assert_eq!(offset_of!(RegisterBlock, misc), 0x20);
+        assert_eq!(offset_of!(RegisterBlock, horizontal_crop), 0x24);
+        assert_eq!(offset_of!(RegisterBlock, vertical_crop), 0x28);
+        assert_eq!(offset_of!(RegisterBlock, frame_size), 0x2c);
+    }
+
+    #[test]
+    fn struct_capture_config_functions() {
+        let config = CaptureConfig(0)
+            .set_hsync_polarity(Polarity::ActiveHigh)
+            .set_burst_length(BurstLength::Incr16)
+            .enable_frame_wrap()
+            .enable();
+        assert_eq!(config.0, 0x0000_0839);
+        assert!(config.is_enabled() && config.is_frame_wrap_enabled());
+        assert_eq!(config.vsync_polarity(), Polarity::ActiveLow);
+        assert_eq!(config.hsync_polarity(), Polarity::ActiveHigh);
+        assert_eq!(config.burst_length(), BurstLength::Incr16);
+        let status = Status(0x0003_5000)
+            .enable_interrupt(Interrupt::MemoryFull)
+            .set_frame_trigger(1);
+        assert_eq!(status.0, 0x0003_5042);
+        assert!(status.has_interrupt(Interrupt::FrameComplete));
+        assert!(!status.has_interrupt(Interrupt::MemoryFull));
+        assert_eq!(status.frame_count(), 3);
+        assert_eq!(status.disable_all_interrupts().0, 0x0003_5040);
+        let pop = FramePop(0)
+            .pop_frame()
+            .clear_interrupt(Interrupt::MemoryFull);
+        assert_eq!(pop.0, 0x21);
+        let crop = Crop(0).set_start(4).set_end(644);
+        assert_eq!(crop.0, 0x0004_0284);
+        assert_eq!((crop.start(), crop.end()), (4, 644));
+    }
+
+    #[test]
+    fn function_camera_capture() {
+        let mock = MockRegisters::<RegisterBlock>::new();
+        // 8 by 8 pixels of 2 bytes fit in the 256-byte buffer.
+        let camera = Camera::new(
+            mock.registers(),
+            MockPads,
+            Config::new(8, 8, PixelFormat::Rgb565),
+        );
+        assert_eq!(mock.raw(0x24), 16);
+        assert_eq!(mock.raw(0x28), 8);
+        assert_eq!(mock.raw(0x10), (128 / BURST_BYTES) as u32);
+        let mut capture = camera.capture(buffer());
+        assert_eq!(mock.raw(0x00), 0x1d);
+        assert_eq!(mock.raw(0x08), (256 / BURST_BYTES) as u32);
+        assert!(!capture.is_done());
+
+        static STATE: CameraState = CameraState::new();
+        let mut cx = Context::from_waker(Waker::noop());
+        {
+            let mut fut = pin!(capture.frame_complete(&STATE));
+            assert_eq!(fut.as_mut().poll(&mut cx), Poll::Pending);
+            assert_eq!(mock.raw(0x0c), 0x43);
+            mock.set_raw(0x0c, 0x1043);
+            STATE.on_interrupt();
+            assert_eq!(mock.raw(0x0c), 0x1040);
+            assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(()));
+        }
+        let (camera, _, frame) = capture.wait();
+        assert_eq!(frame.len, 128);
+        assert!(!frame.truncated);
+        assert_eq!(mock.raw(0x00), 0x1c);
+        assert_eq!(mock.raw(0x14), 0x31);
+
+        // Frame of 256 bytes fills the buffer of 224 usable bytes.
+        let buffer = &mut buffer()[..250];
+        let (camera, _) = camera.free();
+        let camera = Camera::new(camera, MockPads, Config::new(16, 8, PixelFormat::Yuv422));
+        let capture = camera.capture(buffer);
+        assert_eq!(mock.raw(0x08), 7);
+        mock.set_raw(0x0c, 0x2000);
+        let (_, buffer, frame) = capture.wait();
+        assert_eq!(buffer.len(), 250);
+        assert_eq!(frame.len, 224);
+        assert!(frame.truncated);
+        assert_eq!((frame.width, frame.height), (16, 8));
+        assert_eq!(frame.format, PixelFormat::Yuv422);
+    }
+}
@@ -1,28 +1,487 @@
 //! System-on-Chip clock configuration.
-
+//!
+//! The root clock is one of the internal 32-MHz RC oscillator, the external crystal
+//! oscillator, or a tap of the system phase-locked loop (PLL). The processor clock is
+//! divided from the root clock, and the peripheral bus clock is divided from the processor
+//! clock. Peripheral source clocks come from the bus clock or from fixed PLL taps.
+//!
+//! [`ClockConfig`] describes a clock tree and [`ClockConfig::freeze`] derives the
+//! frequencies into [`Clocks`], which peripheral drivers query for their dividers.
+//!
+//! The system PLL is locked by the boot ROM from the clock settings in the image header,
+//! and is not programmed by this module; [`ClockConfig::with_pll`] records its setting.
+//! Root clock source and dividers may be switched at runtime with [`Clocks::reconfigure`].
+//!
+//! ```
+//! # use bouffalo_hal::clocks::{ClockConfig, Pll, RootClock};
+//! # use embedded_time::rate::Hertz;
+//! let xtal = Hertz(40_000_000);
+//! let clocks = ClockConfig::with_pll(xtal, Pll::new(1, 24))
+//!     .set_root(RootClock::Pll)
+//!     .set_bus_divide(4)
+//!     .freeze();
+//! assert_eq!(clocks.cpu_clock(), Hertz(320_000_000));
+//! assert_eq!(clocks.bus_clock(), Hertz(80_000_000));
+//! ```
+use crate::{glb, hbn};
 use embedded_time::rate::Hertz;
 
+/// Frequency of internal RC oscillator.
+pub const RC32M: Hertz = Hertz(32_000_000);
+/// Largest PLL reference divider.
+pub const PLL_REFERENCE_DIVIDE_MAX: u8 = 16;
+/// Smallest and largest PLL feedback multipliers.
+pub const PLL_MULTIPLIER_RANGE: (u16, u16) = (8, 511);
+/// Slowest and fastest PLL reference after division.
+pub const PLL_REFERENCE_RANGE: (Hertz, Hertz) = (Hertz(1_000_000), Hertz(50_000_000));
+/// Slowest and fastest PLL output.
+pub const PLL_OUTPUT_RANGE: (Hertz, Hertz) = (Hertz(400_000_000), Hertz(960_000_000));
+/// PLL output divider for root clock tap.
+const PLL_ROOT_TAP: u32 = 3;
+/// PLL output divider for 160-MHz multiplexer tap.
+const PLL_MUX_160M_TAP: u32 = 6;
+
+/// Root clock source.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RootClock {
+    /// Internal 32-MHz RC oscillator.
+    Rc32m,
+    /// External crystal oscillator.
+    Xtal,
+    /// Root tap of system PLL, one third of its output.
+    Pll,
+}
+
+/// System phase-locked loop configuration.
+///
+/// PLL output is crystal oscillator frequency divided by `reference_divide` and
+/// multiplied by `multiplier`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pll {
+    reference_divide: u8,
+    multiplier: u16,
+}
+
+impl Pll {
+    /// Create a PLL configuration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `reference_divide` is zero or larger than [`PLL_REFERENCE_DIVIDE_MAX`],
+    /// or `multiplier` is out of [`PLL_MULTIPLIER_RANGE`].
+    #[inline]
+    pub const fn new(reference_divide: u8, multiplier: u16) -> Self {
+        assert!(
+            reference_divide >= 1 && reference_divide <= PLL_REFERENCE_DIVIDE_MAX,
+            "PLL reference divider out of range"
+        );
+        assert!(
+            multiplier >= PLL_MULTIPLIER_RANGE.0 && multiplier <= PLL_MULTIPLIER_RANGE.1,
+            "PLL multiplier out of range"
+        );
+        Self {
+            reference_divide,
+            multiplier,
+        }
+    }
+    /// Find a PLL configuration producing exactly `output` from `xtal`.
+    ///
+    /// Returns `None` if no divider and multiplier pair in range reaches `output`.
+    #[inline]
+    pub const fn for_output(xtal: Hertz, output: Hertz) -> Option<Self> {
+        let mut reference_divide = 1;
+        while reference_divide <= PLL_REFERENCE_DIVIDE_MAX {
+            let scaled = output.0 as u64 * reference_divide as u64;
+            let reference = xtal.0 / reference_divide as u32;
+            if scaled.is_multiple_of(xtal.0 as u64)
+                && reference >= PLL_REFERENCE_RANGE.0.0
+                && reference <= PLL_REFERENCE_RANGE.1.0
+            {
+                let multiplier = scaled / xtal.0 as u64;
+                if multiplier >= PLL_MULTIPLIER_RANGE.0 as u64
+                    && multiplier <= PLL_MULTIPLIER_RANGE.1 as u64
+                {
+                    return Some(Self::new(reference_divide, multiplier as u16));
+                }
+            }
+            reference_divide += 1;
+        }
+        None
+    }
+    /// Reference divider.
+    #[inline]
+    pub const fn reference_divide(self) -> u8 {
+        self.reference_divide
+    }
+    /// Feedback multiplier.
+    #[inline]
+    pub const fn multiplier(self) -> u16 {
+        self.multiplier
+    }
+    /// PLL output frequency from `xtal`.
+    #[inline]
+    pub const fn output(self, xtal: Hertz) -> Hertz {
+        Hertz((xtal.0 as u64 * self.multiplier as u64 / self.reference_divide as u64) as u32)
+    }
+}
+
+/// Clock tree configuration builder.
+///
+/// Defaults to crystal oscillator root clock with both dividers at one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClockConfig {
+    xtal: Hertz,
+    root: RootClock,
+    pll: Option<Pll>,
+    cpu_divide: u8,
+    bus_divide: u8,
+}
+
+impl ClockConfig {
+    /// Create a clock tree configuration on crystal oscillator of `xtal`, with PLL off.
+    #[inline]
+    pub const fn new(xtal: Hertz) -> Self {
+        Self {
+            xtal,
+            root: RootClock::Xtal,
+            pll: None,
+            cpu_divide: 1,
+            bus_divide: 1,
+        }
+    }
+    /// Create a clock tree configuration on crystal oscillator of `xtal`, with system
+    /// PLL locked to `pll` settings by the boot ROM.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the divided reference is out of [`PLL_REFERENCE_RANGE`], or the output
+    /// is out of [`PLL_OUTPUT_RANGE`].
+    #[inline]
+    pub const fn with_pll(xtal: Hertz, pll: Pll) -> Self {
+        let reference = xtal.0 / pll.reference_divide as u32;
+        assert!(
+            reference >= PLL_REFERENCE_RANGE.0.0 && reference <= PLL_REFERENCE_RANGE.1.0,
+            "PLL reference frequency out of range"
+        );
+        let output = pll.output(xtal).0;
+        assert!(
+            output >= PLL_OUTPUT_RANGE.0.0 && output <= PLL_OUTPUT_RANGE.1.0,
+            "PLL output frequency out of range"
+        );
+        Self {
+            pll: Some(pll),
+            ..Self::new(xtal)
+        }
+    }
+    /// Select root clock source.
+    #[inline]
+    pub const fn set_root(self, root: RootClock) -> Self {
+        Self { root, ..self }
+    }
+    /// Set processor clock divider from root clock.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `divide` is zero.
+    #[inline]
+    pub const fn set_cpu_divide(self, divide: u8) -> Self {
+        assert!(divide != 0, "processor clock divider must be non-zero");
+        Self {
+            cpu_divide: divide,
+            ..self
+        }
+    }
+    /// Set peripheral bus clock divider from processor clock.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `divide` is zero.
+    #[inline]
+    pub const fn set_bus_divide(self, divide: u8) -> Self {
+        assert!(divide != 0, "bus clock divider must be non-zero");
+        Self {
+            bus_divide: divide,
+            ..self
+        }
+    }
+    /// Derive clock frequencies of this configuration.
+    ///
+    /// This does not write any register; it describes clocks already configured, e.g. by
+    /// the boot ROM. Use [`Clocks::reconfigure`] to switch clocks at runtime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if PLL root clock is selected with PLL disabled.
+    #[inline]
+    pub const fn freeze(self) -> Clocks {
+        assert!(
+            !matches!(self.root, RootClock::Pll) || self.pll.is_some(),
+            "PLL root clock needs PLL enabled"
+        );
+        Clocks { config: self }
+    }
+}
+
 /// Clock settings for current chip.
 #[derive(Debug, Clone)]
 pub struct Clocks {
-    // todo: clock setting fields
-    pub xtal: Hertz,
+    config: ClockConfig,
 }
 
 impl Clocks {
+    /// Clock tree configuration of these clocks.
+    #[inline]
+    pub const fn config(&self) -> ClockConfig {
+        self.config
+    }
     /// Crystal oscillator clock frequency.
     #[inline]
+    pub const fn xtal(&self) -> Hertz {
+        self.config.xtal
+    }
+    /// Low-speed root clock frequency, from RC oscillator or crystal oscillator.
+    #[inline]
     pub const fn xclk(&self) -> Hertz {
-        self.xtal
+        match self.config.root {
+            RootClock::Rc32m => RC32M,
+            RootClock::Xtal | RootClock::Pll => self.config.xtal,
+        }
+    }
+    /// System PLL output frequency, if enabled.
+    #[inline]
+    pub const fn pll_clock(&self) -> Option<Hertz> {
+        match self.config.pll {
+            Some(pll) => Some(pll.output(self.config.xtal)),
+            None => None,
+        }
+    }
+    /// Root clock frequency.
+    #[inline]
+    pub const fn root_clock(&self) -> Hertz {
+        match (self.config.root, self.pll_clock()) {
+            (RootClock::Pll, Some(pll)) => Hertz(pll.0 / PLL_ROOT_TAP),
+            _ => self.xclk(),
+        }
+    }
+    /// Processor core clock frequency.
+    #[inline]
+    pub const fn cpu_clock(&self) -> Hertz {
+        Hertz(self.root_clock().0 / self.config.cpu_divide as u32)
+    }
+    /// Peripheral bus clock frequency.
+    #[inline]
+    pub const fn bus_clock(&self) -> Hertz {
+        Hertz(self.cpu_clock().0 / self.config.bus_divide as u32)
     }
     /// Universal Asynchronous Receiver/Transmitter clock frequency.
+    ///
+    /// UART0 to UART2 run on peripheral bus clock; UART3 and UART4 run on the 160-MHz
+    /// multiplexer, which is only available with PLL enabled.
     #[inline]
     pub const fn uart_clock<const I: usize>(&self) -> Option<Hertz> {
-        // todo: calculate from Clocks structure fields
         match I {
-            0..=2 => Some(Hertz(80_000_000)),
-            3..=4 => Some(Hertz(160_000_000)),
+            0..=2 => Some(self.bus_clock()),
+            3..=4 => self.mux_160m_clock(),
+            _ => unreachable!(),
+        }
+    }
+    /// Serial Peripheral Interface source clock frequency.
+    #[inline]
+    pub const fn spi_clock<const I: usize>(&self) -> Option<Hertz> {
+        match I {
+            0..=1 => self.mux_160m_clock(),
             _ => unreachable!(),
         }
     }
+    /// Inter-Integrated Circuit source clock frequency.
+    #[inline]
+    pub const fn i2c_clock(&self) -> Option<Hertz> {
+        Some(self.xclk())
+    }
+    /// Inter-IC Sound source clock frequency.
+    #[inline]
+    pub const fn i2s_clock(&self) -> Option<Hertz> {
+        // todo: calculate from audio PLL configuration
+        Some(Hertz(24_576_000))
+    }
+    /// Pulse Density Modulation microphone source clock frequency.
+    #[inline]
+    pub const fn pdm_clock(&self) -> Option<Hertz> {
+        // todo: calculate from audio PLL configuration
+        Some(Hertz(24_576_000))
+    }
+    /// Display pixel interface source clock frequency.
+    #[inline]
+    pub const fn dpi_clock(&self) -> Option<Hertz> {
+        self.mux_160m_clock()
+    }
+    #[inline]
+    const fn mux_160m_clock(&self) -> Option<Hertz> {
+        match self.pll_clock() {
+            Some(pll) => Some(Hertz(pll.0 / PLL_MUX_160M_TAP)),
+            None => None,
+        }
+    }
+    /// Switch root clock source and dividers to those of `config` on BL808 and BL616 series.
+    ///
+    /// Root clock is moved onto the always running crystal or RC oscillator before the
+    /// dividers change, and only moved onto PLL once dividers are settled, thus the
+    /// processor never runs faster than either configuration allows during the switch.
+    /// The PLL is not reprogrammed; build `config` from [`config`](Self::config) to keep it.
+    ///
+    /// Peripheral drivers compute their dividers from `Clocks` on creation; those created
+    /// before the switch keep stale dividers and must be released and created again.
+    /// Exclusive access to `self` makes sure no driver is created mid-switch.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `config` is on a different crystal oscillator or PLL setting, or selects
+    /// PLL root clock with PLL disabled.
+    #[inline]
+    pub fn reconfigure(
+        &mut self,
+        config: ClockConfig,
+        glb: &glb::v2::RegisterBlock,
+        hbn: &hbn::RegisterBlock,
+    ) {
+        assert!(
+            config.xtal.0 == self.config.xtal.0,
+            "crystal oscillator cannot be changed"
+        );
+        assert!(
+            config.pll == self.config.pll,
+            "PLL setting cannot be changed"
+        );
+        let clocks = config.freeze();
+        let xclk = match config.root {
+            RootClock::Rc32m => hbn::RootClockSource1::RC32M,
+            RootClock::Xtal | RootClock::Pll => hbn::RootClockSource1::Xtal,
+        };
+        hbn.global.write(
+            hbn.global
+                .read()
+                .set_root_clock_2(hbn::RootClockSource2::Xclk),
+        );
+        unsafe {
+            glb.sys_config_0.modify(|v| {
+                v.set_hclk_divide(config.cpu_divide - 1)
+                    .set_bclk_divide(config.bus_divide - 1)
+            });
+            glb.sys_config_1.modify(|v| v.act_bclk_divide());
+        }
+        while !glb.sys_config_1.read().is_bclk_divide_done() {
+            core::hint::spin_loop();
+        }
+        hbn.global.write(hbn.global.read().set_root_clock_1(xclk));
+        if let RootClock::Pll = config.root {
+            hbn.global.write(
+                hbn.global
+                    .read()
+                    .set_root_clock_2(hbn::RootClockSource2::Pllsel),
+            );
+        }
+        *self = clocks;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ClockConfig, Pll, RootClock};
+    use crate::mock::MockRegisters;
+    use crate::{glb, hbn};
+    use embedded_time::rate::Hertz;
+
+    const XTAL: Hertz = Hertz(40_000_000);
+
+    #[test]
+    fn struct_clock_config_freeze() {
+        let clocks = ClockConfig::new(XTAL).freeze();
+        assert_eq!(clocks.cpu_clock(), XTAL);
+        assert_eq!(clocks.uart_clock::<0>(), Some(XTAL));
+        assert_eq!(clocks.uart_clock::<3>(), None);
+        assert_eq!(clocks.spi_clock::<0>(), None);
+
+        let clocks =
+            ClockConfig::with_pll(XTAL, Pll::for_output(XTAL, Hertz(960_000_000)).unwrap())
+                .set_root(RootClock::Pll)
+                .set_bus_divide(4)
+                .freeze();
+        assert_eq!(clocks.pll_clock(), Some(Hertz(960_000_000)));
+        assert_eq!(clocks.cpu_clock(), Hertz(320_000_000));
+        assert_eq!(clocks.bus_clock(), Hertz(80_000_000));
+        assert_eq!(clocks.uart_clock::<1>(), Some(Hertz(80_000_000)));
+        assert_eq!(clocks.uart_clock::<4>(), Some(Hertz(160_000_000)));
+        assert_eq!(clocks.spi_clock::<1>(), Some(Hertz(160_000_000)));
+        assert_eq!(clocks.i2c_clock(), Some(XTAL));
+
+        let clocks = ClockConfig::new(XTAL)
+            .set_root(RootClock::Rc32m)
+            .set_cpu_divide(2)
+            .freeze();
+        assert_eq!(clocks.xclk(), Hertz(32_000_000));
+        assert_eq!(clocks.cpu_clock(), Hertz(16_000_000));
+
+        // 26 MHz crystal reaches 960 MHz from a 2 MHz reference.
+        let pll = Pll::for_output(Hertz(26_000_000), Hertz(960_000_000)).unwrap();
+        assert_eq!((pll.reference_divide(), pll.multiplier()), (13, 480));
+        assert_eq!(Pll::for_output(XTAL, Hertz(960_000_001)), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "PLL output frequency out of range")]
+    fn struct_clock_config_pll_out_of_range() {
+        let _ = ClockConfig::with_pll(XTAL, Pll::new(1, 100));
+    }
+
+    #[test]
+    #[should_panic(expected = "PLL root clock needs PLL enabled")]
+    fn struct_clock_config_pll_root_without_pll() {
+        let _ = ClockConfig::new(XTAL).set_root(RootClock::Pll).freeze();
+    }
+
+    #[test]
+    fn struct_clocks_reconfigure() {
+        let hbn = MockRegisters::<hbn::RegisterBlock>::new();
+        let hbn = hbn.registers();
+        let glb = glb::v2::MockRegisterBlock::new();
+        // Bus clock divider change completes at once.
+        glb.set_raw(0x94, 0x4);
+
+        let mut clocks = ClockConfig::with_pll(XTAL, Pll::new(1, 24)).freeze();
+        assert_eq!(clocks.cpu_clock(), XTAL);
+        let config = clocks.config().set_root(RootClock::Pll).set_bus_divide(4);
+        clocks.reconfigure(config, &glb, hbn);
+        assert_eq!(glb.raw(0x90), 0x0003_0000);
+        assert_eq!(
+            hbn.global.read().root_clock_1(),
+            hbn::RootClockSource1::Xtal
+        );
+        assert_eq!(
+            hbn.global.read().root_clock_2(),
+            hbn::RootClockSource2::Pllsel
+        );
+        assert_eq!(clocks.cpu_clock(), Hertz(320_000_000));
+
+        let config = clocks.config().set_root(RootClock::Rc32m);
+        clocks.reconfigure(config, &glb, hbn);
+        assert_eq!(
+            hbn.global.read().root_clock_1(),
+            hbn::RootClockSource1::RC32M
+        );
+        assert_eq!(
+            hbn.global.read().root_clock_2(),
+            hbn::RootClockSource2::Xclk
+        );
+        assert_eq!(clocks.cpu_clock(), Hertz(32_000_000));
+    }
+
+    #[test]
+    #[should_panic(expected = "PLL setting cannot be changed")]
+    fn struct_clocks_reconfigure_pll() {
+        let hbn = MockRegisters::<hbn::RegisterBlock>::new();
+        let glb = glb::v2::MockRegisterBlock::new();
+        let mut clocks = ClockConfig::new(XTAL).freeze();
+        let config = ClockConfig::with_pll(XTAL, Pll::new(1, 24)).set_root(RootClock::Pll);
+        clocks.reconfigure(config, &glb, hbn.registers());
+    }
 }
@@ -1,21 +1,27 @@
 //! Display bus interface.
 
-use volatile_register::RW;
+use volatile_register::{RO, RW, WO};
 
 /// Display bus interface registers.
 #[repr(C)]
 pub struct RegisterBlock {
     /// Function configuration register.
     pub config: RW<Config>,
-    _reserved: [u8; 0x7b],
+    /// Number of pixels in a pixel data transfer.
+    pub pixel_count: RW<u32>,
+    /// Bus state.
+    pub bus_state: RO<BusState>,
+    _reserved: [u8; 0x74],
     /// First-in first-out queue configuration 0.
     pub fifo_config_0: RW<FifoConfig0>,
     /// First-in first-out queue configuration 1.
     pub fifo_config_1: RW<FifoConfig1>,
+    /// Write data into first-in first-out queue.
+    pub fifo_write: WO<u32>,
 }
 
 /// Function configuration register.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
 #[repr(transparent)]
 pub struct Config(u32);
 
@@ -277,6 +283,21 @@ impl Config {
     }
 }
 
+/// Bus state register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct BusState(u32);
+
+impl BusState {
+    const BUSY: u32 = 1 << 0;
+
+    /// Check if a transaction is in progress on the bus.
+    #[inline]
+    pub const fn is_busy(self) -> bool {
+        self.0 & Self::BUSY != 0
+    }
+}
+
 /// First-in first-out queue configuration 0.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
@@ -354,7 +375,10 @@ mod tests {
     #[test]
     fn struct_register_block_offset() {
         assert_eq!(offset_of!(RegisterBlock, config), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, pixel_count), 0x04);
+        assert_eq!(offset_of!(RegisterBlock, bus_state), 0x08);
         assert_eq!(offset_of!(RegisterBlock, fifo_config_0), 0x80);
         assert_eq!(offset_of!(RegisterBlock, fifo_config_1), 0x84);
+        assert_eq!(offset_of!(RegisterBlock, fifo_write), 0x88);
     }
 }
@@ -0,0 +1,45 @@
+//! Display panel output.
+//!
+//! [`DbiDisplay`] drives command-mode panels over MIPI-DBI, either the 8-bit parallel
+//! Type B bus or the serial Type C bus. Panel controllers are brought up by porting their
+//! vendor init sequence onto [`write_command`](DbiDisplay::write_command) and
+//! [`write_data`](DbiDisplay::write_data), after which pixels are written into a window
+//! of panel memory with [`write_pixels`](DbiDisplay::write_pixels).
+//!
+//! [`DpiDisplay`] drives video-mode panels over a 16-bit parallel RGB bus, continuously
+//! scanning out a frame buffer in memory with the sync and porch [`Timing`] of the panel.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use bouffalo_hal::{dbi::RegisterBlock, display::{DbiDisplay, DbiPads}};
+//! # fn example(dbi: &'static RegisterBlock, pads: impl DbiPads) {
+//! let mut display = DbiDisplay::new(dbi, pads, 240, 320);
+//! // Sleep out, then display on.
+//! display.write_command(0x11);
+//! display.write_command(0x29);
+//! display.set_window(0, 0, 240, 320).unwrap();
+//! display.write_pixels(&[0xf800; 240]);
+//! # }
+//! ```
+mod dbi;
+pub use dbi::*;
+mod dpi;
+pub use dpi::*;
+
+/// Display output error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// Window exceeds the panel area.
+    WindowOutOfBounds,
+    /// Active area or sync width of a timing is zero.
+    ZeroTiming,
+    /// Pixel clock is faster than the source clock.
+    PixelClockTooHigh,
+    /// Pixel clock cannot be divided from the source clock within tolerance.
+    PixelClockUnreachable,
+    /// Frame buffer is smaller than the active area.
+    FramebufferSize,
+    /// Source clock is not available.
+    ClockSource,
+}
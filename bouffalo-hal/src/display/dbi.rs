@@ -0,0 +1,365 @@
+use super::Error;
+use crate::dbi::{Config, RegisterBlock};
+use crate::gpio::{self, Alternate};
+use core::ops::Deref;
+#[cfg(feature = "embedded-graphics-core")]
+use embedded_graphics_core::{
+    Pixel,
+    draw_target::DrawTarget,
+    geometry::{Dimensions, OriginDimensions, Size},
+    pixelcolor::{IntoStorage, Rgb565},
+    primitives::Rectangle,
+};
+
+/// Column address set command.
+const COLUMN_ADDRESS_SET: u8 = 0x2a;
+/// Page address set command.
+const PAGE_ADDRESS_SET: u8 = 0x2b;
+/// Memory write command.
+const MEMORY_WRITE: u8 = 0x2c;
+
+/// Bus type of a display bus interface.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DbiMode {
+    /// Type B, 8-bit parallel bus with write strobe.
+    TypeB,
+    /// Type C option 1, serial bus carrying data or command flag as ninth bit.
+    TypeC3Wire,
+    /// Type C option 3, serial bus with separate data or command pad.
+    TypeC4Wire,
+}
+
+/// Managed display bus interface peripheral.
+///
+/// Commands and their parameters are written with [`write_command`](Self::write_command)
+/// and [`write_data`](Self::write_data), matching how panel controller datasheets list
+/// init sequences. Pixels are written in 16-bit RGB565 into the window last set by
+/// [`set_window`](Self::set_window).
+pub struct DbiDisplay<DBI, PADS> {
+    dbi: DBI,
+    pads: PADS,
+    config: Config,
+    width: u16,
+    height: u16,
+}
+
+impl<DBI: Deref<Target = RegisterBlock>, PADS> DbiDisplay<DBI, PADS> {
+    /// Create a display bus instance for a panel of `width` by `height` pixels.
+    #[inline]
+    pub fn new(dbi: DBI, pads: PADS, width: u16, height: u16) -> Self
+    where
+        PADS: DbiPads,
+    {
+        let config = match PADS::MODE {
+            DbiMode::TypeB => Config::default().set_type_b(),
+            DbiMode::TypeC3Wire => Config::default().set_type_c().set_type_c_3_wire_mode(),
+            DbiMode::TypeC4Wire => Config::default().set_type_c().set_type_c_4_wire_mode(),
+        };
+        unsafe {
+            dbi.config.write(config);
+            dbi.fifo_config_0
+                .modify(|v| v.disable_dma_transmit().clear_transmit_fifo());
+        }
+        Self {
+            dbi,
+            pads,
+            config,
+            width,
+            height,
+        }
+    }
+    /// Write a command byte with data or command pad low.
+    #[inline]
+    pub fn write_command(&mut self, command: u8) {
+        let config = self
+            .config
+            .enable_command()
+            .set_command(command)
+            .disable_data();
+        self.transfer(config, core::iter::empty());
+    }
+    /// Write parameter bytes with data or command pad high.
+    #[inline]
+    pub fn write_data(&mut self, data: &[u8]) {
+        for chunk in data.chunks(4) {
+            let mut word = [0; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            let config = self
+                .config
+                .disable_command()
+                .enable_data()
+                .set_data_write()
+                .set_data_normal()
+                .set_data_byte_count(chunk.len() as u8 - 1);
+            self.transfer(config, core::iter::once(u32::from_le_bytes(word)));
+        }
+    }
+    /// Set the panel memory window that following pixels are written into.
+    ///
+    /// Returns an error if the window is empty or exceeds the panel.
+    #[inline]
+    pub fn set_window(&mut self, x: u16, y: u16, width: u16, height: u16) -> Result<(), Error> {
+        let (x_end, y_end) = window_end(x, y, width, height, self.width, self.height)?;
+        self.write_command(COLUMN_ADDRESS_SET);
+        self.write_data(&[(x >> 8) as u8, x as u8, (x_end >> 8) as u8, x_end as u8]);
+        self.write_command(PAGE_ADDRESS_SET);
+        self.write_data(&[(y >> 8) as u8, y as u8, (y_end >> 8) as u8, y_end as u8]);
+        Ok(())
+    }
+    /// Write RGB565 pixels from the top-left corner of current window.
+    #[inline]
+    pub fn write_pixels(&mut self, pixels: &[u16]) {
+        self.write_pixel_stream(pixels.len(), pixels.iter().copied());
+    }
+    /// Panel width and height in pixels.
+    #[inline]
+    pub fn size(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+    /// Release the display bus and return its peripheral and pads.
+    #[inline]
+    pub fn free(self) -> (DBI, PADS) {
+        unsafe { self.dbi.config.modify(|v| v.disable_master()) };
+        (self.dbi, self.pads)
+    }
+    /// Write `count` pixels from `pixels` after a memory write command, padding with black
+    /// if the iterator ends early.
+    #[inline]
+    fn write_pixel_stream(&mut self, count: usize, pixels: impl Iterator<Item = u16>) {
+        if count == 0 {
+            return;
+        }
+        let config = self
+            .config
+            .enable_command()
+            .set_command(MEMORY_WRITE)
+            .enable_data()
+            .set_data_write()
+            .set_data_pixel();
+        unsafe { self.dbi.pixel_count.write(count as u32) };
+        // Two pixels are packed into each queue word, first pixel in lower half.
+        let mut pixels = pixels.chain(core::iter::repeat(0)).take(count);
+        let words = core::iter::from_fn(move || {
+            let first = pixels.next()?;
+            let second = pixels.next().unwrap_or(0);
+            Some(first as u32 | (second as u32) << 16)
+        });
+        self.transfer(config, words);
+    }
+    /// Run one bus transaction, feeding `words` into the queue until the bus is idle.
+    #[inline]
+    fn transfer(&self, config: Config, words: impl IntoIterator<Item = u32>) {
+        let mut words = words.into_iter();
+        unsafe {
+            self.dbi.config.write(config.disable_master());
+            // Queue is empty between transactions; prime it before the bus starts.
+            if let Some(word) = words.next() {
+                self.dbi.fifo_write.write(word);
+            }
+            self.dbi.config.write(config.enable_master());
+        }
+        for word in words {
+            while self.dbi.fifo_config_1.read().transmit_available_bytes() == 0 {
+                core::hint::spin_loop();
+            }
+            unsafe { self.dbi.fifo_write.write(word) };
+        }
+        while self.dbi.bus_state.read().is_busy() {
+            core::hint::spin_loop();
+        }
+        unsafe { self.dbi.config.write(config.disable_master()) };
+    }
+}
+
+/// Get last column and row of a window, or an error if it does not fit in the panel.
+#[inline]
+fn window_end(
+    x: u16,
+    y: u16,
+    width: u16,
+    height: u16,
+    panel_width: u16,
+    panel_height: u16,
+) -> Result<(u16, u16), Error> {
+    if width == 0
+        || height == 0
+        || x as u32 + width as u32 > panel_width as u32
+        || y as u32 + height as u32 > panel_height as u32
+    {
+        return Err(Error::WindowOutOfBounds);
+    }
+    Ok((x + width - 1, y + height - 1))
+}
+
+#[cfg(feature = "embedded-graphics-core")]
+impl<DBI, PADS> OriginDimensions for DbiDisplay<DBI, PADS> {
+    #[inline]
+    fn size(&self) -> Size {
+        Size::new(self.width as u32, self.height as u32)
+    }
+}
+
+/// Draw target of RGB565 pixels.
+///
+/// Filled areas inside the panel are streamed into a single window; single pixels and areas
+/// crossing the panel edge are clipped and written one window per pixel.
+#[cfg(feature = "embedded-graphics-core")]
+impl<DBI: Deref<Target = RegisterBlock>, PADS> DrawTarget for DbiDisplay<DBI, PADS> {
+    type Color = Rgb565;
+    type Error = Error;
+
+    #[inline]
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = Pixel<Rgb565>>,
+    {
+        let bounds = self.bounding_box();
+        for Pixel(point, color) in pixels {
+            if bounds.contains(point) {
+                self.set_window(point.x as u16, point.y as u16, 1, 1)?;
+                self.write_pixel_stream(1, core::iter::once(color.into_storage()));
+            }
+        }
+        Ok(())
+    }
+    #[inline]
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = Rgb565>,
+    {
+        if area.is_zero_sized() {
+            return Ok(());
+        }
+        if self.bounding_box().intersection(area) != *area {
+            return self.draw_iter(area.points().zip(colors).map(|(p, c)| Pixel(p, c)));
+        }
+        self.set_window(
+            area.top_left.x as u16,
+            area.top_left.y as u16,
+            area.size.width as u16,
+            area.size.height as u16,
+        )?;
+        let count = area.size.width as usize * area.size.height as usize;
+        self.write_pixel_stream(count, colors.into_iter().map(|c| c.into_storage()));
+        Ok(())
+    }
+    #[inline]
+    fn fill_solid(&mut self, area: &Rectangle, color: Rgb565) -> Result<(), Error> {
+        let area = area.intersection(&self.bounding_box());
+        self.fill_contiguous(&area, core::iter::repeat(color))
+    }
+}
+
+/// Valid display bus pads.
+#[diagnostic::on_unimplemented(
+    message = "the display bus needs write strobe, data or command, chip select and eight data pads in DBI Type B mode, or clock, data, optional data or command, and chip select pads in DBI Type C mode"
+)]
+pub trait DbiPads {
+    /// Bus type selected by these pads.
+    const MODE: DbiMode;
+}
+
+/// Write strobe, data or command, chip select, and data 0 to 7.
+impl<
+    'a,
+    const W: usize,
+    const DC: usize,
+    const CS: usize,
+    const D0: usize,
+    const D1: usize,
+    const D2: usize,
+    const D3: usize,
+    const D4: usize,
+    const D5: usize,
+    const D6: usize,
+    const D7: usize,
+> DbiPads
+    for (
+        Alternate<'a, W, gpio::DbiB>,
+        Alternate<'a, DC, gpio::DbiB>,
+        Alternate<'a, CS, gpio::DbiB>,
+        (
+            Alternate<'a, D0, gpio::DbiB>,
+            Alternate<'a, D1, gpio::DbiB>,
+            Alternate<'a, D2, gpio::DbiB>,
+            Alternate<'a, D3, gpio::DbiB>,
+            Alternate<'a, D4, gpio::DbiB>,
+            Alternate<'a, D5, gpio::DbiB>,
+            Alternate<'a, D6, gpio::DbiB>,
+            Alternate<'a, D7, gpio::DbiB>,
+        ),
+    )
+{
+    const MODE: DbiMode = DbiMode::TypeB;
+}
+
+/// Clock, data, data or command, and chip select.
+impl<'a, const SCL: usize, const SDA: usize, const DC: usize, const CS: usize> DbiPads
+    for (
+        Alternate<'a, SCL, gpio::DbiC>,
+        Alternate<'a, SDA, gpio::DbiC>,
+        Alternate<'a, DC, gpio::DbiC>,
+        Alternate<'a, CS, gpio::DbiC>,
+    )
+{
+    const MODE: DbiMode = DbiMode::TypeC4Wire;
+}
+
+/// Clock, data, and chip select.
+impl<'a, const SCL: usize, const SDA: usize, const CS: usize> DbiPads
+    for (
+        Alternate<'a, SCL, gpio::DbiC>,
+        Alternate<'a, SDA, gpio::DbiC>,
+        Alternate<'a, CS, gpio::DbiC>,
+    )
+{
+    const MODE: DbiMode = DbiMode::TypeC3Wire;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DbiDisplay, DbiMode, DbiPads, Error};
+    use crate::dbi::RegisterBlock;
+    use crate::mock::MockRegisters;
+
+    struct MockPads;
+
+    impl DbiPads for MockPads {
+        const MODE: DbiMode = DbiMode::TypeC4Wire;
+    }
+
+    #[test]
+    fn struct_dbi_display_writes() {
+        let mock = MockRegisters::<RegisterBlock>::new();
+        // Queue always has room for more words.
+        mock.set_raw(0x84, 0x8);
+        let mut display = DbiDisplay::new(mock.registers(), MockPads, 240, 320);
+        assert_eq!(mock.raw(0x00), 0x0000_0002);
+
+        display.write_command(0x11);
+        assert_eq!(mock.raw(0x00), 0x0000_1106);
+        display.write_data(&[0x01, 0x02, 0x03, 0x04]);
+        assert_eq!(mock.raw(0x00), 0x0000_00ca);
+        assert_eq!(mock.raw(0x88), 0x0403_0201);
+        display.write_data(&[0x05]);
+        assert_eq!(mock.raw(0x00), 0x0000_000a);
+        assert_eq!(mock.raw(0x88), 0x0000_0005);
+
+        assert_eq!(
+            display.set_window(230, 0, 20, 1),
+            Err(Error::WindowOutOfBounds)
+        );
+        assert_eq!(
+            display.set_window(0, 0, 0, 1),
+            Err(Error::WindowOutOfBounds)
+        );
+        assert_eq!(display.set_window(10, 20, 100, 300), Ok(()));
+        // Last transfer is the page address range, rows 20 to 319.
+        assert_eq!(mock.raw(0x88), 0x3f01_1400);
+
+        display.write_pixels(&[0x1234, 0x5678, 0x9abc]);
+        assert_eq!(mock.raw(0x00), 0x0000_2c2e);
+        assert_eq!(mock.raw(0x04), 3);
+        assert_eq!(mock.raw(0x88), 0x0000_9abc);
+    }
+}
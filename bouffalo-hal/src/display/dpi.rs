@@ -0,0 +1,385 @@
+use super::Error;
+use crate::clocks::Clocks;
+use crate::dpi::{Config, Polarity, Porch, RegisterBlock, Span};
+use crate::gpio::{self, Alternate};
+use core::ops::Deref;
+use embedded_time::rate::Hertz;
+
+/// Largest pixel clock divider from source clock.
+const MAX_DIVIDE: u32 = 256;
+
+/// Sync and porch timing of a parallel RGB panel.
+///
+/// Horizontal values are in pixel clocks and vertical values are in lines, as listed in
+/// panel datasheets. Each line is front porch, sync, back porch and active pixels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Timing {
+    /// Visible pixels per line.
+    pub h_active: u16,
+    /// Pixel clocks from end of active pixels to horizontal sync.
+    pub h_front_porch: u16,
+    /// Width of horizontal sync pulse.
+    pub h_sync: u16,
+    /// Pixel clocks from horizontal sync to first active pixel.
+    pub h_back_porch: u16,
+    /// Visible lines per frame.
+    pub v_active: u16,
+    /// Lines from end of active lines to vertical sync.
+    pub v_front_porch: u16,
+    /// Width of vertical sync pulse in lines.
+    pub v_sync: u16,
+    /// Lines from vertical sync to first active line.
+    pub v_back_porch: u16,
+    /// Level of active horizontal sync.
+    pub hsync_polarity: Polarity,
+    /// Level of active vertical sync.
+    pub vsync_polarity: Polarity,
+    /// Pixel clock frequency required by the panel.
+    pub pixel_clock: Hertz,
+}
+
+impl Timing {
+    /// Total pixel clocks per line, including blanking.
+    #[inline]
+    pub const fn h_total(&self) -> u32 {
+        self.h_active as u32
+            + self.h_front_porch as u32
+            + self.h_sync as u32
+            + self.h_back_porch as u32
+    }
+    /// Total lines per frame, including blanking.
+    #[inline]
+    pub const fn v_total(&self) -> u32 {
+        self.v_active as u32
+            + self.v_front_porch as u32
+            + self.v_sync as u32
+            + self.v_back_porch as u32
+    }
+    /// Check this timing against `source` clock, returning its divider and actual pixel clock.
+    ///
+    /// Active area and sync widths must be non-zero. The pixel clock must be reachable
+    /// by an integer divider of `source` within 5 percent.
+    #[inline]
+    pub fn validate(&self, source: Hertz) -> Result<(u32, Hertz), Error> {
+        if self.h_active == 0 || self.v_active == 0 || self.h_sync == 0 || self.v_sync == 0 {
+            return Err(Error::ZeroTiming);
+        }
+        pixel_clock_divide_for(source, self.pixel_clock)
+    }
+}
+
+/// Calculate divider of `source` nearest to `pixel_clock`, and the actual pixel clock.
+#[inline]
+pub fn pixel_clock_divide_for(source: Hertz, pixel_clock: Hertz) -> Result<(u32, Hertz), Error> {
+    if pixel_clock.0 > source.0 {
+        return Err(Error::PixelClockTooHigh);
+    }
+    if pixel_clock.0 == 0 {
+        return Err(Error::PixelClockUnreachable);
+    }
+    let divide = ((source.0 + pixel_clock.0 / 2) / pixel_clock.0).clamp(1, MAX_DIVIDE);
+    let actual = source.0 / divide;
+    if actual.abs_diff(pixel_clock.0) as u64 * 20 > pixel_clock.0 as u64 {
+        return Err(Error::PixelClockUnreachable);
+    }
+    Ok((divide, Hertz(actual)))
+}
+
+/// Managed display pixel interface peripheral.
+///
+/// The frame buffer holds RGB565 pixels of the active area row by row, and is scanned
+/// out continuously while the display exists.
+pub struct DpiDisplay<DPI, PADS> {
+    dpi: DPI,
+    pads: PADS,
+    timing: Timing,
+    pixel_clock: Hertz,
+    framebuffer: &'static mut [u16],
+}
+
+impl<DPI: Deref<Target = RegisterBlock>, PADS> DpiDisplay<DPI, PADS> {
+    /// Create and start a pixel interface scanning out `framebuffer` with `timing`, using
+    /// source clock from `clocks`.
+    ///
+    /// Returns an error if the source clock is not configured, the timing is invalid under
+    /// current source clock, or the frame buffer is smaller than its active area.
+    #[inline]
+    pub fn new(
+        dpi: DPI,
+        pads: PADS,
+        timing: Timing,
+        framebuffer: &'static mut [u16],
+        clocks: &Clocks,
+    ) -> Result<Self, Error>
+    where
+        PADS: DpiPads,
+    {
+        let source = clocks.dpi_clock().ok_or(Error::ClockSource)?;
+        let (divide, pixel_clock) = timing.validate(source)?;
+        check_framebuffer(&timing, framebuffer)?;
+        unsafe {
+            dpi.config.modify(|v| v.disable());
+            dpi.horizontal_span.write(
+                Span::default()
+                    .set_active(timing.h_active)
+                    .set_sync(timing.h_sync),
+            );
+            dpi.horizontal_porch.write(
+                Porch::default()
+                    .set_front(timing.h_front_porch)
+                    .set_back(timing.h_back_porch),
+            );
+            dpi.vertical_span.write(
+                Span::default()
+                    .set_active(timing.v_active)
+                    .set_sync(timing.v_sync),
+            );
+            dpi.vertical_porch.write(
+                Porch::default()
+                    .set_front(timing.v_front_porch)
+                    .set_back(timing.v_back_porch),
+            );
+            dpi.frame_address.write(framebuffer.as_ptr() as u32);
+            dpi.clock_divide.write(divide - 1);
+            dpi.config.write(
+                Config::default()
+                    .set_hsync_polarity(timing.hsync_polarity)
+                    .set_vsync_polarity(timing.vsync_polarity)
+                    .enable(),
+            );
+        }
+        Ok(Self {
+            dpi,
+            pads,
+            timing,
+            pixel_clock,
+            framebuffer,
+        })
+    }
+    /// Get actual pixel clock after clock division.
+    #[inline]
+    pub fn pixel_clock(&self) -> Hertz {
+        self.pixel_clock
+    }
+    /// Get actual frame rate after clock division.
+    #[inline]
+    pub fn frame_rate(&self) -> Hertz {
+        Hertz(self.pixel_clock.0 / (self.timing.h_total() * self.timing.v_total()))
+    }
+    /// Panel timing in use.
+    #[inline]
+    pub fn timing(&self) -> Timing {
+        self.timing
+    }
+    /// Frame buffer being scanned out.
+    #[inline]
+    pub fn framebuffer(&mut self) -> &mut [u16] {
+        self.framebuffer
+    }
+    /// Scan out from `framebuffer` starting next frame, returning the previous frame buffer.
+    ///
+    /// Returns an error and leaves the current buffer in place if `framebuffer` is
+    /// smaller than the active area.
+    #[inline]
+    pub fn set_framebuffer(
+        &mut self,
+        framebuffer: &'static mut [u16],
+    ) -> Result<&'static mut [u16], Error> {
+        check_framebuffer(&self.timing, framebuffer)?;
+        unsafe { self.dpi.frame_address.write(framebuffer.as_ptr() as u32) };
+        Ok(core::mem::replace(&mut self.framebuffer, framebuffer))
+    }
+    /// Stop scanning out and return the peripheral, pads and frame buffer.
+    #[inline]
+    pub fn free(self) -> (DPI, PADS, &'static mut [u16]) {
+        unsafe { self.dpi.config.modify(|v| v.disable()) };
+        (self.dpi, self.pads, self.framebuffer)
+    }
+}
+
+#[inline]
+fn check_framebuffer(timing: &Timing, framebuffer: &[u16]) -> Result<(), Error> {
+    if framebuffer.len() < timing.h_active as usize * timing.v_active as usize {
+        return Err(Error::FramebufferSize);
+    }
+    Ok(())
+}
+
+/// Valid display pixel interface pads.
+#[diagnostic::on_unimplemented(
+    message = "the display pixel interface needs pixel clock, horizontal sync, vertical sync, data enable and sixteen data pads in DPI mode"
+)]
+pub trait DpiPads {}
+
+/// Pixel clock, horizontal sync, vertical sync, data enable, and data 0 to 15.
+impl<
+    'a,
+    const C: usize,
+    const H: usize,
+    const V: usize,
+    const E: usize,
+    const D0: usize,
+    const D1: usize,
+    const D2: usize,
+    const D3: usize,
+    const D4: usize,
+    const D5: usize,
+    const D6: usize,
+    const D7: usize,
+    const D8: usize,
+    const D9: usize,
+    const D10: usize,
+    const D11: usize,
+    const D12: usize,
+    const D13: usize,
+    const D14: usize,
+    const D15: usize,
+> DpiPads
+    for (
+        Alternate<'a, C, gpio::Dpi>,
+        Alternate<'a, H, gpio::Dpi>,
+        Alternate<'a, V, gpio::Dpi>,
+        Alternate<'a, E, gpio::Dpi>,
+        (
+            Alternate<'a, D0, gpio::Dpi>,
+            Alternate<'a, D1, gpio::Dpi>,
+            Alternate<'a, D2, gpio::Dpi>,
+            Alternate<'a, D3, gpio::Dpi>,
+            Alternate<'a, D4, gpio::Dpi>,
+            Alternate<'a, D5, gpio::Dpi>,
+            Alternate<'a, D6, gpio::Dpi>,
+            Alternate<'a, D7, gpio::Dpi>,
+            Alternate<'a, D8, gpio::Dpi>,
+            Alternate<'a, D9, gpio::Dpi>,
+            Alternate<'a, D10, gpio::Dpi>,
+            Alternate<'a, D11, gpio::Dpi>,
+            Alternate<'a, D12, gpio::Dpi>,
+            Alternate<'a, D13, gpio::Dpi>,
+            Alternate<'a, D14, gpio::Dpi>,
+            Alternate<'a, D15, gpio::Dpi>,
+        ),
+    )
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DpiDisplay, DpiPads, Error, Timing, pixel_clock_divide_for};
+    use crate::mock::MockRegisters;
+    use crate::{
+        clocks::{ClockConfig, Pll},
+        dpi::{Polarity, RegisterBlock},
+    };
+    use embedded_time::rate::Hertz;
+
+    struct MockPads;
+
+    impl DpiPads for MockPads {}
+
+    const TIMING: Timing = Timing {
+        h_active: 16,
+        h_front_porch: 2,
+        h_sync: 4,
+        h_back_porch: 3,
+        v_active: 8,
+        v_front_porch: 1,
+        v_sync: 1,
+        v_back_porch: 2,
+        hsync_polarity: Polarity::ActiveLow,
+        vsync_polarity: Polarity::ActiveLow,
+        pixel_clock: Hertz(9_000_000),
+    };
+
+    fn framebuffer(len: usize) -> &'static mut [u16] {
+        extern crate std;
+        std::vec![0; len].leak()
+    }
+
+    #[test]
+    fn function_pixel_clock_divide_for() {
+        let source = Hertz(160_000_000);
+        assert_eq!(
+            pixel_clock_divide_for(source, Hertz(9_000_000)),
+            Ok((18, Hertz(8_888_888)))
+        );
+        assert_eq!(
+            pixel_clock_divide_for(source, Hertz(33_000_000)),
+            Ok((5, Hertz(32_000_000)))
+        );
+        assert_eq!(
+            pixel_clock_divide_for(source, Hertz(200_000_000)),
+            Err(Error::PixelClockTooHigh)
+        );
+        // Slowest clock is 625 kHz at largest divider.
+        assert_eq!(
+            pixel_clock_divide_for(source, Hertz(300_000)),
+            Err(Error::PixelClockUnreachable)
+        );
+        // 100 MHz falls between 160 MHz and 80 MHz.
+        assert_eq!(
+            pixel_clock_divide_for(source, Hertz(100_000_000)),
+            Err(Error::PixelClockUnreachable)
+        );
+        let timing = Timing {
+            h_sync: 0,
+            ..TIMING
+        };
+        assert_eq!(timing.validate(source), Err(Error::ZeroTiming));
+    }
+
+    #[test]
+    fn struct_dpi_display_new() {
+        let mock = MockRegisters::<RegisterBlock>::new();
+        let xtal = Hertz(40_000_000);
+        let clocks =
+            ClockConfig::with_pll(xtal, Pll::for_output(xtal, Hertz(960_000_000)).unwrap())
+                .freeze();
+        let result = DpiDisplay::new(
+            mock.registers(),
+            MockPads,
+            TIMING,
+            framebuffer(16 * 8 - 1),
+            &clocks,
+        );
+        assert_eq!(result.err(), Some(Error::FramebufferSize));
+        assert_eq!(mock.raw(0x00), 0);
+        let result = DpiDisplay::new(
+            mock.registers(),
+            MockPads,
+            TIMING,
+            framebuffer(16 * 8),
+            &ClockConfig::new(xtal).freeze(),
+        );
+        assert_eq!(result.err(), Some(Error::ClockSource));
+
+        let mut display = DpiDisplay::new(
+            mock.registers(),
+            MockPads,
+            TIMING,
+            framebuffer(16 * 8),
+            &clocks,
+        )
+        .unwrap();
+        assert_eq!(mock.raw(0x00), 0x7);
+        assert_eq!(mock.raw(0x04), 0x0004_0010);
+        assert_eq!(mock.raw(0x08), 0x0003_0002);
+        assert_eq!(mock.raw(0x0c), 0x0001_0008);
+        assert_eq!(mock.raw(0x10), 0x0002_0001);
+        assert_eq!(mock.raw(0x18), 17);
+        assert_eq!(display.pixel_clock(), Hertz(8_888_888));
+        // 25 clocks per line and 12 lines per frame.
+        assert_eq!(display.frame_rate(), Hertz(8_888_888 / 300));
+
+        assert_eq!(
+            display.set_framebuffer(framebuffer(4)).err(),
+            Some(Error::FramebufferSize)
+        );
+        let next = framebuffer(16 * 8);
+        let address = next.as_ptr() as usize as u32;
+        display.set_framebuffer(next).unwrap();
+        assert_eq!(mock.raw(0x14), address);
+        let (_, _, buffer) = display.free();
+        assert_eq!(mock.raw(0x00), 0x6);
+        assert_eq!(buffer.len(), 16 * 8);
+    }
+}
@@ -1,7 +1,16 @@
 //! Direct Memory Access peripheral.
+//!
+//! With the `dma` feature enabled, `Dma` splits the peripheral into channel tokens
+//! which start `Transfer`s between `'static` buffers and peripherals. Buffers must be
+//! located in memory regions accessible by DMA with data cache disabled or flushed.
 
 use volatile_register::{RO, RW, WO};
 
+#[cfg(feature = "dma")]
+mod transfer;
+#[cfg(feature = "dma")]
+pub use transfer::*;
+
 /// Direct Memory Access peripheral registers.
 #[repr(C)]
 pub struct RegisterBlock {
@@ -0,0 +1,892 @@
+//! DMA channels and transfers with buffer ownership.
+use super::{
+    BurstSize, ChannelRegisters, DMAMode, LliControl, LliItemPool, Periph4DMA01, Periph4DMA2,
+    RegisterBlock, TransferWidth,
+};
+use core::ops::Deref;
+use core::sync::atomic::{Ordering, compiler_fence};
+
+/// Maximum number of data units in one linked list item.
+pub const MAX_TRANSFER_SIZE: usize = 0xfff;
+
+/// Data unit transferred by DMA.
+pub trait Word: Copy + 'static {
+    /// Transfer width of this data unit.
+    const WIDTH: TransferWidth;
+}
+
+impl Word for u8 {
+    const WIDTH: TransferWidth = TransferWidth::Byte;
+}
+
+impl Word for u16 {
+    const WIDTH: TransferWidth = TransferWidth::HalfWord;
+}
+
+impl Word for u32 {
+    const WIDTH: TransferWidth = TransferWidth::Word;
+}
+
+/// Memory region DMA may read from.
+///
+/// # Safety
+///
+/// The returned region must stay valid and at the same address for as long as the
+/// implementor lives, even if it is moved. This holds for `'static` references, whose
+/// memory is never freed, so a transfer dropped early can never leave DMA reading
+/// freed memory.
+pub unsafe trait ReadBuffer {
+    /// Data unit of this buffer.
+    type Word: Word;
+    /// Start address and length in data units of the buffer.
+    fn read_buffer(&self) -> (*const Self::Word, usize);
+}
+
+/// Memory region DMA may write into.
+///
+/// # Safety
+///
+/// The returned region must stay valid and at the same address for as long as the
+/// implementor lives, even if it is moved, and must not be accessed elsewhere.
+pub unsafe trait WriteBuffer {
+    /// Data unit of this buffer.
+    type Word: Word;
+    /// Start address and length in data units of the buffer.
+    fn write_buffer(&mut self) -> (*mut Self::Word, usize);
+}
+
+unsafe impl<W: Word> ReadBuffer for &'static [W] {
+    type Word = W;
+    #[inline]
+    fn read_buffer(&self) -> (*const W, usize) {
+        (self.as_ptr(), self.len())
+    }
+}
+
+unsafe impl<W: Word, const N: usize> ReadBuffer for &'static [W; N] {
+    type Word = W;
+    #[inline]
+    fn read_buffer(&self) -> (*const W, usize) {
+        (self.as_ptr(), N)
+    }
+}
+
+unsafe impl<W: Word> ReadBuffer for &'static mut [W] {
+    type Word = W;
+    #[inline]
+    fn read_buffer(&self) -> (*const W, usize) {
+        (self.as_ptr(), self.len())
+    }
+}
+
+unsafe impl<W: Word, const N: usize> ReadBuffer for &'static mut [W; N] {
+    type Word = W;
+    #[inline]
+    fn read_buffer(&self) -> (*const W, usize) {
+        (self.as_ptr(), N)
+    }
+}
+
+unsafe impl<W: Word> WriteBuffer for &'static mut [W] {
+    type Word = W;
+    #[inline]
+    fn write_buffer(&mut self) -> (*mut W, usize) {
+        (self.as_mut_ptr(), self.len())
+    }
+}
+
+unsafe impl<W: Word, const N: usize> WriteBuffer for &'static mut [W; N] {
+    type Word = W;
+    #[inline]
+    fn write_buffer(&mut self) -> (*mut W, usize) {
+        (self.as_mut_ptr(), N)
+    }
+}
+
+/// Peripheral request line of a DMA transfer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Request {
+    /// Request line on DMA 0 or DMA 1.
+    Dma01(Periph4DMA01),
+    /// Request line on DMA 2.
+    Dma2(Periph4DMA2),
+}
+
+impl Request {
+    /// Check if this request line is routed to DMA peripheral `I`.
+    #[inline]
+    pub const fn is_routed_to(self, i: usize) -> bool {
+        match self {
+            Request::Dma01(_) => i == 0 || i == 1,
+            Request::Dma2(_) => i == 2,
+        }
+    }
+}
+
+/// Peripheral DMA may write data into.
+///
+/// # Safety
+///
+/// `address` must be the transmit data register of the peripheral and `REQUEST` its
+/// transmit request line.
+pub unsafe trait TransmitTarget {
+    /// Data unit of the transmit data register.
+    type Word: Word;
+    /// Transmit request line.
+    const REQUEST: Request;
+    /// Address of the transmit data register.
+    fn transmit_address(&self) -> u32;
+    /// Let peripheral issue transmit requests.
+    fn enable_transmit_dma(&mut self);
+    /// Stop peripheral from issuing transmit requests.
+    fn disable_transmit_dma(&mut self);
+}
+
+/// Peripheral DMA may read data from.
+///
+/// # Safety
+///
+/// `address` must be the receive data register of the peripheral and `REQUEST` its
+/// receive request line.
+pub unsafe trait ReceiveTarget {
+    /// Data unit of the receive data register.
+    type Word: Word;
+    /// Receive request line.
+    const REQUEST: Request;
+    /// Address of the receive data register.
+    fn receive_address(&self) -> u32;
+    /// Let peripheral issue receive requests.
+    fn enable_receive_dma(&mut self);
+    /// Stop peripheral from issuing receive requests.
+    fn disable_receive_dma(&mut self);
+}
+
+/// Managed Direct Memory Access peripheral `I`.
+///
+/// Transfers only start on peripheral request lines routed to DMA `I`; a mismatch fails
+/// to compile.
+pub struct Dma<DMA, const I: usize> {
+    dma: DMA,
+}
+
+impl<DMA: Deref<Target = RegisterBlock>, const I: usize> Dma<DMA, I> {
+    /// Create a DMA instance and enable the peripheral.
+    #[inline]
+    pub fn new(dma: DMA) -> Self {
+        const { assert!(I <= 2, "DMA peripheral number out of range") };
+        unsafe { dma.global_config.modify(|val| val.enable_smdma()) };
+        Self { dma }
+    }
+    /// Split the DMA peripheral into its channels.
+    #[rustfmt::skip]
+    #[inline]
+    pub fn split(self) -> Channels<DMA, I> {
+        let dma = self.dma;
+        Channels {
+            ch0: Channel { dma: unsafe { core::ptr::read(&dma as *const _) } },
+            ch1: Channel { dma: unsafe { core::ptr::read(&dma as *const _) } },
+            ch2: Channel { dma: unsafe { core::ptr::read(&dma as *const _) } },
+            ch3: Channel { dma: unsafe { core::ptr::read(&dma as *const _) } },
+            ch4: Channel { dma: unsafe { core::ptr::read(&dma as *const _) } },
+            ch5: Channel { dma: unsafe { core::ptr::read(&dma as *const _) } },
+            ch6: Channel { dma: unsafe { core::ptr::read(&dma as *const _) } },
+            ch7: Channel { dma },
+        }
+    }
+}
+
+/// All the channels of a DMA peripheral.
+pub struct Channels<DMA, const I: usize> {
+    /// Channel 0.
+    pub ch0: Channel<DMA, I, 0>,
+    /// Channel 1.
+    pub ch1: Channel<DMA, I, 1>,
+    /// Channel 2.
+    pub ch2: Channel<DMA, I, 2>,
+    /// Channel 3.
+    pub ch3: Channel<DMA, I, 3>,
+    /// Channel 4.
+    pub ch4: Channel<DMA, I, 4>,
+    /// Channel 5.
+    pub ch5: Channel<DMA, I, 5>,
+    /// Channel 6.
+    pub ch6: Channel<DMA, I, 6>,
+    /// Channel 7.
+    pub ch7: Channel<DMA, I, 7>,
+}
+
+/// DMA channel `N` of peripheral `I`, claimed as a token.
+pub struct Channel<DMA, const I: usize, const N: usize> {
+    dma: DMA,
+}
+
+impl<DMA: Deref<Target = RegisterBlock>, const I: usize, const N: usize> Channel<DMA, I, N> {
+    /// Transfer data from `buffer` to peripheral `target`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffer` is longer than [`MAX_TRANSFER_SIZE`] data units; use
+    /// [`transmit_gather`](Self::transmit_gather) for longer transfers.
+    #[inline]
+    pub fn transmit<B, T>(self, buffer: B, mut target: T) -> Transfer<DMA, I, N, B, T>
+    where
+        B: ReadBuffer,
+        T: TransmitTarget<Word = B::Word>,
+    {
+        let (ptr, len) = buffer.read_buffer();
+        let mut first = item::<B::Word>(ptr as u32, target.transmit_address(), len, true, false);
+        first.control = first.control.enable_cplt_int();
+        target.enable_transmit_dma();
+        self.start(
+            first,
+            0,
+            DMAMode::Mem2Periph,
+            const { routed::<I>(T::REQUEST) },
+            true,
+        );
+        Transfer::new(self, buffer, target, T::disable_transmit_dma)
+    }
+    /// Transfer data from peripheral `target` into `buffer`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffer` is longer than [`MAX_TRANSFER_SIZE`] data units; use
+    /// [`receive_scatter`](Self::receive_scatter) for longer transfers.
+    #[inline]
+    pub fn receive<B, T>(self, mut buffer: B, mut target: T) -> Transfer<DMA, I, N, B, T>
+    where
+        B: WriteBuffer,
+        T: ReceiveTarget<Word = B::Word>,
+    {
+        let (ptr, len) = buffer.write_buffer();
+        let mut first = item::<B::Word>(target.receive_address(), ptr as u32, len, false, true);
+        first.control = first.control.enable_cplt_int();
+        target.enable_receive_dma();
+        self.start(
+            first,
+            0,
+            DMAMode::Periph2Mem,
+            const { routed::<I>(T::REQUEST) },
+            false,
+        );
+        Transfer::new(self, buffer, target, T::disable_receive_dma)
+    }
+    /// Transfer data from several buffers in turn to peripheral `target`.
+    ///
+    /// The buffers are chained with linked list items in `descriptors`, which must hold
+    /// at least one item fewer than `buffers`. Every buffer may hold up to
+    /// [`MAX_TRANSFER_SIZE`] data units.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffers` is empty, any buffer is too long, or `descriptors` is too short.
+    #[inline]
+    pub fn transmit_gather<W, T>(
+        self,
+        buffers: &'static [&'static [W]],
+        descriptors: &'static mut [LliItemPool],
+        mut target: T,
+    ) -> Transfer<DMA, I, N, LinkedList<&'static [&'static [W]]>, T>
+    where
+        W: Word,
+        T: TransmitTarget<Word = W>,
+    {
+        let address = target.transmit_address();
+        let items = buffers
+            .iter()
+            .map(|buffer| item::<W>(buffer.as_ptr() as u32, address, buffer.len(), true, false));
+        let (first, next) = link(items, buffers.len(), descriptors);
+        target.enable_transmit_dma();
+        self.start(
+            first,
+            next,
+            DMAMode::Mem2Periph,
+            const { routed::<I>(T::REQUEST) },
+            true,
+        );
+        Transfer::new(
+            self,
+            LinkedList {
+                buffers,
+                descriptors,
+            },
+            target,
+            T::disable_transmit_dma,
+        )
+    }
+    /// Transfer data from peripheral `target` into several buffers in turn.
+    ///
+    /// The buffers are chained with linked list items in `descriptors`, which must hold
+    /// at least one item fewer than `buffers`. Every buffer may hold up to
+    /// [`MAX_TRANSFER_SIZE`] data units.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffers` is empty, any buffer is too long, or `descriptors` is too short.
+    #[inline]
+    pub fn receive_scatter<W, T>(
+        self,
+        buffers: &'static mut [&'static mut [W]],
+        descriptors: &'static mut [LliItemPool],
+        mut target: T,
+    ) -> Transfer<DMA, I, N, LinkedList<&'static mut [&'static mut [W]]>, T>
+    where
+        W: Word,
+        T: ReceiveTarget<Word = W>,
+    {
+        let address = target.receive_address();
+        let count = buffers.len();
+        let items = buffers.iter_mut().map(|buffer| {
+            item::<W>(
+                address,
+                buffer.as_mut_ptr() as u32,
+                buffer.len(),
+                false,
+                true,
+            )
+        });
+        let (first, next) = link(items, count, descriptors);
+        target.enable_receive_dma();
+        self.start(
+            first,
+            next,
+            DMAMode::Periph2Mem,
+            const { routed::<I>(T::REQUEST) },
+            false,
+        );
+        Transfer::new(
+            self,
+            LinkedList {
+                buffers,
+                descriptors,
+            },
+            target,
+            T::disable_receive_dma,
+        )
+    }
+    /// Receive data from peripheral `target` continuously into two buffers in turn.
+    ///
+    /// Linked list items in `descriptors` form a ring, so DMA never stops; while one
+    /// buffer is being filled, the other one is handed out by
+    /// [`CircularTransfer::poll`]. Each buffer may hold up to [`MAX_TRANSFER_SIZE`]
+    /// data units.
+    #[inline]
+    pub fn receive_circular<W, T, const L: usize>(
+        self,
+        buffers: &'static mut [[W; L]; 2],
+        descriptors: &'static mut [LliItemPool; 2],
+        mut target: T,
+    ) -> CircularTransfer<DMA, I, N, W, L, T>
+    where
+        W: Word,
+        T: ReceiveTarget<Word = W>,
+    {
+        let address = target.receive_address();
+        let addresses = [
+            buffers[0].as_mut_ptr() as u32,
+            buffers[1].as_mut_ptr() as u32,
+        ];
+        let ring = |i: usize| {
+            let mut item = item::<W>(address, addresses[i], L, false, true);
+            item.control = item.control.enable_cplt_int();
+            item
+        };
+        descriptors[0] = ring(0);
+        descriptors[1] = ring(1);
+        descriptors[0].linked_list_item = &descriptors[1] as *const LliItemPool as u32;
+        descriptors[1].linked_list_item = &descriptors[0] as *const LliItemPool as u32;
+        let first = ring(0);
+        target.enable_receive_dma();
+        self.start(
+            first,
+            descriptors[0].linked_list_item,
+            DMAMode::Periph2Mem,
+            const { routed::<I>(T::REQUEST) },
+            false,
+        );
+        CircularTransfer {
+            channel: self,
+            buffers,
+            descriptors,
+            target,
+            next: 0,
+        }
+    }
+    #[inline]
+    fn registers(&self) -> &ChannelRegisters {
+        &self.dma.channels[N]
+    }
+    #[inline]
+    fn start(&self, first: LliItemPool, next: u32, mode: DMAMode, request: Request, tx: bool) {
+        let channel = self.registers();
+        // Make buffer contents and linked list items visible before DMA starts.
+        compiler_fence(Ordering::Release);
+        unsafe {
+            self.dma
+                .interrupts
+                .transfer_complete_clear
+                .write(super::TransferCompleteClear(0).clear_cplt_int(N as u8));
+            self.dma
+                .interrupts
+                .error_clear
+                .write(super::ErrorClear(0).clear_err_int(N as u8));
+            channel.source_address.write(first.source_address);
+            channel.destination_address.write(first.destination_address);
+            channel.linked_list_item.write(next);
+            channel.control.write(first.control);
+            channel.config.modify(|val| {
+                let val = val.set_dma_mode(mode);
+                let val = match (request, tx) {
+                    (Request::Dma01(p), true) => val.set_dst_periph4dma01(p),
+                    (Request::Dma01(p), false) => val.set_src_periph4dma01(p),
+                    (Request::Dma2(p), true) => val.set_dst_periph4dma2(p),
+                    (Request::Dma2(p), false) => val.set_src_periph4dma2(p),
+                };
+                val.enable_cplt_int().enable_err_int().enable_ch()
+            });
+        }
+    }
+    #[inline]
+    fn is_busy(&self) -> bool {
+        self.registers().config.read().is_ch_enabled()
+    }
+    #[inline]
+    fn halt(&self) {
+        unsafe { self.registers().config.modify(|val| val.disable_ch()) };
+    }
+}
+
+/// Request line `request`, checked to be routed to DMA peripheral `I`.
+#[inline]
+const fn routed<const I: usize>(request: Request) -> Request {
+    assert!(
+        request.is_routed_to(I),
+        "peripheral request line is not routed to this DMA peripheral"
+    );
+    request
+}
+
+/// Linked list item for one buffer.
+#[inline]
+fn item<W: Word>(
+    source: u32,
+    destination: u32,
+    len: usize,
+    src_inc: bool,
+    dst_inc: bool,
+) -> LliItemPool {
+    if len > MAX_TRANSFER_SIZE {
+        panic!("DMA buffer too long for one linked list item");
+    }
+    let control = LliControl(0)
+        .set_src_transfer_width(W::WIDTH)
+        .set_dst_transfer_width(W::WIDTH)
+        .set_src_bst_size(BurstSize::INCR1)
+        .set_dst_bst_size(BurstSize::INCR1)
+        .set_transfer_size(len as u16);
+    let control = if src_inc {
+        control.enable_src_addr_inc()
+    } else {
+        control.disable_src_addr_inc()
+    };
+    let control = if dst_inc {
+        control.enable_dst_addr_inc()
+    } else {
+        control.disable_dst_addr_inc()
+    };
+    LliItemPool {
+        source_address: source,
+        destination_address: destination,
+        linked_list_item: 0,
+        control,
+    }
+}
+
+/// Chain `items` through `descriptors`, returning first item and address of the next.
+///
+/// Only the last item raises the completion interrupt.
+#[inline]
+fn link(
+    mut items: impl Iterator<Item = LliItemPool>,
+    count: usize,
+    descriptors: &mut [LliItemPool],
+) -> (LliItemPool, u32) {
+    if count == 0 || descriptors.len() + 1 < count {
+        panic!("DMA linked list needs one descriptor for every buffer but the first");
+    }
+    let mut first = items.next().unwrap();
+    let rest = &mut descriptors[..count - 1];
+    for (slot, item) in rest.iter_mut().zip(items) {
+        *slot = item;
+    }
+    for i in 1..rest.len() {
+        rest[i - 1].linked_list_item = &rest[i] as *const LliItemPool as u32;
+    }
+    let next = match rest.first() {
+        Some(item) => item as *const LliItemPool as u32,
+        None => 0,
+    };
+    match rest.last_mut() {
+        Some(last) => last.control = last.control.enable_cplt_int(),
+        None => first.control = first.control.enable_cplt_int(),
+    }
+    (first, next)
+}
+
+/// Buffers chained by linked list items, owned by a scatter-gather transfer.
+pub struct LinkedList<B> {
+    buffers: B,
+    descriptors: &'static mut [LliItemPool],
+}
+
+impl<B> LinkedList<B> {
+    /// Release the buffers and linked list item storage.
+    #[inline]
+    pub fn free(self) -> (B, &'static mut [LliItemPool]) {
+        (self.buffers, self.descriptors)
+    }
+}
+
+/// Continuous DMA reception into two alternating buffers.
+///
+/// Dropping the transfer halts its channel.
+pub struct CircularTransfer<DMA, const I: usize, const N: usize, W, const L: usize, T>
+where
+    DMA: Deref<Target = RegisterBlock>,
+    W: Word,
+    T: ReceiveTarget<Word = W>,
+{
+    channel: Channel<DMA, I, N>,
+    buffers: &'static mut [[W; L]; 2],
+    descriptors: &'static mut [LliItemPool; 2],
+    target: T,
+    next: usize,
+}
+
+impl<DMA, const I: usize, const N: usize, W, const L: usize, T> CircularTransfer<DMA, I, N, W, L, T>
+where
+    DMA: Deref<Target = RegisterBlock>,
+    W: Word,
+    T: ReceiveTarget<Word = W>,
+{
+    /// Get the buffer just filled, if DMA has moved on to the other one.
+    ///
+    /// The returned buffer must be consumed before DMA comes back to refill it; data
+    /// would be overwritten in place otherwise.
+    #[inline]
+    pub fn poll(&mut self) -> Option<&[W; L]> {
+        let writing = self.channel.registers().destination_address.read();
+        let other = &self.buffers[1 - self.next];
+        let start = other.as_ptr() as u32;
+        let end = start + (L * core::mem::size_of::<W>()) as u32;
+        if !(start..end).contains(&writing) {
+            return None;
+        }
+        // Do not let reads of the buffer move before DMA has finished it.
+        compiler_fence(Ordering::Acquire);
+        let done = self.next;
+        self.next = 1 - self.next;
+        Some(&self.buffers[done])
+    }
+    /// Stop reception, returning channel, buffers, linked list items and peripheral.
+    #[inline]
+    pub fn stop(
+        self,
+    ) -> (
+        Channel<DMA, I, N>,
+        &'static mut [[W; L]; 2],
+        &'static mut [LliItemPool; 2],
+        T,
+    ) {
+        let mut this = core::mem::ManuallyDrop::new(self);
+        this.channel.halt();
+        this.target.disable_receive_dma();
+        // SAFETY: `this` is never dropped, so every field is moved out exactly once.
+        unsafe {
+            (
+                core::ptr::read(&this.channel),
+                core::ptr::read(&this.buffers),
+                core::ptr::read(&this.descriptors),
+                core::ptr::read(&this.target),
+            )
+        }
+    }
+}
+
+impl<DMA, const I: usize, const N: usize, W, const L: usize, T> Drop
+    for CircularTransfer<DMA, I, N, W, L, T>
+where
+    DMA: Deref<Target = RegisterBlock>,
+    W: Word,
+    T: ReceiveTarget<Word = W>,
+{
+    #[inline]
+    fn drop(&mut self) {
+        self.channel.halt();
+        self.target.disable_receive_dma();
+    }
+}
+
+/// Ongoing DMA transfer owning its channel, buffer and peripheral.
+///
+/// Dropping an unfinished transfer halts the channel. The buffer is `'static`, so
+/// even a leaked transfer cannot let DMA access freed memory.
+pub struct Transfer<DMA: Deref<Target = RegisterBlock>, const I: usize, const N: usize, B, T> {
+    inner: Option<(Channel<DMA, I, N>, B, T)>,
+    release: fn(&mut T),
+}
+
+impl<DMA: Deref<Target = RegisterBlock>, const I: usize, const N: usize, B, T>
+    Transfer<DMA, I, N, B, T>
+{
+    #[inline]
+    fn new(channel: Channel<DMA, I, N>, buffer: B, target: T, release: fn(&mut T)) -> Self {
+        Self {
+            inner: Some((channel, buffer, target)),
+            release,
+        }
+    }
+    /// Check if the transfer has completed.
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        match &self.inner {
+            Some((channel, _, _)) => !channel.is_busy(),
+            None => true,
+        }
+    }
+    /// Check if the transfer was aborted by a bus error.
+    #[inline]
+    pub fn is_error(&self) -> bool {
+        match &self.inner {
+            Some((channel, _, _)) => channel
+                .dma
+                .interrupts
+                .error_state
+                .read()
+                .if_err_int_occurs(N as u8),
+            None => false,
+        }
+    }
+    /// Wait for transfer completion, returning channel, buffer and peripheral.
+    #[inline]
+    pub fn wait(mut self) -> (Channel<DMA, I, N>, B, T) {
+        while !self.is_done() {
+            core::hint::spin_loop();
+        }
+        // Do not let reads of the buffer move before completion.
+        compiler_fence(Ordering::Acquire);
+        let (channel, buffer, mut target) = self.inner.take().unwrap();
+        (self.release)(&mut target);
+        (channel, buffer, target)
+    }
+}
+
+impl<DMA: Deref<Target = RegisterBlock>, const I: usize, const N: usize, B, T> Drop
+    for Transfer<DMA, I, N, B, T>
+{
+    #[inline]
+    fn drop(&mut self) {
+        if let Some((channel, _, target)) = &mut self.inner {
+            channel.halt();
+            (self.release)(target);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Dma, MAX_TRANSFER_SIZE, ReceiveTarget, Request, TransmitTarget, item};
+    use crate::dma::{
+        DMAMode, LliItemPool, Periph4DMA01, Periph4DMA2, RegisterBlock, TransferWidth,
+    };
+    use crate::mock::MockRegisters;
+
+    struct MockTarget {
+        enabled: bool,
+    }
+
+    unsafe impl TransmitTarget for MockTarget {
+        type Word = u16;
+        const REQUEST: Request = Request::Dma01(Periph4DMA01::Spi0Tx);
+        fn transmit_address(&self) -> u32 {
+            0x2000_a888
+        }
+        fn enable_transmit_dma(&mut self) {
+            self.enabled = true;
+        }
+        fn disable_transmit_dma(&mut self) {
+            self.enabled = false;
+        }
+    }
+
+    #[test]
+    fn function_channel_transmit() {
+        extern crate std;
+        let mock = MockRegisters::<RegisterBlock>::new();
+        let channels = Dma::<_, 0>::new(mock.registers()).split();
+        assert!(mock.registers().global_config.read().is_smdma_enabled());
+        assert!(<MockTarget as TransmitTarget>::REQUEST.is_routed_to(1));
+        let spi1 = Request::Dma2(Periph4DMA2::Spi1Tx);
+        assert!(spi1.is_routed_to(2) && !spi1.is_routed_to(0) && !spi1.is_routed_to(1));
+
+        let buffer: &'static [u16] = std::boxed::Box::leak(std::vec![1u16; 100].into_boxed_slice());
+        let transfer = channels.ch2.transmit(buffer, MockTarget { enabled: false });
+        let channel = &mock.registers().channels[2];
+        assert_eq!(channel.source_address.read(), buffer.as_ptr() as u32);
+        assert_eq!(channel.destination_address.read(), 0x2000_a888);
+        assert_eq!(channel.linked_list_item.read(), 0);
+        let control = channel.control.read();
+        assert_eq!(control.transfer_size(), 100);
+        assert_eq!(control.src_transfer_width(), TransferWidth::HalfWord);
+        assert!(control.is_src_addr_inc_enabled());
+        assert!(!control.is_dst_addr_inc_enabled());
+        let config = channel.config.read();
+        assert_eq!(config.dma_mode(), DMAMode::Mem2Periph);
+        assert_eq!(config.dst_periph4dma01(), Periph4DMA01::Spi0Tx);
+        assert!(config.is_ch_enabled());
+        assert!(!transfer.is_done());
+        // Channel registers of other channels are untouched.
+        assert!(!mock.registers().channels[1].config.read().is_ch_enabled());
+
+        // Hardware disables channel on completion.
+        unsafe { channel.config.modify(|val| val.disable_ch()) };
+        assert!(transfer.is_done());
+        let (_, _, target) = transfer.wait();
+        assert!(!target.enabled);
+
+        // Dropping an unfinished transfer halts its channel.
+        let transfer = channels.ch3.transmit(buffer, MockTarget { enabled: false });
+        assert!(mock.registers().channels[3].config.read().is_ch_enabled());
+        drop(transfer);
+        assert!(!mock.registers().channels[3].config.read().is_ch_enabled());
+    }
+
+    #[test]
+    fn function_channel_transmit_gather() {
+        extern crate std;
+        let mock = MockRegisters::<RegisterBlock>::new();
+        let channels = Dma::<_, 0>::new(mock.registers()).split();
+        let buffers: &'static [&'static [u16]] = std::boxed::Box::leak(std::boxed::Box::new([
+            &[1u16; 3][..],
+            &[2u16; 4][..],
+            &[3u16; 5][..],
+        ]));
+        let descriptors = std::boxed::Box::leak(std::boxed::Box::new(
+            [(); 2].map(|_| item::<u16>(0, 0, 0, false, false)),
+        ));
+        let transfer =
+            channels
+                .ch0
+                .transmit_gather(buffers, descriptors, MockTarget { enabled: false });
+        let channel = &mock.registers().channels[0];
+        assert_eq!(channel.source_address.read(), buffers[0].as_ptr() as u32);
+        assert_eq!(channel.control.read().transfer_size(), 3);
+        assert!(!channel.control.read().is_cplt_int_enabled());
+        unsafe { channel.config.modify(|val| val.disable_ch()) };
+        let (_, _, target) = transfer.wait();
+        assert!(!target.enabled);
+    }
+
+    unsafe impl ReceiveTarget for MockTarget {
+        type Word = u16;
+        const REQUEST: Request = Request::Dma01(Periph4DMA01::PdmRx);
+        fn receive_address(&self) -> u32 {
+            0x2000_ac8c
+        }
+        fn enable_receive_dma(&mut self) {
+            self.enabled = true;
+        }
+        fn disable_receive_dma(&mut self) {
+            self.enabled = false;
+        }
+    }
+
+    #[test]
+    fn function_channel_receive_circular() {
+        extern crate std;
+        let mock = MockRegisters::<RegisterBlock>::new();
+        let channels = Dma::<_, 0>::new(mock.registers()).split();
+        let buffers = std::boxed::Box::leak(std::boxed::Box::new([[0u16; 8]; 2]));
+        let descriptors = std::boxed::Box::leak(std::boxed::Box::new(
+            [(); 2].map(|_| item::<u16>(0, 0, 0, false, false)),
+        ));
+        let first = buffers[0].as_ptr() as u32;
+        let second = buffers[1].as_ptr() as u32;
+        let mut transfer =
+            channels
+                .ch1
+                .receive_circular(buffers, descriptors, MockTarget { enabled: false });
+        let channel = &mock.registers().channels[1];
+        assert_eq!(channel.source_address.read(), 0x2000_ac8c);
+        assert_eq!(channel.destination_address.read(), first);
+        assert_eq!(channel.config.read().dma_mode(), DMAMode::Periph2Mem);
+        assert_eq!(
+            channel.config.read().src_periph4dma01(),
+            Periph4DMA01::PdmRx
+        );
+        assert!(transfer.poll().is_none());
+        // DMA moved on to the second buffer.
+        unsafe { channel.destination_address.write(second + 6) };
+        assert_eq!(transfer.poll().map(|b| b.as_ptr() as u32), Some(first));
+        assert!(transfer.poll().is_none());
+        // And back to the first.
+        unsafe { channel.destination_address.write(first) };
+        assert_eq!(transfer.poll().map(|b| b.as_ptr() as u32), Some(second));
+
+        let (_, _, descriptors, target) = transfer.stop();
+        assert!(!target.enabled);
+        assert!(!channel.config.read().is_ch_enabled());
+        assert_eq!(
+            descriptors[0].linked_list_item,
+            &descriptors[1] as *const LliItemPool as u32
+        );
+        assert_eq!(
+            descriptors[1].linked_list_item,
+            &descriptors[0] as *const LliItemPool as u32
+        );
+        assert_eq!(descriptors[1].destination_address, second);
+        assert!(descriptors[1].control.is_cplt_int_enabled());
+    }
+
+    #[test]
+    fn function_linked_list_items() {
+        extern crate std;
+        let descriptors: &mut [LliItemPool] = std::boxed::Box::leak(std::boxed::Box::new(
+            [(); 3].map(|_| item::<u8>(0, 0, 0, false, false)),
+        ));
+        let items =
+            (0..3).map(|i| item::<u32>(0x1000 * i, 0x2000_a888, 10 + i as usize, true, false));
+        let (first, next) = super::link(items, 3, descriptors);
+        assert_eq!(first.source_address, 0);
+        assert_eq!(first.control.transfer_size(), 10);
+        assert!(!first.control.is_cplt_int_enabled());
+        assert_eq!(next, &descriptors[0] as *const LliItemPool as u32);
+        assert_eq!(descriptors[0].source_address, 0x1000);
+        assert_eq!(
+            descriptors[0].linked_list_item,
+            &descriptors[1] as *const LliItemPool as u32
+        );
+        assert!(!descriptors[0].control.is_cplt_int_enabled());
+        assert_eq!(descriptors[1].source_address, 0x2000);
+        assert_eq!(descriptors[1].control.transfer_size(), 12);
+        assert_eq!(descriptors[1].linked_list_item, 0);
+        assert!(descriptors[1].control.is_cplt_int_enabled());
+        // Spare descriptors are left alone.
+        assert_eq!(descriptors[2].control.transfer_size(), 0);
+
+        // A single buffer needs no descriptors and completes on the first item.
+        let (first, next) = super::link(
+            core::iter::once(item::<u8>(0, 0, 1, true, false)),
+            1,
+            &mut [],
+        );
+        assert_eq!(next, 0);
+        assert!(first.control.is_cplt_int_enabled());
+    }
+
+    #[test]
+    #[should_panic(expected = "DMA buffer too long for one linked list item")]
+    fn function_item_too_long() {
+        item::<u8>(0, 0, MAX_TRANSFER_SIZE + 1, true, false);
+    }
+}
@@ -0,0 +1,185 @@
+//! Display pixel interface.
+
+use volatile_register::RW;
+
+/// Display pixel interface registers.
+#[repr(C)]
+pub struct RegisterBlock {
+    /// Function configuration register.
+    pub config: RW<Config>,
+    /// Horizontal active and sync width in pixel clocks.
+    pub horizontal_span: RW<Span>,
+    /// Horizontal front and back porch in pixel clocks.
+    pub horizontal_porch: RW<Porch>,
+    /// Vertical active and sync width in lines.
+    pub vertical_span: RW<Span>,
+    /// Vertical front and back porch in lines.
+    pub vertical_porch: RW<Porch>,
+    /// Start address of frame buffer.
+    pub frame_address: RW<u32>,
+    /// Pixel clock divider from source clock, minus one.
+    pub clock_divide: RW<u32>,
+}
+
+/// Function configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[repr(transparent)]
+pub struct Config(u32);
+
+/// Level of an active sync signal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Polarity {
+    /// Signal is active high.
+    ActiveHigh,
+    /// Signal is active low.
+    ActiveLow,
+}
+
+impl Config {
+    const ENABLE: u32 = 1 << 0;
+    const HSYNC_POLARITY: u32 = 1 << 1;
+    const VSYNC_POLARITY: u32 = 1 << 2;
+
+    /// Enable pixel output.
+    #[inline]
+    pub const fn enable(self) -> Self {
+        Self(self.0 | Self::ENABLE)
+    }
+    /// Disable pixel output.
+    #[inline]
+    pub const fn disable(self) -> Self {
+        Self(self.0 & !Self::ENABLE)
+    }
+    /// Check if pixel output is enabled.
+    #[inline]
+    pub const fn is_enabled(self) -> bool {
+        self.0 & Self::ENABLE != 0
+    }
+    /// Set horizontal sync polarity.
+    #[inline]
+    pub const fn set_hsync_polarity(self, polarity: Polarity) -> Self {
+        match polarity {
+            Polarity::ActiveHigh => Self(self.0 & !Self::HSYNC_POLARITY),
+            Polarity::ActiveLow => Self(self.0 | Self::HSYNC_POLARITY),
+        }
+    }
+    /// Get horizontal sync polarity.
+    #[inline]
+    pub const fn hsync_polarity(self) -> Polarity {
+        if self.0 & Self::HSYNC_POLARITY != 0 {
+            Polarity::ActiveLow
+        } else {
+            Polarity::ActiveHigh
+        }
+    }
+    /// Set vertical sync polarity.
+    #[inline]
+    pub const fn set_vsync_polarity(self, polarity: Polarity) -> Self {
+        match polarity {
+            Polarity::ActiveHigh => Self(self.0 & !Self::VSYNC_POLARITY),
+            Polarity::ActiveLow => Self(self.0 | Self::VSYNC_POLARITY),
+        }
+    }
+    /// Get vertical sync polarity.
+    #[inline]
+    pub const fn vsync_polarity(self) -> Polarity {
+        if self.0 & Self::VSYNC_POLARITY != 0 {
+            Polarity::ActiveLow
+        } else {
+            Polarity::ActiveHigh
+        }
+    }
+}
+
+/// Active and sync width register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[repr(transparent)]
+pub struct Span(u32);
+
+impl Span {
+    const ACTIVE: u32 = 0xffff;
+    const SYNC: u32 = 0xffff << 16;
+
+    /// Set active width.
+    #[inline]
+    pub const fn set_active(self, val: u16) -> Self {
+        Self(self.0 & !Self::ACTIVE | val as u32)
+    }
+    /// Get active width.
+    #[inline]
+    pub const fn active(self) -> u16 {
+        (self.0 & Self::ACTIVE) as u16
+    }
+    /// Set sync width.
+    #[inline]
+    pub const fn set_sync(self, val: u16) -> Self {
+        Self(self.0 & !Self::SYNC | ((val as u32) << 16))
+    }
+    /// Get sync width.
+    #[inline]
+    pub const fn sync(self) -> u16 {
+        ((self.0 & Self::SYNC) >> 16) as u16
+    }
+}
+
+/// Front and back porch register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[repr(transparent)]
+pub struct Porch(u32);
+
+impl Porch {
+    const FRONT: u32 = 0xffff;
+    const BACK: u32 = 0xffff << 16;
+
+    /// Set front porch.
+    #[inline]
+    pub const fn set_front(self, val: u16) -> Self {
+        Self(self.0 & !Self::FRONT | val as u32)
+    }
+    /// Get front porch.
+    #[inline]
+    pub const fn front(self) -> u16 {
+        (self.0 & Self::FRONT) as u16
+    }
+    /// Set back porch.
+    #[inline]
+    pub const fn set_back(self, val: u16) -> Self {
+        Self(self.0 & !Self::BACK | ((val as u32) << 16))
+    }
+    /// Get back porch.
+    #[inline]
+    pub const fn back(self) -> u16 {
+        ((self.0 & Self::BACK) >> 16) as u16
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Config, Polarity, Porch, RegisterBlock, Span};
+    use memoffset::offset_of;
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, config), 0x00);
+        assert_eq!(offset_of!(RegisterBlock, horizontal_span), 0x04);
+        assert_eq!(offset_of!(RegisterBlock, horizontal_porch), 0x08);
+        assert_eq!(offset_of!(RegisterBlock, vertical_span), 0x0c);
+        assert_eq!(offset_of!(RegisterBlock, vertical_porch), 0x10);
+        assert_eq!(offset_of!(RegisterBlock, frame_address), 0x14);
+        assert_eq!(offset_of!(RegisterBlock, clock_divide), 0x18);
+    }
+
+    #[test]
+    fn struct_timing_registers_functions() {
+        let config = Config(0).set_hsync_polarity(Polarity::ActiveLow).enable();
+        assert_eq!(config.0, 0x3);
+        assert_eq!(config.hsync_polarity(), Polarity::ActiveLow);
+        assert_eq!(config.vsync_polarity(), Polarity::ActiveHigh);
+        let span = Span(0).set_active(480).set_sync(4);
+        assert_eq!(span.0, 0x0004_01e0);
+        assert_eq!((span.active(), span.sync()), (480, 4));
+        let porch = Porch(0).set_front(8).set_back(43);
+        assert_eq!(porch.0, 0x002b_0008);
+        assert_eq!((porch.front(), porch.back()), (8, 43));
+    }
+}
@@ -1,4 +1,31 @@
 //! Ethernet Media Access Control peripheral.
+//!
+//! The [`Ethernet`] driver talks to an external physical layer device over the reduced
+//! media-independent interface. Frames are exchanged through rings of buffer descriptors:
+//! the first `TX` descriptors send frames out of [`Buffers`], and the next `RX` descriptors
+//! receive frames into them. Physical layer registers are accessed over the management
+//! data interface, which is used by [`Ethernet::link_status`] to poll the link.
+//!
+//! With the `smoltcp` feature enabled, [`Ethernet`] implements `smoltcp::phy::Device` and
+//! may be passed directly to a `smoltcp` network interface.
+//!
+//! Frame buffers are read and written by the peripheral directly. They must be placed in
+//! memory reachable by the peripheral and not held in the data cache of the core.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use bouffalo_hal::emac::{Buffers, Ethernet, Pads, RegisterBlock};
+//! # fn example(emac: &'static RegisterBlock, pads: impl Pads) {
+//! static mut BUFFERS: Buffers<4, 4> = Buffers::new();
+//! let buffers = unsafe { &mut *core::ptr::addr_of_mut!(BUFFERS) };
+//! let mut ethernet = Ethernet::new(emac, pads, [0x18, 0xb9, 0x05, 0x00, 0x00, 0x01], buffers);
+//! while ethernet.link_status(0).is_none() {}
+//! ethernet.receive(|frame| frame.len());
+//! # }
+//! ```
+use crate::gpio::{self, Alternate};
+use core::ops::Deref;
 use volatile_register::{RO, RW};
 
 /// Ethernet Media Access Control peripheral registers.
@@ -42,6 +69,21 @@ pub struct RegisterBlock {
     pub hash: [RW<Hash>; 2],
     /// Transmit control.
     pub transmit_control: RW<TransmitControl>,
+    _reserved2: [u8; 0x3ac],
+    /// Buffer descriptors; transmit descriptors come first, followed by receive ones.
+    pub descriptors: [BufferDescriptor; DESCRIPTOR_COUNT],
+}
+
+/// Total number of transmit and receive buffer descriptors.
+pub const DESCRIPTOR_COUNT: usize = 128;
+
+/// Buffer descriptor of a transmit or receive frame.
+#[repr(C)]
+pub struct BufferDescriptor {
+    /// Frame length, ownership and state.
+    pub control: RW<DescriptorControl>,
+    /// Address of frame buffer.
+    pub address: RW<u32>,
 }
 
 /// EMAC mode configuration register.
@@ -123,11 +165,978 @@ pub struct Hash(u32);
 #[repr(transparent)]
 pub struct TransmitControl(u32);
 
+impl Mode {
+    const RECEIVE: u32 = 1 << 0;
+    const TRANSMIT: u32 = 1 << 1;
+    const BROADCAST_REJECT: u32 = 1 << 3;
+    const PROMISCUOUS: u32 = 1 << 5;
+    const FULL_DUPLEX: u32 = 1 << 10;
+    const CRC: u32 = 1 << 13;
+    const HUGE_FRAMES: u32 = 1 << 14;
+    const PAD: u32 = 1 << 15;
+    const RMII: u32 = 1 << 17;
+
+    /// Enable frame reception.
+    #[inline]
+    pub const fn enable_receive(self) -> Self {
+        Self(self.0 | Self::RECEIVE)
+    }
+    /// Disable frame reception.
+    #[inline]
+    pub const fn disable_receive(self) -> Self {
+        Self(self.0 & !Self::RECEIVE)
+    }
+    /// Check if frame reception is enabled.
+    #[inline]
+    pub const fn is_receive_enabled(self) -> bool {
+        self.0 & Self::RECEIVE != 0
+    }
+    /// Enable frame transmission.
+    #[inline]
+    pub const fn enable_transmit(self) -> Self {
+        Self(self.0 | Self::TRANSMIT)
+    }
+    /// Disable frame transmission.
+    #[inline]
+    pub const fn disable_transmit(self) -> Self {
+        Self(self.0 & !Self::TRANSMIT)
+    }
+    /// Check if frame transmission is enabled.
+    #[inline]
+    pub const fn is_transmit_enabled(self) -> bool {
+        self.0 & Self::TRANSMIT != 0
+    }
+    /// Accept broadcast frames.
+    #[inline]
+    pub const fn accept_broadcast(self) -> Self {
+        Self(self.0 & !Self::BROADCAST_REJECT)
+    }
+    /// Reject broadcast frames.
+    #[inline]
+    pub const fn reject_broadcast(self) -> Self {
+        Self(self.0 | Self::BROADCAST_REJECT)
+    }
+    /// Check if broadcast frames are accepted.
+    #[inline]
+    pub const fn is_broadcast_accepted(self) -> bool {
+        self.0 & Self::BROADCAST_REJECT == 0
+    }
+    /// Enable promiscuous mode, receiving frames of all addresses.
+    #[inline]
+    pub const fn enable_promiscuous(self) -> Self {
+        Self(self.0 | Self::PROMISCUOUS)
+    }
+    /// Disable promiscuous mode.
+    #[inline]
+    pub const fn disable_promiscuous(self) -> Self {
+        Self(self.0 & !Self::PROMISCUOUS)
+    }
+    /// Check if promiscuous mode is enabled.
+    #[inline]
+    pub const fn is_promiscuous_enabled(self) -> bool {
+        self.0 & Self::PROMISCUOUS != 0
+    }
+    /// Set full or half duplex.
+    #[inline]
+    pub const fn set_full_duplex(self, full: bool) -> Self {
+        if full {
+            Self(self.0 | Self::FULL_DUPLEX)
+        } else {
+            Self(self.0 & !Self::FULL_DUPLEX)
+        }
+    }
+    /// Check if full duplex is configured.
+    #[inline]
+    pub const fn is_full_duplex(self) -> bool {
+        self.0 & Self::FULL_DUPLEX != 0
+    }
+    /// Append frame check sequence to transmitted frames.
+    #[inline]
+    pub const fn enable_crc(self) -> Self {
+        Self(self.0 | Self::CRC)
+    }
+    /// Do not append frame check sequence.
+    #[inline]
+    pub const fn disable_crc(self) -> Self {
+        Self(self.0 & !Self::CRC)
+    }
+    /// Check if frame check sequence is appended.
+    #[inline]
+    pub const fn is_crc_enabled(self) -> bool {
+        self.0 & Self::CRC != 0
+    }
+    /// Receive frames longer than maximum frame length.
+    #[inline]
+    pub const fn enable_huge_frames(self) -> Self {
+        Self(self.0 | Self::HUGE_FRAMES)
+    }
+    /// Truncate frames longer than maximum frame length.
+    #[inline]
+    pub const fn disable_huge_frames(self) -> Self {
+        Self(self.0 & !Self::HUGE_FRAMES)
+    }
+    /// Check if frames longer than maximum frame length are received.
+    #[inline]
+    pub const fn is_huge_frames_enabled(self) -> bool {
+        self.0 & Self::HUGE_FRAMES != 0
+    }
+    /// Pad short transmitted frames to minimum frame length.
+    #[inline]
+    pub const fn enable_pad(self) -> Self {
+        Self(self.0 | Self::PAD)
+    }
+    /// Do not pad short transmitted frames.
+    #[inline]
+    pub const fn disable_pad(self) -> Self {
+        Self(self.0 & !Self::PAD)
+    }
+    /// Check if short transmitted frames are padded.
+    #[inline]
+    pub const fn is_pad_enabled(self) -> bool {
+        self.0 & Self::PAD != 0
+    }
+    /// Use reduced media-independent interface.
+    #[inline]
+    pub const fn enable_rmii(self) -> Self {
+        Self(self.0 | Self::RMII)
+    }
+    /// Use media-independent interface.
+    #[inline]
+    pub const fn disable_rmii(self) -> Self {
+        Self(self.0 & !Self::RMII)
+    }
+    /// Check if reduced media-independent interface is used.
+    #[inline]
+    pub const fn is_rmii_enabled(self) -> bool {
+        self.0 & Self::RMII != 0
+    }
+}
+
+/// Interrupt event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Interrupt {
+    /// Frame transmitted.
+    TransmitBuffer = 0,
+    /// Frame transmission failed.
+    TransmitError = 1,
+    /// Frame received.
+    ReceiveFrame = 2,
+    /// Frame reception failed.
+    ReceiveError = 3,
+    /// Frame dropped for lack of empty receive buffer.
+    Busy = 4,
+}
+
+impl InterruptSource {
+    /// Check if an interrupt event is pending.
+    #[inline]
+    pub const fn has_interrupt(self, val: Interrupt) -> bool {
+        self.0 & (1 << val as u32) != 0
+    }
+    /// Clear an interrupt event by writing one.
+    #[inline]
+    pub const fn clear_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 | (1 << val as u32))
+    }
+}
+
+impl InterruptMask {
+    /// Mask an interrupt event.
+    #[inline]
+    pub const fn mask_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 & !(1 << val as u32))
+    }
+    /// Unmask an interrupt event.
+    #[inline]
+    pub const fn unmask_interrupt(self, val: Interrupt) -> Self {
+        Self(self.0 | (1 << val as u32))
+    }
+    /// Check if an interrupt event is masked.
+    #[inline]
+    pub const fn is_interrupt_masked(self, val: Interrupt) -> bool {
+        self.0 & (1 << val as u32) == 0
+    }
+}
+
+impl FrameLength {
+    const MAXIMUM: u32 = 0xffff;
+    const MINIMUM: u32 = 0xffff << 16;
+
+    /// Set maximum frame length in bytes.
+    #[inline]
+    pub const fn set_maximum(self, val: u16) -> Self {
+        Self((self.0 & !Self::MAXIMUM) | val as u32)
+    }
+    /// Get maximum frame length in bytes.
+    #[inline]
+    pub const fn maximum(self) -> u16 {
+        (self.0 & Self::MAXIMUM) as u16
+    }
+    /// Set minimum frame length in bytes.
+    #[inline]
+    pub const fn set_minimum(self, val: u16) -> Self {
+        Self((self.0 & !Self::MINIMUM) | ((val as u32) << 16))
+    }
+    /// Get minimum frame length in bytes.
+    #[inline]
+    pub const fn minimum(self) -> u16 {
+        ((self.0 & Self::MINIMUM) >> 16) as u16
+    }
+}
+
+impl TransmitBuffer {
+    /// Set number of transmit buffer descriptors.
+    #[inline]
+    pub const fn set_count(self, val: u8) -> Self {
+        Self((self.0 & !0xff) | val as u32)
+    }
+    /// Get number of transmit buffer descriptors.
+    #[inline]
+    pub const fn count(self) -> u8 {
+        (self.0 & 0xff) as u8
+    }
+}
+
+impl MiiMode {
+    const CLOCK_DIVIDE: u32 = 0xff;
+    const NO_PREAMBLE: u32 = 1 << 8;
+
+    /// Set management data clock divide factor.
+    #[inline]
+    pub const fn set_clock_divide(self, val: u8) -> Self {
+        Self((self.0 & !Self::CLOCK_DIVIDE) | val as u32)
+    }
+    /// Get management data clock divide factor.
+    #[inline]
+    pub const fn clock_divide(self) -> u8 {
+        (self.0 & Self::CLOCK_DIVIDE) as u8
+    }
+    /// Send preamble before management frames.
+    #[inline]
+    pub const fn enable_preamble(self) -> Self {
+        Self(self.0 & !Self::NO_PREAMBLE)
+    }
+    /// Do not send preamble before management frames.
+    #[inline]
+    pub const fn disable_preamble(self) -> Self {
+        Self(self.0 | Self::NO_PREAMBLE)
+    }
+}
+
+impl MiiCommand {
+    const READ: u32 = 1 << 1;
+    const WRITE: u32 = 1 << 2;
+
+    /// Start a management register read.
+    #[inline]
+    pub const fn read_status(self) -> Self {
+        Self((self.0 & !Self::WRITE) | Self::READ)
+    }
+    /// Start a management register write.
+    #[inline]
+    pub const fn write_control(self) -> Self {
+        Self((self.0 & !Self::READ) | Self::WRITE)
+    }
+}
+
+impl MiiAddress {
+    /// Set physical layer device address.
+    #[inline]
+    pub const fn set_phy(self, val: u8) -> Self {
+        Self((self.0 & !0x1f) | (val as u32 & 0x1f))
+    }
+    /// Get physical layer device address.
+    #[inline]
+    pub const fn phy(self) -> u8 {
+        (self.0 & 0x1f) as u8
+    }
+    /// Set physical layer register address.
+    #[inline]
+    pub const fn set_register(self, val: u8) -> Self {
+        Self((self.0 & !(0x1f << 8)) | ((val as u32 & 0x1f) << 8))
+    }
+    /// Get physical layer register address.
+    #[inline]
+    pub const fn register(self) -> u8 {
+        ((self.0 >> 8) & 0x1f) as u8
+    }
+}
+
+impl ControlWrite {
+    /// Set data to write into a physical layer register.
+    #[inline]
+    pub const fn set_data(self, val: u16) -> Self {
+        Self((self.0 & !0xffff) | val as u32)
+    }
+}
+
+impl ControlRead {
+    /// Get data read from a physical layer register.
+    #[inline]
+    pub const fn data(self) -> u16 {
+        (self.0 & 0xffff) as u16
+    }
+}
+
+impl MiiState {
+    const BUSY: u32 = 1 << 1;
+
+    /// Check if a management operation is ongoing.
+    #[inline]
+    pub const fn is_busy(self) -> bool {
+        self.0 & Self::BUSY != 0
+    }
+}
+
+impl MacAddress {
+    /// Register values holding bytes 2 to 5 and bytes 0 to 1 of `address`.
+    #[inline]
+    pub const fn from_bytes(address: [u8; 6]) -> [Self; 2] {
+        [
+            Self(u32::from_be_bytes([
+                address[2], address[3], address[4], address[5],
+            ])),
+            Self(u32::from_be_bytes([0, 0, address[0], address[1]])),
+        ]
+    }
+}
+
+/// Buffer descriptor control word.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct DescriptorControl(u32);
+
+impl DescriptorControl {
+    const LENGTH: u32 = 0xffff << 16;
+    const OWNED: u32 = 1 << 15;
+    const INTERRUPT: u32 = 1 << 14;
+    const WRAP: u32 = 1 << 13;
+    const TRANSMIT_PAD: u32 = 1 << 12;
+    const TRANSMIT_CRC: u32 = 1 << 11;
+    const TRANSMIT_ERRORS: u32 = (1 << 8) | (1 << 3) | (1 << 2) | (1 << 1) | (1 << 0);
+    const RECEIVE_TOO_LONG: u32 = 1 << 3;
+    const RECEIVE_ERRORS: u32 = (1 << 6) | (1 << 5) | (1 << 4) | (1 << 2) | (1 << 1) | (1 << 0);
+
+    /// Descriptor owned by software without any frame.
+    #[inline]
+    pub const fn new_idle(wrap: bool) -> Self {
+        if wrap { Self(Self::WRAP) } else { Self(0) }
+    }
+    /// Receive descriptor waiting for a frame.
+    #[inline]
+    pub const fn new_owned(wrap: bool) -> Self {
+        let val = Self(Self::OWNED | Self::INTERRUPT);
+        if wrap { Self(val.0 | Self::WRAP) } else { val }
+    }
+    /// Transmit descriptor ready to send `len` bytes with padding and frame check sequence.
+    #[inline]
+    pub const fn new_transmit(len: u16, wrap: bool) -> Self {
+        Self(
+            Self::new_owned(wrap).0
+                | ((len as u32) << 16)
+                | Self::TRANSMIT_PAD
+                | Self::TRANSMIT_CRC,
+        )
+    }
+    /// Frame length in bytes.
+    #[inline]
+    pub const fn length(self) -> u16 {
+        ((self.0 & Self::LENGTH) >> 16) as u16
+    }
+    /// Check if the descriptor is owned by the peripheral.
+    #[inline]
+    pub const fn is_owned(self) -> bool {
+        self.0 & Self::OWNED != 0
+    }
+    /// Check if this is the last descriptor of its ring.
+    #[inline]
+    pub const fn is_wrap(self) -> bool {
+        self.0 & Self::WRAP != 0
+    }
+    /// Check if a transmitted frame failed.
+    #[inline]
+    pub const fn is_transmit_error(self) -> bool {
+        self.0 & Self::TRANSMIT_ERRORS != 0
+    }
+    /// Check if a received frame is longer than maximum frame length.
+    #[inline]
+    pub const fn is_too_long(self) -> bool {
+        self.0 & Self::RECEIVE_TOO_LONG != 0
+    }
+    /// Check if a received frame has errors other than length.
+    #[inline]
+    pub const fn is_receive_error(self) -> bool {
+        self.0 & Self::RECEIVE_ERRORS != 0
+    }
+}
+
+/// Size of each frame buffer in bytes.
+pub const BUFFER_SIZE: usize = 1536;
+
+/// Largest frame to transmit in bytes, excluding frame check sequence.
+pub const MAX_FRAME_SIZE: usize = 1514;
+
+/// Shortest frame on the wire in bytes, including frame check sequence.
+const MIN_FRAME_SIZE: u16 = 64;
+
+/// Length of frame check sequence at the end of each received frame.
+const CRC_SIZE: usize = 4;
+
+/// Management data clock divide factor, keeping the clock below 2.5 MHz.
+const MII_CLOCK_DIVIDE: u8 = 64;
+
+/// Physical layer basic control register.
+const PHY_CONTROL: u8 = 0;
+/// Physical layer basic status register.
+const PHY_STATUS: u8 = 1;
+/// Physical layer auto-negotiation advertisement register.
+const PHY_ADVERTISEMENT: u8 = 4;
+/// Physical layer auto-negotiation link partner ability register.
+const PHY_PARTNER: u8 = 5;
+
+/// Transmit and receive frame buffers.
+#[repr(C, align(4))]
+pub struct Buffers<const TX: usize, const RX: usize> {
+    transmit: [[u8; BUFFER_SIZE]; TX],
+    receive: [[u8; BUFFER_SIZE]; RX],
+}
+
+impl<const TX: usize, const RX: usize> Buffers<TX, RX> {
+    /// Create zeroed frame buffers.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            transmit: [[0; BUFFER_SIZE]; TX],
+            receive: [[0; BUFFER_SIZE]; RX],
+        }
+    }
+}
+
+impl<const TX: usize, const RX: usize> Default for Buffers<TX, RX> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Ethernet transmission error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// Frame is longer than [`MAX_FRAME_SIZE`].
+    FrameTooLong,
+}
+
+/// Frame counters kept by the driver.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Statistics {
+    /// Received frames dropped for being longer than a frame buffer.
+    pub receive_oversize: u32,
+    /// Received frames dropped for other reception errors.
+    pub receive_errors: u32,
+    /// Frames failed to transmit, or dropped for being longer than [`MAX_FRAME_SIZE`].
+    pub transmit_errors: u32,
+}
+
+/// Link speed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Speed {
+    /// 10 Mbit/s.
+    Mbps10,
+    /// 100 Mbit/s.
+    Mbps100,
+}
+
+/// Link duplex mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Duplex {
+    /// Half duplex.
+    Half,
+    /// Full duplex.
+    Full,
+}
+
+/// State of an established link.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Link {
+    /// Link speed.
+    pub speed: Speed,
+    /// Link duplex mode.
+    pub duplex: Duplex,
+}
+
+/// Managed Ethernet Media Access Control peripheral.
+///
+/// `TX` and `RX` are the numbers of transmit and receive buffer descriptors.
+pub struct Ethernet<EMAC, PADS, const TX: usize, const RX: usize> {
+    emac: EMAC,
+    pads: PADS,
+    buffers: &'static mut Buffers<TX, RX>,
+    transmit_next: usize,
+    receive_next: usize,
+    statistics: Statistics,
+}
+
+impl<EMAC: Deref<Target = RegisterBlock>, PADS, const TX: usize, const RX: usize>
+    Ethernet<EMAC, PADS, TX, RX>
+{
+    /// Create an Ethernet instance with given MAC address and frame buffers.
+    ///
+    /// Frames longer than a frame buffer are truncated by the peripheral, and dropped on
+    /// reception.
+    #[inline]
+    pub fn new(
+        emac: EMAC,
+        pads: PADS,
+        mac_address: [u8; 6],
+        buffers: &'static mut Buffers<TX, RX>,
+    ) -> Self
+    where
+        PADS: Pads,
+    {
+        const {
+            assert!(
+                TX > 0 && RX > 0 && TX + RX <= DESCRIPTOR_COUNT,
+                "invalid number of Ethernet buffer descriptors"
+            )
+        };
+        let [address_0, address_1] = MacAddress::from_bytes(mac_address);
+        unsafe {
+            emac.mode.modify(|v| v.disable_receive().disable_transmit());
+            emac.interrupt_mask.write(InterruptMask(0));
+            emac.interrupt_source.write(InterruptSource(0x7f));
+            emac.mac_address[0].write(address_0);
+            emac.mac_address[1].write(address_1);
+            emac.frame_length.write(
+                FrameLength(0)
+                    .set_minimum(MIN_FRAME_SIZE)
+                    .set_maximum(BUFFER_SIZE as u16),
+            );
+            emac.transmit_buffer
+                .write(TransmitBuffer(0).set_count(TX as u8));
+            emac.mii_mode.write(
+                MiiMode(0)
+                    .set_clock_divide(MII_CLOCK_DIVIDE)
+                    .enable_preamble(),
+            );
+            for (i, buffer) in buffers.transmit.iter().enumerate() {
+                let descriptor = &emac.descriptors[i];
+                descriptor.address.write(buffer.as_ptr() as u32);
+                descriptor
+                    .control
+                    .write(DescriptorControl::new_idle(i == TX - 1));
+            }
+            for (i, buffer) in buffers.receive.iter().enumerate() {
+                let descriptor = &emac.descriptors[TX + i];
+                descriptor.address.write(buffer.as_ptr() as u32);
+                descriptor
+                    .control
+                    .write(DescriptorControl::new_owned(i == RX - 1));
+            }
+            emac.mode.modify(|v| {
+                v.enable_rmii()
+                    .enable_crc()
+                    .enable_pad()
+                    .disable_huge_frames()
+                    .accept_broadcast()
+                    .disable_promiscuous()
+                    .set_full_duplex(true)
+                    .enable_receive()
+                    .enable_transmit()
+            });
+        }
+        Self {
+            emac,
+            pads,
+            buffers,
+            transmit_next: 0,
+            receive_next: 0,
+            statistics: Statistics::default(),
+        }
+    }
+    /// Process the next received frame, if any.
+    ///
+    /// The frame is passed to `f` without its frame check sequence. Frames with reception
+    /// errors are dropped and counted in [`statistics`](Self::statistics).
+    #[inline]
+    pub fn receive<R>(&mut self, f: impl FnOnce(&[u8]) -> R) -> Option<R> {
+        let emac = &*self.emac;
+        let len = next_frame(emac, TX, RX, &mut self.receive_next, &mut self.statistics)?;
+        let ans = f(&self.buffers.receive[self.receive_next][..len]);
+        release_frame(emac, TX, RX, &mut self.receive_next);
+        Some(ans)
+    }
+    /// Transmit a frame of `len` bytes filled in by `f`.
+    ///
+    /// Frame check sequence is appended and short frames are padded by the peripheral.
+    /// Returns `WouldBlock` if no transmit buffer is free, or [`Error::FrameTooLong`]
+    /// without calling `f` if `len` is larger than [`MAX_FRAME_SIZE`].
+    #[inline]
+    pub fn transmit<R>(
+        &mut self,
+        len: usize,
+        f: impl FnOnce(&mut [u8]) -> R,
+    ) -> nb::Result<R, Error> {
+        if len > MAX_FRAME_SIZE {
+            return Err(nb::Error::Other(Error::FrameTooLong));
+        }
+        let emac = &*self.emac;
+        if !transmit_ready(emac, self.transmit_next, &mut self.statistics) {
+            return Err(nb::Error::WouldBlock);
+        }
+        let ans = f(&mut self.buffers.transmit[self.transmit_next][..len]);
+        start_transmit(emac, TX, &mut self.transmit_next, len);
+        Ok(ans)
+    }
+    /// Frame counters since this instance is created.
+    #[inline]
+    pub fn statistics(&self) -> Statistics {
+        self.statistics
+    }
+    /// Read a physical layer register over management data interface.
+    #[inline]
+    pub fn read_phy(&mut self, phy: u8, register: u8) -> u16 {
+        self.select_phy_register(phy, register);
+        unsafe { self.emac.mii_command.write(MiiCommand(0).read_status()) };
+        while self.emac.mii_state.read().is_busy() {
+            core::hint::spin_loop();
+        }
+        self.emac.control_read.read().data()
+    }
+    /// Write a physical layer register over management data interface.
+    #[inline]
+    pub fn write_phy(&mut self, phy: u8, register: u8, value: u16) {
+        self.select_phy_register(phy, register);
+        unsafe {
+            self.emac
+                .control_write
+                .write(ControlWrite(0).set_data(value));
+            self.emac.mii_command.write(MiiCommand(0).write_control());
+        }
+        while self.emac.mii_state.read().is_busy() {
+            core::hint::spin_loop();
+        }
+    }
+    /// Poll link state of the physical layer device at address `phy`.
+    ///
+    /// Returns `None` if the link is down. Duplex mode of the peripheral is updated to
+    /// match an established link.
+    #[inline]
+    pub fn link_status(&mut self, phy: u8) -> Option<Link> {
+        // Link status bit latches low; the first read returns any past link failure.
+        self.read_phy(phy, PHY_STATUS);
+        if self.read_phy(phy, PHY_STATUS) & (1 << 2) == 0 {
+            return None;
+        }
+        let control = self.read_phy(phy, PHY_CONTROL);
+        let link = if control & (1 << 12) != 0 {
+            let abilities = self.read_phy(phy, PHY_ADVERTISEMENT) & self.read_phy(phy, PHY_PARTNER);
+            match abilities {
+                a if a & (1 << 8) != 0 => Link {
+                    speed: Speed::Mbps100,
+                    duplex: Duplex::Full,
+                },
+                a if a & (1 << 7) != 0 => Link {
+                    speed: Speed::Mbps100,
+                    duplex: Duplex::Half,
+                },
+                a if a & (1 << 6) != 0 => Link {
+                    speed: Speed::Mbps10,
+                    duplex: Duplex::Full,
+                },
+                _ => Link {
+                    speed: Speed::Mbps10,
+                    duplex: Duplex::Half,
+                },
+            }
+        } else {
+            Link {
+                speed: if control & (1 << 13) != 0 {
+                    Speed::Mbps100
+                } else {
+                    Speed::Mbps10
+                },
+                duplex: if control & (1 << 8) != 0 {
+                    Duplex::Full
+                } else {
+                    Duplex::Half
+                },
+            }
+        };
+        unsafe {
+            self.emac
+                .mode
+                .modify(|v| v.set_full_duplex(link.duplex == Duplex::Full))
+        };
+        Some(link)
+    }
+    /// Release the Ethernet instance and return its peripheral, pads and buffers.
+    #[inline]
+    pub fn free(self) -> (EMAC, PADS, &'static mut Buffers<TX, RX>) {
+        unsafe {
+            self.emac
+                .mode
+                .modify(|v| v.disable_receive().disable_transmit())
+        };
+        (self.emac, self.pads, self.buffers)
+    }
+    #[inline]
+    fn select_phy_register(&mut self, phy: u8, register: u8) {
+        while self.emac.mii_state.read().is_busy() {
+            core::hint::spin_loop();
+        }
+        unsafe {
+            self.emac
+                .mii_address
+                .write(MiiAddress(0).set_phy(phy).set_register(register))
+        };
+    }
+}
+
+/// Length of the next good frame from receive descriptor `next` on, dropping frames with
+/// errors on the way.
+#[inline]
+fn next_frame(
+    emac: &RegisterBlock,
+    tx: usize,
+    rx: usize,
+    next: &mut usize,
+    statistics: &mut Statistics,
+) -> Option<usize> {
+    loop {
+        let control = emac.descriptors[tx + *next].control.read();
+        if control.is_owned() {
+            return None;
+        }
+        let len = control.length() as usize;
+        if control.is_too_long() || len > BUFFER_SIZE {
+            statistics.receive_oversize += 1;
+        } else if control.is_receive_error() || len < CRC_SIZE {
+            statistics.receive_errors += 1;
+        } else {
+            return Some(len - CRC_SIZE);
+        }
+        // Re-arm the descriptor in place, so the ring keeps its order.
+        release_frame(emac, tx, rx, next);
+    }
+}
+
+/// Give receive descriptor `next` back to the peripheral and advance to the next one.
+#[inline]
+fn release_frame(emac: &RegisterBlock, tx: usize, rx: usize, next: &mut usize) {
+    unsafe {
+        emac.descriptors[tx + *next]
+            .control
+            .write(DescriptorControl::new_owned(*next == rx - 1))
+    };
+    *next = (*next + 1) % rx;
+}
+
+/// Check if transmit descriptor `next` is free, counting its last frame if it failed.
+#[inline]
+fn transmit_ready(emac: &RegisterBlock, next: usize, statistics: &mut Statistics) -> bool {
+    let descriptor = &emac.descriptors[next];
+    let control = descriptor.control.read();
+    if control.is_owned() {
+        return false;
+    }
+    if control.is_transmit_error() {
+        statistics.transmit_errors += 1;
+        unsafe {
+            descriptor
+                .control
+                .write(DescriptorControl::new_idle(control.is_wrap()))
+        };
+    }
+    true
+}
+
+/// Hand transmit descriptor `next` holding `len` bytes to the peripheral.
+#[inline]
+fn start_transmit(emac: &RegisterBlock, tx: usize, next: &mut usize, len: usize) {
+    unsafe {
+        emac.descriptors[*next]
+            .control
+            .write(DescriptorControl::new_transmit(len as u16, *next == tx - 1))
+    };
+    *next = (*next + 1) % tx;
+}
+
+#[cfg(feature = "smoltcp")]
+impl<EMAC: Deref<Target = RegisterBlock>, PADS, const TX: usize, const RX: usize>
+    smoltcp::phy::Device for Ethernet<EMAC, PADS, TX, RX>
+{
+    type RxToken<'a>
+        = ReceiveToken<'a>
+    where
+        Self: 'a;
+    type TxToken<'a>
+        = TransmitToken<'a>
+    where
+        Self: 'a;
+
+    #[inline]
+    fn receive(
+        &mut self,
+        _timestamp: smoltcp::time::Instant,
+    ) -> Option<(ReceiveToken<'_>, TransmitToken<'_>)> {
+        let emac = &*self.emac;
+        let len = next_frame(emac, TX, RX, &mut self.receive_next, &mut self.statistics)?;
+        if !transmit_ready(emac, self.transmit_next, &mut self.statistics) {
+            return None;
+        }
+        let Buffers { transmit, receive } = &mut *self.buffers;
+        let receive = ReceiveToken {
+            emac,
+            frame: &receive[self.receive_next][..len],
+            transmit_count: TX,
+            receive_count: RX,
+            next: &mut self.receive_next,
+        };
+        let transmit = TransmitToken {
+            emac,
+            buffer: &mut transmit[self.transmit_next],
+            transmit_count: TX,
+            next: &mut self.transmit_next,
+            statistics: &mut self.statistics,
+        };
+        Some((receive, transmit))
+    }
+    #[inline]
+    fn transmit(&mut self, _timestamp: smoltcp::time::Instant) -> Option<TransmitToken<'_>> {
+        let emac = &*self.emac;
+        if !transmit_ready(emac, self.transmit_next, &mut self.statistics) {
+            return None;
+        }
+        Some(TransmitToken {
+            emac,
+            buffer: &mut self.buffers.transmit[self.transmit_next],
+            transmit_count: TX,
+            next: &mut self.transmit_next,
+            statistics: &mut self.statistics,
+        })
+    }
+    #[inline]
+    fn capabilities(&self) -> smoltcp::phy::DeviceCapabilities {
+        let mut capabilities = smoltcp::phy::DeviceCapabilities::default();
+        capabilities.medium = smoltcp::phy::Medium::Ethernet;
+        capabilities.max_transmission_unit = MAX_FRAME_SIZE;
+        capabilities.max_burst_size = Some(if TX < RX { TX } else { RX });
+        capabilities
+    }
+}
+
+/// Received frame of an [`Ethernet`] device.
+#[cfg(feature = "smoltcp")]
+pub struct ReceiveToken<'a> {
+    emac: &'a RegisterBlock,
+    frame: &'a [u8],
+    transmit_count: usize,
+    receive_count: usize,
+    next: &'a mut usize,
+}
+
+#[cfg(feature = "smoltcp")]
+impl smoltcp::phy::RxToken for ReceiveToken<'_> {
+    #[inline]
+    fn consume<R, F>(self, f: F) -> R
+    where
+        F: FnOnce(&[u8]) -> R,
+    {
+        let ans = f(self.frame);
+        release_frame(
+            self.emac,
+            self.transmit_count,
+            self.receive_count,
+            self.next,
+        );
+        ans
+    }
+}
+
+/// Free transmit buffer of an [`Ethernet`] device.
+///
+/// Frames longer than [`MAX_FRAME_SIZE`] are dropped and counted as transmit errors.
+#[cfg(feature = "smoltcp")]
+pub struct TransmitToken<'a> {
+    emac: &'a RegisterBlock,
+    buffer: &'a mut [u8; BUFFER_SIZE],
+    transmit_count: usize,
+    next: &'a mut usize,
+    statistics: &'a mut Statistics,
+}
+
+#[cfg(feature = "smoltcp")]
+impl smoltcp::phy::TxToken for TransmitToken<'_> {
+    #[inline]
+    fn consume<R, F>(self, len: usize, f: F) -> R
+    where
+        F: FnOnce(&mut [u8]) -> R,
+    {
+        if len > MAX_FRAME_SIZE {
+            // Let `f` fill in what fits, but never send a frame the peripheral would cut.
+            self.statistics.transmit_errors += 1;
+            return f(&mut self.buffer[..len.min(BUFFER_SIZE)]);
+        }
+        let ans = f(&mut self.buffer[..len]);
+        start_transmit(self.emac, self.transmit_count, self.next, len);
+        ans
+    }
+}
+
+/// Valid reduced media-independent interface pads.
+#[diagnostic::on_unimplemented(
+    message = "the Ethernet interface needs reference clock, transmit enable, two transmit data, \
+               carrier sense, two receive data, management clock and management data pads in EMAC mode"
+)]
+pub trait Pads {}
+
+/// Reference clock, transmit enable, transmit data 0 and 1, carrier sense and receive data
+/// valid, receive data 0 and 1, management data clock and management data.
+impl<
+    'a,
+    const N1: usize,
+    const N2: usize,
+    const N3: usize,
+    const N4: usize,
+    const N5: usize,
+    const N6: usize,
+    const N7: usize,
+    const N8: usize,
+    const N9: usize,
+> Pads
+    for (
+        Alternate<'a, N1, gpio::Emac>,
+        Alternate<'a, N2, gpio::Emac>,
+        Alternate<'a, N3, gpio::Emac>,
+        Alternate<'a, N4, gpio::Emac>,
+        Alternate<'a, N5, gpio::Emac>,
+        Alternate<'a, N6, gpio::Emac>,
+        Alternate<'a, N7, gpio::Emac>,
+        Alternate<'a, N8, gpio::Emac>,
+        Alternate<'a, N9, gpio::Emac>,
+    )
+{
+}
+
 #[cfg(test)]
 mod tests {
-    use super::RegisterBlock;
+    use super::{
+        BUFFER_SIZE, Buffers, DescriptorControl, Duplex, Error, Ethernet, Link, MAX_FRAME_SIZE,
+        Pads, RegisterBlock, Speed, Statistics,
+    };
+    use crate::mock::MockRegisters;
     use memoffset::offset_of;
 
+    struct MockPads;
+
+    impl Pads for MockPads {}
+
+    fn buffers() -> &'static mut Buffers<2, 2> {
+        extern crate std;
+        std::boxed::Box::leak(std::boxed::Box::new(Buffers::new()))
+    }
+
     #[test]
     fn struct_register_block_offset() {
         assert_eq!(offset_of!(RegisterBlock, mode), 0x00);
@@ -146,5 +1155,122 @@ mod tests {
         assert_eq!(offset_of!(RegisterBlock, mac_address), 0x40);
         assert_eq!(offset_of!(RegisterBlock, hash), 0x48);
         assert_eq!(offset_of!(RegisterBlock, transmit_control), 0x50);
+        assert_eq!(offset_of!(RegisterBlock, descriptors), 0x400);
+        assert_eq!(core::mem::size_of::<RegisterBlock>(), 0x800);
+    }
+
+    #[test]
+    fn function_ethernet_receive() {
+        let mock = MockRegisters::<RegisterBlock>::new();
+        let buffers = buffers();
+        buffers.receive[1][0] = 0xab;
+        let mut ethernet = Ethernet::new(
+            mock.registers(),
+            MockPads,
+            [0x18, 0xb9, 0x05, 0x12, 0x34, 0x56],
+            buffers,
+        );
+        assert_eq!(mock.raw(0x00), 0x0002_a403);
+        assert_eq!(mock.raw(0x18), 0x0040_0600);
+        assert_eq!(mock.raw(0x20), 2);
+        assert_eq!(mock.raw(0x40), 0x0512_3456);
+        assert_eq!(mock.raw(0x44), 0x0000_18b9);
+        assert_eq!(mock.raw(0x400), 0x0000_0000);
+        assert_eq!(mock.raw(0x408), 0x0000_2000);
+        assert_eq!(mock.raw(0x410), 0x0000_c000);
+        assert_eq!(mock.raw(0x418), 0x0000_e000);
+        assert_eq!(ethernet.receive(|frame| frame.len()), None);
+
+        // Oversize frame truncated by the peripheral, followed by a good frame.
+        mock.set_raw(0x410, (1600 << 16) | (1 << 3));
+        mock.set_raw(0x418, (68 << 16) | (1 << 13));
+        assert_eq!(
+            ethernet.receive(|frame| (frame.len(), frame[0])),
+            Some((64, 0xab))
+        );
+        assert_eq!(
+            ethernet.statistics(),
+            Statistics {
+                receive_oversize: 1,
+                ..Statistics::default()
+            }
+        );
+        assert_eq!(mock.raw(0x410), 0x0000_c000);
+        assert_eq!(mock.raw(0x418), 0x0000_e000);
+        assert_eq!(ethernet.receive(|frame| frame.len()), None);
+
+        // Ring starts over from its first descriptor.
+        mock.set_raw(0x410, (BUFFER_SIZE as u32 + 4) << 16);
+        mock.set_raw(0x418, (60 << 16) | (1 << 13) | (1 << 1));
+        assert_eq!(ethernet.receive(|frame| frame.len()), None);
+        assert_eq!(ethernet.statistics().receive_oversize, 2);
+        assert_eq!(ethernet.statistics().receive_errors, 1);
+        assert_eq!(mock.raw(0x410), 0x0000_c000);
+        assert_eq!(mock.raw(0x418), 0x0000_e000);
+    }
+
+    #[test]
+    fn function_ethernet_transmit() {
+        let mock = MockRegisters::<RegisterBlock>::new();
+        let mut ethernet = Ethernet::new(mock.registers(), MockPads, [0; 6], buffers());
+        assert_eq!(ethernet.transmit(60, |buffer| buffer.fill(1)), Ok(()));
+        assert_eq!(mock.raw(0x400), 0x003c_d800);
+        assert_eq!(ethernet.transmit(42, |buffer| buffer.len()), Ok(42));
+        assert_eq!(mock.raw(0x408), 0x002a_f800);
+        assert_eq!(
+            ethernet.transmit(42, |buffer| buffer.len()),
+            Err(nb::Error::WouldBlock)
+        );
+
+        // First frame failed with transmit underrun.
+        mock.set_raw(0x400, (60 << 16) | (1 << 8));
+        assert_eq!(ethernet.transmit(14, |buffer| buffer.len()), Ok(14));
+        assert_eq!(ethernet.statistics().transmit_errors, 1);
+        assert_eq!(mock.raw(0x400), 0x000e_d800);
+
+        // Frames longer than the limit are rejected before touching any buffer.
+        mock.set_raw(0x408, 0);
+        assert_eq!(
+            ethernet.transmit(MAX_FRAME_SIZE + 1, |_| unreachable!()),
+            Err(nb::Error::Other(Error::FrameTooLong))
+        );
+        assert_eq!(mock.raw(0x408), 0);
+    }
+
+    #[test]
+    fn function_ethernet_link_status() {
+        let mock = MockRegisters::<RegisterBlock>::new();
+        let mut ethernet = Ethernet::new(mock.registers(), MockPads, [0; 6], buffers());
+        assert_eq!(ethernet.link_status(1), None);
+        assert_eq!(mock.raw(0x30), 0x0000_0101);
+        assert_eq!(mock.raw(0x2c), 0x2);
+
+        // Auto-negotiated 100 Mbit/s half duplex.
+        mock.set_raw(0x38, (1 << 12) | (1 << 7) | (1 << 2));
+        assert_eq!(
+            ethernet.link_status(1),
+            Some(Link {
+                speed: Speed::Mbps100,
+                duplex: Duplex::Half,
+            })
+        );
+        assert_eq!(mock.raw(0x30), 0x0000_0501);
+        assert_eq!(mock.raw(0x00) & (1 << 10), 0);
+
+        ethernet.write_phy(1, 0, 0x2100);
+        assert_eq!(mock.raw(0x30), 0x0000_0001);
+        assert_eq!(mock.raw(0x34), 0x2100);
+        assert_eq!(mock.raw(0x2c), 0x4);
+    }
+
+    #[test]
+    fn struct_descriptor_control_functions() {
+        let control = DescriptorControl::new_transmit(1514, true);
+        assert_eq!(control.length(), 1514);
+        assert!(control.is_owned() && control.is_wrap());
+        assert!(!control.is_transmit_error());
+        assert_eq!(DescriptorControl::new_idle(false), DescriptorControl(0));
+        assert!(!DescriptorControl(1 << 3).is_receive_error());
+        assert!(DescriptorControl(1 << 3).is_too_long());
     }
 }
@@ -0,0 +1,268 @@
+//! Serial flash controller and execute-in-place (XIP) safety helpers.
+//!
+//! Firmware usually executes in place from serial flash. While the flash is erased or
+//! programmed, or while its pads are reconfigured away from [`Function::Flash`], any
+//! instruction fetch or data read from flash stalls or returns garbage, which hangs
+//! the core or corrupts the operation. Such operations must therefore run entirely
+//! from RAM with flash access paused, using [`with_flash_paused`].
+//!
+//! Functions that run while flash is paused are declared with
+//! [`ram_code!`](crate::ram_code), which places them in the `.ram_code` section. The
+//! `bouffalo-rt` linker scripts copy this section into RAM together with `.data` at
+//! startup.
+//!
+//! [`Function::Flash`]: crate::glb::v2::Function::Flash
+use volatile_register::RW;
+
+/// Serial flash controller registers.
+#[repr(C)]
+pub struct RegisterBlock {
+    _reserved0: [u8; 0x4],
+    /// Bus interface configuration register.
+    pub interface_config: RW<InterfaceConfig>,
+}
+
+/// Bus interface configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct InterfaceConfig(u32);
+
+// Functions used while flash is paused are `inline(always)`, so that they are inlined
+// into RAM-resident code and never fetched from flash.
+impl InterfaceConfig {
+    const XIP_STOPPED: u32 = 1 << 26;
+    const XIP_STOP: u32 = 1 << 27;
+
+    /// Request execute-in-place bus accesses to stop.
+    #[inline(always)]
+    pub const fn stop_xip(self) -> Self {
+        Self(self.0 | Self::XIP_STOP)
+    }
+    /// Resume execute-in-place bus accesses.
+    #[inline(always)]
+    pub const fn resume_xip(self) -> Self {
+        Self(self.0 & !Self::XIP_STOP)
+    }
+    /// Check if execute-in-place bus accesses stopping is requested.
+    #[inline(always)]
+    pub const fn is_xip_stop_requested(self) -> bool {
+        self.0 & Self::XIP_STOP != 0
+    }
+    /// Check if execute-in-place bus accesses have stopped.
+    #[inline(always)]
+    pub const fn is_xip_stopped(self) -> bool {
+        self.0 & Self::XIP_STOPPED != 0
+    }
+}
+
+/// Number of polls to wait for the flash controller before giving up.
+pub const PAUSE_TIMEOUT: u32 = 100_000;
+
+/// Flash pause error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PauseError {
+    /// Flash controller did not stop execute-in-place accesses in time.
+    ///
+    /// The closure was not called, and execute-in-place was resumed.
+    Timeout,
+}
+
+/// Declare functions placed in RAM, which may run while flash is paused.
+///
+/// Each function is placed in the `.ram_code` section and never inlined into flash
+/// resident callers. The whole call tree must be RAM-resident: a RAM function may only
+/// call other `ram_code!` functions, or `#[inline(always)]` functions, and may only
+/// read data located in RAM.
+///
+/// ```
+/// bouffalo_hal::ram_code! {
+///     /// Program one word of flash.
+///     pub fn program_word(address: usize, value: u32) {
+///         // Issue flash commands through the serial flash controller.
+///         # let _ = (address, value);
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! ram_code {
+    ($($item:item)*) => {
+        $(
+            #[unsafe(link_section = ".ram_code")]
+            #[inline(never)]
+            $item
+        )*
+    };
+}
+
+/// Run `f(arg)` from RAM with flash execute-in-place accesses paused.
+///
+/// `f` is a function pointer rather than a closure, as a closure body is compiled into
+/// its caller's section, which is usually flash. It is called indirectly, thus never
+/// inlined out of its own section:
+///
+/// ```no_run
+/// # use bouffalo_hal::flash::{RegisterBlock, with_flash_paused};
+/// bouffalo_hal::ram_code! {
+///     fn erase_sector(address: usize) -> bool {
+///         // Issue erase command through the serial flash controller.
+///         # let _ = address;
+///         true
+///     }
+/// }
+///
+/// # fn example(flash: &RegisterBlock) {
+/// let erased = unsafe { with_flash_paused(flash, erase_sector, 0x1_0000) };
+/// # }
+/// ```
+///
+/// Interrupts are disabled by a critical section for the whole operation, as interrupt
+/// handlers may live in flash. This function waits at most [`PAUSE_TIMEOUT`] polls for
+/// the flash controller to stop; on timeout, [`PauseError::Timeout`] is returned
+/// without calling `f`. Flash access is always resumed before returning.
+///
+/// Flash memory cached before the pause may be stale after erase or program operations;
+/// invalidate the cache before reading modified flash regions.
+///
+/// # Safety
+///
+/// Everything executed by `f` must be RAM-resident, otherwise the core fetches from
+/// paused flash and hangs, which at worst leaves flash half-written and the device
+/// unbootable. The section of a function pointer cannot be checked by the compiler;
+/// therefore:
+///
+/// - `f` must be declared with [`ram_code!`](crate::ram_code), and may only call other
+///   `ram_code!` functions or `#[inline(always)]` functions.
+/// - No code or data read by `f` may be in flash, including string constants and
+///   lookup tables in `.rodata`; panicking inside `f` is thus not permitted.
+/// - No other core or bus master may access flash during the pause.
+#[unsafe(link_section = ".ram_code")]
+#[inline(never)]
+pub unsafe fn with_flash_paused<A, R>(
+    flash: &RegisterBlock,
+    f: fn(A) -> R,
+    arg: A,
+) -> Result<R, PauseError> {
+    // SAFETY: released below on every path, before this function returns.
+    let restore = unsafe { critical_section::acquire() };
+    let config = flash.interface_config.read();
+    unsafe { flash.interface_config.write(config.stop_xip()) };
+    let mut polls = 0;
+    let ans = loop {
+        if flash.interface_config.read().is_xip_stopped() {
+            break Ok(f(arg));
+        }
+        if polls == PAUSE_TIMEOUT {
+            break Err(PauseError::Timeout);
+        }
+        polls += 1;
+    };
+    let config = flash.interface_config.read();
+    unsafe { flash.interface_config.write(config.resume_xip()) };
+    unsafe { critical_section::release(restore) };
+    ans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InterfaceConfig, PauseError, RegisterBlock, with_flash_paused};
+    use crate::mock::MockRegisters;
+    use core::sync::atomic::{AtomicBool, Ordering};
+    use memoffset::offset_of;
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, interface_config), 0x04);
+    }
+
+    #[test]
+    fn struct_interface_config_functions() {
+        let val = InterfaceConfig(0).stop_xip();
+        assert_eq!(val.0, 0x0800_0000);
+        assert!(val.is_xip_stop_requested());
+        assert!(!val.is_xip_stopped());
+        let val = InterfaceConfig(0x0c00_0000);
+        assert!(val.is_xip_stopped());
+        assert_eq!(val.resume_xip().0, 0x0400_0000);
+    }
+
+    crate::ram_code! {
+        fn ram_resident(called: &AtomicBool) -> u32 {
+            called.store(true, Ordering::Relaxed);
+            42
+        }
+    }
+
+    #[test]
+    fn function_with_flash_paused() {
+        // Flash controller reports stopped, thus the function runs.
+        let flash = MockRegisters::<RegisterBlock>::new();
+        flash.set_raw(0x04, 0x0400_0000);
+        let called = AtomicBool::new(false);
+        assert_eq!(
+            unsafe { with_flash_paused(&flash, ram_resident, &called) },
+            Ok(42)
+        );
+        assert!(called.load(Ordering::Relaxed));
+        assert!(!flash.interface_config.read().is_xip_stop_requested());
+        // Flash controller never stops; flash access still resumes.
+        flash.set_raw(0x04, 0);
+        let called = AtomicBool::new(false);
+        assert_eq!(
+            unsafe { with_flash_paused(&flash, ram_resident, &called) },
+            Err(PauseError::Timeout)
+        );
+        assert!(!called.load(Ordering::Relaxed));
+        assert_eq!(flash.raw(0x04), 0);
+    }
+
+    // Reads the section layout of the test executable, which depends on the host
+    // object format; run with `--ignored` on Linux hosts.
+    #[cfg(target_os = "linux")]
+    #[test]
+    #[ignore = "inspects the ELF layout of the host test executable"]
+    fn function_with_flash_paused_ram_resident() {
+        extern crate std;
+        use std::vec::Vec;
+
+        // Find the file offset range of `.ram_code` in this test executable.
+        let elf = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+        let word = |at: usize, len: usize| {
+            elf[at..at + len]
+                .iter()
+                .rev()
+                .fold(0usize, |acc, &b| (acc << 8) | b as usize)
+        };
+        let (shoff, shentsize, shnum, shstrndx) =
+            (word(0x28, 8), word(0x3a, 2), word(0x3c, 2), word(0x3e, 2));
+        let header = |i: usize| shoff + i * shentsize;
+        let strtab = word(header(shstrndx) + 0x18, 8);
+        let ram_code = (0..shnum)
+            .map(header)
+            .find(|&h| elf[strtab + word(h, 4)..].starts_with(b".ram_code\0"))
+            .map(|h| {
+                let offset = word(h + 0x18, 8);
+                offset..offset + word(h + 0x20, 8)
+            })
+            .expect("no .ram_code section");
+
+        // Translate runtime addresses into file offsets using the memory map.
+        let maps = std::fs::read_to_string("/proc/self/maps").unwrap();
+        let exe = std::env::current_exe().unwrap();
+        let file_offset = |address: usize| {
+            maps.lines().find_map(|line| {
+                let fields: Vec<_> = line.split_whitespace().collect();
+                let (start, end) = fields[0].split_once('-')?;
+                let start = usize::from_str_radix(start, 16).ok()?;
+                let end = usize::from_str_radix(end, 16).ok()?;
+                let offset = usize::from_str_radix(fields[2], 16).ok()?;
+                let path = std::path::Path::new(fields.get(5)?);
+                (path == exe && (start..end).contains(&address)).then(|| address - start + offset)
+            })
+        };
+        let helper = with_flash_paused::<&AtomicBool, u32> as *const () as usize;
+        assert!(ram_code.contains(&file_offset(helper).unwrap()));
+        assert!(ram_code.contains(&file_offset(ram_resident as *const () as usize).unwrap()));
+        let flash_resident = function_with_flash_paused as *const () as usize;
+        assert!(!ram_code.contains(&file_offset(flash_resident).unwrap()));
+    }
+}
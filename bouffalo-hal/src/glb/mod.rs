@@ -6,6 +6,8 @@ pub mod v2;
 
 /// Pin pull direction.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum Pull {
     /// No internal pulls.
@@ -14,10 +16,27 @@ pub enum Pull {
     Up = 1,
     /// Internally pulled down.
     Down = 2,
+    /// Reserved field value read from hardware.
+    Reserved(u8),
+}
+
+impl Pull {
+    /// Get raw field value of this variant.
+    #[inline]
+    pub(crate) const fn bits(self) -> u32 {
+        match self {
+            Self::None => 0,
+            Self::Up => 1,
+            Self::Down => 2,
+            Self::Reserved(val) => val as u32,
+        }
+    }
 }
 
 /// Pin drive strength.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum Drive {
     /// Drive strength 0.
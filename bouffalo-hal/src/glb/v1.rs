@@ -30,6 +30,46 @@ pub struct RegisterBlock {
     pub gpio_interrupt_mode: [RW<GpioInterruptMode>; 16],
 }
 
+impl RegisterBlock {
+    /// Set every pin not set in `used_mask` to its lowest-power state with `default`
+    /// pull.
+    ///
+    /// Bit `n` of `used_mask` stands for pin `n`; configuration of used pins is never
+    /// touched. Input buffer, Schmitt trigger, output driver and interrupt of unused
+    /// pins are disabled and their pull is set to `default`. Each configuration
+    /// register holds two pins, and it is written at most once, only if any unused pin
+    /// in it is changed; output enable and interrupt mask registers are likewise only
+    /// written if changed. Pulling unused pins down is a common power optimization step
+    /// at boot, as floating inputs waste power and pick up noise.
+    #[inline]
+    pub fn set_unused_pins(&self, used_mask: u64, default: Pull) {
+        for (n, config) in self.gpio_config.iter().enumerate() {
+            let old = config.read();
+            let mut val = old;
+            for idx in 0..2 {
+                if used_mask & (1 << (n * 2 + idx)) == 0 {
+                    val = val
+                        .disable_input(idx)
+                        .disable_schmitt(idx)
+                        .set_pull(idx, default);
+                }
+            }
+            if val != old {
+                unsafe { config.write(val) };
+            }
+        }
+        let unused = !(used_mask as u32);
+        let output_enable = self.gpio_output_enable.read();
+        if output_enable & unused != 0 {
+            unsafe { self.gpio_output_enable.write(output_enable & !unused) };
+        }
+        let interrupt_mask = self.gpio_interrupt_mask.read();
+        if !interrupt_mask & unused != 0 {
+            unsafe { self.gpio_interrupt_mask.write(interrupt_mask | unused) };
+        }
+    }
+}
+
 /// Generic Purpose Input/Output Configuration register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 #[repr(transparent)]
@@ -94,18 +134,24 @@ impl GpioConfig {
             0 => Pull::None,
             1 => Pull::Up,
             2 => Pull::Down,
-            _ => unreachable!(),
+            n => Pull::Reserved(n as u8),
         }
     }
     /// Set pull direction of current pin.
     #[inline]
     pub const fn set_pull(self, idx: usize, val: Pull) -> Self {
-        Self((self.0 & !(Self::PULL << (idx * 16))) | ((val as u32) << (4 + (idx * 16))))
+        Self(
+            (self.0 & !(Self::PULL << (idx * 16)))
+                | (((val.bits() << 4) & Self::PULL) << (idx * 16)),
+        )
     }
     /// Set function of current pin.
     #[inline]
     pub const fn set_function(self, idx: usize, val: Function) -> Self {
-        Self((self.0 & !(Self::FUNCTION << (idx * 16))) | ((val as u32) << (8 + (idx * 16))))
+        Self(
+            (self.0 & !(Self::FUNCTION << (idx * 16)))
+                | (((val.bits() << 8) & Self::FUNCTION) << (idx * 16)),
+        )
     }
     /// Get function of current pin.
     #[inline]
@@ -134,7 +180,7 @@ impl GpioConfig {
             21 => Function::KeyScanIn,
             22 => Function::KeyScanDrive,
             23 => Function::CamMisc,
-            _ => unreachable!(),
+            n => Function::Reserved(n as u8),
         }
     }
 }
@@ -196,6 +242,41 @@ pub enum Function {
     KeyScanIn = 21,
     KeyScanDrive = 22,
     CamMisc = 23,
+    /// Reserved field value read from hardware.
+    Reserved(u8),
+}
+
+impl Function {
+    /// Get raw field value of this variant.
+    #[inline]
+    pub(crate) const fn bits(self) -> u32 {
+        match self {
+            Self::ClkOut => 0,
+            Self::BtCoexist => 1,
+            Self::Flash => 2,
+            Self::I2s => 3,
+            Self::Spi => 4,
+            Self::I2c => 6,
+            Self::Uart => 7,
+            Self::Pwm => 8,
+            Self::Cam => 9,
+            Self::Analog => 10,
+            Self::Gpio => 11,
+            Self::RfTest => 12,
+            Self::Scan => 13,
+            Self::E21Jtag => 14,
+            Self::Debug => 15,
+            Self::ExternalPa => 16,
+            Self::UsbTranceiver => 17,
+            Self::UsbController => 18,
+            Self::EMac => 19,
+            Self::Qdec => 20,
+            Self::KeyScanIn => 21,
+            Self::KeyScanDrive => 22,
+            Self::CamMisc => 23,
+            Self::Reserved(val) => val as u32,
+        }
+    }
 }
 
 /// Pin interrupt mode.
@@ -211,3 +292,58 @@ pub enum InterruptMode {
     AsyncLowLevel = 6,
     AsyncHighLevel = 7,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Function, GpioConfig, Pull, RegisterBlock};
+    use crate::mock::MockRegisters;
+
+    #[test]
+    fn struct_gpio_config_reserved_values() {
+        let val = GpioConfig(0x0030_0000);
+        assert_eq!(val.pull(0), Pull::None);
+        assert_eq!(val.pull(1), Pull::Reserved(3));
+        assert_eq!(val.set_pull(1, val.pull(1)), val);
+        for n in [5].into_iter().chain(24..=31) {
+            let val = GpioConfig(n << 8);
+            assert_eq!(val.function(0), Function::Reserved(n as u8));
+            assert_eq!(val.set_function(0, val.function(0)), val);
+        }
+        let val = GpioConfig(0).set_function(1, Function::Reserved(0xff));
+        assert_eq!(val.0, 0x1f00_0000);
+    }
+
+    #[test]
+    fn function_set_unused_pins() {
+        let glb = MockRegisters::<RegisterBlock>::new();
+        // Pin 0 is in use with pull up, sharing its register with unused pin 1.
+        let used = GpioConfig(0).enable_input(0).set_pull(0, Pull::Up);
+        let config = used.enable_input(1).enable_schmitt(1).set_pull(1, Pull::Up);
+        glb.set_raw(0x100, config.0);
+        glb.set_raw(0x190, 0b11);
+        // Pin 5 is in use without pulls.
+        let used_mask = 0b1 | (1 << 5);
+        glb.set_unused_pins(used_mask, Pull::Down);
+        assert_eq!(glb.gpio_config[0].read(), used.set_pull(1, Pull::Down));
+        assert_eq!(
+            glb.gpio_config[2].read(),
+            GpioConfig(0).set_pull(0, Pull::Down)
+        );
+        let low_power = GpioConfig(0)
+            .set_pull(0, Pull::Down)
+            .set_pull(1, Pull::Down);
+        for n in [1].into_iter().chain(3..16) {
+            assert_eq!(glb.gpio_config[n].read(), low_power);
+        }
+        assert_eq!(glb.gpio_output_enable.read(), 0b01);
+        assert_eq!(glb.gpio_interrupt_mask.read(), !0b10_0001);
+
+        // Nothing left to change, registers are kept as is.
+        let snapshot: [u32; 38] = core::array::from_fn(|i| glb.raw(0x100 + i * 4));
+        glb.set_unused_pins(used_mask, Pull::Down);
+        glb.set_unused_pins(!0, Pull::None);
+        for (i, val) in snapshot.into_iter().enumerate() {
+            assert_eq!(glb.raw(0x100 + i * 4), val);
+        }
+    }
+}
@@ -2,48 +2,214 @@
 use super::{Drive, Pull};
 use volatile_register::{RO, RW, WO};
 
+/// Number of pins on BL808 and BL616.
+pub const PIN_COUNT: usize = 46;
+
 /// Global configuration registers.
 #[repr(C)]
 pub struct RegisterBlock {
-    _reserved0: [u8; 0x150],
+    _reserved0: [u8; 0x90],
+    /// System clock configuration 0.
+    pub sys_config_0: RW<SysConfig0>,
+    /// System clock configuration 1.
+    pub sys_config_1: RW<SysConfig1>,
+    _reserved1: [u8; 0x90],
+    /// Clock output channel configuration.
+    pub clock_out_config: RW<ClockOutConfig>,
+    _reserved2: [u8; 0x24],
     /// Universal Asynchronous Receiver/Transmitter clock and mode configurations.
     pub uart_config: RW<UartConfig>,
     /// Universal Asynchronous Receiver/Transmitter signal multiplexer.
     pub uart_mux_group: [RW<UartMuxGroup>; 2],
-    _reserved1: [u8; 0x24],
+    _reserved3: [u8; 0x24],
     /// Inter-Integrated Circuit configuration register.
     pub i2c_config: RW<I2cConfig>,
-    _reserved2: [u8; 0x2c],
+    _reserved4: [u8; 0x2c],
     /// Serial Peripheral Interface configuration register.
     pub spi_config: RW<SpiConfig>,
-    _reserved3: [u8; 0x1c],
+    _reserved5: [u8; 0x1c],
     /// Pulse Width Modulation configuration register.
     pub pwm_config: RW<PwmConfig>,
-    _reserved4: [u8; 0x25c],
+    _reserved6: [u8; 0x25c],
     /// SDH configuration register.
     pub sdh_config: RW<SdhConfig>,
-    _reserved5: [u8; 0xdd],
+    _reserved7: [u8; 0xdd],
     pub param_config: RW<ParamConfig>,
-    _reserved6: [u8; 0x70],
+    _reserved8: [u8; 0x70],
     // TODO: clock_config_0, clock_config_2, clock_config_3 registers
     /// Clock generation configuration 1.
     pub clock_config_1: RW<ClockConfig1>,
-    _reserved7: [u8; 0x148],
+    _reserved9: [u8; 0x148],
     /// LDO12UHS config.
     pub ldo12uhs_config: RW<Ldo12uhsConfig>,
-    _reserved8: [u8; 0x1f0],
+    _reserved10: [u8; 0x1f0],
     /// Generic Purpose Input/Output config.
-    pub gpio_config: [RW<GpioConfig>; 46],
-    _reserved9: [u8; 0x148],
+    pub gpio_config: [RW<GpioConfig>; PIN_COUNT],
+    _reserved11: [u8; 0x148],
     /// Read value from Generic Purpose Input/Output pads.
     pub gpio_input: [RO<u32>; 2],
-    _reserved10: [u8; 0x18],
+    _reserved12: [u8; 0x18],
     /// Write value to Generic Purpose Input/Output pads.
     pub gpio_output: [RW<u32>; 2],
     /// Set pin output value to high.
     pub gpio_set: [WO<u32>; 2],
     /// Clear pin output value to low.
     pub gpio_clear: [WO<u32>; 2],
+    /// Output sequencer timing configuration.
+    pub gpio_tx_config: RW<GpioTxConfig>,
+    /// Output sequencer FIFO control and status.
+    pub gpio_tx_fifo: RW<GpioTxFifo>,
+    /// Push one step into output sequencer FIFO.
+    pub gpio_tx_data: WO<u32>,
+}
+
+impl RegisterBlock {
+    /// Set every pin not set in `used_mask` to its lowest-power state with `default`
+    /// pull.
+    ///
+    /// Bit `n` of `used_mask` stands for pin `n` on either GPIO port; configuration of
+    /// used pins is never touched. Input buffer, output driver, Schmitt trigger and
+    /// interrupt of unused pins are disabled and their pull is set to `default`; pins
+    /// already in this state are skipped, thus no register is written more than needed.
+    /// Pulling unused pins down is a common power optimization step at boot, as floating
+    /// inputs waste power and pick up noise. Before deep sleep, take a snapshot with
+    /// [`save`](Self::save) first.
+    #[inline]
+    pub fn set_unused_pins(&self, used_mask: u64, default: Pull) {
+        for (_, config) in self.masked_pins(!used_mask) {
+            let old = config.read();
+            let val = old
+                .disable_input()
+                .disable_output()
+                .disable_schmitt()
+                .mask_interrupt()
+                .set_pull(default);
+            if val != old {
+                unsafe { config.write(val) };
+            }
+        }
+    }
+    /// Get function currently assigned to `pin`.
+    ///
+    /// Useful to detect pin multiplexer conflicts during board bring-up, e.g. a pin
+    /// claimed by firmware on another core.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pin` is larger than 45.
+    #[inline]
+    pub fn function_of(&self, pin: usize) -> Function {
+        self.gpio_config[pin].read().function()
+    }
+    /// Take a snapshot of every pin configuration, e.g. before deep sleep.
+    ///
+    /// Output latch of pins in set-clear modes is read back in the output bit of the
+    /// snapshot, thus [`restore`](Self::restore) brings it back as well.
+    #[inline]
+    pub fn save(&self) -> [u32; PIN_COUNT] {
+        core::array::from_fn(|n| self.gpio_config[n].read().bits())
+    }
+    /// Restore pin configurations from a snapshot taken by [`save`](Self::save).
+    ///
+    /// Output latch of pins in set-clear modes is written through `gpio_set` or
+    /// `gpio_clear` before their configuration, thus a restored output never glitches.
+    /// Write-to-trigger set, clear and interrupt clear bits in the snapshot are ignored.
+    #[inline]
+    pub fn restore(&self, snapshot: &[u32; PIN_COUNT]) {
+        for (n, (config, &bits)) in self.gpio_config.iter().zip(snapshot).enumerate() {
+            let val = GpioConfig::from_bits(bits & !GpioConfig::TRIGGER);
+            if val.mode() != Mode::Normal {
+                let bit = 1 << (n & 0x1F);
+                unsafe {
+                    match val.output_value() {
+                        true => self.gpio_set[n >> 5].write(bit),
+                        false => self.gpio_clear[n >> 5].write(bit),
+                    }
+                }
+            }
+            unsafe { config.write(val) };
+        }
+    }
+    /// Read input levels of all pins in GPIO `bank`.
+    ///
+    /// Bank 0 holds io0 to io31, bank 1 holds io32 to io45; bit `i` is pin
+    /// `bank * 32 + i`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bank` is not 0 or 1.
+    #[inline]
+    pub fn read_bank(&self, bank: usize) -> u32 {
+        self.gpio_input[bank].read()
+    }
+    /// Drive pins in `mask` of GPIO `bank` to levels in `value`.
+    ///
+    /// Bits in `mask & value` are written to `gpio_set` and bits in `mask & !value` to
+    /// `gpio_clear`, thus pins outside `mask` are never changed. Only pins in set-clear
+    /// modes follow these writes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bank` is not 0 or 1.
+    #[inline]
+    pub fn write_bank(&self, bank: usize, value: u32, mask: u32) {
+        unsafe {
+            self.gpio_set[bank].write(mask & value);
+            self.gpio_clear[bank].write(mask & !value);
+        }
+    }
+    /// Get mask of pins with an active, unmasked interrupt.
+    ///
+    /// Bit `n` stands for pin `n` on either GPIO port. Pins with masked interrupt are
+    /// not reported, even if their interrupt flag is set.
+    ///
+    /// This GLB has no bank-wide interrupt status register like `gpio_input`: each
+    /// interrupt flag and mask bit lives only in the configuration register of its own
+    /// pin, thus this function reads all `PIN_COUNT` configuration registers. Check a
+    /// single pin through its own configuration register in latency-sensitive handlers.
+    #[inline]
+    pub fn pending_interrupts(&self) -> u64 {
+        let mut ans = 0;
+        for (n, config) in self.gpio_config.iter().enumerate() {
+            let val = config.read();
+            if val.has_interrupt() && !val.is_interrupt_masked() {
+                ans |= 1 << n;
+            }
+        }
+        ans
+    }
+    /// Clear interrupt flags of pins in `mask`.
+    ///
+    /// Bit `n` stands for pin `n` on either GPIO port; bits above the last pin are
+    /// ignored.
+    #[inline]
+    pub fn clear_interrupts(&self, mask: u64) {
+        for (_, config) in self.masked_pins(mask) {
+            clear_interrupt(config, config.read());
+        }
+    }
+    /// Iterate over configuration registers of pins set in `mask`, with pin numbers.
+    #[inline]
+    pub(crate) fn masked_pins(&self, mask: u64) -> impl Iterator<Item = (usize, &RW<GpioConfig>)> {
+        self.gpio_config
+            .iter()
+            .enumerate()
+            .filter(move |&(n, _)| mask & (1 << n) != 0)
+    }
+}
+
+/// Clear interrupt flag of a pin whose configuration register currently holds `val`.
+///
+/// Interrupt clear bit is not self-clearing, thus it is pulsed high and then low.
+#[inline]
+pub(crate) fn clear_interrupt(config: &RW<GpioConfig>, val: GpioConfig) {
+    pulse_clear(val, |v| unsafe { config.write(v) })
+}
+
+#[inline]
+fn pulse_clear(val: GpioConfig, mut write: impl FnMut(GpioConfig)) {
+    write(val.clear_interrupt());
+    write(val);
 }
 
 /// Universal Asynchronous Receiver/Transmitter clock and mode configuration.
@@ -438,6 +604,109 @@ pub enum SpiMode {
     Slave = 1,
 }
 
+/// System clock configuration register 0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct SysConfig0(u32);
+
+impl SysConfig0 {
+    const HCLK_DIVIDE: u32 = 0xff << 8;
+    const BCLK_DIVIDE: u32 = 0xff << 16;
+
+    /// Set processor and high-speed bus clock divider, minus one.
+    #[inline]
+    pub const fn set_hclk_divide(self, val: u8) -> Self {
+        Self((self.0 & !Self::HCLK_DIVIDE) | ((val as u32) << 8))
+    }
+    /// Get processor and high-speed bus clock divider, minus one.
+    #[inline]
+    pub const fn hclk_divide(self) -> u8 {
+        ((self.0 & Self::HCLK_DIVIDE) >> 8) as u8
+    }
+    /// Set peripheral bus clock divider from processor clock, minus one.
+    #[inline]
+    pub const fn set_bclk_divide(self, val: u8) -> Self {
+        Self((self.0 & !Self::BCLK_DIVIDE) | ((val as u32) << 16))
+    }
+    /// Get peripheral bus clock divider from processor clock, minus one.
+    #[inline]
+    pub const fn bclk_divide(self) -> u8 {
+        ((self.0 & Self::BCLK_DIVIDE) >> 16) as u8
+    }
+}
+
+/// System clock configuration register 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct SysConfig1(u32);
+
+impl SysConfig1 {
+    const BCLK_DIVIDE_ACT: u32 = 1 << 0;
+    const BCLK_PROTECT_DONE: u32 = 1 << 2;
+
+    /// Apply new peripheral bus clock divider.
+    #[inline]
+    pub const fn act_bclk_divide(self) -> Self {
+        Self(self.0 | Self::BCLK_DIVIDE_ACT)
+    }
+    /// Check if peripheral bus clock divider change has completed.
+    #[inline]
+    pub const fn is_bclk_divide_done(self) -> bool {
+        self.0 & Self::BCLK_PROTECT_DONE != 0
+    }
+}
+
+/// Clock output channel configuration register.
+///
+/// Pad `ioN` in clock output function outputs channel `N % 4`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct ClockOutConfig(u32);
+
+impl ClockOutConfig {
+    const SOURCE: u32 = 0x3;
+    const ENABLE: u32 = 1 << 8;
+    const DIVIDE: u32 = 0xf << 16;
+
+    /// Set clock source selection of `channel`.
+    #[inline]
+    pub const fn set_source(self, channel: usize, val: u8) -> Self {
+        let shift = channel as u32 * 2;
+        Self((self.0 & !(Self::SOURCE << shift)) | ((val as u32 & Self::SOURCE) << shift))
+    }
+    /// Get clock source selection of `channel`.
+    #[inline]
+    pub const fn source(self, channel: usize) -> u8 {
+        ((self.0 >> (channel as u32 * 2)) & Self::SOURCE) as u8
+    }
+    /// Enable output of `channel`.
+    #[inline]
+    pub const fn enable(self, channel: usize) -> Self {
+        Self(self.0 | (Self::ENABLE << channel))
+    }
+    /// Disable output of `channel`.
+    #[inline]
+    pub const fn disable(self, channel: usize) -> Self {
+        Self(self.0 & !(Self::ENABLE << channel))
+    }
+    /// Check if output of `channel` is enabled.
+    #[inline]
+    pub const fn is_enabled(self, channel: usize) -> bool {
+        self.0 & (Self::ENABLE << channel) != 0
+    }
+    /// Set clock divider of `channel`, minus one.
+    #[inline]
+    pub const fn set_divide(self, channel: usize, val: u8) -> Self {
+        let shift = channel as u32 * 4;
+        Self((self.0 & !(Self::DIVIDE << shift)) | (((val as u32) << 16 & Self::DIVIDE) << shift))
+    }
+    /// Get clock divider of `channel`, minus one.
+    #[inline]
+    pub const fn divide(self, channel: usize) -> u8 {
+        (((self.0 >> (channel as u32 * 4)) & Self::DIVIDE) >> 16) as u8
+    }
+}
+
 /// Clock generation configuration register 1.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 #[repr(transparent)]
@@ -532,7 +801,10 @@ impl ClockConfig1 {
 }
 
 /// Generic Purpose Input/Output Configuration register.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+///
+/// Its `Debug` output decodes the register fields on a single line.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(transparent)]
 pub struct GpioConfig(u32);
 
@@ -552,7 +824,85 @@ impl GpioConfig {
     const CLEAR: u32 = 1 << 26;
     const INPUT: u32 = 1 << 28;
     const MODE: u32 = 0x3 << 30;
-
+    /// Bits that reflect pad state or trigger an action, rather than configuration.
+    const VOLATILE: u32 = Self::TRIGGER | Self::HAS_INTERRUPT | Self::INPUT | Self::OUTPUT;
+    /// Bits that trigger an action when written.
+    const TRIGGER: u32 = Self::SET | Self::CLEAR | Self::CLEAR_INTERRUPT;
+
+    /// Check if two configurations describe the same pin setup.
+    ///
+    /// Unlike `==`, this ignores bits that do not express configuration intent:
+    /// the write-to-trigger `SET`, `CLEAR` and `CLEAR_INTERRUPT` bits, the
+    /// `HAS_INTERRUPT` status flag, and the latched `INPUT` and `OUTPUT` pad values.
+    /// All other bits, including function, mode, pull, drive, interrupt mode and
+    /// interrupt mask, must be equal.
+    #[inline]
+    pub const fn significant_eq(self, other: Self) -> bool {
+        (self.0 & !Self::VOLATILE) == (other.0 & !Self::VOLATILE)
+    }
+    /// Get configuration as bytes in little-endian order.
+    ///
+    /// Byte order is the same on every host, thus the bytes can be sent to another
+    /// machine and decoded by [`GpioConfig::from_bytes`] there.
+    #[inline]
+    pub const fn to_bytes(self) -> [u8; 4] {
+        self.0.to_le_bytes()
+    }
+    /// Create configuration from bytes in little-endian order.
+    #[inline]
+    pub const fn from_bytes(bytes: [u8; 4]) -> Self {
+        Self(u32::from_le_bytes(bytes))
+    }
+    /// Get raw register value of this configuration.
+    ///
+    /// Together with [`GpioConfig::from_bits`] it allows to snapshot and restore a
+    /// pin configuration, e.g. across sleep.
+    #[inline]
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+    /// Create configuration from raw register value.
+    ///
+    /// Every bit is kept as is, including reserved positions, thus
+    /// `GpioConfig::from_bits(x).bits() == x` for any `x`.
+    #[inline]
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+    /// Baseline configuration of a GPIO output pin.
+    ///
+    /// Output is enabled, input and Schmitt trigger disabled, function is
+    /// [`Function::Gpio`] in normal mode, with no pulls, drive strength 0 and interrupt
+    /// masked. Use it as a named starting point for chained setters instead of the
+    /// all-zero default value.
+    #[inline]
+    pub const fn output() -> Self {
+        Self(0)
+            .enable_output()
+            .set_function(Function::Gpio)
+            .mask_interrupt()
+    }
+    /// Baseline configuration of a GPIO input pin.
+    ///
+    /// Input and Schmitt trigger are enabled, output disabled, function is
+    /// [`Function::Gpio`] in normal mode, with no pulls, drive strength 0 and interrupt
+    /// masked.
+    #[inline]
+    pub const fn input() -> Self {
+        Self(0)
+            .enable_input()
+            .enable_schmitt()
+            .set_function(Function::Gpio)
+            .mask_interrupt()
+    }
+    /// Baseline configuration of an analog pin.
+    ///
+    /// Digital input, output and Schmitt trigger are disabled, function is
+    /// [`Function::Analog`], with no pulls, drive strength 0 and interrupt masked.
+    #[inline]
+    pub const fn analog() -> Self {
+        Self(0).set_function(Function::Analog).mask_interrupt()
+    }
     /// Enable input function of current pin.
     #[inline]
     pub const fn enable_input(self) -> Self {
@@ -613,14 +963,23 @@ impl GpioConfig {
     pub const fn is_interrupt_masked(self) -> bool {
         self.0 & Self::INTERRUPT_MASK != 0
     }
-    /// Get output of current pin.
+    /// Get output value of current pin, used as output level in normal mode.
     #[inline]
-    pub const fn output(self) -> bool {
+    pub const fn output_value(self) -> bool {
         self.0 & Self::OUTPUT != 0
     }
-    /// Get intput of current pin.
+    /// Set output of current pin, used as output level in normal mode.
     #[inline]
-    pub const fn input(self) -> bool {
+    pub const fn set_output(self, val: bool) -> Self {
+        if val {
+            Self(self.0 | Self::OUTPUT)
+        } else {
+            Self(self.0 & !Self::OUTPUT)
+        }
+    }
+    /// Get input value of current pin.
+    #[inline]
+    pub const fn input_value(self) -> bool {
         self.0 & Self::INPUT != 0
     }
     /// Check if current pin has interrupt function.
@@ -688,13 +1047,13 @@ impl GpioConfig {
             26 => Function::JtagM0,
             27 => Function::JtagD0,
             31 => Function::ClockOut,
-            _ => unreachable!(),
+            n => Function::Reserved(n as u8),
         }
     }
     /// Set function of current pin.
     #[inline]
     pub const fn set_function(self, val: Function) -> Self {
-        Self((self.0 & !Self::FUNCTION) | ((val as u32) << 8))
+        Self((self.0 & !Self::FUNCTION) | ((val.bits() << 8) & Self::FUNCTION))
     }
     /// Get interrupt mode of current pin.
     pub const fn interrupt_mode(self) -> InterruptMode {
@@ -708,13 +1067,13 @@ impl GpioConfig {
             9 => InterruptMode::AsyncRisingEdge,
             10 => InterruptMode::AsyncLowLevel,
             11 => InterruptMode::AsyncHighLevel,
-            _ => unreachable!(),
+            n => InterruptMode::Reserved(n as u8),
         }
     }
     /// Set interrupt mode of current pin.
     #[inline]
     pub const fn set_interrupt_mode(self, val: InterruptMode) -> Self {
-        Self((self.0 & !Self::INTERRUPT_MODE) | ((val as u32) << 16))
+        Self((self.0 & !Self::INTERRUPT_MODE) | ((val.bits() << 16) & Self::INTERRUPT_MODE))
     }
     /// Get mode of current pin.
     pub const fn mode(self) -> Mode {
@@ -737,21 +1096,303 @@ impl GpioConfig {
             0 => Pull::None,
             1 => Pull::Up,
             2 => Pull::Down,
-            _ => unreachable!(),
+            n => Pull::Reserved(n as u8),
         }
     }
     /// Set pull direction of current pin.
     #[inline]
     pub const fn set_pull(self, val: Pull) -> Self {
-        Self((self.0 & !Self::PULL) | ((val as u32) << 4))
+        Self((self.0 & !Self::PULL) | ((val.bits() << 4) & Self::PULL))
     }
     /// Reset value of GPIO_CONFIG register.
-    #[allow(unused)]
     pub(crate) const RESET_VALUE: Self = Self(0x0040_0b02);
+    /// Safe default pin configuration: GPIO function, input enabled, output disabled,
+    /// no pull and interrupt masked.
+    pub const SAFE_DEFAULT: Self = Self::RESET_VALUE.enable_input();
+}
+
+impl core::fmt::Debug for GpioConfig {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut s = f.debug_struct("GpioConfig");
+        s.field("bits", &format_args!("{:#010x}", self.0))
+            .field("function", &self.function())
+            .field("pull", &self.pull())
+            .field("drive", &self.drive())
+            .field("mode", &self.mode())
+            .field("input_enabled", &self.is_input_enabled())
+            .field("output_enabled", &self.is_output_enabled())
+            .field("interrupt_masked", &self.is_interrupt_masked());
+        if self.has_interrupt() {
+            s.field("interrupt_mode", &self.interrupt_mode());
+        }
+        s.finish()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for GpioConfig {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "GpioConfig {{ bits: {=u32:#x}, function: {}, pull: {}, drive: {}, mode: {}, input_enabled: {=bool}, output_enabled: {=bool}, interrupt_masked: {=bool}",
+            self.0,
+            self.function(),
+            self.pull(),
+            self.drive(),
+            self.mode(),
+            self.is_input_enabled(),
+            self.is_output_enabled(),
+            self.is_interrupt_masked(),
+        );
+        if self.has_interrupt() {
+            defmt::write!(f, ", interrupt_mode: {} }}", self.interrupt_mode());
+        } else {
+            defmt::write!(f, " }}");
+        }
+    }
+}
+
+/// Decoded fields of a [`GpioConfig`].
+///
+/// With the `serde` feature it serializes as a human-readable struct, which lets a
+/// host tool print a pin-mux table of a dumped configuration. Bits not covered by the
+/// decoded fields, including reserved positions, are kept in `other_bits`, thus
+/// converting back to [`GpioConfig`] restores the exact register value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GpioConfigFields {
+    /// Pin alternate function.
+    pub function: Function,
+    /// Pull direction.
+    pub pull: Pull,
+    /// Drive strength.
+    pub drive: Drive,
+    /// Pin mode as GPIO.
+    pub mode: Mode,
+    /// Interrupt mode.
+    pub interrupt_mode: InterruptMode,
+    /// Whether interrupt is masked.
+    pub interrupt_masked: bool,
+    /// Whether input function is enabled.
+    pub input_enabled: bool,
+    /// Whether output function is enabled.
+    pub output_enabled: bool,
+    /// Whether Schmitt trigger is enabled.
+    pub schmitt_enabled: bool,
+    /// Remaining bits of the register value.
+    pub other_bits: u32,
+}
+
+impl GpioConfigFields {
+    const DECODED: u32 = GpioConfig::INPUT_ENABLE
+        | GpioConfig::SCHMITT
+        | GpioConfig::DRIVE
+        | GpioConfig::PULL
+        | GpioConfig::OUTPUT_ENABLE
+        | GpioConfig::FUNCTION
+        | GpioConfig::INTERRUPT_MODE
+        | GpioConfig::INTERRUPT_MASK
+        | GpioConfig::MODE;
+}
+
+impl From<GpioConfig> for GpioConfigFields {
+    #[inline]
+    fn from(val: GpioConfig) -> Self {
+        Self {
+            function: val.function(),
+            pull: val.pull(),
+            drive: val.drive(),
+            mode: val.mode(),
+            interrupt_mode: val.interrupt_mode(),
+            interrupt_masked: val.is_interrupt_masked(),
+            input_enabled: val.is_input_enabled(),
+            output_enabled: val.is_output_enabled(),
+            schmitt_enabled: val.is_schmitt_enabled(),
+            other_bits: val.0 & !Self::DECODED,
+        }
+    }
+}
+
+impl From<GpioConfigFields> for GpioConfig {
+    #[inline]
+    fn from(val: GpioConfigFields) -> Self {
+        let mut config = GpioConfig(val.other_bits & !GpioConfigFields::DECODED)
+            .set_function(val.function)
+            .set_pull(val.pull)
+            .set_drive(val.drive)
+            .set_mode(val.mode)
+            .set_interrupt_mode(val.interrupt_mode);
+        config = match val.interrupt_masked {
+            true => config.mask_interrupt(),
+            false => config.unmask_interrupt(),
+        };
+        config = match val.input_enabled {
+            true => config.enable_input(),
+            false => config.disable_input(),
+        };
+        config = match val.output_enabled {
+            true => config.enable_output(),
+            false => config.disable_output(),
+        };
+        match val.schmitt_enabled {
+            true => config.enable_schmitt(),
+            false => config.disable_schmitt(),
+        }
+    }
+}
+
+/// Compile-time validated builder for [`GpioConfig`] presets.
+///
+/// The builder starts from the register reset value. When [`build`](Self::build) is
+/// evaluated in a constant, a nonsensical combination fails compilation, e.g. an analog
+/// pad with its digital output driver enabled. Valid configurations build as usual:
+///
+/// ```
+/// use bouffalo_hal::glb::{Pull, v2::{Function, GpioConfigBuilder}};
+///
+/// const BUTTON: bouffalo_hal::glb::v2::GpioConfig = GpioConfigBuilder::new()
+///     .set_function(Function::Gpio)
+///     .enable_input()
+///     .set_pull(Pull::Up)
+///     .build();
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GpioConfigBuilder(GpioConfig);
+
+impl GpioConfigBuilder {
+    /// Create a builder from the GPIO configuration reset value.
+    #[inline]
+    pub const fn new() -> Self {
+        Self(GpioConfig::RESET_VALUE)
+    }
+    /// Enable input function.
+    #[inline]
+    pub const fn enable_input(self) -> Self {
+        Self(self.0.enable_input())
+    }
+    /// Disable input function.
+    #[inline]
+    pub const fn disable_input(self) -> Self {
+        Self(self.0.disable_input())
+    }
+    /// Enable output function.
+    #[inline]
+    pub const fn enable_output(self) -> Self {
+        Self(self.0.enable_output())
+    }
+    /// Disable output function.
+    #[inline]
+    pub const fn disable_output(self) -> Self {
+        Self(self.0.disable_output())
+    }
+    /// Enable Schmitt trigger.
+    #[inline]
+    pub const fn enable_schmitt(self) -> Self {
+        Self(self.0.enable_schmitt())
+    }
+    /// Disable Schmitt trigger.
+    #[inline]
+    pub const fn disable_schmitt(self) -> Self {
+        Self(self.0.disable_schmitt())
+    }
+    /// Mask interrupt.
+    #[inline]
+    pub const fn mask_interrupt(self) -> Self {
+        Self(self.0.mask_interrupt())
+    }
+    /// Unmask interrupt.
+    #[inline]
+    pub const fn unmask_interrupt(self) -> Self {
+        Self(self.0.unmask_interrupt())
+    }
+    /// Set drive strength.
+    #[inline]
+    pub const fn set_drive(self, val: Drive) -> Self {
+        Self(self.0.set_drive(val))
+    }
+    /// Set pin function.
+    #[inline]
+    pub const fn set_function(self, val: Function) -> Self {
+        Self(self.0.set_function(val))
+    }
+    /// Set interrupt mode.
+    #[inline]
+    pub const fn set_interrupt_mode(self, val: InterruptMode) -> Self {
+        Self(self.0.set_interrupt_mode(val))
+    }
+    /// Set GPIO output mode.
+    #[inline]
+    pub const fn set_mode(self, val: Mode) -> Self {
+        Self(self.0.set_mode(val))
+    }
+    /// Set pull direction.
+    #[inline]
+    pub const fn set_pull(self, val: Pull) -> Self {
+        Self(self.0.set_pull(val))
+    }
+    /// Validate and return the configuration.
+    #[inline]
+    pub const fn try_build(self) -> Result<GpioConfig, GpioConfigError> {
+        let config = self.0;
+        if matches!(config.function(), Function::Analog) {
+            if config.is_output_enabled() {
+                return Err(GpioConfigError::AnalogOutput);
+            }
+            if config.is_input_enabled() {
+                return Err(GpioConfigError::AnalogInput);
+            }
+            if !matches!(config.pull(), Pull::None) {
+                return Err(GpioConfigError::AnalogPull);
+            }
+        }
+        if !config.is_interrupt_masked() && !config.is_input_enabled() {
+            return Err(GpioConfigError::InterruptWithoutInput);
+        }
+        Ok(config)
+    }
+    /// Validate and return the configuration, panicking on invalid combinations.
+    ///
+    /// Use it in a constant to turn invalid configurations into compile errors.
+    #[inline]
+    pub const fn build(self) -> GpioConfig {
+        match self.try_build() {
+            Ok(config) => config,
+            Err(GpioConfigError::AnalogOutput) => panic!("analog pin must not enable output"),
+            Err(GpioConfigError::AnalogInput) => {
+                panic!("analog pin must not enable digital input")
+            }
+            Err(GpioConfigError::AnalogPull) => panic!("analog pin must not enable pull"),
+            Err(GpioConfigError::InterruptWithoutInput) => {
+                panic!("unmasked interrupt requires input enabled")
+            }
+        }
+    }
+}
+
+impl Default for GpioConfigBuilder {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Invalid combination in GPIO configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GpioConfigError {
+    /// Output driver is enabled on an analog pin.
+    AnalogOutput,
+    /// Digital input buffer is enabled on an analog pin.
+    AnalogInput,
+    /// Pull resistor is enabled on an analog pin.
+    AnalogPull,
+    /// Interrupt is unmasked while input function is disabled, thus it never triggers.
+    InterruptWithoutInput,
 }
 
 /// Pin alternate function.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum Function {
     Sdh = 0,
@@ -779,10 +1420,49 @@ pub enum Function {
     JtagM0 = 26,
     JtagD0 = 27,
     ClockOut = 31,
+    /// Reserved field value read from hardware.
+    Reserved(u8),
+}
+
+impl Function {
+    /// Get raw field value of this variant.
+    #[inline]
+    pub(crate) const fn bits(self) -> u32 {
+        match self {
+            Self::Sdh => 0,
+            Self::Spi0 => 1,
+            Self::Flash => 2,
+            Self::I2s => 3,
+            Self::Pdm => 4,
+            Self::I2c0 => 5,
+            Self::I2c1 => 6,
+            Self::Uart => 7,
+            Self::Emac => 8,
+            Self::Cam => 9,
+            Self::Analog => 10,
+            Self::Gpio => 11,
+            Self::Pwm0 => 16,
+            Self::Pwm1 => 17,
+            Self::Spi1 => 18,
+            Self::I2c2 => 19,
+            Self::I2c3 => 20,
+            Self::MmUart => 21,
+            Self::DbiB => 22,
+            Self::DbiC => 23,
+            Self::Dpi => 24,
+            Self::JtagLp => 25,
+            Self::JtagM0 => 26,
+            Self::JtagD0 => 27,
+            Self::ClockOut => 31,
+            Self::Reserved(val) => val as u32,
+        }
+    }
 }
 
 /// Pin interrupt mode.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum InterruptMode {
     SyncFallingEdge = 0,
@@ -794,10 +1474,33 @@ pub enum InterruptMode {
     AsyncRisingEdge = 9,
     AsyncLowLevel = 10,
     AsyncHighLevel = 11,
+    /// Reserved field value read from hardware.
+    Reserved(u8),
+}
+
+impl InterruptMode {
+    /// Get raw field value of this variant.
+    #[inline]
+    pub(crate) const fn bits(self) -> u32 {
+        match self {
+            Self::SyncFallingEdge => 0,
+            Self::SyncRisingEdge => 1,
+            Self::SyncLowLevel => 2,
+            Self::SyncHighLevel => 3,
+            Self::SyncBothEdges => 4,
+            Self::AsyncFallingEdge => 8,
+            Self::AsyncRisingEdge => 9,
+            Self::AsyncLowLevel => 10,
+            Self::AsyncHighLevel => 11,
+            Self::Reserved(val) => val as u32,
+        }
+    }
 }
 
 /// Pin mode as GPIO.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum Mode {
     Normal = 0,
@@ -806,6 +1509,125 @@ pub enum Mode {
     BufferedSetClear = 3,
 }
 
+/// Output sequencer timing configuration register.
+///
+/// Pads in [`Mode::Programmable`] output one level per sequencer FIFO entry. Each entry
+/// lasts `total_time` bus clock cycles; an entry of `0` drives the pads high for
+/// `code0_high_time` cycles and an entry of `1` for `code1_high_time` cycles, then low
+/// until the entry ends. An inverted code drives low first, then high.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct GpioTxConfig(u32);
+
+impl GpioTxConfig {
+    const ENABLE: u32 = 1 << 0;
+    const INVERT_CODE0: u32 = 1 << 1;
+    const INVERT_CODE1: u32 = 1 << 2;
+    const TOTAL_TIME: u32 = 0x1ff << 7;
+    const CODE0_HIGH_TIME: u32 = 0xff << 16;
+    const CODE1_HIGH_TIME: u32 = 0xff << 24;
+
+    /// Enable output sequencer.
+    #[inline]
+    pub const fn enable(self) -> Self {
+        Self(self.0 | Self::ENABLE)
+    }
+    /// Disable output sequencer.
+    #[inline]
+    pub const fn disable(self) -> Self {
+        Self(self.0 & !Self::ENABLE)
+    }
+    /// Check if output sequencer is enabled.
+    #[inline]
+    pub const fn is_enabled(self) -> bool {
+        self.0 & Self::ENABLE != 0
+    }
+    /// Invert levels of entry `0`.
+    #[inline]
+    pub const fn enable_invert_code0(self) -> Self {
+        Self(self.0 | Self::INVERT_CODE0)
+    }
+    /// Keep levels of entry `0`.
+    #[inline]
+    pub const fn disable_invert_code0(self) -> Self {
+        Self(self.0 & !Self::INVERT_CODE0)
+    }
+    /// Check if levels of entry `0` are inverted.
+    #[inline]
+    pub const fn is_invert_code0_enabled(self) -> bool {
+        self.0 & Self::INVERT_CODE0 != 0
+    }
+    /// Invert levels of entry `1`.
+    #[inline]
+    pub const fn enable_invert_code1(self) -> Self {
+        Self(self.0 | Self::INVERT_CODE1)
+    }
+    /// Keep levels of entry `1`.
+    #[inline]
+    pub const fn disable_invert_code1(self) -> Self {
+        Self(self.0 & !Self::INVERT_CODE1)
+    }
+    /// Check if levels of entry `1` are inverted.
+    #[inline]
+    pub const fn is_invert_code1_enabled(self) -> bool {
+        self.0 & Self::INVERT_CODE1 != 0
+    }
+    /// Set length of one FIFO entry in bus clock cycles.
+    #[inline]
+    pub const fn set_total_time(self, val: u16) -> Self {
+        Self((self.0 & !Self::TOTAL_TIME) | (((val as u32) << 7) & Self::TOTAL_TIME))
+    }
+    /// Get length of one FIFO entry in bus clock cycles.
+    #[inline]
+    pub const fn total_time(self) -> u16 {
+        ((self.0 & Self::TOTAL_TIME) >> 7) as u16
+    }
+    /// Set high time of entry `0` in bus clock cycles.
+    #[inline]
+    pub const fn set_code0_high_time(self, val: u8) -> Self {
+        Self((self.0 & !Self::CODE0_HIGH_TIME) | ((val as u32) << 16))
+    }
+    /// Get high time of entry `0` in bus clock cycles.
+    #[inline]
+    pub const fn code0_high_time(self) -> u8 {
+        ((self.0 & Self::CODE0_HIGH_TIME) >> 16) as u8
+    }
+    /// Set high time of entry `1` in bus clock cycles.
+    #[inline]
+    pub const fn set_code1_high_time(self, val: u8) -> Self {
+        Self((self.0 & !Self::CODE1_HIGH_TIME) | ((val as u32) << 24))
+    }
+    /// Get high time of entry `1` in bus clock cycles.
+    #[inline]
+    pub const fn code1_high_time(self) -> u8 {
+        ((self.0 & Self::CODE1_HIGH_TIME) >> 24) as u8
+    }
+}
+
+/// Output sequencer FIFO control and status register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct GpioTxFifo(u32);
+
+impl GpioTxFifo {
+    const CLEAR: u32 = 1 << 2;
+    const COUNT: u32 = 0xff << 8;
+
+    /// Depth of output sequencer FIFO in entries.
+    pub const DEPTH: usize = 128;
+
+    /// Discard all entries in FIFO.
+    #[inline]
+    pub const fn clear(self) -> Self {
+        Self(self.0 | Self::CLEAR)
+    }
+    /// Get number of entries waiting in FIFO.
+    #[inline]
+    pub const fn count(self) -> usize {
+        ((self.0 & Self::COUNT) >> 8) as usize
+    }
+}
+
 /// Ldo12uhs configuration register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 #[repr(transparent)]
@@ -842,19 +1664,27 @@ impl Ldo12uhsConfig {
     }
 }
 
+/// Global configuration registers backed by ordinary memory, for unit tests.
+#[cfg(test)]
+pub(crate) type MockRegisterBlock = crate::mock::MockRegisters<RegisterBlock>;
+
 #[cfg(test)]
 mod tests {
     use crate::glb::v2::SpiClockSource;
 
     use super::{
-        Drive, Function, GpioConfig, I2cClockSource, I2cConfig, InterruptMode, Mode, Pull,
-        PwmConfig, PwmSignal0, PwmSignal1, RegisterBlock, SdhConfig, SpiConfig, UartConfig,
-        UartMuxGroup, UartSignal,
+        Drive, Function, GpioConfig, GpioConfigBuilder, GpioConfigError, GpioConfigFields,
+        GpioTxConfig, GpioTxFifo, I2cClockSource, I2cConfig, InterruptMode, MockRegisterBlock,
+        Mode, Pull, PwmConfig, PwmSignal0, PwmSignal1, RegisterBlock, SdhConfig, SpiConfig,
+        UartConfig, UartMuxGroup, UartSignal, pulse_clear,
     };
     use memoffset::offset_of;
 
     #[test]
     fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, sys_config_0), 0x90);
+        assert_eq!(offset_of!(RegisterBlock, sys_config_1), 0x94);
+        assert_eq!(offset_of!(RegisterBlock, clock_out_config), 0x128);
         assert_eq!(offset_of!(RegisterBlock, uart_config), 0x150);
         assert_eq!(offset_of!(RegisterBlock, uart_mux_group), 0x154);
         assert_eq!(offset_of!(RegisterBlock, i2c_config), 0x180);
@@ -869,6 +1699,131 @@ mod tests {
         assert_eq!(offset_of!(RegisterBlock, gpio_output), 0xae4);
         assert_eq!(offset_of!(RegisterBlock, gpio_set), 0xaec);
         assert_eq!(offset_of!(RegisterBlock, gpio_clear), 0xaf4);
+        assert_eq!(offset_of!(RegisterBlock, gpio_tx_config), 0xafc);
+        assert_eq!(offset_of!(RegisterBlock, gpio_tx_fifo), 0xb00);
+        assert_eq!(offset_of!(RegisterBlock, gpio_tx_data), 0xb04);
+    }
+
+    #[test]
+    fn function_save_restore() {
+        let glb = MockRegisterBlock::new();
+        let output_high = GpioConfig::output()
+            .set_mode(Mode::SetClear)
+            .set_output(true);
+        let output_low = output_high.set_output(false);
+        let button = GpioConfig::input().set_pull(Pull::Up);
+        glb.set_raw(0x8c4, output_high.0);
+        glb.set_raw(0x8c4 + 33 * 4, output_low.0);
+        glb.set_raw(0x8c4 + 2 * 4, button.0 | (1 << 20));
+        glb.set_raw(0x8c4 + 45 * 4, 0x1234_5678);
+        let snapshot = glb.save();
+        assert_eq!(snapshot[0], output_high.0);
+        assert_eq!(snapshot[45], 0x1234_5678);
+
+        glb.set_unused_pins(0, Pull::Down);
+        for n in 0..46 {
+            let val = glb.gpio_config[n].read();
+            assert!(!val.is_input_enabled() && !val.is_output_enabled());
+            assert_eq!(val.pull(), Pull::Down);
+        }
+        glb.restore(&snapshot);
+        for n in [0, 33, 45] {
+            assert_eq!(glb.save()[n], snapshot[n] & !GpioConfig::TRIGGER);
+        }
+        assert_eq!(glb.gpio_config[2].read(), button);
+        // Set-clear output latches are written back.
+        assert_eq!(glb.raw(0xaec), 1 << 0);
+        assert_eq!(glb.raw(0xaf8), 1 << 1);
+    }
+
+    #[test]
+    fn function_pending_interrupts() {
+        let glb = MockRegisterBlock::new();
+        let pending = GpioConfig(1 << 21).enable_input().unmask_interrupt();
+        // Pins 0 and 45 have pending interrupts, pin 3 is pending but masked, and
+        // pin 7 is unmasked without pending interrupt.
+        glb.set_raw(0x8c4, pending.0);
+        glb.set_raw(0x8c4 + 45 * 4, pending.0);
+        glb.set_raw(0x8c4 + 3 * 4, pending.mask_interrupt().0);
+        glb.set_raw(
+            0x8c4 + 7 * 4,
+            GpioConfig(0).enable_input().unmask_interrupt().0,
+        );
+        assert_eq!(glb.pending_interrupts(), 1 | (1 << 45));
+    }
+
+    #[test]
+    fn function_clear_interrupts() {
+        let glb = MockRegisterBlock::new();
+        // Bits above the last pin select nothing.
+        let mut pins = glb.masked_pins(1 | (1 << 3) | (1 << 45) | (!0 << 46));
+        assert_eq!(pins.next().map(|(n, _)| n), Some(0));
+        assert_eq!(pins.next().map(|(n, _)| n), Some(3));
+        assert_eq!(pins.next().map(|(n, _)| n), Some(45));
+        assert!(pins.next().is_none());
+
+        let pending = GpioConfig(1 << 21).enable_input().unmask_interrupt();
+        let mut writes = [GpioConfig(0); 3];
+        let mut len = 0;
+        pulse_clear(pending, |v| {
+            writes[len] = v;
+            len += 1;
+        });
+        assert_eq!(writes[..len], [pending.clear_interrupt(), pending]);
+
+        glb.set_raw(0x8c4 + 3 * 4, pending.0);
+        glb.clear_interrupts(1 << 3);
+        // Clear bit is left low after the pulse, while the rest of the register keeps.
+        assert_eq!(glb.gpio_config[3].read(), pending);
+    }
+
+    #[test]
+    fn function_read_write_bank() {
+        let glb = MockRegisterBlock::new();
+        glb.set_raw(0xac8, 0x0000_2a55);
+        assert_eq!(glb.read_bank(1), 0x0000_2a55);
+        glb.write_bank(0, 0x00ff_00f0, 0x0000_0ff0);
+        assert_eq!(glb.raw(0xaec), 0x0000_00f0);
+        assert_eq!(glb.raw(0xaf4), 0x0000_0f00);
+        glb.write_bank(1, u32::MAX, 0x8000_0001);
+        assert_eq!(glb.raw(0xaf0), 0x8000_0001);
+        assert_eq!(glb.raw(0xaf8), 0);
+        // Bits outside the mask never reach either register.
+        for (value, mask) in [(0, 0), (u32::MAX, 0), (0x1234_5678, 0x00ff_ff00)] {
+            glb.write_bank(0, value, mask);
+            assert_eq!(glb.raw(0xaec) & !mask, 0);
+            assert_eq!(glb.raw(0xaf4) & !mask, 0);
+            assert_eq!(glb.raw(0xaec) | glb.raw(0xaf4), mask);
+        }
+    }
+
+    #[test]
+    fn function_set_unused_pins() {
+        let glb = MockRegisterBlock::new();
+        // Pins 0 and 1 are in use with pull up, pin 45 is in use without pulls.
+        let used = GpioConfig(0).enable_input().set_pull(Pull::Up);
+        glb.set_raw(0x8c4, used.0);
+        glb.set_raw(0x8c4 + 4, used.0);
+        // Pin 2 is unused, but configured as output with pull up.
+        let unused = GpioConfig(0)
+            .enable_output()
+            .enable_schmitt()
+            .set_pull(Pull::Up);
+        glb.set_raw(0x8c4 + 8, unused.0);
+        let used_mask = 0b11 | (1 << 45);
+        glb.set_unused_pins(used_mask, Pull::Down);
+        assert_eq!(glb.gpio_config[0].read(), used);
+        assert_eq!(glb.gpio_config[1].read(), used);
+        assert_eq!(glb.gpio_config[45].read(), GpioConfig(0));
+        let low_power = GpioConfig(0).mask_interrupt().set_pull(Pull::Down);
+        for n in 2..45 {
+            assert_eq!(glb.gpio_config[n].read(), low_power);
+        }
+        // Bits above last pin are ignored.
+        glb.set_unused_pins(!0 << 46 | used_mask | 0b100, Pull::None);
+        assert_eq!(glb.gpio_config[3].read().pull(), Pull::None);
+        assert_eq!(glb.gpio_config[2].read().pull(), Pull::Down);
+        assert_eq!(glb.gpio_config[45].read(), GpioConfig(0));
     }
 
     #[test]
@@ -903,11 +1858,11 @@ mod tests {
         assert_eq!(val.0, 0x00000000);
         assert!(!val.is_interrupt_masked());
 
-        assert!(GpioConfig(0x01000000).output());
-        assert!(!GpioConfig(0x00000000).output());
+        assert!(GpioConfig(0x01000000).output_value());
+        assert!(!GpioConfig(0x00000000).output_value());
 
-        assert!(GpioConfig(0x10000000).input());
-        assert!(!GpioConfig(0x00000000).input());
+        assert!(GpioConfig(0x10000000).input_value());
+        assert!(!GpioConfig(0x00000000).input_value());
 
         assert!(GpioConfig(0x00200000).has_interrupt());
         assert!(!GpioConfig(0x00000000).has_interrupt());
@@ -967,6 +1922,211 @@ mod tests {
         assert_eq!(val.pull(), Pull::Down);
     }
 
+    #[test]
+    fn struct_gpio_config_reserved_values() {
+        let val = GpioConfig(0x30);
+        assert_eq!(val.pull(), Pull::Reserved(3));
+        assert_eq!(val.set_pull(val.pull()), val);
+        for n in (12..=15).chain(28..=30) {
+            let val = GpioConfig(n << 8);
+            assert_eq!(val.function(), Function::Reserved(n as u8));
+            assert_eq!(val.set_function(val.function()), val);
+        }
+        for n in (5..=7).chain(12..=15) {
+            let val = GpioConfig(n << 16);
+            assert_eq!(val.interrupt_mode(), InterruptMode::Reserved(n as u8));
+            assert_eq!(val.set_interrupt_mode(val.interrupt_mode()), val);
+        }
+        // Reserved values never overflow into neighbouring fields.
+        let val = GpioConfig(0).set_function(Function::Reserved(0xff));
+        assert_eq!(val.0, 0x0000_1f00);
+    }
+
+    #[test]
+    fn struct_gpio_config_significant_eq() {
+        let val = GpioConfig::RESET_VALUE;
+        let latched = GpioConfig(val.0 | 0x1120_0000)
+            .set()
+            .clear()
+            .clear_interrupt();
+        assert_ne!(val, latched);
+        assert!(val.significant_eq(latched));
+        assert!(latched.significant_eq(val));
+
+        assert!(!val.significant_eq(val.enable_output()));
+        assert!(!val.significant_eq(val.set_pull(Pull::Down)));
+        assert!(!val.significant_eq(val.unmask_interrupt()));
+        assert!(!val.significant_eq(val.set_mode(Mode::SetClear)));
+    }
+
+    #[test]
+    fn struct_gpio_config_bytes() {
+        let val = GpioConfig(0x4b40_0b43);
+        assert_eq!(val.to_bytes(), [0x43, 0x0b, 0x40, 0x4b]);
+        assert_eq!(GpioConfig::from_bytes(val.to_bytes()), val);
+        for raw in [0, 1, 0x8000_0000, u32::MAX, GpioConfig::RESET_VALUE.0] {
+            assert_eq!(GpioConfig::from_bytes(GpioConfig(raw).to_bytes()).0, raw);
+        }
+    }
+
+    #[test]
+    fn struct_gpio_config_presets() {
+        const OUTPUT: GpioConfig = GpioConfig::output();
+        assert_eq!(OUTPUT.bits(), 0x0040_0b40);
+        assert!(OUTPUT.is_output_enabled() && !OUTPUT.is_input_enabled());
+        assert_eq!(OUTPUT.function(), Function::Gpio);
+        assert_eq!(OUTPUT.mode(), Mode::Normal);
+        assert_eq!(OUTPUT.pull(), Pull::None);
+        assert_eq!(OUTPUT.drive(), Drive::Drive0);
+
+        const INPUT: GpioConfig = GpioConfig::input();
+        assert_eq!(INPUT.bits(), 0x0040_0b03);
+        assert!(INPUT.is_input_enabled() && !INPUT.is_output_enabled());
+        assert!(INPUT.is_schmitt_enabled());
+        assert_eq!(INPUT.function(), Function::Gpio);
+
+        const ANALOG: GpioConfig = GpioConfig::analog();
+        assert_eq!(ANALOG.bits(), 0x0040_0a00);
+        assert!(!ANALOG.is_input_enabled() && !ANALOG.is_output_enabled());
+        assert_eq!(ANALOG.function(), Function::Analog);
+
+        let button = GpioConfig::input().set_pull(Pull::Up);
+        assert_eq!(GpioConfig::from_bits(button.bits()), button);
+        assert_eq!(GpioConfig::from_bits(0x1234_5678).bits(), 0x1234_5678);
+    }
+
+    #[test]
+    fn struct_gpio_config_debug() {
+        extern crate std;
+        let val = GpioConfig::input().set_pull(Pull::Up);
+        assert_eq!(
+            std::format!("{val:?}"),
+            "GpioConfig { bits: 0x00400b13, function: Gpio, pull: Up, drive: Drive0, \
+             mode: Normal, input_enabled: true, output_enabled: false, interrupt_masked: true }"
+        );
+        // Interrupt mode 5 is reserved, but not decoded without interrupt function.
+        let val = GpioConfig(0x0005_0b01);
+        assert!(!std::format!("{val:?}").contains("interrupt_mode"));
+        let val = val.set_interrupt_mode(InterruptMode::AsyncRisingEdge).0 | (1 << 21);
+        assert!(
+            std::format!("{:?}", GpioConfig(val)).ends_with(", interrupt_mode: AsyncRisingEdge }")
+        );
+    }
+
+    #[test]
+    fn struct_gpio_config_bits_round_trip() {
+        // Random register values from a fixed-seed xorshift generator.
+        let mut state = 0x2545_f491_u32;
+        for _ in 0..10_000 {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            assert_eq!(GpioConfig::from_bits(state).bits(), state);
+            let fields = GpioConfigFields::from(GpioConfig::from_bits(state));
+            assert_eq!(GpioConfig::from(fields).bits(), state);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn struct_gpio_config_serde() {
+        let val = GpioConfig::RESET_VALUE.set_function(Function::Uart);
+        let json = serde_json::to_string(&val).unwrap();
+        assert_eq!(serde_json::from_str::<GpioConfig>(&json).unwrap(), val);
+        let json = serde_json::to_string(&val.function()).unwrap();
+        assert_eq!(json, "\"Uart\"");
+        assert_eq!(
+            serde_json::from_str::<Function>(&json).unwrap(),
+            Function::Uart
+        );
+
+        let val = GpioConfig::input().set_pull(Pull::Up).0 | 0x0000_8000;
+        let fields = GpioConfigFields::from(GpioConfig(val));
+        let json = serde_json::to_string(&fields).unwrap();
+        assert_eq!(
+            json,
+            "{\"function\":\"Gpio\",\"pull\":\"Up\",\"drive\":\"Drive0\",\"mode\":\"Normal\",\
+             \"interrupt_mode\":\"SyncFallingEdge\",\"interrupt_masked\":true,\
+             \"input_enabled\":true,\"output_enabled\":false,\"schmitt_enabled\":true,\
+             \"other_bits\":32768}"
+        );
+        let fields = serde_json::from_str::<GpioConfigFields>(&json).unwrap();
+        assert_eq!(GpioConfig::from(fields).0, val);
+    }
+
+    #[test]
+    fn struct_gpio_config_builder_functions() {
+        const OUTPUT: GpioConfig = GpioConfigBuilder::new()
+            .set_function(Function::Gpio)
+            .disable_input()
+            .enable_output()
+            .set_drive(Drive::Drive1)
+            .build();
+        assert!(OUTPUT.is_output_enabled());
+        assert_eq!(OUTPUT.drive(), Drive::Drive1);
+        const INTERRUPT: GpioConfig = GpioConfigBuilder::new()
+            .enable_input()
+            .unmask_interrupt()
+            .set_interrupt_mode(InterruptMode::SyncRisingEdge)
+            .build();
+        assert!(!INTERRUPT.is_interrupt_masked());
+        const ANALOG: GpioConfig = GpioConfigBuilder::new()
+            .set_function(Function::Analog)
+            .disable_input()
+            .disable_output()
+            .set_pull(Pull::None)
+            .build();
+        assert_eq!(ANALOG.function(), Function::Analog);
+
+        let analog = GpioConfigBuilder::new().set_function(Function::Analog);
+        assert_eq!(
+            analog.enable_output().try_build(),
+            Err(GpioConfigError::AnalogOutput)
+        );
+        assert_eq!(
+            analog.enable_input().try_build(),
+            Err(GpioConfigError::AnalogInput)
+        );
+        assert_eq!(
+            analog.set_pull(Pull::Up).try_build(),
+            Err(GpioConfigError::AnalogPull)
+        );
+        assert_eq!(
+            GpioConfigBuilder::new()
+                .disable_input()
+                .unmask_interrupt()
+                .try_build(),
+            Err(GpioConfigError::InterruptWithoutInput)
+        );
+    }
+
+    #[test]
+    fn struct_gpio_tx_config_functions() {
+        let val = GpioTxConfig(0)
+            .set_total_time(0x1ff)
+            .set_code0_high_time(0x12)
+            .set_code1_high_time(0xff)
+            .enable();
+        assert_eq!(val.0, 0xff12_ff81);
+        assert!(val.is_enabled());
+        assert_eq!(val.total_time(), 0x1ff);
+        assert_eq!(val.code0_high_time(), 0x12);
+        assert_eq!(val.code1_high_time(), 0xff);
+        let val = val.set_total_time(0x200).disable();
+        assert_eq!(val.0, 0xff12_0000);
+        assert!(!val.is_enabled());
+        let val = val.enable_invert_code0().enable_invert_code1();
+        assert_eq!(val.0, 0xff12_0006);
+        assert!(val.is_invert_code0_enabled() && val.is_invert_code1_enabled());
+        let val = val.disable_invert_code0();
+        assert_eq!(val.0, 0xff12_0004);
+        assert!(!val.is_invert_code0_enabled());
+
+        let val = GpioTxFifo(0x0000_4000);
+        assert_eq!(val.count(), 0x40);
+        assert_eq!(val.clear().0, 0x0000_4004);
+    }
+
     #[test]
     fn struct_uart_config_functions() {
         let mut config = UartConfig(0x0);
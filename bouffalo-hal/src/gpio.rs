@@ -87,7 +87,7 @@
 //! # fn main() {
 //! # let glb: &bouffalo_hal::glb::RegisterBlock = unsafe { &*core::ptr::null() };
 //! # let p: Peripherals = Peripherals { gpio: Pads::__pads_from_glb(glb), glb: (), uart0: UART0 };
-//! # let clocks = Clocks { xtal: Hertz(40_000_000) };
+//! # let clocks = bouffalo_hal::clocks::ClockConfig::new(Hertz(40_000_000)).freeze();
 //! // Prepare UART transmit and receive pads by converting io14 and io15 into
 //! // UART signal alternate mode.
 //! # #[cfg(feature = "glb-v2")]
@@ -117,21 +117,60 @@
 //! ```
 
 mod alternate;
+mod clock_out;
 mod convert;
+mod debounce;
 mod disabled;
+mod dyn_pin;
+mod emergency_stop;
+mod encoder;
+mod events;
+mod frequency;
 mod gpio_group;
+mod guard;
 mod input;
+#[cfg(any(doc, feature = "glb-v2"))]
+mod jtag;
+mod locked;
 mod output;
 mod pad_dummy;
 mod pad_v1;
 mod pad_v2;
+mod port;
+mod self_test;
+mod sequencer;
 mod typestate;
 
+pub use clock_out::{
+    CLOCK_OUT_CHANNELS, CLOCK_OUT_DIVIDE_MAX, ClockOutError, ClockOutPin, ClockOutSource,
+};
 pub use convert::{IntoPad, IntoPadv2};
+pub use debounce::Debouncer;
+pub use dyn_pin::{DynMode, DynPin, DynPinError};
+pub use emergency_stop::{EmergencyStop, Failsafe, FailsafeError};
+pub use encoder::Encoder;
+pub use events::{Edge, Event, GpioEvents, Overflow};
+#[cfg(any(doc, target_arch = "riscv32", target_arch = "riscv64"))]
+pub use frequency::FrequencyCounter;
 pub use gpio_group::Pads;
+#[cfg(any(doc, feature = "glb-v2"))]
+pub use jtag::JtagD0Pins;
+pub use output::{Output, OutputType};
+pub use port::{Port, Port0, Port1};
+pub use self_test::{SelfTestError, loopback_test};
+pub use sequencer::{SEQUENCER_DEPTH, SEQUENCER_ENTRY_MAX, SequencerError, SequencerPin};
 pub use typestate::*;
-pub use {alternate::Alternate, disabled::Disabled, input::Input, output::Output};
-pub use {pad_v1::Padv1, pad_v2::Padv2};
+pub use {alternate::Alternate, disabled::Disabled, input::Input, locked::Locked};
+pub use {
+    pad_v1::Padv1,
+    pad_v2::{CheckFunctions, Padv2, PinConflict},
+};
+
+/// Error type of GPIO pad operations.
+///
+/// Reading and writing GPIO pads never fails, allowing generic drivers to rule out
+/// pad errors at compile time.
+pub type Error = core::convert::Infallible;
 
 cfg_if::cfg_if! {
     if #[cfg(feature = "glb-v1")] {
@@ -1,17 +1,53 @@
 use super::{
     convert::IntoPad,
     input::Input,
+    locked::Locked,
     output::Output,
-    typestate::{Floating, PullDown, PullUp},
+    typestate::{Digital, Floating, PullDown, PullUp},
 };
 #[cfg(any(doc, feature = "glb-v2"))]
 use super::{convert::IntoPadv2, typestate};
+use crate::glb::{Drive, Pull};
 
 /// GPIO pad with alternate mode.
 pub struct Alternate<'a, const N: usize, M> {
     inner: super::Inner<'a, N, M>,
 }
 
+impl<'a, const N: usize, M> Alternate<'a, N, M> {
+    /// Lock configuration of this pad, keeping it in current alternate function.
+    #[inline]
+    pub fn lock(self) -> Locked<Self> {
+        Locked::new(self)
+    }
+    /// Get drive strength of this pad.
+    #[inline]
+    pub fn drive(&self) -> Drive {
+        self.inner.drive()
+    }
+    /// Get pull direction of this pad.
+    #[inline]
+    pub fn pull(&self) -> Pull {
+        self.inner.pull()
+    }
+}
+
+// Analog pads keep pull and drive turned off to leave the analog signal unloaded.
+impl<'a, const N: usize, M: Digital> Alternate<'a, N, M> {
+    /// Set drive strength of this pad in place, keeping its current mode.
+    #[inline]
+    pub fn set_drive(&mut self, val: Drive) -> &mut Self {
+        self.inner.set_drive(val);
+        self
+    }
+    /// Set pull direction of this pad in place, keeping its current mode.
+    #[inline]
+    pub fn set_pull(&mut self, val: Pull) -> &mut Self {
+        self.inner.set_pull(val);
+        self
+    }
+}
+
 impl<'a, const N: usize, M> IntoPad<'a, N> for Alternate<'a, N, M> {
     #[inline]
     fn into_pull_up_output(self) -> Output<'a, N, PullUp> {
@@ -85,6 +121,65 @@ impl<'a, const N: usize, M> IntoPadv2<'a, N> for Alternate<'a, N, M> {
     fn into_jtag_lp(self) -> Alternate<'a, N, typestate::JtagLp> {
         self.inner.into_jtag_lp().into()
     }
+    #[inline]
+    fn into_i2s(self) -> Alternate<'a, N, typestate::I2s> {
+        self.inner.into_i2s().into()
+    }
+    #[inline]
+    fn into_pdm(self) -> Alternate<'a, N, typestate::Pdm> {
+        self.inner.into_pdm().into()
+    }
+    #[inline]
+    fn into_emac(self) -> Alternate<'a, N, typestate::Emac> {
+        self.inner.into_emac().into()
+    }
+    #[inline]
+    fn into_cam(self) -> Alternate<'a, N, typestate::Cam> {
+        self.inner.into_cam().into()
+    }
+    #[inline]
+    fn into_dbi_b(self) -> Alternate<'a, N, typestate::DbiB> {
+        self.inner.into_dbi_b().into()
+    }
+    #[inline]
+    fn into_dbi_c(self) -> Alternate<'a, N, typestate::DbiC> {
+        self.inner.into_dbi_c().into()
+    }
+    #[inline]
+    fn into_dpi(self) -> Alternate<'a, N, typestate::Dpi> {
+        self.inner.into_dpi().into()
+    }
+    #[inline]
+    fn into_clock_out(
+        self,
+        source: super::ClockOutSource,
+        divide: u8,
+    ) -> Result<super::ClockOutPin<'a, N>, (Self, super::ClockOutError)> {
+        self.inner
+            .into_clock_out(source, divide)
+            .map_err(|(inner, e)| (inner.into(), e))
+    }
+    #[inline]
+    fn into_sequencer(
+        self,
+        tick_cycles: u16,
+    ) -> Result<super::SequencerPin<'a, N>, (Self, super::SequencerError)> {
+        self.inner
+            .into_sequencer(tick_cycles)
+            .map_err(|(inner, e)| (inner.into(), e))
+    }
+    #[inline]
+    fn into_analog(self) -> Alternate<'a, N, typestate::Analog> {
+        self.inner.into_analog().into()
+    }
+    #[inline]
+    fn into_open_drain_output(self) -> Output<'a, N, typestate::OpenDrain<Floating>> {
+        self.inner.into_open_drain_output().into()
+    }
+    #[inline]
+    fn into_pull_up_open_drain_output(self) -> Output<'a, N, typestate::OpenDrain<PullUp>> {
+        self.inner.into_pull_up_open_drain_output().into()
+    }
 }
 
 impl<'a, const N: usize, M> From<super::Inner<'a, N, M>> for Alternate<'a, N, M> {
@@ -93,3 +188,39 @@ impl<'a, const N: usize, M> From<super::Inner<'a, N, M>> for Alternate<'a, N, M>
         Self { inner }
     }
 }
+
+#[cfg(any(doc, feature = "glb-v2"))]
+impl<'a, const N: usize, const I: usize> super::CheckFunctions
+    for Alternate<'a, N, typestate::Spi<I>>
+{
+    #[inline]
+    fn check_functions(&self) -> Result<(), super::PinConflict> {
+        self.inner.check_function(typestate::Spi::<I>::FUNCTION_V2)
+    }
+}
+
+#[cfg(any(doc, feature = "glb-v2"))]
+impl<'a, const N: usize, const I: usize> super::CheckFunctions
+    for Alternate<'a, N, typestate::I2c<I>>
+{
+    #[inline]
+    fn check_functions(&self) -> Result<(), super::PinConflict> {
+        self.inner.check_function(typestate::I2c::<I>::FUNCTION_V2)
+    }
+}
+
+#[cfg(any(doc, feature = "glb-v2"))]
+impl<'a, const N: usize> super::CheckFunctions for Alternate<'a, N, typestate::Uart> {
+    #[inline]
+    fn check_functions(&self) -> Result<(), super::PinConflict> {
+        self.inner.check_function(crate::glb::v2::Function::Uart)
+    }
+}
+
+#[cfg(any(doc, feature = "glb-v2"))]
+impl<'a, const N: usize> super::CheckFunctions for Alternate<'a, N, typestate::MmUart> {
+    #[inline]
+    fn check_functions(&self) -> Result<(), super::PinConflict> {
+        self.inner.check_function(crate::glb::v2::Function::MmUart)
+    }
+}
@@ -0,0 +1,228 @@
+use super::guard::{PadGuard, Shared};
+use super::{Floating, alternate::Alternate, convert::IntoPad, input::Input, typestate};
+use crate::clocks::{Clocks, RC32M};
+use crate::glb::v2;
+use embedded_time::rate::Hertz;
+
+/// Number of clock output channels; pad `ioN` outputs channel `N % 4`.
+pub const CLOCK_OUT_CHANNELS: usize = 4;
+/// Largest clock output divider.
+pub const CLOCK_OUT_DIVIDE_MAX: u8 = 16;
+
+/// Internal clock that can be exported on a clock output pad.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClockOutSource {
+    /// Internal 32-MHz RC oscillator.
+    Rc32m,
+    /// External crystal oscillator.
+    Xtal,
+    /// Peripheral bus clock.
+    BusClock,
+    /// Camera sensor reference clock.
+    CameraReference,
+    /// Inter-IC Sound reference clock.
+    I2sReference,
+}
+
+/// Clock sources of each channel, indexed by source selection value.
+const ROUTES: [[ClockOutSource; 4]; CLOCK_OUT_CHANNELS] = {
+    use ClockOutSource::*;
+    [
+        [CameraReference, I2sReference, Rc32m, Xtal],
+        [CameraReference, I2sReference, Rc32m, Xtal],
+        [CameraReference, I2sReference, Xtal, BusClock],
+        [CameraReference, I2sReference, Xtal, BusClock],
+    ]
+};
+
+/// Clock output configuration error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClockOutError {
+    /// Clock source cannot be routed to this pad.
+    NotRoutable,
+    /// Divider is zero or larger than [`CLOCK_OUT_DIVIDE_MAX`].
+    InvalidDivider,
+    /// Another pad outputs a different clock on the same channel.
+    ChannelInUse,
+}
+
+/// Internal clock output on pad `N`.
+///
+/// Switching a pad with `into_clock_out` exports an internal clock on it, which is
+/// useful for checking the clock tree with an oscilloscope. The pad is restored to a
+/// floating input when this guard is dropped.
+///
+/// ```no_run
+/// # use bouffalo_hal::gpio::{ClockOutSource, Disabled, IntoPadv2};
+/// # fn example(io8: Disabled<'static, 8>) {
+/// let clock_out = io8.into_clock_out(ClockOutSource::Rc32m, 1).ok().unwrap();
+/// // Measure 32 MHz on io8.
+/// drop(clock_out);
+/// # }
+/// ```
+///
+/// Pads `ioN` and `ioM` share one channel if `N % 4 == M % 4`, thus they can only
+/// output the same clock at once.
+pub struct ClockOutPin<'a, const N: usize> {
+    guard: PadGuard<'a, N, typestate::ClockOut>,
+}
+
+impl<'a, const N: usize> ClockOutPin<'a, N> {
+    #[cfg(any(doc, feature = "glb-v2"))]
+    #[inline]
+    pub(crate) fn new(
+        base: &'a v2::RegisterBlock,
+        pad: Alternate<'a, N, typestate::ClockOut>,
+    ) -> Self {
+        Self {
+            guard: PadGuard::new(base, pad),
+        }
+    }
+    /// Clock source being output.
+    #[inline]
+    pub fn source(&self) -> ClockOutSource {
+        let channel = N % CLOCK_OUT_CHANNELS;
+        let select = self.guard.base.clock_out_config.read().source(channel);
+        ROUTES[channel][select as usize]
+    }
+    /// Clock divider in use.
+    #[inline]
+    pub fn divide(&self) -> u8 {
+        self.guard
+            .base
+            .clock_out_config
+            .read()
+            .divide(N % CLOCK_OUT_CHANNELS)
+            + 1
+    }
+    /// Expected output frequency under `clocks`, or `None` if the source frequency is
+    /// unknown.
+    #[inline]
+    pub fn frequency(&self, clocks: &Clocks) -> Option<Hertz> {
+        let source = match self.source() {
+            ClockOutSource::Rc32m => RC32M,
+            ClockOutSource::Xtal => clocks.xtal(),
+            ClockOutSource::BusClock => clocks.bus_clock(),
+            ClockOutSource::I2sReference => clocks.i2s_clock()?,
+            ClockOutSource::CameraReference => return None,
+        };
+        Some(Hertz(source.0 / self.divide() as u32))
+    }
+    /// Stop clock output, returning the pad as floating input.
+    #[inline]
+    pub fn free(self) -> Input<'a, N, Floating> {
+        self.guard.free()
+    }
+}
+
+/// Select `source` divided by `divide` on the channel of pad `n`.
+#[cfg(any(doc, feature = "glb-v2"))]
+#[inline]
+pub(crate) fn route(
+    base: &v2::RegisterBlock,
+    n: usize,
+    source: ClockOutSource,
+    divide: u8,
+) -> Result<(), ClockOutError> {
+    let channel = n % CLOCK_OUT_CHANNELS;
+    let Some(select) = ROUTES[channel].iter().position(|&s| s == source) else {
+        return Err(ClockOutError::NotRoutable);
+    };
+    if divide == 0 || divide > CLOCK_OUT_DIVIDE_MAX {
+        return Err(ClockOutError::InvalidDivider);
+    }
+    let config = base.clock_out_config.read();
+    if super::guard::in_use::<typestate::ClockOut>(base, n)
+        && (config.source(channel) as usize != select || config.divide(channel) != divide - 1)
+    {
+        return Err(ClockOutError::ChannelInUse);
+    }
+    unsafe {
+        base.clock_out_config.write(
+            config
+                .set_source(channel, select as u8)
+                .set_divide(channel, divide - 1)
+                .enable(channel),
+        )
+    };
+    Ok(())
+}
+
+impl Shared for typestate::ClockOut {
+    type Released<'a, const N: usize> = Input<'a, N, Floating>;
+
+    #[inline]
+    fn shares(n: usize, m: usize, config: v2::GpioConfig) -> bool {
+        m % CLOCK_OUT_CHANNELS == n % CLOCK_OUT_CHANNELS
+            && config.function() == v2::Function::ClockOut
+    }
+    #[inline]
+    fn release<'a, const N: usize>(pad: Alternate<'a, N, Self>) -> Input<'a, N, Floating> {
+        pad.into_floating_input()
+    }
+    #[inline]
+    fn stop(base: &v2::RegisterBlock, n: usize) {
+        let channel = n % CLOCK_OUT_CHANNELS;
+        unsafe { base.clock_out_config.modify(|v| v.disable(channel)) };
+    }
+}
+
+#[cfg(all(test, feature = "glb-v2"))]
+mod tests {
+    use super::{ClockOutError, ClockOutSource};
+    use crate::clocks::ClockConfig;
+    use crate::glb::v2::{Function, MockRegisterBlock};
+    use crate::gpio::{Disabled, IntoPad, IntoPadv2, Padv2};
+    use embedded_time::rate::Hertz;
+
+    #[test]
+    fn struct_clock_out_pin() {
+        let glb = MockRegisterBlock::new();
+        let clocks = ClockConfig::new(Hertz(40_000_000)).freeze();
+        let io8 = Disabled::from(Padv2::<8, _>::__from_glb(&glb));
+        let clock_out = io8.into_clock_out(ClockOutSource::Rc32m, 1).ok().unwrap();
+        assert_eq!(glb.gpio_config[8].read().function(), Function::ClockOut);
+        assert_eq!(glb.raw(0x128), 0x0000_0102);
+        assert_eq!(clock_out.source(), ClockOutSource::Rc32m);
+        assert_eq!(clock_out.frequency(&clocks), Some(Hertz(32_000_000)));
+
+        // io0 shares channel 0 with io8.
+        let io0 = Disabled::from(Padv2::<0, _>::__from_glb(&glb));
+        let Err((io0, e)) = io0.into_clock_out(ClockOutSource::Xtal, 1) else {
+            panic!("channel 0 is in use");
+        };
+        assert_eq!(e, ClockOutError::ChannelInUse);
+        // The pad is handed back untouched and can be used otherwise.
+        assert_ne!(glb.gpio_config[0].read().function(), Function::ClockOut);
+        let _ = io0.into_floating_input();
+        let io4 = Disabled::from(Padv2::<4, _>::__from_glb(&glb));
+        let shared = io4.into_clock_out(ClockOutSource::Rc32m, 1).ok().unwrap();
+        drop(clock_out);
+        assert_eq!(glb.gpio_config[8].read().function(), Function::Gpio);
+        assert_eq!(glb.raw(0x128), 0x0000_0102);
+        let _ = shared.free();
+        assert_eq!(glb.raw(0x128), 0x0000_0002);
+
+        let io2 = Disabled::from(Padv2::<2, _>::__from_glb(&glb));
+        assert_eq!(
+            io2.into_clock_out(ClockOutSource::Rc32m, 1)
+                .err()
+                .map(|(_, e)| e),
+            Some(ClockOutError::NotRoutable)
+        );
+        let io3 = Disabled::from(Padv2::<3, _>::__from_glb(&glb));
+        assert_eq!(
+            io3.into_clock_out(ClockOutSource::BusClock, 17)
+                .err()
+                .map(|(_, e)| e),
+            Some(ClockOutError::InvalidDivider)
+        );
+        let io7 = Disabled::from(Padv2::<7, _>::__from_glb(&glb));
+        let clock_out = io7
+            .into_clock_out(ClockOutSource::BusClock, 4)
+            .ok()
+            .unwrap();
+        assert_eq!(glb.raw(0x128), 0x3000_08c2);
+        assert_eq!(clock_out.frequency(&clocks), Some(Hertz(10_000_000)));
+    }
+}
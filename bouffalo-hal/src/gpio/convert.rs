@@ -1,7 +1,9 @@
 use super::{
     alternate::Alternate,
+    clock_out::{ClockOutError, ClockOutPin, ClockOutSource},
     input::Input,
     output::Output,
+    sequencer::{SequencerError, SequencerPin},
     typestate::{self, Floating, PullDown, PullUp},
 };
 
@@ -19,6 +21,17 @@ pub trait IntoPad<'a, const N: usize> {
     fn into_pull_down_input(self) -> Input<'a, N, PullDown>;
     /// Configures the pad to operate as a floating input pad.
     fn into_floating_input(self) -> Input<'a, N, Floating>;
+    /// Configures the pad to operate as a floating input pad with schmitt trigger
+    /// enabled.
+    #[inline]
+    fn into_schmitt_input(self) -> Input<'a, N, Floating>
+    where
+        Self: Sized,
+    {
+        let mut pad = self.into_floating_input();
+        pad.enable_schmitt();
+        pad
+    }
 }
 
 /// Trait for GLBv2 pad mode conversations.
@@ -45,4 +58,77 @@ pub trait IntoPadv2<'a, const N: usize> {
     fn into_jtag_m0(self) -> Alternate<'a, N, typestate::JtagM0>;
     /// Configures the pin to operate as LP core JTAG.
     fn into_jtag_lp(self) -> Alternate<'a, N, typestate::JtagLp>;
+    /// Configures the pin to operate as an Inter-IC Sound signal pin.
+    fn into_i2s(self) -> Alternate<'a, N, typestate::I2s>;
+    /// Configures the pin to operate as a Pulse Density Modulation microphone pin.
+    fn into_pdm(self) -> Alternate<'a, N, typestate::Pdm>;
+    /// Configures the pin to operate as an Ethernet reduced media-independent interface pin.
+    fn into_emac(self) -> Alternate<'a, N, typestate::Emac>;
+    /// Configures the pin to operate as a parallel camera interface pin.
+    fn into_cam(self) -> Alternate<'a, N, typestate::Cam>;
+    /// Configures the pin to operate as a display bus interface type B pin.
+    fn into_dbi_b(self) -> Alternate<'a, N, typestate::DbiB>;
+    /// Configures the pin to operate as a display bus interface type C pin.
+    fn into_dbi_c(self) -> Alternate<'a, N, typestate::DbiC>;
+    /// Configures the pin to operate as a display pixel interface pin.
+    fn into_dpi(self) -> Alternate<'a, N, typestate::Dpi>;
+    /// Configures the pin to output internal clock `source` divided by `divide`.
+    ///
+    /// Returns an error along with the unchanged pin if `source` cannot be routed to this
+    /// pin, `divide` is out of range, or another pin already outputs a different clock on
+    /// the same channel.
+    fn into_clock_out(
+        self,
+        source: ClockOutSource,
+        divide: u8,
+    ) -> Result<ClockOutPin<'a, N>, (Self, ClockOutError)>
+    where
+        Self: Sized;
+    /// Configures the pin to output patterns from the output sequencer, with steps
+    /// counted in ticks of `tick_cycles` bus clock cycles.
+    ///
+    /// Returns an error along with the untouched pin if `tick_cycles` is zero or longer
+    /// than [`SEQUENCER_ENTRY_MAX`](super::SEQUENCER_ENTRY_MAX).
+    fn into_sequencer(
+        self,
+        tick_cycles: u16,
+    ) -> Result<SequencerPin<'a, N>, (Self, SequencerError)>
+    where
+        Self: Sized;
+    /// Configures the pin to operate as an analog signal pin.
+    ///
+    /// Input buffer, output driver and pulls are disabled and function is set to
+    /// analog by one register write, so the pad never loads the analog signal in an
+    /// intermediate state. The returned pad has no digital level operations, nor pull
+    /// and drive strength settings:
+    ///
+    /// ```compile_fail
+    /// # use bouffalo_hal::gpio::{Disabled, IntoPadv2};
+    /// use embedded_hal::digital::OutputPin;
+    ///
+    /// fn drive(pad: Disabled<'static, 17>) {
+    ///     let mut pot = pad.into_analog();
+    ///     // Error: analog pads cannot be driven.
+    ///     pot.set_high().ok();
+    /// }
+    /// ```
+    ///
+    /// Use the [`IntoPad`] functions to turn it back into a digital pad.
+    fn into_analog(self) -> Alternate<'a, N, typestate::Analog>;
+    /// Configures the pad to operate as a floating open-drain output pad.
+    ///
+    /// `set_low` actively pulls the line low, while `set_high` only releases it: output
+    /// is disabled, the pad goes high-impedance and the high level is set by an external
+    /// pull-up resistor. The output latch is kept low, so the pad never drives a high
+    /// level. The pad starts released. Input buffer stays enabled, thus the line level
+    /// can be read back, e.g. for software I2C or 1-Wire.
+    ///
+    /// The returned pad is typed [`OpenDrain`](typestate::OpenDrain); use
+    /// [`Output::into_push_pull`] to drive both levels.
+    fn into_open_drain_output(self) -> Output<'a, N, typestate::OpenDrain<Floating>>;
+    /// Configures the pad to operate as an open-drain output pad with internal pull-up.
+    ///
+    /// Same as [`into_open_drain_output`](Self::into_open_drain_output), except that the
+    /// released line is pulled high by the internal pull-up resistor.
+    fn into_pull_up_open_drain_output(self) -> Output<'a, N, typestate::OpenDrain<PullUp>>;
 }
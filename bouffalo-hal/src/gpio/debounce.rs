@@ -0,0 +1,186 @@
+//! Input debouncing by a sliding window of level samples.
+//!
+//! [`Debouncer::update`] samples the wrapped pin once per call and shifts the sample
+//! into a 32-bit history. The debounced state changes only after the last `samples`
+//! readings all agree, thus the debounce time is `samples` times the call period: with
+//! `update` called every millisecond and a window of 8 samples, a press is reported
+//! 8 ms after the contacts stop bouncing. Mechanical buttons usually bounce for less
+//! than 10 ms; longer windows reject more noise at the cost of response delay.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use bouffalo_hal::gpio::{Debouncer, Input, PullUp};
+//! # use embedded_hal::delay::DelayNs;
+//! # fn example(button: Input<'static, 22, PullUp>, delay: &mut impl DelayNs) {
+//! // Button pulls the pin low when pressed.
+//! let mut button = Debouncer::new(button, 8);
+//! loop {
+//!     button.update().ok();
+//!     if button.just_pressed() {
+//!         // Handle one clean press.
+//!     }
+//!     delay.delay_ms(1);
+//! }
+//! # }
+//! ```
+use embedded_hal::digital::InputPin;
+
+/// Debounced input pin.
+///
+/// Works with any [`InputPin`], including typed pads and [`DynPin`](super::DynPin).
+pub struct Debouncer<P> {
+    pin: P,
+    window: u32,
+    history: u32,
+    active_low: bool,
+    pressed: bool,
+    changed: bool,
+}
+
+impl<P: InputPin> Debouncer<P> {
+    /// Debounce `pin` over a window of `samples` readings, low level meaning pressed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `samples` is zero or larger than 32.
+    #[inline]
+    pub fn new(pin: P, samples: u32) -> Self {
+        Self::with_level(pin, samples, true)
+    }
+    /// Debounce `pin` over a window of `samples` readings, high level meaning pressed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `samples` is zero or larger than 32.
+    #[inline]
+    pub fn new_active_high(pin: P, samples: u32) -> Self {
+        Self::with_level(pin, samples, false)
+    }
+    #[inline]
+    fn with_level(pin: P, samples: u32, active_low: bool) -> Self {
+        assert!(
+            (1..=32).contains(&samples),
+            "debounce window must be 1 to 32 samples"
+        );
+        Self {
+            pin,
+            window: u32::MAX >> (32 - samples),
+            history: 0,
+            active_low,
+            pressed: false,
+            changed: false,
+        }
+    }
+    /// Sample the pin once and update the debounced state; call this periodically.
+    #[inline]
+    pub fn update(&mut self) -> Result<(), P::Error> {
+        let pressed = if self.active_low {
+            self.pin.is_low()?
+        } else {
+            self.pin.is_high()?
+        };
+        self.history = (self.history << 1) | pressed as u32;
+        let stable = match self.history & self.window {
+            0 => Some(false),
+            w if w == self.window => Some(true),
+            _ => None,
+        };
+        self.changed = matches!(stable, Some(s) if s != self.pressed);
+        if let Some(s) = stable {
+            self.pressed = s;
+        }
+        Ok(())
+    }
+    /// Check if the input is pressed after debouncing.
+    #[inline]
+    pub fn is_pressed(&self) -> bool {
+        self.pressed
+    }
+    /// Check if the last [`update`](Self::update) detected a press.
+    #[inline]
+    pub fn just_pressed(&self) -> bool {
+        self.changed && self.pressed
+    }
+    /// Check if the last [`update`](Self::update) detected a release.
+    #[inline]
+    pub fn just_released(&self) -> bool {
+        self.changed && !self.pressed
+    }
+    /// Release the wrapped pin.
+    #[inline]
+    pub fn free(self) -> P {
+        self.pin
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Debouncer;
+    use core::convert::Infallible;
+    use embedded_hal::digital::{ErrorType, InputPin};
+
+    struct MockPin<'a> {
+        levels: core::slice::Iter<'a, bool>,
+    }
+
+    impl ErrorType for MockPin<'_> {
+        type Error = Infallible;
+    }
+
+    impl InputPin for MockPin<'_> {
+        fn is_high(&mut self) -> Result<bool, Infallible> {
+            Ok(*self.levels.next().unwrap())
+        }
+        fn is_low(&mut self) -> Result<bool, Infallible> {
+            self.is_high().map(|high| !high)
+        }
+    }
+
+    #[test]
+    fn struct_debouncer_bouncy_press() {
+        // Button is active low: bounces on press, holds, then bounces on release.
+        let levels = [
+            true, true, false, true, false, false, true, false, false, false, false, false, false,
+            true, false, true, true, true, true, true, true,
+        ];
+        let mut button = Debouncer::new(
+            MockPin {
+                levels: levels.iter(),
+            },
+            4,
+        );
+        let (mut presses, mut releases) = (0, 0);
+        for i in 0..levels.len() {
+            button.update().unwrap();
+            presses += button.just_pressed() as u32;
+            releases += button.just_released() as u32;
+            if i == 10 {
+                assert!(button.is_pressed());
+            }
+        }
+        assert_eq!((presses, releases), (1, 1));
+        assert!(!button.is_pressed());
+
+        let levels = [true, true, false, true, true];
+        let mut sensor = Debouncer::new_active_high(
+            MockPin {
+                levels: levels.iter(),
+            },
+            2,
+        );
+        sensor.update().unwrap();
+        assert!(!sensor.just_pressed());
+        sensor.update().unwrap();
+        assert!(sensor.just_pressed());
+        sensor.update().unwrap();
+        sensor.update().unwrap();
+        assert!(sensor.is_pressed() && !sensor.just_pressed() && !sensor.just_released());
+    }
+
+    #[test]
+    #[should_panic]
+    fn struct_debouncer_empty_window() {
+        let _ = Debouncer::new(MockPin { levels: [].iter() }, 0);
+    }
+}
@@ -85,6 +85,65 @@ impl<'a, const N: usize> IntoPadv2<'a, N> for Disabled<'a, N> {
     fn into_jtag_lp(self) -> Alternate<'a, N, typestate::JtagLp> {
         self.inner.into_jtag_lp().into()
     }
+    #[inline]
+    fn into_i2s(self) -> Alternate<'a, N, typestate::I2s> {
+        self.inner.into_i2s().into()
+    }
+    #[inline]
+    fn into_pdm(self) -> Alternate<'a, N, typestate::Pdm> {
+        self.inner.into_pdm().into()
+    }
+    #[inline]
+    fn into_emac(self) -> Alternate<'a, N, typestate::Emac> {
+        self.inner.into_emac().into()
+    }
+    #[inline]
+    fn into_cam(self) -> Alternate<'a, N, typestate::Cam> {
+        self.inner.into_cam().into()
+    }
+    #[inline]
+    fn into_dbi_b(self) -> Alternate<'a, N, typestate::DbiB> {
+        self.inner.into_dbi_b().into()
+    }
+    #[inline]
+    fn into_dbi_c(self) -> Alternate<'a, N, typestate::DbiC> {
+        self.inner.into_dbi_c().into()
+    }
+    #[inline]
+    fn into_dpi(self) -> Alternate<'a, N, typestate::Dpi> {
+        self.inner.into_dpi().into()
+    }
+    #[inline]
+    fn into_clock_out(
+        self,
+        source: super::ClockOutSource,
+        divide: u8,
+    ) -> Result<super::ClockOutPin<'a, N>, (Self, super::ClockOutError)> {
+        self.inner
+            .into_clock_out(source, divide)
+            .map_err(|(inner, e)| (inner.into(), e))
+    }
+    #[inline]
+    fn into_sequencer(
+        self,
+        tick_cycles: u16,
+    ) -> Result<super::SequencerPin<'a, N>, (Self, super::SequencerError)> {
+        self.inner
+            .into_sequencer(tick_cycles)
+            .map_err(|(inner, e)| (inner.into(), e))
+    }
+    #[inline]
+    fn into_analog(self) -> Alternate<'a, N, typestate::Analog> {
+        self.inner.into_analog().into()
+    }
+    #[inline]
+    fn into_open_drain_output(self) -> Output<'a, N, typestate::OpenDrain<Floating>> {
+        self.inner.into_open_drain_output().into()
+    }
+    #[inline]
+    fn into_pull_up_open_drain_output(self) -> Output<'a, N, typestate::OpenDrain<PullUp>> {
+        self.inner.into_pull_up_open_drain_output().into()
+    }
 }
 
 impl<'a, const N: usize> From<super::Inner<'a, N, typestate::Disabled>> for Disabled<'a, N> {
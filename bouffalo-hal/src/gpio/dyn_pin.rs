@@ -0,0 +1,283 @@
+//! Type-erased GPIO pads.
+use super::OutputType;
+use super::pad_v2::{open_drain_into_push_pull, push_pull_into_open_drain};
+use super::typestate::{Floating, OpenDrain, PullDown, PullUp};
+use crate::glb::{Pull, v2};
+use embedded_hal::digital::{ErrorKind, ErrorType, InputPin, OutputPin};
+
+/// Number of GPIO pads on BL808 and BL616 series.
+const PAD_COUNT: u8 = 46;
+
+/// Runtime mode tag of a [`DynPin`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DynMode {
+    /// Input mode with pull direction.
+    Input(Pull),
+    /// Push-pull output mode with pull direction.
+    PushPull(Pull),
+    /// Open-drain output mode with pull direction.
+    OpenDrain(Pull),
+}
+
+/// Error on type-erased GPIO pad operations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DynPinError {
+    /// Pad number does not exist on this chip, or does not match the typed pad.
+    InvalidPin,
+    /// Operation or conversion is not allowed in current pad mode.
+    InvalidMode,
+}
+
+impl embedded_hal::digital::Error for DynPinError {
+    #[inline]
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+}
+
+/// GPIO pad with pad number and mode known at runtime.
+///
+/// Typed pads like `Output<'a, 8, Floating>` are distinct types for every pad number,
+/// thus cannot be stored in one array; call `downgrade` to erase the number into a
+/// `DynPin`, and convert back with `TryFrom`. Bank and bit mask are computed once on
+/// creation, so that reading and writing levels involve no branching on pad number.
+///
+/// ```no_run
+/// # use bouffalo_hal::gpio::{DynPin, Output, Floating};
+/// use embedded_hal::digital::OutputPin;
+///
+/// fn scan(rows: &mut [DynPin<'static>; 4]) {
+///     for row in rows {
+///         row.set_low().ok();
+///         row.set_high().ok();
+///     }
+/// }
+/// # fn downgrade(a: Output<'static, 0, Floating>, b: Output<'static, 1, Floating>,
+/// #     c: Output<'static, 2, Floating>, d: Output<'static, 3, Floating>) {
+/// let mut rows = [a.downgrade(), b.downgrade(), c.downgrade(), d.downgrade()];
+/// scan(&mut rows);
+/// # }
+/// ```
+pub struct DynPin<'a> {
+    base: &'a v2::RegisterBlock,
+    number: u8,
+    mask: u32,
+    mode: DynMode,
+}
+
+// SAFETY: same as typed pads, a `DynPin` only modifies its own configuration register
+// and its own bit in `gpio_set` or `gpio_clear`.
+unsafe impl<'a> Send for DynPin<'a> {}
+
+impl<'a> DynPin<'a> {
+    /// Create a type-erased pad from its number and current mode.
+    ///
+    /// # Safety
+    ///
+    /// The pad must be configured in `mode` and not be owned by any other pad handle.
+    #[inline]
+    pub unsafe fn new(
+        base: &'a v2::RegisterBlock,
+        number: u8,
+        mode: DynMode,
+    ) -> Result<Self, DynPinError> {
+        if number >= PAD_COUNT {
+            return Err(DynPinError::InvalidPin);
+        }
+        Ok(Self::from_parts(base, number, mode))
+    }
+    /// Get pad number.
+    #[inline]
+    pub const fn number(&self) -> u8 {
+        self.number
+    }
+    /// Get current pad mode.
+    #[inline]
+    pub const fn mode(&self) -> DynMode {
+        self.mode
+    }
+    /// Switch this output pad between push-pull and open-drain output, keeping current
+    /// level.
+    ///
+    /// As open-drain output, `set_high` releases the line instead of driving it. The pad
+    /// configuration is changed by one register write; output value register is only
+    /// written while the pad is not driven, thus there is no glitch on the line during
+    /// the transition. Returns `InvalidMode` on input pads.
+    #[inline]
+    pub fn set_output_type(&mut self, ty: OutputType) -> Result<(), DynPinError> {
+        self.mode = match (self.mode, ty) {
+            (DynMode::Input(_), _) => return Err(DynPinError::InvalidMode),
+            (DynMode::PushPull(pull), OutputType::OpenDrain) => {
+                push_pull_into_open_drain(self.base, self.number as usize);
+                DynMode::OpenDrain(pull)
+            }
+            (DynMode::OpenDrain(pull), OutputType::PushPull) => {
+                open_drain_into_push_pull(self.base, self.number as usize);
+                DynMode::PushPull(pull)
+            }
+            (mode, _) => mode,
+        };
+        Ok(())
+    }
+    #[inline]
+    pub(crate) const fn from_parts(base: &'a v2::RegisterBlock, number: u8, mode: DynMode) -> Self {
+        Self {
+            base,
+            number,
+            mask: 1 << (number & 0x1F),
+            mode,
+        }
+    }
+    #[inline]
+    pub(crate) const fn base(&self) -> &'a v2::RegisterBlock {
+        self.base
+    }
+    #[inline]
+    const fn bank(&self) -> usize {
+        (self.number >> 5) as usize
+    }
+}
+
+impl ErrorType for DynPin<'_> {
+    type Error = DynPinError;
+}
+
+impl InputPin for DynPin<'_> {
+    #[inline]
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.base.gpio_input[self.bank()].read() & self.mask != 0)
+    }
+    #[inline]
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.base.gpio_input[self.bank()].read() & self.mask == 0)
+    }
+}
+
+impl OutputPin for DynPin<'_> {
+    #[inline]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        match self.mode {
+            DynMode::PushPull(_) => unsafe { self.base.gpio_clear[self.bank()].write(self.mask) },
+            DynMode::OpenDrain(_) => {
+                let config = &self.base.gpio_config[self.number as usize];
+                unsafe { config.write(config.read().enable_output()) };
+            }
+            DynMode::Input(_) => return Err(DynPinError::InvalidMode),
+        }
+        Ok(())
+    }
+    #[inline]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        match self.mode {
+            DynMode::PushPull(_) => unsafe { self.base.gpio_set[self.bank()].write(self.mask) },
+            DynMode::OpenDrain(_) => {
+                let config = &self.base.gpio_config[self.number as usize];
+                unsafe { config.write(config.read().disable_output()) };
+            }
+            DynMode::Input(_) => return Err(DynPinError::InvalidMode),
+        }
+        Ok(())
+    }
+}
+
+/// Pull direction of pad type states.
+pub trait PullMode {
+    /// Pull direction in runtime value.
+    const PULL: Pull;
+}
+
+impl PullMode for Floating {
+    const PULL: Pull = Pull::None;
+}
+
+impl PullMode for PullUp {
+    const PULL: Pull = Pull::Up;
+}
+
+impl PullMode for PullDown {
+    const PULL: Pull = Pull::Down;
+}
+
+/// Output type and pull direction of output pad type states.
+pub trait OutputMode {
+    /// Output mode in runtime value.
+    const MODE: DynMode;
+}
+
+impl<M: PullMode> OutputMode for M {
+    const MODE: DynMode = DynMode::PushPull(M::PULL);
+}
+
+impl<M: PullMode> OutputMode for OpenDrain<M> {
+    const MODE: DynMode = DynMode::OpenDrain(M::PULL);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DynMode, DynPin, DynPinError};
+    use crate::glb::{Pull, v2::MockRegisterBlock};
+    use crate::gpio::{OutputType, Padv2, typestate};
+    use embedded_hal::digital::{InputPin, OutputPin};
+
+    #[test]
+    fn struct_dyn_pin() {
+        let glb = MockRegisterBlock::new();
+        let io8 = Padv2::<8, _>::__from_glb(&glb).into_floating_output();
+        let io40 = Padv2::<40, _>::__from_glb(&glb).into_pull_up_output();
+        let io41 = Padv2::<41, _>::__from_glb(&glb)
+            .into_pull_down_output()
+            .into_open_drain();
+        let io45 = Padv2::<45, _>::__from_glb(&glb).into_pull_up_input();
+        let mut pins = [
+            io8.downgrade(),
+            io40.downgrade(),
+            io41.downgrade(),
+            io45.downgrade(),
+        ];
+        assert_eq!(pins[0].mode(), DynMode::PushPull(Pull::None));
+        assert_eq!(pins[2].mode(), DynMode::OpenDrain(Pull::Down));
+        assert_eq!(pins[3].mode(), DynMode::Input(Pull::Up));
+
+        pins[0].set_high().unwrap();
+        pins[1].set_low().unwrap();
+        assert_eq!(glb.raw(0xaec), 1 << 8);
+        assert_eq!(glb.raw(0xaf8), 1 << 8);
+        pins[2].set_low().unwrap();
+        assert!(glb.gpio_config[41].read().is_output_enabled());
+        pins[2].set_high().unwrap();
+        assert!(!glb.gpio_config[41].read().is_output_enabled());
+        assert_eq!(pins[3].set_high(), Err(DynPinError::InvalidMode));
+
+        // Switch back to a push-pull high from the released line.
+        pins[2].set_output_type(OutputType::PushPull).unwrap();
+        assert_eq!(pins[2].mode(), DynMode::PushPull(Pull::Down));
+        let config = glb.gpio_config[41].read();
+        assert!(config.is_output_enabled() && !config.is_input_enabled());
+        assert_eq!(glb.raw(0xaf0), 1 << 9);
+        pins[2].set_output_type(OutputType::OpenDrain).unwrap();
+        assert_eq!(pins[2].mode(), DynMode::OpenDrain(Pull::Down));
+        assert!(glb.gpio_config[41].read().is_input_enabled());
+        let result = pins[3].set_output_type(OutputType::OpenDrain);
+        assert_eq!(result, Err(DynPinError::InvalidMode));
+
+        glb.set_raw(0xac8, 1 << 13);
+        assert!(pins[3].is_high().unwrap());
+        assert!(pins[1].is_low().unwrap());
+
+        let [io8, _, _, io45] = pins;
+        assert!(Padv2::<8, typestate::Input<typestate::Floating>>::try_from(io8).is_err());
+        let io45 = Padv2::<44, typestate::Input<typestate::PullUp>>::try_from(io45);
+        assert_eq!(io45.err(), Some(DynPinError::InvalidPin));
+        let io45 = unsafe { DynPin::new(&glb, 45, DynMode::Input(Pull::Up)) }.unwrap();
+        let io45 = Padv2::<45, typestate::Input<typestate::PullDown>>::try_from(io45);
+        assert_eq!(io45.err(), Some(DynPinError::InvalidMode));
+        let io45 = unsafe { DynPin::new(&glb, 45, DynMode::Input(Pull::Up)) }.unwrap();
+        let mut io45 = Padv2::<45, typestate::Input<typestate::PullUp>>::try_from(io45)
+            .ok()
+            .unwrap();
+        assert!(io45.is_high().unwrap());
+
+        let invalid = unsafe { DynPin::new(&glb, 46, DynMode::Input(Pull::None)) };
+        assert_eq!(invalid.err(), Some(DynPinError::InvalidPin));
+    }
+}
@@ -0,0 +1,394 @@
+//! Emergency stop input driving failsafe outputs.
+//!
+//! [`EmergencyStop`] takes a set of failsafe output pads with their safe levels, handing
+//! each back wrapped in [`Failsafe`] for ordinary use. When the emergency stop pin
+//! interrupt fires, [`EmergencyStop::on_interrupt`] drives all failsafe pads to safe
+//! levels before doing anything else, and only then clears the interrupt and records
+//! the trip.
+//!
+//! # Latency
+//!
+//! After the handler is entered, push-pull pads reach safe levels by one load of the
+//! stop pin configuration register, followed by at most four stores into the write-only
+//! `gpio_clear` and `gpio_set` registers; no read-modify-write is involved. Failsafe
+//! pads must be in set-clear mode, which is the mode configured by `into_*_output`.
+//! Open-drain pads only drive low with their output enabled, thus each of them takes
+//! one more load and store of its own configuration register.
+//!
+//! Remaining latency is decided by interrupt dispatch. The GPIO interrupt source is
+//! given the highest priority in the platform-level interrupt controller (PLIC) by
+//! [`EmergencyStop::enable_interrupt`], so it is claimed before other pending sources.
+//!
+//! # Preemption
+//!
+//! An emergency stop does **not** preempt another interrupt handler. The trap handler of
+//! `bouffalo-rt` runs with interrupts disabled and does not support nested interrupts,
+//! and this module provides no way around it; an emergency stop that fires during
+//! another interrupt handler is only served after that handler returns. PLIC priority
+//! only decides which of the sources pending at the same time is claimed first. Where
+//! stop latency must be bounded under any interrupt load, keep every other interrupt
+//! handler short, or force the safe state by external hardware.
+//!
+//! Between a trip and [`EmergencyStop::reset`], level changes through [`Failsafe`]
+//! are refused with [`FailsafeError::Tripped`], so that ordinary code cannot drive a
+//! failsafe pad out of its safe level.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use bouffalo_hal::{glb, gpio::{EmergencyStop, Failsafe, Floating, Output}};
+//! # use embedded_hal::digital::{OutputPin, PinState};
+//! static ESTOP: EmergencyStop<12> = EmergencyStop::new();
+//!
+//! # fn init(motor: Output<'static, 8, Floating>, brake: Output<'static, 9, Floating>) {
+//! let mut motor = ESTOP.add_failsafe(motor, PinState::Low);
+//! let brake = ESTOP.add_failsafe(brake, PinState::High);
+//! motor.set_high().ok();
+//! # }
+//! // In the GPIO interrupt handler, before any other processing:
+//! # fn handler(glb: &glb::v2::RegisterBlock) {
+//! if ESTOP.on_interrupt(glb) {
+//!     // Emergency stop tripped; failsafe pads are already in safe state.
+//! }
+//! # }
+//! ```
+use super::Output;
+#[cfg(any(doc, feature = "glb-v2"))]
+use super::dyn_pin::{DynMode, OutputMode};
+use crate::glb::v2;
+use core::ops::Deref;
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering, compiler_fence};
+use embedded_hal::digital::PinState;
+use embedded_hal::digital::{ErrorKind, ErrorType, OutputPin, StatefulOutputPin};
+use plic::{HartContext, InterruptSource, Plic};
+
+/// Emergency stop on interrupt of pin `N`.
+pub struct EmergencyStop<const N: usize> {
+    // Bit masks of failsafe pins per bank.
+    drive_low: [AtomicU32; 2],
+    drive_high: [AtomicU32; 2],
+    open_drain: [AtomicU32; 2],
+    tripped: AtomicBool,
+}
+
+impl<const N: usize> EmergencyStop<N> {
+    /// Create an emergency stop with no failsafe pins.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            drive_low: [const { AtomicU32::new(0) }; 2],
+            drive_high: [const { AtomicU32::new(0) }; 2],
+            open_drain: [const { AtomicU32::new(0) }; 2],
+            tripped: AtomicBool::new(false),
+        }
+    }
+    /// Take an output pad to be driven to `level` when emergency stop trips.
+    ///
+    /// The pad is kept in its current output type; push-pull and open-drain pads are
+    /// both supported.
+    #[cfg(any(doc, feature = "glb-v2"))]
+    #[inline]
+    pub fn add_failsafe<'a, const M: usize, P: OutputMode>(
+        &self,
+        pad: Output<'a, M, P>,
+        level: PinState,
+    ) -> Failsafe<'_, 'a, N, M, P> {
+        let open_drain = matches!(P::MODE, DynMode::OpenDrain(_));
+        self.add_failsafe_pin(M, level, open_drain);
+        Failsafe {
+            stop: self,
+            pad,
+            level,
+        }
+    }
+    #[cfg(any(doc, test, feature = "glb-v2"))]
+    #[inline]
+    fn add_failsafe_pin(&self, pin: usize, level: PinState, open_drain: bool) {
+        let (bank, bit) = (pin >> 5, 1 << (pin & 0x1F));
+        let (add, remove) = match level {
+            PinState::Low => (&self.drive_low[bank], &self.drive_high[bank]),
+            PinState::High => (&self.drive_high[bank], &self.drive_low[bank]),
+        };
+        remove.fetch_and(!bit, Ordering::Relaxed);
+        if open_drain {
+            self.open_drain[bank].fetch_or(bit, Ordering::Relaxed);
+        } else {
+            self.open_drain[bank].fetch_and(!bit, Ordering::Relaxed);
+        }
+        add.fetch_or(bit, Ordering::Release);
+    }
+    #[inline]
+    fn remove_failsafe_pin(&self, pin: usize) {
+        let (bank, bit) = (pin >> 5, 1 << (pin & 0x1F));
+        self.drive_low[bank].fetch_and(!bit, Ordering::Release);
+        self.drive_high[bank].fetch_and(!bit, Ordering::Release);
+    }
+    /// Enable interrupt `source` of the stop pin for `context` at the highest priority
+    /// of `plic`.
+    ///
+    /// Returns the priority set, which is the highest one the controller implements.
+    #[inline]
+    pub fn enable_interrupt<S: InterruptSource + Copy, C: HartContext>(
+        &self,
+        plic: &Plic,
+        source: S,
+        context: C,
+    ) -> u32 {
+        // Priority register keeps implemented bits only.
+        plic.set_priority(source, u32::MAX);
+        plic.enable(source, context);
+        plic.get_priority(source)
+    }
+    /// Handle GPIO interrupt; call this first in the GPIO interrupt handler.
+    ///
+    /// If pin `N` has a pending and unmasked interrupt, drives all failsafe pins to
+    /// their safe levels, clears interrupt flag of pin `N` and returns `true`. Otherwise
+    /// returns `false` and changes nothing.
+    #[inline]
+    pub fn on_interrupt(&self, glb: &v2::RegisterBlock) -> bool {
+        let config = glb.gpio_config[N].read();
+        if !config.has_interrupt() || config.is_interrupt_masked() {
+            return false;
+        }
+        for bank in 0..2 {
+            let low = self.drive_low[bank].load(Ordering::Acquire);
+            let high = self.drive_high[bank].load(Ordering::Acquire);
+            let open_drain = self.open_drain[bank].load(Ordering::Relaxed);
+            if low & !open_drain != 0 {
+                unsafe { glb.gpio_clear[bank].write(low & !open_drain) };
+            }
+            if high & !open_drain != 0 {
+                unsafe { glb.gpio_set[bank].write(high & !open_drain) };
+            }
+            // Open-drain pads keep low latched; output enable decides the line level.
+            let mut pins = (low | high) & open_drain;
+            while pins != 0 {
+                let bit = pins.trailing_zeros() as usize;
+                pins &= pins - 1;
+                let pad = &glb.gpio_config[bank * 32 + bit];
+                let config = pad.read();
+                let config = if low & (1 << bit) != 0 {
+                    config.enable_output()
+                } else {
+                    config.disable_output()
+                };
+                unsafe { pad.write(config) };
+            }
+        }
+        v2::clear_interrupt(&glb.gpio_config[N], config);
+        self.tripped.store(true, Ordering::Release);
+        true
+    }
+    /// Check if emergency stop has tripped since creation or last reset.
+    #[inline]
+    pub fn is_tripped(&self) -> bool {
+        self.tripped.load(Ordering::Acquire)
+    }
+    /// Reset tripped state after the machine is brought back to a safe state.
+    ///
+    /// Failsafe pins are not changed; they accept level changes again from now on.
+    #[inline]
+    pub fn reset(&self) {
+        self.tripped.store(false, Ordering::Release);
+    }
+}
+
+impl<const N: usize> Default for EmergencyStop<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Error on failsafe pad operations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FailsafeError {
+    /// Emergency stop has tripped; the pad is held at its safe level until reset.
+    Tripped,
+}
+
+impl embedded_hal::digital::Error for FailsafeError {
+    #[inline]
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+}
+
+/// Failsafe output pad `M` of emergency stop on pin `N`.
+///
+/// The pad is driven as usual until emergency stop trips; its output type cannot be
+/// changed while it is a failsafe pad. After a trip, `set_low`, `set_high` and `toggle`
+/// return [`FailsafeError::Tripped`] without changing the pad until
+/// [`EmergencyStop::reset`] is called.
+///
+/// If the emergency stop fires while a level is being written, the write may land after
+/// the interrupt handler has driven the safe level; the pad is then driven back to the
+/// safe level right away and the write returns `Tripped`.
+pub struct Failsafe<'s, 'a, const N: usize, const M: usize, P> {
+    stop: &'s EmergencyStop<N>,
+    pad: Output<'a, M, P>,
+    level: PinState,
+}
+
+impl<'a, const N: usize, const M: usize, P> Failsafe<'_, 'a, N, M, P> {
+    /// Stop driving this pad on emergency stop, returning the pad.
+    #[inline]
+    pub fn free(self) -> Output<'a, M, P> {
+        self.stop.remove_failsafe_pin(M);
+        self.pad
+    }
+}
+
+impl<'a, const N: usize, const M: usize, P> Deref for Failsafe<'_, 'a, N, M, P> {
+    type Target = Output<'a, M, P>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.pad
+    }
+}
+
+impl<'a, const N: usize, const M: usize, P> Failsafe<'_, 'a, N, M, P>
+where
+    Output<'a, M, P>: OutputPin<Error = super::Error>,
+{
+    #[inline]
+    fn drive(&mut self, f: impl FnOnce(&mut Output<'a, M, P>)) -> Result<(), FailsafeError> {
+        if self.stop.is_tripped() {
+            return Err(FailsafeError::Tripped);
+        }
+        f(&mut self.pad);
+        // Keep the tripped check below after the level write.
+        compiler_fence(Ordering::SeqCst);
+        if self.stop.is_tripped() {
+            // Emergency stop fired during the write; restore the safe level.
+            let Ok(()) = self.pad.set_state(self.level);
+            return Err(FailsafeError::Tripped);
+        }
+        Ok(())
+    }
+}
+
+impl<const N: usize, const M: usize, P> ErrorType for Failsafe<'_, '_, N, M, P> {
+    type Error = FailsafeError;
+}
+
+impl<'a, const N: usize, const M: usize, P> OutputPin for Failsafe<'_, 'a, N, M, P>
+where
+    Output<'a, M, P>: OutputPin<Error = super::Error>,
+{
+    #[inline]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.drive(|pad| {
+            let Ok(()) = pad.set_low();
+        })
+    }
+    #[inline]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.drive(|pad| {
+            let Ok(()) = pad.set_high();
+        })
+    }
+}
+
+impl<'a, const N: usize, const M: usize, P> StatefulOutputPin for Failsafe<'_, 'a, N, M, P>
+where
+    Output<'a, M, P>: StatefulOutputPin<Error = super::Error>,
+{
+    #[inline]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        let Ok(high) = self.pad.is_set_high();
+        Ok(high)
+    }
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        let Ok(low) = self.pad.is_set_low();
+        Ok(low)
+    }
+    #[inline]
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        self.drive(|pad| {
+            let Ok(()) = pad.toggle();
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EmergencyStop, FailsafeError};
+    use crate::glb::v2::MockRegisterBlock;
+    use embedded_hal::digital::{OutputPin, PinState, StatefulOutputPin};
+
+    #[test]
+    fn struct_emergency_stop_drives_failsafe() {
+        let glb = MockRegisterBlock::new();
+        let estop = EmergencyStop::<12>::new();
+        estop.add_failsafe_pin(3, PinState::High, false);
+        // Safe level is replaced.
+        estop.add_failsafe_pin(3, PinState::Low, false);
+        estop.add_failsafe_pin(40, PinState::High, false);
+        // Open-drain pads io5 and io6, with output enabled on io6.
+        estop.add_failsafe_pin(5, PinState::Low, true);
+        estop.add_failsafe_pin(6, PinState::High, true);
+        glb.set_raw(0x8c4 + 6 * 4, 1 << 6);
+
+        // Interrupt of other pins does not trip.
+        glb.set_raw(0x8c4 + 11 * 4, 1 << 21);
+        assert!(!estop.on_interrupt(&glb));
+        assert!(!estop.is_tripped());
+        assert_eq!(glb.raw(0xaf4), 0);
+        assert_eq!(glb.raw(0xaf0), 0);
+
+        // Masked interrupt of the stop pin does not trip.
+        glb.set_raw(0x8c4 + 12 * 4, (1 << 21) | (1 << 22));
+        assert!(!estop.on_interrupt(&glb));
+        assert!(!estop.is_tripped());
+
+        glb.set_raw(0x8c4 + 12 * 4, 1 << 21);
+        assert!(estop.on_interrupt(&glb));
+        assert!(estop.is_tripped());
+        // gpio_clear[0] for io3, gpio_set[1] for io40.
+        assert_eq!(glb.raw(0xaf4), 1 << 3);
+        assert_eq!(glb.raw(0xaec), 0);
+        assert_eq!(glb.raw(0xaf0), 1 << 8);
+        assert_eq!(glb.raw(0xaf8), 0);
+        // io5 pulls the line low, io6 releases it.
+        assert_eq!(glb.raw(0x8c4 + 5 * 4), 1 << 6);
+        assert_eq!(glb.raw(0x8c4 + 6 * 4), 0);
+        assert_eq!(glb.raw(0x8c4 + 12 * 4) & (1 << 20), 0);
+
+        estop.reset();
+        assert!(!estop.is_tripped());
+        estop.remove_failsafe_pin(3);
+        glb.set_raw(0xaf4, 0);
+        assert!(estop.on_interrupt(&glb));
+        assert_eq!(glb.raw(0xaf4), 0);
+    }
+
+    #[cfg(feature = "glb-v2")]
+    #[test]
+    fn struct_failsafe_holds_safe_level() {
+        use crate::gpio::{Disabled, IntoPad, Padv2};
+        let glb = MockRegisterBlock::new();
+        let estop = EmergencyStop::<12>::new();
+        let io8 = Disabled::from(Padv2::<8, _>::__from_glb(&glb)).into_floating_output();
+        let mut io8 = estop.add_failsafe(io8, PinState::Low);
+        io8.set_high().unwrap();
+        assert_eq!(glb.raw(0xaec), 1 << 8);
+        glb.set_raw(0xaec, 0);
+
+        glb.set_raw(0x8c4 + 12 * 4, 1 << 21);
+        assert!(estop.on_interrupt(&glb));
+        assert_eq!(glb.raw(0xaf4), 1 << 8);
+        glb.set_raw(0xaf4, 0);
+        // Level changes are refused until reset.
+        assert_eq!(io8.set_high(), Err(FailsafeError::Tripped));
+        assert_eq!(io8.toggle(), Err(FailsafeError::Tripped));
+        assert_eq!(io8.set_low(), Err(FailsafeError::Tripped));
+        assert_eq!(glb.raw(0xaec) | glb.raw(0xaf4), 0);
+
+        estop.reset();
+        io8.set_high().unwrap();
+        assert_eq!(glb.raw(0xaec), 1 << 8);
+    }
+}
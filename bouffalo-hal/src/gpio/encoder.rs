@@ -0,0 +1,227 @@
+//! Quadrature rotary encoder decoding.
+//!
+//! A quadrature encoder outputs two square waves A and B shifted by a quarter period;
+//! their levels step through the Gray code `00, 10, 11, 01` in one direction and the
+//! reverse sequence in the other. Each step is one count, and mechanical encoders
+//! usually produce 2 or 4 counts per detent.
+//!
+//! [`Encoder::update`] samples both phases and follows this sequence. It may be called
+//! periodically, faster than the highest step rate, or from the interrupt handler of
+//! both pins listening on both edges, i.e. after
+//! [`listen`](super::Input::listen)`(InterruptMode::SyncBothEdges)`. A sample where
+//! both phases changed at once skips a state, so its direction cannot be known; it is
+//! counted in [`Encoder::invalid_transitions`] instead of moving the position, which
+//! keeps contact bounce from corrupting the count.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use bouffalo_hal::gpio::{Encoder, Input, PullUp};
+//! # use core::num::NonZeroU8;
+//! # fn example(a: Input<'static, 20, PullUp>, b: Input<'static, 21, PullUp>) {
+//! let mut knob = Encoder::new(a, b, NonZeroU8::new(4).unwrap()).unwrap();
+//! loop {
+//!     knob.update().ok();
+//!     let steps = knob.take_delta();
+//!     // Move a menu cursor by `steps`.
+//! #   let _ = steps;
+//! }
+//! # }
+//! ```
+use core::num::NonZeroU8;
+use embedded_hal::digital::InputPin;
+
+/// Count change for each transition, indexed by `previous << 2 | current` state,
+/// where state is `a << 1 | b`; `None` stands for an invalid transition.
+const TRANSITIONS: [Option<i8>; 16] = [
+    Some(0),
+    Some(-1),
+    Some(1),
+    None,
+    Some(1),
+    Some(0),
+    None,
+    Some(-1),
+    Some(-1),
+    None,
+    Some(0),
+    Some(1),
+    None,
+    Some(1),
+    Some(-1),
+    Some(0),
+];
+
+/// Quadrature rotary encoder over phase pins A and B.
+///
+/// Works with any [`InputPin`], including typed pads and [`DynPin`](super::DynPin).
+pub struct Encoder<A, B> {
+    a: A,
+    b: B,
+    state: u8,
+    count: i32,
+    counts_per_detent: i32,
+    taken: i32,
+    invalid: u32,
+}
+
+impl<A: InputPin, B: InputPin<Error = A::Error>> Encoder<A, B> {
+    /// Decode encoder on phase pins `a` and `b` with `counts_per_detent` counts per
+    /// detent, starting at position zero.
+    #[inline]
+    pub fn new(mut a: A, mut b: B, counts_per_detent: NonZeroU8) -> Result<Self, A::Error> {
+        let state = sample(&mut a, &mut b)?;
+        Ok(Self {
+            a,
+            b,
+            state,
+            count: 0,
+            counts_per_detent: counts_per_detent.get() as i32,
+            taken: 0,
+            invalid: 0,
+        })
+    }
+    /// Sample both phases once and update the position.
+    #[inline]
+    pub fn update(&mut self) -> Result<(), A::Error> {
+        let state = sample(&mut self.a, &mut self.b)?;
+        match TRANSITIONS[((self.state << 2) | state) as usize] {
+            Some(delta) => self.count = self.count.wrapping_add(delta as i32),
+            None => self.invalid = self.invalid.wrapping_add(1),
+        }
+        self.state = state;
+        Ok(())
+    }
+    /// Get position in detents; positive in the direction where A leads B.
+    ///
+    /// Detent boundaries lie halfway between detents, so that contact bounce around a
+    /// resting detent never changes the position.
+    #[inline]
+    pub fn position(&self) -> i32 {
+        self.count
+            .wrapping_add(self.counts_per_detent / 2)
+            .div_euclid(self.counts_per_detent)
+    }
+    /// Get detents moved since the last call of this function.
+    #[inline]
+    pub fn take_delta(&mut self) -> i32 {
+        let position = self.position();
+        let delta = position.wrapping_sub(self.taken);
+        self.taken = position;
+        delta
+    }
+    /// Get raw position in counts.
+    #[inline]
+    pub fn count(&self) -> i32 {
+        self.count
+    }
+    /// Get number of transitions ignored because both phases changed at once.
+    #[inline]
+    pub fn invalid_transitions(&self) -> u32 {
+        self.invalid
+    }
+    /// Release the phase pins.
+    #[inline]
+    pub fn free(self) -> (A, B) {
+        (self.a, self.b)
+    }
+}
+
+#[inline]
+fn sample<A: InputPin, B: InputPin<Error = A::Error>>(
+    a: &mut A,
+    b: &mut B,
+) -> Result<u8, A::Error> {
+    Ok(((a.is_high()? as u8) << 1) | b.is_high()? as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Encoder;
+    use core::cell::Cell;
+    use core::convert::Infallible;
+    use core::num::NonZeroU8;
+    use embedded_hal::digital::{ErrorType, InputPin};
+
+    struct MockPin<'a>(&'a Cell<bool>);
+
+    impl ErrorType for MockPin<'_> {
+        type Error = Infallible;
+    }
+
+    impl InputPin for MockPin<'_> {
+        fn is_high(&mut self) -> Result<bool, Infallible> {
+            Ok(self.0.get())
+        }
+        fn is_low(&mut self) -> Result<bool, Infallible> {
+            Ok(!self.0.get())
+        }
+    }
+
+    fn feed(
+        knob: &mut Encoder<MockPin, MockPin>,
+        (a, b): (&Cell<bool>, &Cell<bool>),
+        levels: &[(bool, bool)],
+    ) {
+        for &(la, lb) in levels {
+            a.set(la);
+            b.set(lb);
+            knob.update().unwrap();
+        }
+    }
+
+    #[test]
+    fn struct_encoder_gray_code() {
+        let (a, b) = (Cell::new(false), Cell::new(false));
+        let four = NonZeroU8::new(4).unwrap();
+        let mut knob = Encoder::new(MockPin(&a), MockPin(&b), four).unwrap();
+        // One detent forward, A leading B, and one count beyond.
+        let forward = [(true, false), (true, true), (false, true), (false, false)];
+        feed(&mut knob, (&a, &b), &forward);
+        feed(&mut knob, (&a, &b), &forward[..1]);
+        assert_eq!((knob.count(), knob.position()), (5, 1));
+        assert_eq!(knob.take_delta(), 1);
+        assert_eq!(knob.take_delta(), 0);
+
+        // Back through the reverse sequence, repeated samples changing nothing.
+        feed(
+            &mut knob,
+            (&a, &b),
+            &[(true, false), (true, false), (false, false), (false, true)],
+        );
+        feed(
+            &mut knob,
+            (&a, &b),
+            &[(true, true), (true, false), (false, false), (false, true)],
+        );
+        assert_eq!((knob.count(), knob.position()), (-1, 0));
+        assert_eq!(knob.take_delta(), -1);
+        // Boundary to the next detent lies halfway.
+        feed(&mut knob, (&a, &b), &[(true, true)]);
+        assert_eq!((knob.count(), knob.position()), (-2, 0));
+        feed(&mut knob, (&a, &b), &[(true, false)]);
+        assert_eq!((knob.count(), knob.position()), (-3, -1));
+        assert_eq!(knob.invalid_transitions(), 0);
+
+        // Both phases flipping at once is ignored.
+        a.set(false);
+        b.set(true);
+        knob.update().unwrap();
+        assert_eq!((knob.count(), knob.invalid_transitions()), (-3, 1));
+    }
+
+    #[test]
+    fn struct_encoder_bounce_at_rest() {
+        let (a, b) = (Cell::new(false), Cell::new(false));
+        let four = NonZeroU8::new(4).unwrap();
+        let mut knob = Encoder::new(MockPin(&a), MockPin(&b), four).unwrap();
+        // Phase B bounces one count backwards and back while resting on a detent.
+        for _ in 0..3 {
+            feed(&mut knob, (&a, &b), &[(false, true)]);
+            assert_eq!((knob.count(), knob.position()), (-1, 0));
+            feed(&mut knob, (&a, &b), &[(false, false)]);
+        }
+        assert_eq!(knob.take_delta(), 0);
+        assert_eq!(knob.invalid_transitions(), 0);
+    }
+}
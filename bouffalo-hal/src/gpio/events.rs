@@ -0,0 +1,300 @@
+//! Aggregated GPIO interrupt events.
+//!
+//! Instead of registering one waker for every pin, the interrupt handler forwards all
+//! pending GPIO interrupts into one [`GpioEvents`] queue, and a single task consumes
+//! them as a stream of `(pin, edge)` pairs.
+//!
+//! The queue has a fixed capacity `C`. If events arrive faster than they are consumed,
+//! new events are dropped and an overflow flag is raised; the next call to
+//! [`GpioEvents::next_event`] then returns [`Overflow`] so the consumer knows to
+//! resynchronize, e.g. by reading current pin levels.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use bouffalo_hal::{glb, gpio::GpioEvents};
+//! static EVENTS: GpioEvents<16> = GpioEvents::new();
+//!
+//! // In the GPIO interrupt handler:
+//! # fn handler(glb: &glb::v2::RegisterBlock) {
+//! EVENTS.on_interrupt(glb);
+//! # }
+//!
+//! // In the application task:
+//! # async fn task() {
+//! loop {
+//!     match EVENTS.next_event().await {
+//!         Ok(event) => { /* handle event.pin and event.edge */ }
+//!         Err(_overflow) => { /* some events were lost */ }
+//!     }
+//! }
+//! # }
+//! ```
+use crate::glb::v2;
+use atomic_waker::AtomicWaker;
+use core::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering};
+use core::task::{Context, Poll};
+
+/// Pin interrupt event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Event {
+    /// Pin number of the pad that raised the interrupt.
+    pub pin: u8,
+    /// Trigger condition that raised the interrupt.
+    pub edge: Edge,
+}
+
+/// Trigger condition of a pin interrupt event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Edge {
+    /// Signal changed from low to high.
+    Rising = 0,
+    /// Signal changed from high to low.
+    Falling = 1,
+    /// Signal is at high level.
+    HighLevel = 2,
+    /// Signal is at low level.
+    LowLevel = 3,
+}
+
+/// Events were dropped because the event queue was full.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Overflow;
+
+/// Bounded queue of pin interrupt events with capacity `C`.
+///
+/// There should be only one producer (the interrupt handler) and one consumer task.
+pub struct GpioEvents<const C: usize> {
+    buffer: [AtomicU8; C],
+    // Free running counters; `tail - head` is the number of queued events.
+    head: AtomicUsize,
+    tail: AtomicUsize,
+    overflow: AtomicBool,
+    waker: AtomicWaker,
+}
+
+impl<const C: usize> GpioEvents<C> {
+    /// Create an empty event queue.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            buffer: [const { AtomicU8::new(0) }; C],
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+            overflow: AtomicBool::new(false),
+            waker: AtomicWaker::new(),
+        }
+    }
+    /// Collect all pending pin interrupts; call this in the GPIO interrupt handler.
+    ///
+    /// Interrupt flags of collected pins are cleared; masked pins are skipped.
+    pub fn on_interrupt(&self, glb: &v2::RegisterBlock) {
+        for pin in 0..glb.gpio_config.len() {
+            let config = glb.gpio_config[pin].read();
+            if !config.has_interrupt() || config.is_interrupt_masked() {
+                continue;
+            }
+            v2::clear_interrupt(&glb.gpio_config[pin], config);
+            let edge = match config.interrupt_mode() {
+                v2::InterruptMode::SyncRisingEdge | v2::InterruptMode::AsyncRisingEdge => {
+                    Edge::Rising
+                }
+                v2::InterruptMode::SyncFallingEdge | v2::InterruptMode::AsyncFallingEdge => {
+                    Edge::Falling
+                }
+                v2::InterruptMode::SyncHighLevel | v2::InterruptMode::AsyncHighLevel => {
+                    Edge::HighLevel
+                }
+                v2::InterruptMode::SyncLowLevel | v2::InterruptMode::AsyncLowLevel => {
+                    Edge::LowLevel
+                }
+                // Pin level after a both-edge trigger tells which edge it was.
+                v2::InterruptMode::SyncBothEdges => {
+                    if glb.gpio_input[pin >> 5].read() & (1 << (pin & 0x1F)) != 0 {
+                        Edge::Rising
+                    } else {
+                        Edge::Falling
+                    }
+                }
+                // Trigger of a reserved mode is unknown, drop the event.
+                v2::InterruptMode::Reserved(_) => continue,
+            };
+            self.push(Event {
+                pin: pin as u8,
+                edge,
+            });
+        }
+    }
+    /// Push an event into the queue, waking the consumer.
+    ///
+    /// Returns `false` and raises the overflow flag if the queue is full.
+    pub fn push(&self, event: Event) -> bool {
+        let head = self.head.load(Ordering::Acquire);
+        let tail = self.tail.load(Ordering::Relaxed);
+        let ans = if tail.wrapping_sub(head) >= C {
+            self.overflow.store(true, Ordering::Release);
+            false
+        } else {
+            let raw = (event.pin << 2) | event.edge as u8;
+            self.buffer[tail % C].store(raw, Ordering::Relaxed);
+            self.tail.store(tail.wrapping_add(1), Ordering::Release);
+            true
+        };
+        self.waker.wake();
+        ans
+    }
+    /// Poll for the next event in arrival order.
+    ///
+    /// Returns `Err(Overflow)` once after events have been dropped.
+    pub fn poll_next_event(&self, cx: &mut Context<'_>) -> Poll<Result<Event, Overflow>> {
+        if let Some(ans) = self.try_next_event() {
+            return Poll::Ready(ans);
+        }
+        self.waker.register(cx.waker());
+        // Check again in case an event arrived before the waker was registered.
+        match self.try_next_event() {
+            Some(ans) => Poll::Ready(ans),
+            None => Poll::Pending,
+        }
+    }
+    /// Wait for the next event in arrival order.
+    ///
+    /// Returns `Err(Overflow)` once after events have been dropped.
+    #[inline]
+    pub async fn next_event(&self) -> Result<Event, Overflow> {
+        core::future::poll_fn(|cx| self.poll_next_event(cx)).await
+    }
+    #[inline]
+    fn try_next_event(&self) -> Option<Result<Event, Overflow>> {
+        if self.overflow.swap(false, Ordering::Acquire) {
+            return Some(Err(Overflow));
+        }
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Acquire);
+        if head == tail {
+            return None;
+        }
+        let raw = self.buffer[head % C].load(Ordering::Relaxed);
+        self.head.store(head.wrapping_add(1), Ordering::Release);
+        let edge = match raw & 0b11 {
+            0 => Edge::Rising,
+            1 => Edge::Falling,
+            2 => Edge::HighLevel,
+            _ => Edge::LowLevel,
+        };
+        Some(Ok(Event {
+            pin: raw >> 2,
+            edge,
+        }))
+    }
+}
+
+impl<const C: usize> Default for GpioEvents<C> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Edge, Event, GpioEvents, Overflow};
+    use crate::glb::v2::{InterruptMode, MockRegisterBlock};
+    use core::task::{Context, Poll, Waker};
+
+    fn poll<const C: usize>(events: &GpioEvents<C>) -> Poll<Result<Event, Overflow>> {
+        events.poll_next_event(&mut Context::from_waker(Waker::noop()))
+    }
+
+    #[test]
+    fn struct_gpio_events_arrival_order() {
+        let events = GpioEvents::<4>::new();
+        assert_eq!(poll(&events), Poll::Pending);
+        let sequence = [
+            Event {
+                pin: 8,
+                edge: Edge::Rising,
+            },
+            Event {
+                pin: 45,
+                edge: Edge::Falling,
+            },
+            Event {
+                pin: 0,
+                edge: Edge::LowLevel,
+            },
+            Event {
+                pin: 8,
+                edge: Edge::Falling,
+            },
+            Event {
+                pin: 31,
+                edge: Edge::HighLevel,
+            },
+            Event {
+                pin: 32,
+                edge: Edge::Rising,
+            },
+        ];
+        for (i, &event) in sequence.iter().enumerate() {
+            assert!(events.push(event));
+            // Keep at most two events in queue to exercise wrapping indices.
+            if i % 2 == 1 {
+                assert_eq!(poll(&events), Poll::Ready(Ok(sequence[i - 1])));
+                assert_eq!(poll(&events), Poll::Ready(Ok(event)));
+            }
+        }
+        assert_eq!(poll(&events), Poll::Pending);
+    }
+
+    #[test]
+    fn struct_gpio_events_overflow() {
+        let events = GpioEvents::<2>::new();
+        let event = |pin| Event {
+            pin,
+            edge: Edge::Rising,
+        };
+        assert!(events.push(event(1)));
+        assert!(events.push(event(2)));
+        assert!(!events.push(event(3)));
+        assert_eq!(poll(&events), Poll::Ready(Err(Overflow)));
+        assert_eq!(poll(&events), Poll::Ready(Ok(event(1))));
+        assert_eq!(poll(&events), Poll::Ready(Ok(event(2))));
+        assert_eq!(poll(&events), Poll::Pending);
+    }
+
+    #[test]
+    fn function_gpio_events_on_interrupt() {
+        let glb = MockRegisterBlock::new();
+        // Set interrupt pending flag and interrupt mode.
+        let pending = |pin: usize, mode: InterruptMode| {
+            glb.set_raw(0x8c4 + pin * 4, (1 << 21) | (mode.bits() << 16))
+        };
+        pending(3, InterruptMode::SyncFallingEdge);
+        pending(40, InterruptMode::SyncBothEdges);
+        pending(41, InterruptMode::AsyncRisingEdge);
+        unsafe { glb.gpio_config[41].modify(|val| val.mask_interrupt()) };
+        // io40 reads high after its both-edges interrupt.
+        glb.set_raw(0xac8, 1 << 8);
+        let events = GpioEvents::<4>::new();
+        events.on_interrupt(&glb);
+        assert_eq!(
+            poll(&events),
+            Poll::Ready(Ok(Event {
+                pin: 3,
+                edge: Edge::Falling
+            }))
+        );
+        assert_eq!(
+            poll(&events),
+            Poll::Ready(Ok(Event {
+                pin: 40,
+                edge: Edge::Rising
+            }))
+        );
+        assert_eq!(poll(&events), Poll::Pending);
+        // Interrupt clear bit is released after the pulse.
+        assert_eq!(glb.raw(0x8c4 + 3 * 4) & (1 << 20), 0);
+    }
+}
@@ -0,0 +1,160 @@
+//! Frequency counter by gated counting of input edges.
+//!
+//! [`FrequencyCounter::frequency_counter`] opens a gate of given duration timed by the
+//! `mcycle` core cycle counter, and counts edges latched in the interrupt flag of the
+//! pin meanwhile. Frequency is computed from the edge count over cycles actually
+//! elapsed, rather than over nominal gate duration, thus it stays accurate if the gate
+//! overruns by a few cycles.
+//!
+//! The trigger mode of the pin is set to rising edge before measuring, replacing any
+//! mode set by [`Input::listen`](super::Input::listen). The pin interrupt is masked
+//! during the gate and remains masked afterwards, so that no interrupt handler would
+//! take the flag away. Each edge is taken by one pass of the polling
+//! loop, thus signal frequency is limited to a fraction of core clock rather than by
+//! counter width. Frequency is computed in 128-bit arithmetic and saturates at
+//! `u32::MAX` hertz.
+//!
+//! The cycle counter is read by RISC-V instructions, thus [`FrequencyCounter`] is only
+//! available when compiling for RISC-V.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+//! # fn example(clocks: &bouffalo_hal::clocks::Clocks, mut pin: bouffalo_hal::gpio::Input<'static, 12, bouffalo_hal::gpio::Floating>) {
+//! use bouffalo_hal::gpio::FrequencyCounter;
+//! use core::time::Duration;
+//!
+//! let mut counter = FrequencyCounter::new(clocks);
+//! let frequency = counter.frequency_counter(&mut pin, Duration::from_millis(100));
+//! # }
+//! ```
+#[cfg(any(doc, target_arch = "riscv32", target_arch = "riscv64"))]
+use crate::clocks::Clocks;
+#[cfg(any(doc, target_arch = "riscv32", target_arch = "riscv64"))]
+use core::time::Duration;
+#[cfg(any(test, doc, target_arch = "riscv32", target_arch = "riscv64"))]
+use embedded_time::rate::Hertz;
+
+/// Edge counter for frequency measurement, gated by core cycle counter.
+#[cfg(any(doc, target_arch = "riscv32", target_arch = "riscv64"))]
+#[derive(Clone, Copy, Debug)]
+pub struct FrequencyCounter {
+    cpu_clock: Hertz,
+}
+
+#[cfg(any(doc, target_arch = "riscv32", target_arch = "riscv64"))]
+impl FrequencyCounter {
+    /// Create a frequency counter timing its gate under given clock settings.
+    #[inline]
+    pub const fn new(clocks: &Clocks) -> Self {
+        Self {
+            cpu_clock: clocks.cpu_clock(),
+        }
+    }
+    /// Count rising edges of `pin` over a gate of duration `gate`, returning frequency.
+    ///
+    /// Interrupt of `pin` is masked and set to trigger on rising edges, and its flag is
+    /// cleared after each edge counted.
+    #[inline]
+    pub fn frequency_counter<const N: usize, M>(
+        &mut self,
+        pin: &mut super::Input<'_, N, M>,
+        gate: Duration,
+    ) -> Hertz {
+        let hz = self.cpu_clock.0;
+        let gate_cycles = gate.as_nanos() * hz as u128 / 1_000_000_000;
+        let gate_cycles = gate_cycles.min(u64::MAX as u128) as u64;
+        pin.mask_interrupt();
+        pin.set_rising_edge_trigger();
+        pin.clear_interrupt();
+        let stopwatch = crate::profiling::Stopwatch::start();
+        let (edges, cycles) = count_edges(
+            gate_cycles,
+            || stopwatch.elapsed(),
+            || {
+                let edge = pin.has_interrupt();
+                if edge {
+                    pin.clear_interrupt();
+                }
+                edge
+            },
+        );
+        frequency(edges, cycles, hz)
+    }
+}
+
+/// Count edges reported by `edge` until `elapsed` reaches `gate_cycles`.
+///
+/// Returns edge count and cycles actually elapsed.
+#[cfg(any(test, doc, target_arch = "riscv32", target_arch = "riscv64"))]
+#[inline]
+fn count_edges(
+    gate_cycles: u64,
+    mut elapsed: impl FnMut() -> u64,
+    mut edge: impl FnMut() -> bool,
+) -> (u32, u64) {
+    let mut edges = 0u32;
+    loop {
+        let cycles = elapsed();
+        if cycles >= gate_cycles {
+            return (edges, cycles);
+        }
+        if edge() {
+            edges = edges.saturating_add(1);
+        }
+    }
+}
+
+/// Compute frequency from edge count over `cycles` of a core clock at `hz`.
+#[cfg(any(test, doc, target_arch = "riscv32", target_arch = "riscv64"))]
+#[inline]
+fn frequency(edges: u32, cycles: u64, hz: u32) -> Hertz {
+    if cycles == 0 {
+        return Hertz(0);
+    }
+    let ans = edges as u128 * hz as u128 / cycles as u128;
+    Hertz(ans.min(u32::MAX as u128) as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{count_edges, frequency};
+    use core::cell::Cell;
+    use embedded_time::rate::Hertz;
+
+    #[test]
+    fn function_frequency() {
+        assert_eq!(frequency(1000, 320_000_000, 320_000_000), Hertz(1000));
+        assert_eq!(frequency(3277, 4_000_000, 40_000_000), Hertz(32770));
+        assert_eq!(frequency(12, 40, 40_000_000), Hertz(12_000_000));
+        assert_eq!(frequency(5, 400_000_000, 40_000_000), Hertz(0));
+        assert_eq!(frequency(u32::MAX, 1, 320_000_000), Hertz(u32::MAX));
+        assert_eq!(frequency(100, 0, 320_000_000), Hertz(0));
+    }
+
+    #[test]
+    fn function_count_edges() {
+        // Polling loop takes 7 cycles per pass; an edge is latched every third pass.
+        let now = Cell::new(0u64);
+        let passes = Cell::new(0u32);
+        let elapsed = || {
+            let ans = now.get();
+            now.set(ans + 7);
+            ans
+        };
+        let edge = || {
+            passes.set(passes.get() + 1);
+            passes.get().is_multiple_of(3)
+        };
+        let (edges, cycles) = count_edges(100, elapsed, edge);
+        // Gate overruns to the first cycle count read at or after 100.
+        assert_eq!(cycles, 105);
+        assert_eq!(passes.get(), 15);
+        assert_eq!(edges, 5);
+        assert_eq!(
+            count_edges(0, || 3, || panic!("gate already closed")),
+            (0, 3)
+        );
+    }
+}
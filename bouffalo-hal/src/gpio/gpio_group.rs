@@ -1,4 +1,6 @@
 use super::disabled::Disabled;
+#[cfg(feature = "glb-v2")]
+use super::port::{Port, Port0, Port1};
 
 /// Available GPIO pads.
 pub struct Pads<'a> {
@@ -94,6 +96,53 @@ pub struct Pads<'a> {
     pub io44: Disabled<'a, 44>,
     /// GPIO I/O 45.
     pub io45: Disabled<'a, 45>,
+    #[cfg(feature = "glb-v2")]
+    base: &'a crate::glb::RegisterBlock,
+}
+
+#[cfg(feature = "glb-v2")]
+impl<'a> Pads<'a> {
+    /// Take the whole set of pads as port handles of io0 to io31 and io32 to io45.
+    ///
+    /// Consuming every pad ensures that no other pad structure uses pins of the ports.
+    #[inline]
+    pub fn into_ports(self) -> (Port0<'a>, Port1<'a>) {
+        (
+            Port::from_all_pins(self.base),
+            Port::from_all_pins(self.base),
+        )
+    }
+    /// Bring every pad to a known state, discarding configuration left by bootloaders.
+    ///
+    /// Each pad is written [`GpioConfig::SAFE_DEFAULT`](crate::glb::v2::GpioConfig::SAFE_DEFAULT),
+    /// a floating GPIO input with interrupt masked, and its output value is cleared. It
+    /// takes the whole set of pads, thus it is only available before any pad is moved
+    /// out for use.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.reset_except(&[]);
+    }
+    /// Bring every pad except those in `keep` to a known state.
+    ///
+    /// Pads in use during board initialization, e.g. the active JTAG or flash pads,
+    /// should be kept, as resetting them breaks the debug connection or code fetch.
+    /// Pad numbers beyond io45 in `keep` are ignored.
+    #[inline]
+    pub fn reset_except(&mut self, keep: &[u8]) {
+        use crate::glb::v2::GpioConfig;
+        let keep = keep
+            .iter()
+            .filter(|&&n| (n as usize) < super::pad_v2::PIN_COUNT)
+            .fold(0u64, |mask, &n| mask | (1 << n));
+        let mut clear = [0u32; 2];
+        for (n, config) in self.base.masked_pins(!keep) {
+            unsafe { config.write(GpioConfig::SAFE_DEFAULT) };
+            clear[n >> 5] |= 1 << (n & 0x1F);
+        }
+        for (register, bits) in self.base.gpio_clear.iter().zip(clear) {
+            unsafe { register.write(bits) };
+        }
+    }
 }
 
 // Internal function for macros, do not use.
@@ -148,6 +197,46 @@ impl<'a> Pads<'a> {
             io43: super::Inner::__from_glb(base).into(),
             io44: super::Inner::__from_glb(base).into(),
             io45: super::Inner::__from_glb(base).into(),
+            #[cfg(feature = "glb-v2")]
+            base,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "glb-v2"))]
+mod tests {
+    use super::Pads;
+    use crate::glb::{
+        Pull,
+        v2::{Function, GpioConfig, MockRegisterBlock},
+    };
+
+    #[test]
+    fn struct_pads_reset() {
+        let glb = MockRegisterBlock::new();
+        for n in 0..46 {
+            glb.set_raw(0x8c4 + n * 4, 0x4b00_0f5c);
+        }
+        Pads::__pads_from_glb(&glb).reset();
+        assert!((0..46).all(|n| glb.raw(0x8c4 + n * 4) == 0x0040_0b03));
+        assert_eq!((glb.raw(0xaf4), glb.raw(0xaf8)), (0xffff_ffff, 0x0000_3fff));
+        let val = GpioConfig::SAFE_DEFAULT;
+        assert_eq!(val.function(), Function::Gpio);
+        assert!(val.is_input_enabled() && !val.is_output_enabled());
+        assert_eq!(val.pull(), Pull::None);
+        assert!(val.is_interrupt_masked());
+
+        for n in 0..46 {
+            glb.set_raw(0x8c4 + n * 4, 0x4b00_0f5c);
+        }
+        Pads::__pads_from_glb(&glb).reset_except(&[0, 1, 40, 200]);
+        for n in 0..46 {
+            let expected = match n {
+                0 | 1 | 40 => 0x4b00_0f5c,
+                _ => 0x0040_0b03,
+            };
+            assert_eq!(glb.raw(0x8c4 + n * 4), expected);
         }
+        assert_eq!((glb.raw(0xaf4), glb.raw(0xaf8)), (0xffff_fffc, 0x0000_3eff));
     }
 }
@@ -0,0 +1,70 @@
+use super::alternate::Alternate;
+use crate::glb::v2;
+use core::mem::ManuallyDrop;
+
+/// Pad mode served by a hardware block that several pads may share.
+pub(crate) trait Shared: Sized {
+    /// Pad type returned once the hardware block releases the pad.
+    type Released<'a, const N: usize>;
+    /// Check if pad `m` with `config` is served by the hardware block of pad `n`.
+    fn shares(n: usize, m: usize, config: v2::GpioConfig) -> bool;
+    /// Switch the pad back to an ordinary GPIO mode.
+    fn release<'a, const N: usize>(pad: Alternate<'a, N, Self>) -> Self::Released<'a, N>;
+    /// Stop the hardware block of pad `n` after its last pad is released.
+    fn stop(base: &v2::RegisterBlock, n: usize);
+}
+
+/// Check if any pad other than `n` is served by the hardware block of pad `n`.
+#[inline]
+pub(crate) fn in_use<M: Shared>(base: &v2::RegisterBlock, n: usize) -> bool {
+    base.gpio_config
+        .iter()
+        .enumerate()
+        .any(|(m, config)| m != n && M::shares(n, m, config.read()))
+}
+
+/// Pad `N` handed to a shared hardware block, released when this guard is dropped.
+pub(crate) struct PadGuard<'a, const N: usize, M: Shared> {
+    pub(crate) base: &'a v2::RegisterBlock,
+    pad: ManuallyDrop<Alternate<'a, N, M>>,
+}
+
+impl<'a, const N: usize, M: Shared> PadGuard<'a, N, M> {
+    #[cfg(any(doc, feature = "glb-v2"))]
+    #[inline]
+    pub(crate) fn new(base: &'a v2::RegisterBlock, pad: Alternate<'a, N, M>) -> Self {
+        Self {
+            base,
+            pad: ManuallyDrop::new(pad),
+        }
+    }
+    /// Release the pad, stopping the hardware block if no other pad uses it.
+    #[inline]
+    pub(crate) fn free(self) -> M::Released<'a, N> {
+        let mut this = ManuallyDrop::new(self);
+        // SAFETY: `this` is never dropped, so the pad is taken only once.
+        let pad = unsafe { ManuallyDrop::take(&mut this.pad) };
+        release(this.base, pad)
+    }
+}
+
+impl<const N: usize, M: Shared> Drop for PadGuard<'_, N, M> {
+    #[inline]
+    fn drop(&mut self) {
+        // SAFETY: the pad is not used after the guard is dropped.
+        let pad = unsafe { ManuallyDrop::take(&mut self.pad) };
+        release(self.base, pad);
+    }
+}
+
+#[inline]
+fn release<'a, const N: usize, M: Shared>(
+    base: &v2::RegisterBlock,
+    pad: Alternate<'a, N, M>,
+) -> M::Released<'a, N> {
+    let pad = M::release(pad);
+    if !in_use::<M>(base, N) {
+        M::stop(base, N);
+    }
+    pad
+}
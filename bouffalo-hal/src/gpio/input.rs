@@ -1,10 +1,18 @@
 #[cfg(any(doc, feature = "glb-v2"))]
-use super::{alternate::Alternate, convert::IntoPadv2};
+use super::{
+    alternate::Alternate,
+    convert::IntoPadv2,
+    dyn_pin::{DynPin, DynPinError, PullMode},
+};
 use super::{
     convert::IntoPad,
+    locked::Locked,
     output::Output,
     typestate::{self, Floating, PullDown, PullUp},
 };
+#[cfg(any(doc, feature = "glb-v2"))]
+use crate::glb::v2::InterruptMode;
+use crate::glb::{Drive, Pull};
 use embedded_hal::digital::{ErrorType, InputPin};
 
 /// GPIO pad in input mode.
@@ -13,17 +21,58 @@ pub struct Input<'a, const N: usize, M> {
 }
 
 impl<'a, const N: usize, M> Input<'a, N, M> {
-    /// Enable schmitt trigger.
+    /// Lock configuration of this pad, leaving only level operations available.
+    #[inline]
+    pub fn lock(self) -> Locked<Self> {
+        Locked::new(self)
+    }
+    /// Get drive strength of this pad.
+    #[inline]
+    pub fn drive(&self) -> Drive {
+        self.inner.drive()
+    }
+    /// Set drive strength of this pad in place, keeping its current mode.
+    #[inline]
+    pub fn set_drive(&mut self, val: Drive) -> &mut Self {
+        self.inner.set_drive(val);
+        self
+    }
+    /// Get pull direction of this pad.
+    #[inline]
+    pub fn pull(&self) -> Pull {
+        self.inner.pull()
+    }
+    /// Set pull direction of this pad in place, keeping its current mode.
+    ///
+    /// The pull type parameter of this pad is left unchanged; use `into_*` functions to
+    /// change the pull direction along with the type.
+    #[inline]
+    pub fn set_pull(&mut self, val: Pull) -> &mut Self {
+        self.inner.set_pull(val);
+        self
+    }
+    /// Enable schmitt trigger in place, keeping pull and drive settings.
+    ///
+    /// Schmitt trigger adds hysteresis to the input buffer, which filters noise on
+    /// slow or bouncing edges, e.g. from buttons.
     #[inline]
     pub fn enable_schmitt(&mut self) {
         self.inner.enable_schmitt()
     }
-    /// Disable schmitt trigger.
+    /// Disable schmitt trigger in place, keeping pull and drive settings.
     #[inline]
     pub fn disable_schmitt(&mut self) {
         self.inner.disable_schmitt()
     }
+    /// Check if schmitt trigger is enabled.
+    #[inline]
+    pub fn is_schmitt_enabled(&self) -> bool {
+        self.inner.is_schmitt_enabled()
+    }
     /// Clear interrupt flag.
+    ///
+    /// This function must be called in the interrupt handler, or the interrupt would
+    /// fire again once the handler returns.
     #[inline]
     pub fn clear_interrupt(&mut self) {
         self.inner.clear_interrupt()
@@ -33,6 +82,11 @@ impl<'a, const N: usize, M> Input<'a, N, M> {
     pub fn has_interrupt(&self) -> bool {
         self.inner.has_interrupt()
     }
+    /// Check if interrupt flag is set, same as [`has_interrupt`](Self::has_interrupt).
+    #[inline]
+    pub fn check_interrupt(&self) -> bool {
+        self.inner.has_interrupt()
+    }
     /// Mask interrupt.
     #[inline]
     pub fn mask_interrupt(&mut self) {
@@ -43,6 +97,38 @@ impl<'a, const N: usize, M> Input<'a, N, M> {
     pub fn unmask_interrupt(&mut self) {
         self.inner.unmask_interrupt();
     }
+    /// Latch interrupt flag of this pad on rising edges, keeping interrupt mask as is.
+    #[cfg(any(doc, target_arch = "riscv32", target_arch = "riscv64"))]
+    #[inline]
+    pub(crate) fn set_rising_edge_trigger(&mut self) {
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "glb-v1")] {
+                self.inner
+                    .set_interrupt_mode(crate::glb::v1::InterruptMode::SyncRisingEdge)
+            } else if #[cfg(feature = "glb-v2")] {
+                self.inner
+                    .set_interrupt_mode(crate::glb::v2::InterruptMode::SyncRisingEdge)
+            }
+        }
+    }
+}
+
+#[cfg(any(doc, feature = "glb-v2"))]
+impl<'a, const N: usize, M> Input<'a, N, M> {
+    /// Start listening to interrupt on this pad in given trigger mode.
+    ///
+    /// Synchronous and asynchronous modes are kept as given: synchronous modes sample
+    /// the pad on GPIO clock, while asynchronous modes also detect edges without clock,
+    /// e.g. to wake up from low power states.
+    #[inline]
+    pub fn listen(&mut self, mode: InterruptMode) {
+        self.inner.listen(mode)
+    }
+    /// Stop listening to interrupt on this pad.
+    #[inline]
+    pub fn unlisten(&mut self) {
+        self.inner.unlisten()
+    }
 }
 
 impl<'a, const N: usize, M> IntoPad<'a, N> for Input<'a, N, M> {
@@ -118,10 +204,88 @@ impl<'a, const N: usize, M> IntoPadv2<'a, N> for Input<'a, N, M> {
     fn into_jtag_lp(self) -> Alternate<'a, N, typestate::JtagLp> {
         self.inner.into_jtag_lp().into()
     }
+    #[inline]
+    fn into_i2s(self) -> Alternate<'a, N, typestate::I2s> {
+        self.inner.into_i2s().into()
+    }
+    #[inline]
+    fn into_pdm(self) -> Alternate<'a, N, typestate::Pdm> {
+        self.inner.into_pdm().into()
+    }
+    #[inline]
+    fn into_emac(self) -> Alternate<'a, N, typestate::Emac> {
+        self.inner.into_emac().into()
+    }
+    #[inline]
+    fn into_cam(self) -> Alternate<'a, N, typestate::Cam> {
+        self.inner.into_cam().into()
+    }
+    #[inline]
+    fn into_dbi_b(self) -> Alternate<'a, N, typestate::DbiB> {
+        self.inner.into_dbi_b().into()
+    }
+    #[inline]
+    fn into_dbi_c(self) -> Alternate<'a, N, typestate::DbiC> {
+        self.inner.into_dbi_c().into()
+    }
+    #[inline]
+    fn into_dpi(self) -> Alternate<'a, N, typestate::Dpi> {
+        self.inner.into_dpi().into()
+    }
+    #[inline]
+    fn into_clock_out(
+        self,
+        source: super::ClockOutSource,
+        divide: u8,
+    ) -> Result<super::ClockOutPin<'a, N>, (Self, super::ClockOutError)> {
+        self.inner
+            .into_clock_out(source, divide)
+            .map_err(|(inner, e)| (inner.into(), e))
+    }
+    #[inline]
+    fn into_sequencer(
+        self,
+        tick_cycles: u16,
+    ) -> Result<super::SequencerPin<'a, N>, (Self, super::SequencerError)> {
+        self.inner
+            .into_sequencer(tick_cycles)
+            .map_err(|(inner, e)| (inner.into(), e))
+    }
+    #[inline]
+    fn into_analog(self) -> Alternate<'a, N, typestate::Analog> {
+        self.inner.into_analog().into()
+    }
+    #[inline]
+    fn into_open_drain_output(self) -> Output<'a, N, typestate::OpenDrain<Floating>> {
+        self.inner.into_open_drain_output().into()
+    }
+    #[inline]
+    fn into_pull_up_open_drain_output(self) -> Output<'a, N, typestate::OpenDrain<PullUp>> {
+        self.inner.into_pull_up_open_drain_output().into()
+    }
+}
+
+#[cfg(any(doc, feature = "glb-v2"))]
+impl<'a, const N: usize, M: PullMode> Input<'a, N, M> {
+    /// Erase pad number and mode into a runtime [`DynPin`].
+    #[inline]
+    pub fn downgrade(self) -> DynPin<'a> {
+        self.inner.downgrade()
+    }
+}
+
+#[cfg(any(doc, feature = "glb-v2"))]
+impl<'a, const N: usize, M: PullMode> TryFrom<DynPin<'a>> for Input<'a, N, M> {
+    type Error = DynPinError;
+
+    #[inline]
+    fn try_from(pin: DynPin<'a>) -> Result<Self, Self::Error> {
+        super::Inner::try_from(pin).map(Self::from)
+    }
 }
 
 impl<'a, const N: usize, M> ErrorType for Input<'a, N, M> {
-    type Error = core::convert::Infallible;
+    type Error = super::Error;
 }
 
 impl<'a, const N: usize, M> InputPin for Input<'a, N, M> {
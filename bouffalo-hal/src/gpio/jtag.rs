@@ -0,0 +1,139 @@
+use super::{
+    alternate::Alternate,
+    convert::{IntoPad, IntoPadv2},
+    input::Input,
+    typestate::{Floating, JtagD0},
+};
+use core::mem::ManuallyDrop;
+
+/// D0 core JTAG interface on pads io0 to io3.
+///
+/// The D0 core JTAG signals are only available as a group of the four pads io0, io1,
+/// io2 and io3. This guard takes exactly those pads, configures them all for D0 core
+/// JTAG, and restores them to floating inputs when dropped.
+///
+/// ```no_run
+/// # use bouffalo_hal::gpio::{Disabled, JtagD0Pins};
+/// # fn enable(io0: Disabled<'static, 0>, io1: Disabled<'static, 1>,
+/// #           io2: Disabled<'static, 2>, io3: Disabled<'static, 3>) {
+/// let jtag = JtagD0Pins::new(io0, io1, io2, io3);
+/// // Debugger may now be attached to the D0 core.
+/// drop(jtag);
+/// # }
+/// ```
+///
+/// Individual pads may still be switched with `into_jtag_d0` if needed.
+pub struct JtagD0Pins<'a> {
+    pads: ManuallyDrop<JtagD0Pads<'a>>,
+}
+
+type JtagD0Pads<'a> = (
+    Alternate<'a, 0, JtagD0>,
+    Alternate<'a, 1, JtagD0>,
+    Alternate<'a, 2, JtagD0>,
+    Alternate<'a, 3, JtagD0>,
+);
+
+impl<'a> JtagD0Pins<'a> {
+    /// Configure pads io0 to io3 as D0 core JTAG.
+    #[inline]
+    pub fn new(
+        io0: impl IntoPadv2<'a, 0>,
+        io1: impl IntoPadv2<'a, 1>,
+        io2: impl IntoPadv2<'a, 2>,
+        io3: impl IntoPadv2<'a, 3>,
+    ) -> Self {
+        Self {
+            pads: ManuallyDrop::new((
+                io0.into_jtag_d0(),
+                io1.into_jtag_d0(),
+                io2.into_jtag_d0(),
+                io3.into_jtag_d0(),
+            )),
+        }
+    }
+    /// Disable D0 core JTAG, returning the pads as floating inputs.
+    #[inline]
+    pub fn free(
+        self,
+    ) -> (
+        Input<'a, 0, Floating>,
+        Input<'a, 1, Floating>,
+        Input<'a, 2, Floating>,
+        Input<'a, 3, Floating>,
+    ) {
+        let mut this = ManuallyDrop::new(self);
+        // SAFETY: `this` is never dropped, so the pads are taken only once.
+        let pads = unsafe { ManuallyDrop::take(&mut this.pads) };
+        restore(pads)
+    }
+}
+
+impl Drop for JtagD0Pins<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        // SAFETY: the pads are not used after the guard is dropped.
+        let pads = unsafe { ManuallyDrop::take(&mut self.pads) };
+        restore(pads);
+    }
+}
+
+#[inline]
+fn restore(
+    (io0, io1, io2, io3): JtagD0Pads<'_>,
+) -> (
+    Input<'_, 0, Floating>,
+    Input<'_, 1, Floating>,
+    Input<'_, 2, Floating>,
+    Input<'_, 3, Floating>,
+) {
+    (
+        io0.into_floating_input(),
+        io1.into_floating_input(),
+        io2.into_floating_input(),
+        io3.into_floating_input(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JtagD0Pins;
+    use crate::glb::{
+        Pull,
+        v2::{Function, MockRegisterBlock},
+    };
+    use crate::gpio::{Disabled, Padv2};
+
+    #[test]
+    fn struct_jtag_d0_pins() {
+        let glb = MockRegisterBlock::new();
+        let jtag = JtagD0Pins::new(
+            Disabled::from(Padv2::<0, _>::__from_glb(&glb)),
+            Disabled::from(Padv2::<1, _>::__from_glb(&glb)),
+            Disabled::from(Padv2::<2, _>::__from_glb(&glb)),
+            Disabled::from(Padv2::<3, _>::__from_glb(&glb)),
+        );
+        for n in 0..4 {
+            assert_eq!(glb.gpio_config[n].read().function(), Function::JtagD0);
+        }
+        assert_ne!(glb.gpio_config[4].read().function(), Function::JtagD0);
+        drop(jtag);
+        for n in 0..4 {
+            let config = glb.gpio_config[n].read();
+            assert_eq!(config.function(), Function::Gpio);
+            assert!(config.is_input_enabled());
+            assert!(!config.is_output_enabled());
+            assert_eq!(config.pull(), Pull::None);
+        }
+
+        let jtag = JtagD0Pins::new(
+            Disabled::from(Padv2::<0, _>::__from_glb(&glb)),
+            Disabled::from(Padv2::<1, _>::__from_glb(&glb)),
+            Disabled::from(Padv2::<2, _>::__from_glb(&glb)),
+            Disabled::from(Padv2::<3, _>::__from_glb(&glb)),
+        );
+        let (io0, _, _, io3) = jtag.free();
+        let _ = (io0, io3);
+        assert_eq!(glb.gpio_config[3].read().function(), Function::Gpio);
+    }
+}
@@ -0,0 +1,96 @@
+use embedded_hal::digital::{ErrorType, InputPin, OutputPin};
+
+/// GPIO pad with its configuration locked.
+///
+/// BL808 and BL616 GPIO configuration registers have no hardware lock bit, thus the
+/// lock is enforced in software by the type system: a locked pad keeps its current
+/// function, and can still be read or driven, but offers no way to call `into_*` or
+/// other configuration changing functions. The lock lasts until the structure is
+/// dropped; there is no way to unlock it to retrieve the pad again.
+///
+/// ```compile_fail
+/// # use bouffalo_hal::gpio::{Floating, IntoPad, Locked, Output};
+/// fn reconfigure(led: Locked<Output<'static, 8, Floating>>) {
+///     // Error: a locked pad cannot be reconfigured.
+///     let _ = led.into_floating_input();
+/// }
+/// ```
+///
+/// Locked output pads can still be driven:
+///
+/// ```
+/// # use bouffalo_hal::gpio::{Floating, Locked, Output};
+/// use embedded_hal::digital::OutputPin;
+///
+/// fn blink(led: &mut Locked<Output<'static, 8, Floating>>) {
+///     led.set_high().ok();
+///     led.set_low().ok();
+/// }
+/// ```
+pub struct Locked<P> {
+    pad: P,
+}
+
+impl<P> Locked<P> {
+    /// Lock the configuration of the given pad.
+    #[inline]
+    pub const fn new(pad: P) -> Self {
+        Self { pad }
+    }
+}
+
+impl<P> core::ops::Deref for Locked<P> {
+    type Target = P;
+
+    #[inline]
+    fn deref(&self) -> &P {
+        &self.pad
+    }
+}
+
+impl<P: ErrorType> ErrorType for Locked<P> {
+    type Error = P::Error;
+}
+
+impl<P: InputPin> InputPin for Locked<P> {
+    #[inline]
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        self.pad.is_high()
+    }
+    #[inline]
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        self.pad.is_low()
+    }
+}
+
+impl<P: OutputPin> OutputPin for Locked<P> {
+    #[inline]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.pad.set_low()
+    }
+    #[inline]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.pad.set_high()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Locked;
+    use crate::glb::v2::{Function, MockRegisterBlock};
+    use crate::gpio::Padv2;
+    use embedded_hal::digital::OutputPin;
+
+    #[test]
+    fn struct_locked_keeps_configuration() {
+        let glb = MockRegisterBlock::new();
+        let pad = Padv2::<8, _>::__from_glb(&glb).into_floating_output();
+        let config = glb.gpio_config[8].read();
+        let mut locked = Locked::new(pad);
+        locked.set_high().unwrap();
+        assert_eq!(glb.raw(0xaec), 1 << 8);
+        assert_eq!(locked.drive(), config.drive());
+        assert_eq!(glb.gpio_config[8].read(), config);
+        assert_eq!(glb.gpio_config[8].read().function(), Function::Gpio);
+    }
+}
@@ -1,12 +1,17 @@
 #[cfg(any(doc, feature = "glb-v2"))]
-use super::{alternate::Alternate, convert::IntoPadv2};
+use super::{
+    alternate::Alternate,
+    convert::IntoPadv2,
+    dyn_pin::{DynPin, DynPinError, OutputMode, PullMode},
+};
 use super::{
     convert::IntoPad,
     input::Input,
+    locked::Locked,
     typestate::{self, Floating, PullDown, PullUp},
 };
-use crate::glb::Drive;
-use embedded_hal::digital::{ErrorType, OutputPin};
+use crate::glb::{Drive, Pull};
+use embedded_hal::digital::{ErrorType, InputPin, OutputPin, StatefulOutputPin};
 
 /// GPIO pad in output mode.
 pub struct Output<'a, const N: usize, M> {
@@ -14,15 +19,79 @@ pub struct Output<'a, const N: usize, M> {
 }
 
 impl<'a, const N: usize, M> Output<'a, N, M> {
+    /// Lock configuration of this pad, leaving only level operations available.
+    #[inline]
+    pub fn lock(self) -> Locked<Self> {
+        Locked::new(self)
+    }
     /// Get drive strength of this pad.
     #[inline]
     pub fn drive(&self) -> Drive {
         self.inner.drive()
     }
-    /// Set drive strength of this pad.
+    /// Set drive strength of this pad in place, keeping its current mode.
+    ///
+    /// ```no_run
+    /// # use bouffalo_hal::{gpio::Output, glb::{Drive, Pull}};
+    /// # fn tune(led: &mut Output<'static, 8, bouffalo_hal::gpio::Floating>) {
+    /// led.set_drive(Drive::Drive3).set_pull(Pull::None);
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_drive(&mut self, val: Drive) -> &mut Self {
+        self.inner.set_drive(val);
+        self
+    }
+    /// Get pull direction of this pad.
+    #[inline]
+    pub fn pull(&self) -> Pull {
+        self.inner.pull()
+    }
+    /// Set pull direction of this pad in place, keeping its current mode.
+    ///
+    /// The pull type parameter of this pad is left unchanged; use `into_*` functions to
+    /// change the pull direction along with the type.
+    #[inline]
+    pub fn set_pull(&mut self, val: Pull) -> &mut Self {
+        self.inner.set_pull(val);
+        self
+    }
+}
+
+/// Output type of a GPIO pad.
+///
+/// Typed pads carry their output type in the type state, e.g. `Output<'a, N, PullUp>`
+/// for push-pull and `Output<'a, N, OpenDrain<PullUp>>` for open-drain output; this
+/// value selects it at runtime on a [`DynPin`](super::DynPin).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputType {
+    /// Actively drive both high and low levels.
+    PushPull,
+    /// Actively drive low level only; high level releases the line.
+    ///
+    /// The high level is set by a pull-up resistor, either internal or external.
+    OpenDrain,
+}
+
+#[cfg(any(doc, feature = "glb-v2"))]
+impl<'a, const N: usize, M> Output<'a, N, M> {
+    /// Switch this pad into buffered set-clear mode.
+    ///
+    /// Output changes are latched onto the pad on the next GPIO clock edge instead of
+    /// immediately; see [`Padv2::enable_buffered_set_clear`](super::Padv2::enable_buffered_set_clear).
+    #[inline]
+    pub fn enable_buffered_set_clear(&mut self) {
+        self.inner.enable_buffered_set_clear()
+    }
+    /// Switch this pad back into immediate set-clear mode.
+    #[inline]
+    pub fn disable_buffered_set_clear(&mut self) {
+        self.inner.disable_buffered_set_clear()
+    }
+    /// Check if this pad is in buffered set-clear mode.
     #[inline]
-    pub fn set_drive(&mut self, val: Drive) {
-        self.inner.set_drive(val)
+    pub fn is_buffered_set_clear(&self) -> bool {
+        self.inner.is_buffered_set_clear()
     }
 }
 
@@ -99,13 +168,116 @@ impl<'a, const N: usize, M> IntoPadv2<'a, N> for Output<'a, N, M> {
     fn into_jtag_lp(self) -> Alternate<'a, N, typestate::JtagLp> {
         self.inner.into_jtag_lp().into()
     }
+    #[inline]
+    fn into_i2s(self) -> Alternate<'a, N, typestate::I2s> {
+        self.inner.into_i2s().into()
+    }
+    #[inline]
+    fn into_pdm(self) -> Alternate<'a, N, typestate::Pdm> {
+        self.inner.into_pdm().into()
+    }
+    #[inline]
+    fn into_emac(self) -> Alternate<'a, N, typestate::Emac> {
+        self.inner.into_emac().into()
+    }
+    #[inline]
+    fn into_cam(self) -> Alternate<'a, N, typestate::Cam> {
+        self.inner.into_cam().into()
+    }
+    #[inline]
+    fn into_dbi_b(self) -> Alternate<'a, N, typestate::DbiB> {
+        self.inner.into_dbi_b().into()
+    }
+    #[inline]
+    fn into_dbi_c(self) -> Alternate<'a, N, typestate::DbiC> {
+        self.inner.into_dbi_c().into()
+    }
+    #[inline]
+    fn into_dpi(self) -> Alternate<'a, N, typestate::Dpi> {
+        self.inner.into_dpi().into()
+    }
+    #[inline]
+    fn into_clock_out(
+        self,
+        source: super::ClockOutSource,
+        divide: u8,
+    ) -> Result<super::ClockOutPin<'a, N>, (Self, super::ClockOutError)> {
+        self.inner
+            .into_clock_out(source, divide)
+            .map_err(|(inner, e)| (inner.into(), e))
+    }
+    #[inline]
+    fn into_sequencer(
+        self,
+        tick_cycles: u16,
+    ) -> Result<super::SequencerPin<'a, N>, (Self, super::SequencerError)> {
+        self.inner
+            .into_sequencer(tick_cycles)
+            .map_err(|(inner, e)| (inner.into(), e))
+    }
+    #[inline]
+    fn into_analog(self) -> Alternate<'a, N, typestate::Analog> {
+        self.inner.into_analog().into()
+    }
+    #[inline]
+    fn into_open_drain_output(self) -> Output<'a, N, typestate::OpenDrain<Floating>> {
+        self.inner.into_open_drain_output().into()
+    }
+    #[inline]
+    fn into_pull_up_open_drain_output(self) -> Output<'a, N, typestate::OpenDrain<PullUp>> {
+        self.inner.into_pull_up_open_drain_output().into()
+    }
+}
+
+#[cfg(any(doc, feature = "glb-v2"))]
+impl<'a, const N: usize, M: PullMode> Output<'a, N, M> {
+    /// Switch this pad into open-drain output, keeping current level.
+    ///
+    /// As open-drain output, `set_high` releases the line instead of driving it.
+    #[inline]
+    pub fn into_open_drain(self) -> Output<'a, N, typestate::OpenDrain<M>> {
+        self.inner.into_open_drain().into()
+    }
+}
+
+#[cfg(any(doc, feature = "glb-v2"))]
+impl<'a, const N: usize, M: PullMode> Output<'a, N, typestate::OpenDrain<M>> {
+    /// Switch this pad into push-pull output, keeping current level.
+    ///
+    /// A released line becomes a driven high level.
+    #[inline]
+    pub fn into_push_pull(self) -> Output<'a, N, M> {
+        self.inner.into_push_pull().into()
+    }
+}
+
+#[cfg(any(doc, feature = "glb-v2"))]
+impl<'a, const N: usize, M: OutputMode> Output<'a, N, M> {
+    /// Erase pad number and mode into a runtime [`DynPin`].
+    #[inline]
+    pub fn downgrade(self) -> DynPin<'a> {
+        self.inner.downgrade()
+    }
+}
+
+#[cfg(any(doc, feature = "glb-v2"))]
+impl<'a, const N: usize, M: OutputMode> TryFrom<DynPin<'a>> for Output<'a, N, M> {
+    type Error = DynPinError;
+
+    #[inline]
+    fn try_from(pin: DynPin<'a>) -> Result<Self, Self::Error> {
+        super::Inner::try_from(pin).map(Self::from)
+    }
 }
 
 impl<'a, const N: usize, M> ErrorType for Output<'a, N, M> {
-    type Error = core::convert::Infallible;
+    type Error = super::Error;
 }
 
-impl<'a, const N: usize, M> OutputPin for Output<'a, N, M> {
+impl<'a, const N: usize, M> OutputPin for Output<'a, N, M>
+where
+    super::Inner<'a, N, typestate::Output<M>>: OutputPin<Error = super::Error>,
+{
     #[inline]
     fn set_low(&mut self) -> Result<(), Self::Error> {
         self.inner.set_low()
@@ -116,11 +288,50 @@ impl<'a, const N: usize, M> OutputPin for Output<'a, N, M> {
     }
 }
 
+/// Reads the line level of open-drain output pads.
+///
+/// Open-drain pads keep input function enabled, thus the value follows the line level
+/// whether the pad pulls it low or releases it.
+impl<'a, const N: usize, M> InputPin for Output<'a, N, M>
+where
+    super::Inner<'a, N, typestate::Output<M>>: InputPin<Error = super::Error>,
+{
+    #[inline]
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        self.inner.is_high()
+    }
+    #[inline]
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        self.inner.is_low()
+    }
+}
+
+impl<'a, const N: usize, M> StatefulOutputPin for Output<'a, N, M>
+where
+    super::Inner<'a, N, typestate::Output<M>>: StatefulOutputPin<Error = super::Error>,
+{
+    #[inline]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        self.inner.is_set_high()
+    }
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        self.inner.is_set_low()
+    }
+    #[inline]
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        self.inner.toggle()
+    }
+}
+
 // This part of implementation using `embedded_hal_027` is designed for backward compatibility of
 // ecosystem crates, as some of them depends on embedded-hal v0.2.7 traits.
 // We encourage ecosystem developers to use embedded-hal v1.0.0 traits; after that, this part of code
 // would be removed in the future.
-impl<'a, const N: usize, M> embedded_hal_027::digital::v2::OutputPin for Output<'a, N, M> {
+impl<'a, const N: usize, M> embedded_hal_027::digital::v2::OutputPin for Output<'a, N, M>
+where
+    Self: OutputPin<Error = core::convert::Infallible>,
+{
     type Error = core::convert::Infallible;
     #[inline]
     fn set_low(&mut self) -> Result<(), Self::Error> {
@@ -1,8 +1,8 @@
 #![allow(dead_code)]
 use super::typestate::{Floating, Input, Output, PullDown, PullUp};
-use crate::glb::Drive;
+use crate::glb::{Drive, Pull};
 use core::marker::PhantomData;
-use embedded_hal::digital::{ErrorType, InputPin, OutputPin};
+use embedded_hal::digital::{ErrorType, InputPin, OutputPin, StatefulOutputPin};
 
 pub struct PadDummy<'a, const N: usize, M> {
     _unused: PhantomData<(&'a (), M)>,
@@ -18,6 +18,10 @@ impl<'a, const N: usize, M> PadDummy<'a, N, Input<M>> {
         unimplemented!()
     }
     #[inline]
+    pub fn is_schmitt_enabled(&self) -> bool {
+        unimplemented!()
+    }
+    #[inline]
     pub fn clear_interrupt(&mut self) {
         unimplemented!()
     }
@@ -35,18 +39,23 @@ impl<'a, const N: usize, M> PadDummy<'a, N, Input<M>> {
     }
 }
 
-impl<'a, const N: usize, M> PadDummy<'a, N, Output<M>> {
+impl<'a, const N: usize, M> PadDummy<'a, N, M> {
     #[inline]
     pub fn drive(&self) -> Drive {
         unimplemented!()
     }
     #[inline]
-    pub fn set_drive(&mut self, _: Drive) {
+    pub fn set_drive(&mut self, _: Drive) -> &mut Self {
+        unimplemented!()
+    }
+    #[inline]
+    pub fn pull(&self) -> Pull {
+        unimplemented!()
+    }
+    #[inline]
+    pub fn set_pull(&mut self, _: Pull) -> &mut Self {
         unimplemented!()
     }
-}
-
-impl<'a, const N: usize, M> PadDummy<'a, N, M> {
     #[inline]
     pub fn into_pull_up_output(self) -> PadDummy<'a, N, Output<PullUp>> {
         unimplemented!()
@@ -74,11 +83,11 @@ impl<'a, const N: usize, M> PadDummy<'a, N, M> {
 }
 
 impl<'a, const N: usize, M> ErrorType for PadDummy<'a, N, Input<M>> {
-    type Error = core::convert::Infallible;
+    type Error = super::Error;
 }
 
 impl<'a, const N: usize, M> ErrorType for PadDummy<'a, N, Output<M>> {
-    type Error = core::convert::Infallible;
+    type Error = super::Error;
 }
 
 impl<'a, const N: usize, M> InputPin for PadDummy<'a, N, Input<M>> {
@@ -103,6 +112,17 @@ impl<'a, const N: usize, M> OutputPin for PadDummy<'a, N, Output<M>> {
     }
 }
 
+impl<'a, const N: usize, M> StatefulOutputPin for PadDummy<'a, N, Output<M>> {
+    #[inline]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        unimplemented!()
+    }
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        unimplemented!()
+    }
+}
+
 // Macro internal functions, do not use.
 impl<'a, const N: usize> PadDummy<'a, N, super::typestate::Disabled> {
     #[doc(hidden)]
@@ -1,7 +1,7 @@
 use super::typestate::{Floating, Input, Output, PullDown, PullUp};
 use crate::glb::{Drive, Pull, v1};
 use core::marker::PhantomData;
-use embedded_hal::digital::{ErrorType, InputPin, OutputPin};
+use embedded_hal::digital::{ErrorType, InputPin, OutputPin, StatefulOutputPin};
 
 /// Raw GPIO pad of BL602 and BL702.
 pub struct Padv1<'a, const N: usize, M> {
@@ -24,6 +24,13 @@ impl<'a, const N: usize, M> Padv1<'a, N, Input<M>> {
             .disable_schmitt(N & 0x1);
         unsafe { self.base.gpio_config[N >> 1].write(config) };
     }
+    /// Check if schmitt trigger is enabled.
+    #[inline]
+    pub fn is_schmitt_enabled(&self) -> bool {
+        self.base.gpio_config[N >> 1]
+            .read()
+            .is_schmitt_enabled(N & 0x1)
+    }
     /// Clear interrupt flag.
     #[inline]
     pub fn clear_interrupt(&mut self) {
@@ -48,20 +55,6 @@ impl<'a, const N: usize, M> Padv1<'a, N, Input<M>> {
     }
 }
 
-impl<'a, const N: usize, M> Padv1<'a, N, Output<M>> {
-    /// Get drive strength of this pin.
-    #[inline]
-    pub fn drive(&self) -> Drive {
-        self.base.gpio_config[N >> 1].read().drive(N & 0x1)
-    }
-    /// Set drive strength of this pin.
-    #[inline]
-    pub fn set_drive(&mut self, val: Drive) {
-        let config = self.base.gpio_config[N >> 1].read().set_drive(N & 0x1, val);
-        unsafe { self.base.gpio_config[N >> 1].write(config) };
-    }
-}
-
 impl<'a, const N: usize, M> Padv1<'a, N, Input<M>> {
     /// Get interrupt mode.
     #[inline]
@@ -81,6 +74,30 @@ impl<'a, const N: usize, M> Padv1<'a, N, Input<M>> {
 }
 
 impl<'a, const N: usize, M> Padv1<'a, N, M> {
+    /// Get drive strength of this pin.
+    #[inline]
+    pub fn drive(&self) -> Drive {
+        self.base.gpio_config[N >> 1].read().drive(N & 0x1)
+    }
+    /// Set drive strength of this pin, keeping its current function and direction.
+    #[inline]
+    pub fn set_drive(&mut self, val: Drive) -> &mut Self {
+        let config = self.base.gpio_config[N >> 1].read().set_drive(N & 0x1, val);
+        unsafe { self.base.gpio_config[N >> 1].write(config) };
+        self
+    }
+    /// Get pull direction of this pin.
+    #[inline]
+    pub fn pull(&self) -> Pull {
+        self.base.gpio_config[N >> 1].read().pull(N & 0x1)
+    }
+    /// Set pull direction of this pin, keeping its current function and direction.
+    #[inline]
+    pub fn set_pull(&mut self, val: Pull) -> &mut Self {
+        let config = self.base.gpio_config[N >> 1].read().set_pull(N & 0x1, val);
+        unsafe { self.base.gpio_config[N >> 1].write(config) };
+        self
+    }
     /// Configures the pin to operate as a pull up output pin.
     #[inline]
     pub fn into_pull_up_output(self) -> Padv1<'a, N, Output<PullUp>> {
@@ -180,11 +197,11 @@ impl<'a, const N: usize, M> Padv1<'a, N, M> {
 }
 
 impl<'a, const N: usize, M> ErrorType for Padv1<'a, N, Input<M>> {
-    type Error = core::convert::Infallible;
+    type Error = super::Error;
 }
 
 impl<'a, const N: usize, M> ErrorType for Padv1<'a, N, Output<M>> {
-    type Error = core::convert::Infallible;
+    type Error = super::Error;
 }
 
 impl<'a, const N: usize, M> InputPin for Padv1<'a, N, Input<M>> {
@@ -213,6 +230,17 @@ impl<'a, const N: usize, M> OutputPin for Padv1<'a, N, Output<M>> {
     }
 }
 
+impl<'a, const N: usize, M> StatefulOutputPin for Padv1<'a, N, Output<M>> {
+    #[inline]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.base.gpio_output_value.read() & (1 << N) != 0)
+    }
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.base.gpio_output_value.read() & (1 << N) == 0)
+    }
+}
+
 // Macro internal functions, do not use.
 impl<'a, const N: usize> Padv1<'a, N, super::typestate::Disabled> {
     #[doc(hidden)]
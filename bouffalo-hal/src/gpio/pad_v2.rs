@@ -1,20 +1,79 @@
 use super::{
     Spi,
+    dyn_pin::{DynMode, DynPin, DynPinError, OutputMode, PullMode},
     typestate::{
-        Floating, I2c, Input, JtagD0, JtagLp, JtagM0, MmUart, Output, PullDown, PullUp, Pwm, Sdh,
-        Uart,
+        Analog, Cam, DbiB, DbiC, Digital, Dpi, Emac, Floating, I2c, I2s, Input, JtagD0, JtagLp,
+        JtagM0, MmUart, OpenDrain, Output, Pdm, PullDown, PullUp, Pwm, Sdh, Uart,
     },
 };
 use crate::glb::{Drive, Pull, v2};
 use core::marker::PhantomData;
-use embedded_hal::digital::{ErrorType, InputPin, OutputPin};
+use embedded_hal::digital::{ErrorType, InputPin, OutputPin, StatefulOutputPin};
+
+pub(crate) use crate::glb::v2::PIN_COUNT;
+
+/// Pin already claimed by another function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PinConflict {
+    /// Number of the conflicting pin.
+    pub pin: u8,
+    /// Function currently assigned to the pin.
+    pub function: v2::Function,
+}
+
+/// Pads whose pin functions can be checked by peripheral constructors.
+///
+/// Typed pads are assigned their function on conversion, but another core, a
+/// bootloader leftover or a stolen pad handle may reassign the pin afterwards. Checked
+/// constructors like [`Spi::new_checked`](crate::spi::Spi::new_checked) use this trait
+/// to catch such conflicts during bring-up.
+pub trait CheckFunctions {
+    /// Check that every pad is still assigned to the function of its mode.
+    fn check_functions(&self) -> Result<(), PinConflict>;
+}
+
+impl<A: CheckFunctions, B: CheckFunctions> CheckFunctions for (A, B) {
+    #[inline]
+    fn check_functions(&self) -> Result<(), PinConflict> {
+        self.0.check_functions()?;
+        self.1.check_functions()
+    }
+}
+
+impl<A: CheckFunctions, B: CheckFunctions, C: CheckFunctions> CheckFunctions for (A, B, C) {
+    #[inline]
+    fn check_functions(&self) -> Result<(), PinConflict> {
+        self.0.check_functions()?;
+        self.1.check_functions()?;
+        self.2.check_functions()
+    }
+}
+
+impl<A: CheckFunctions, B: CheckFunctions, C: CheckFunctions, D: CheckFunctions> CheckFunctions
+    for (A, B, C, D)
+{
+    #[inline]
+    fn check_functions(&self) -> Result<(), PinConflict> {
+        self.0.check_functions()?;
+        self.1.check_functions()?;
+        self.2.check_functions()?;
+        self.3.check_functions()
+    }
+}
 
 /// Raw GPIO pad of BL808 and BL616.
+///
+/// Pad number `N` is checked at compile time; there is no pad beyond io45.
 pub struct Padv2<'a, const N: usize, M> {
     base: &'a v2::RegisterBlock,
     _mode: PhantomData<M>,
 }
 
+// SAFETY: a pad only modifies its own configuration register, and changes its output
+// level by writing its own bit into `gpio_set` or `gpio_clear`, thus it can be moved
+// into another execution context, e.g. an interrupt handler.
+unsafe impl<'a, const N: usize, M: Send> Send for Padv2<'a, N, M> {}
+
 impl<'a, const N: usize, M> Padv2<'a, N, Input<M>> {
     /// Enable schmitt trigger.
     #[inline]
@@ -28,17 +87,54 @@ impl<'a, const N: usize, M> Padv2<'a, N, Input<M>> {
         let config = self.base.gpio_config[N].read().disable_schmitt();
         unsafe { self.base.gpio_config[N].write(config) };
     }
+    /// Check if schmitt trigger is enabled.
+    #[inline]
+    pub fn is_schmitt_enabled(&self) -> bool {
+        self.base.gpio_config[N].read().is_schmitt_enabled()
+    }
+    /// Start listening to interrupt on this pin in given trigger mode.
+    ///
+    /// Configures interrupt mode, clears any stale interrupt flag and then unmasks the
+    /// interrupt.
+    #[inline]
+    pub fn listen(&mut self, mode: v2::InterruptMode) {
+        let config = self.base.gpio_config[N]
+            .read()
+            .set_interrupt_mode(mode)
+            .mask_interrupt();
+        unsafe { self.base.gpio_config[N].write(config) };
+        self.clear_interrupt();
+        let config = self.base.gpio_config[N].read().unmask_interrupt();
+        unsafe { self.base.gpio_config[N].write(config) };
+    }
+    /// Stop listening to interrupt on this pin.
+    ///
+    /// Masks the interrupt and clears pending interrupt flag.
+    #[inline]
+    pub fn unlisten(&mut self) {
+        let config = self.base.gpio_config[N].read().mask_interrupt();
+        unsafe { self.base.gpio_config[N].write(config) };
+        self.clear_interrupt();
+    }
     /// Clear interrupt flag.
+    ///
+    /// This function must be called in the interrupt handler, or the interrupt would
+    /// fire again once the handler returns.
     #[inline]
     pub fn clear_interrupt(&mut self) {
-        let config = self.base.gpio_config[N].read().clear_interrupt();
-        unsafe { self.base.gpio_config[N].write(config) };
+        let config = &self.base.gpio_config[N];
+        v2::clear_interrupt(config, config.read());
     }
     /// Check if interrupt flag is set.
     #[inline]
     pub fn has_interrupt(&self) -> bool {
         self.base.gpio_config[N].read().has_interrupt()
     }
+    /// Check if interrupt flag is set, same as [`has_interrupt`](Self::has_interrupt).
+    #[inline]
+    pub fn check_interrupt(&self) -> bool {
+        self.has_interrupt()
+    }
     /// Mask interrupt.
     #[inline]
     pub fn mask_interrupt(&mut self) {
@@ -54,17 +150,88 @@ impl<'a, const N: usize, M> Padv2<'a, N, Input<M>> {
 }
 
 impl<'a, const N: usize, M> Padv2<'a, N, Output<M>> {
-    /// Get drive strength of this pin.
+    /// Switch this pin into buffered set-clear mode.
+    ///
+    /// In set-clear mode a write to `gpio_set` or `gpio_clear` changes the pad at once.
+    /// In buffered set-clear mode the written value is held in an output buffer and
+    /// latched onto the pad on the next GPIO clock edge, so every pin of the same bank
+    /// updated by one register write changes in the same cycle. This allows glitch-free
+    /// updates of parallel buses. `set_high` and `set_low` work the same in both modes.
     #[inline]
-    pub fn drive(&self) -> Drive {
-        self.base.gpio_config[N].read().drive()
+    pub fn enable_buffered_set_clear(&mut self) {
+        let config = self.base.gpio_config[N]
+            .read()
+            .set_mode(v2::Mode::BufferedSetClear);
+        unsafe { self.base.gpio_config[N].write(config) };
     }
-    /// Set drive strength of this pin.
+    /// Switch this pin back into immediate set-clear mode.
     #[inline]
-    pub fn set_drive(&mut self, val: Drive) {
-        let config = self.base.gpio_config[N].read().set_drive(val);
+    pub fn disable_buffered_set_clear(&mut self) {
+        let config = self.base.gpio_config[N].read().set_mode(v2::Mode::SetClear);
         unsafe { self.base.gpio_config[N].write(config) };
     }
+    /// Check if this pin is in buffered set-clear mode.
+    #[inline]
+    pub fn is_buffered_set_clear(&self) -> bool {
+        self.base.gpio_config[N].read().mode() == v2::Mode::BufferedSetClear
+    }
+}
+
+impl<'a, const N: usize, M: PullMode> Padv2<'a, N, Output<M>> {
+    /// Switch this pin into open-drain output, keeping current level.
+    ///
+    /// The pad configuration is changed by one register write; output value register is
+    /// only written while the pad is not driven, thus there is no glitch on the line
+    /// during the transition.
+    #[inline]
+    pub fn into_open_drain(self) -> Padv2<'a, N, Output<OpenDrain<M>>> {
+        push_pull_into_open_drain(self.base, N);
+        Padv2 {
+            base: self.base,
+            _mode: PhantomData,
+        }
+    }
+}
+
+impl<'a, const N: usize, M: PullMode> Padv2<'a, N, Output<OpenDrain<M>>> {
+    /// Switch this pin into push-pull output, keeping current level.
+    ///
+    /// A released line becomes a driven high level. Output value register is written
+    /// before the pad is driven, thus there is no glitch on the line.
+    #[inline]
+    pub fn into_push_pull(self) -> Padv2<'a, N, Output<M>> {
+        open_drain_into_push_pull(self.base, N);
+        Padv2 {
+            base: self.base,
+            _mode: PhantomData,
+        }
+    }
+}
+
+// Switch push-pull output pad `n` into open-drain output, keeping its level.
+#[inline]
+pub(crate) fn push_pull_into_open_drain(base: &v2::RegisterBlock, n: usize) {
+    let config = base.gpio_config[n].read();
+    // Output latch of set-clear modes is read back in the output bit.
+    let config = if config.output_value() {
+        config.enable_input().disable_output()
+    } else {
+        config.enable_input()
+    };
+    unsafe { base.gpio_config[n].write(config) };
+    // Open-drain output only ever drives low.
+    unsafe { base.gpio_clear[n >> 5].write(1 << (n & 0x1F)) };
+}
+
+// Switch open-drain output pad `n` into push-pull output, keeping its level.
+#[inline]
+pub(crate) fn open_drain_into_push_pull(base: &v2::RegisterBlock, n: usize) {
+    let config = base.gpio_config[n].read();
+    if !config.is_output_enabled() {
+        // Line is released high; latch high before driving.
+        unsafe { base.gpio_set[n >> 5].write(1 << (n & 0x1F)) };
+    }
+    unsafe { base.gpio_config[n].write(config.disable_input().enable_output()) };
 }
 
 impl<'a, const N: usize, M> Padv2<'a, N, Input<M>> {
@@ -81,7 +248,44 @@ impl<'a, const N: usize, M> Padv2<'a, N, Input<M>> {
     }
 }
 
+impl<'a, const N: usize, M: Digital> Padv2<'a, N, M> {
+    /// Set drive strength of this pin, keeping its current function and direction.
+    #[inline]
+    pub fn set_drive(&mut self, val: Drive) -> &mut Self {
+        let config = self.base.gpio_config[N].read().set_drive(val);
+        unsafe { self.base.gpio_config[N].write(config) };
+        self
+    }
+    /// Set pull direction of this pin, keeping its current function and direction.
+    #[inline]
+    pub fn set_pull(&mut self, val: Pull) -> &mut Self {
+        let config = self.base.gpio_config[N].read().set_pull(val);
+        unsafe { self.base.gpio_config[N].write(config) };
+        self
+    }
+}
+
 impl<'a, const N: usize, M> Padv2<'a, N, M> {
+    /// Fails compilation when evaluated for a pad that does not exist.
+    const VALID: () = assert!(N < PIN_COUNT, "GPIO pad number out of range");
+    /// Input and output register bank of this pad.
+    const BANK: usize = {
+        let () = Self::VALID;
+        N >> 5
+    };
+    /// Bit of this pad in its register bank.
+    const BIT: u32 = 1 << (N & 0x1F);
+
+    /// Get drive strength of this pin.
+    #[inline]
+    pub fn drive(&self) -> Drive {
+        self.base.gpio_config[N].read().drive()
+    }
+    /// Get pull direction of this pin.
+    #[inline]
+    pub fn pull(&self) -> Pull {
+        self.base.gpio_config[N].read().pull()
+    }
     /// Configures the pin to operate as a pull up output pin.
     #[inline]
     pub fn into_pull_up_output(self) -> Padv2<'a, N, Output<PullUp>> {
@@ -310,6 +514,220 @@ impl<'a, const N: usize, M> Padv2<'a, N, M> {
             _mode: PhantomData,
         }
     }
+    /// Configures the pin to operate as an Inter-IC Sound signal pin.
+    #[inline]
+    pub fn into_i2s(self) -> Padv2<'a, N, I2s> {
+        let config = v2::GpioConfig::RESET_VALUE
+            .enable_input()
+            .enable_output()
+            .enable_schmitt()
+            .set_drive(Drive::Drive0)
+            .set_pull(Pull::None)
+            .set_function(v2::Function::I2s);
+        unsafe { self.base.gpio_config[N].write(config) };
+        Padv2 {
+            base: self.base,
+            _mode: PhantomData,
+        }
+    }
+    /// Configures the pin to operate as a Pulse Density Modulation microphone pin.
+    #[inline]
+    pub fn into_pdm(self) -> Padv2<'a, N, Pdm> {
+        let config = v2::GpioConfig::RESET_VALUE
+            .enable_input()
+            .enable_output()
+            .enable_schmitt()
+            .set_drive(Drive::Drive0)
+            .set_pull(Pull::None)
+            .set_function(v2::Function::Pdm);
+        unsafe { self.base.gpio_config[N].write(config) };
+        Padv2 {
+            base: self.base,
+            _mode: PhantomData,
+        }
+    }
+    /// Configures the pin to operate as an Ethernet reduced media-independent interface pin.
+    #[inline]
+    pub fn into_emac(self) -> Padv2<'a, N, Emac> {
+        let config = v2::GpioConfig::RESET_VALUE
+            .enable_input()
+            .enable_output()
+            .enable_schmitt()
+            .set_drive(Drive::Drive0)
+            .set_pull(Pull::None)
+            .set_function(v2::Function::Emac);
+        unsafe { self.base.gpio_config[N].write(config) };
+        Padv2 {
+            base: self.base,
+            _mode: PhantomData,
+        }
+    }
+    /// Configures the pin to operate as a parallel camera interface pin.
+    #[inline]
+    pub fn into_cam(self) -> Padv2<'a, N, Cam> {
+        let config = v2::GpioConfig::RESET_VALUE
+            .enable_input()
+            .disable_output()
+            .enable_schmitt()
+            .set_drive(Drive::Drive0)
+            .set_pull(Pull::None)
+            .set_function(v2::Function::Cam);
+        unsafe { self.base.gpio_config[N].write(config) };
+        Padv2 {
+            base: self.base,
+            _mode: PhantomData,
+        }
+    }
+    /// Configures the pin to operate as a display bus interface type B pin.
+    #[inline]
+    pub fn into_dbi_b(self) -> Padv2<'a, N, DbiB> {
+        let config = v2::GpioConfig::RESET_VALUE
+            .enable_input()
+            .enable_output()
+            .enable_schmitt()
+            .set_drive(Drive::Drive0)
+            .set_pull(Pull::None)
+            .set_function(v2::Function::DbiB);
+        unsafe { self.base.gpio_config[N].write(config) };
+        Padv2 {
+            base: self.base,
+            _mode: PhantomData,
+        }
+    }
+    /// Configures the pin to operate as a display bus interface type C pin.
+    #[inline]
+    pub fn into_dbi_c(self) -> Padv2<'a, N, DbiC> {
+        let config = v2::GpioConfig::RESET_VALUE
+            .enable_input()
+            .enable_output()
+            .enable_schmitt()
+            .set_drive(Drive::Drive0)
+            .set_pull(Pull::None)
+            .set_function(v2::Function::DbiC);
+        unsafe { self.base.gpio_config[N].write(config) };
+        Padv2 {
+            base: self.base,
+            _mode: PhantomData,
+        }
+    }
+    /// Configures the pin to operate as a display pixel interface pin.
+    #[inline]
+    pub fn into_dpi(self) -> Padv2<'a, N, Dpi> {
+        let config = v2::GpioConfig::RESET_VALUE
+            .enable_input()
+            .enable_output()
+            .enable_schmitt()
+            .set_drive(Drive::Drive0)
+            .set_pull(Pull::None)
+            .set_function(v2::Function::Dpi);
+        unsafe { self.base.gpio_config[N].write(config) };
+        Padv2 {
+            base: self.base,
+            _mode: PhantomData,
+        }
+    }
+    /// Configures the pin to output internal clock `source` divided by `divide`.
+    #[cfg(any(doc, feature = "glb-v2"))]
+    #[inline]
+    pub fn into_clock_out(
+        self,
+        source: super::ClockOutSource,
+        divide: u8,
+    ) -> Result<super::ClockOutPin<'a, N>, (Self, super::ClockOutError)> {
+        if let Err(e) = super::clock_out::route(self.base, N, source, divide) {
+            return Err((self, e));
+        }
+        let config = v2::GpioConfig::RESET_VALUE
+            .disable_input()
+            .enable_output()
+            .set_drive(Drive::Drive0)
+            .set_pull(Pull::None)
+            .set_function(v2::Function::ClockOut);
+        unsafe { self.base.gpio_config[N].write(config) };
+        let pad: Padv2<'a, N, super::ClockOut> = Padv2 {
+            base: self.base,
+            _mode: PhantomData,
+        };
+        Ok(super::ClockOutPin::new(self.base, pad.into()))
+    }
+    /// Configures the pin to output patterns from the output sequencer.
+    #[cfg(any(doc, feature = "glb-v2"))]
+    #[inline]
+    pub fn into_sequencer(
+        self,
+        tick_cycles: u16,
+    ) -> Result<super::SequencerPin<'a, N>, (Self, super::SequencerError)> {
+        if let Err(e) = super::sequencer::configure(self.base, N, tick_cycles) {
+            return Err((self, e));
+        }
+        let config = self.base.gpio_config[N]
+            .read()
+            .set_function(v2::Function::Gpio)
+            .set_mode(v2::Mode::Programmable)
+            .disable_input()
+            .enable_output()
+            .set_pull(Pull::None);
+        unsafe { self.base.gpio_config[N].write(config) };
+        let pad: Padv2<'a, N, super::Sequenced> = Padv2 {
+            base: self.base,
+            _mode: PhantomData,
+        };
+        Ok(super::SequencerPin::new(self.base, pad.into(), tick_cycles))
+    }
+    /// Configures the pin to operate as an analog signal pin.
+    ///
+    /// Digital input and output buffers and pulls are disabled so that they do not load
+    /// the analog signal.
+    #[inline]
+    pub fn into_analog(self) -> Padv2<'a, N, Analog> {
+        unsafe { self.base.gpio_config[N].write(v2::GpioConfig::analog()) };
+        Padv2 {
+            base: self.base,
+            _mode: PhantomData,
+        }
+    }
+    /// Check that this pin is assigned to `function`.
+    #[cfg(any(doc, feature = "glb-v2"))]
+    #[inline]
+    pub(crate) fn check_function(&self, function: v2::Function) -> Result<(), PinConflict> {
+        match self.base.function_of(N) {
+            current if current == function => Ok(()),
+            current => Err(PinConflict {
+                pin: N as u8,
+                function: current,
+            }),
+        }
+    }
+    /// Configures the pin to operate as a floating open-drain output pin.
+    ///
+    /// `set_high` disables output of the pin instead of driving it; the pin starts
+    /// released.
+    #[inline]
+    pub fn into_open_drain_output(self) -> Padv2<'a, N, Output<OpenDrain<Floating>>> {
+        self.into_open_drain_with(Pull::None)
+    }
+    /// Configures the pin to operate as an open-drain output pin with internal pull-up.
+    #[inline]
+    pub fn into_pull_up_open_drain_output(self) -> Padv2<'a, N, Output<OpenDrain<PullUp>>> {
+        self.into_open_drain_with(Pull::Up)
+    }
+    #[inline]
+    fn into_open_drain_with<P>(self, pull: Pull) -> Padv2<'a, N, Output<OpenDrain<P>>> {
+        let config = self.base.gpio_config[N]
+            .read()
+            .set_function(v2::Function::Gpio)
+            .set_mode(v2::Mode::SetClear)
+            .enable_input()
+            .disable_output()
+            .set_pull(pull);
+        unsafe { self.base.gpio_config[N].write(config) };
+        // Open-drain output only ever drives low; latch low while the line is released.
+        unsafe { self.base.gpio_clear[Self::BANK].write(Self::BIT) };
+        Padv2 {
+            base: self.base,
+            _mode: PhantomData,
+        }
+    }
     /// Configures the pin to operate as a SPI pin.
     #[inline]
     pub fn into_spi<const I: usize>(self) -> Padv2<'a, N, Spi<I>> {
@@ -351,45 +769,502 @@ impl<'a, const N: usize, M> Padv2<'a, N, M> {
 }
 
 impl<'a, const N: usize, M> ErrorType for Padv2<'a, N, Input<M>> {
-    type Error = core::convert::Infallible;
+    type Error = super::Error;
 }
 
 impl<'a, const N: usize, M> ErrorType for Padv2<'a, N, Output<M>> {
-    type Error = core::convert::Infallible;
+    type Error = super::Error;
 }
 
 impl<'a, const N: usize, M> InputPin for Padv2<'a, N, Input<M>> {
     #[inline]
     fn is_high(&mut self) -> Result<bool, Self::Error> {
-        Ok(self.base.gpio_input[N >> 5].read() & (1 << (N & 0x1F)) != 0)
+        Ok(self.base.gpio_input[Self::BANK].read() & Self::BIT != 0)
+    }
+    #[inline]
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.base.gpio_input[Self::BANK].read() & Self::BIT == 0)
+    }
+}
+
+// Open-drain pads keep input function enabled to read back the released line.
+impl<'a, const N: usize, M> InputPin for Padv2<'a, N, Output<OpenDrain<M>>> {
+    #[inline]
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.base.gpio_input[Self::BANK].read() & Self::BIT != 0)
     }
     #[inline]
     fn is_low(&mut self) -> Result<bool, Self::Error> {
-        Ok(self.base.gpio_input[N >> 5].read() & (1 << (N & 0x1F)) == 0)
+        Ok(self.base.gpio_input[Self::BANK].read() & Self::BIT == 0)
     }
 }
 
-impl<'a, const N: usize, M> OutputPin for Padv2<'a, N, Output<M>> {
+// Push-pull levels are written through write-only set and clear registers, so that no
+// read-modify-write of the shared output register races with other pads in the bank.
+impl<'a, const N: usize, M: PullMode> OutputPin for Padv2<'a, N, Output<M>> {
     #[inline]
     fn set_low(&mut self) -> Result<(), Self::Error> {
-        unsafe { self.base.gpio_clear[N >> 5].write(1 << (N & 0x1F)) };
+        unsafe { self.base.gpio_clear[Self::BANK].write(Self::BIT) };
         Ok(())
     }
     #[inline]
     fn set_high(&mut self) -> Result<(), Self::Error> {
-        unsafe { self.base.gpio_set[N >> 5].write(1 << (N & 0x1F)) };
+        unsafe { self.base.gpio_set[Self::BANK].write(Self::BIT) };
+        Ok(())
+    }
+}
+
+// Driven level is read back from the output latch instead of the pad input.
+impl<'a, const N: usize, M: PullMode> StatefulOutputPin for Padv2<'a, N, Output<M>> {
+    #[inline]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.base.gpio_config[N].read().output_value())
+    }
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(!self.base.gpio_config[N].read().output_value())
+    }
+    #[inline]
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        let config = self.base.gpio_config[N].read();
+        match config.mode() {
+            // Normal mode drives the output bit in configuration register directly.
+            v2::Mode::Normal => unsafe {
+                self.base.gpio_config[N].write(config.set_output(!config.output_value()))
+            },
+            // Set-clear modes latch output through set and clear registers.
+            _ if config.output_value() => unsafe {
+                self.base.gpio_clear[Self::BANK].write(Self::BIT)
+            },
+            _ => unsafe { self.base.gpio_set[Self::BANK].write(Self::BIT) },
+        }
         Ok(())
     }
 }
 
+// Open-drain output keeps low level latched; output enable decides whether the line is
+// pulled low or released.
+impl<'a, const N: usize, M: PullMode> OutputPin for Padv2<'a, N, Output<OpenDrain<M>>> {
+    #[inline]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        let config = self.base.gpio_config[N].read().enable_output();
+        unsafe { self.base.gpio_config[N].write(config) };
+        Ok(())
+    }
+    #[inline]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        let config = self.base.gpio_config[N].read().disable_output();
+        unsafe { self.base.gpio_config[N].write(config) };
+        Ok(())
+    }
+}
+
+// The line is released, thus set high, while output is disabled.
+impl<'a, const N: usize, M: PullMode> StatefulOutputPin for Padv2<'a, N, Output<OpenDrain<M>>> {
+    #[inline]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(!self.base.gpio_config[N].read().is_output_enabled())
+    }
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.base.gpio_config[N].read().is_output_enabled())
+    }
+    #[inline]
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        let config = self.base.gpio_config[N].read();
+        let config = if config.is_output_enabled() {
+            config.disable_output()
+        } else {
+            config.enable_output()
+        };
+        unsafe { self.base.gpio_config[N].write(config) };
+        Ok(())
+    }
+}
+
+impl<'a, const N: usize, M: PullMode> Padv2<'a, N, Input<M>> {
+    /// Erase pad number and mode into a runtime [`DynPin`].
+    #[inline]
+    pub fn downgrade(self) -> DynPin<'a> {
+        DynPin::from_parts(self.base, N as u8, DynMode::Input(M::PULL))
+    }
+}
+
+impl<'a, const N: usize, M: OutputMode> Padv2<'a, N, Output<M>> {
+    /// Erase pad number and mode into a runtime [`DynPin`].
+    #[inline]
+    pub fn downgrade(self) -> DynPin<'a> {
+        DynPin::from_parts(self.base, N as u8, M::MODE)
+    }
+}
+
+impl<'a, const N: usize, M: PullMode> TryFrom<DynPin<'a>> for Padv2<'a, N, Input<M>> {
+    type Error = DynPinError;
+
+    #[inline]
+    fn try_from(pin: DynPin<'a>) -> Result<Self, Self::Error> {
+        let () = Self::VALID;
+        if pin.number() as usize != N {
+            return Err(DynPinError::InvalidPin);
+        }
+        match pin.mode() {
+            DynMode::Input(pull) if pull == M::PULL => Ok(Self {
+                base: pin.base(),
+                _mode: PhantomData,
+            }),
+            _ => Err(DynPinError::InvalidMode),
+        }
+    }
+}
+
+impl<'a, const N: usize, M: OutputMode> TryFrom<DynPin<'a>> for Padv2<'a, N, Output<M>> {
+    type Error = DynPinError;
+
+    #[inline]
+    fn try_from(pin: DynPin<'a>) -> Result<Self, Self::Error> {
+        let () = Self::VALID;
+        if pin.number() as usize != N {
+            return Err(DynPinError::InvalidPin);
+        }
+        if pin.mode() != M::MODE {
+            return Err(DynPinError::InvalidMode);
+        }
+        Ok(Self {
+            base: pin.base(),
+            _mode: PhantomData,
+        })
+    }
+}
+
 // Macro internal functions, do not use.
 impl<'a, const N: usize> Padv2<'a, N, super::typestate::Disabled> {
     #[doc(hidden)]
     #[inline]
     pub fn __from_glb(base: &'a v2::RegisterBlock) -> Self {
+        let () = Self::VALID;
         Self {
             base,
             _mode: PhantomData,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{OpenDrain, Output, Padv2, PullUp};
+    use crate::glb::{
+        Drive, Pull,
+        v2::{Function, InterruptMode, MockRegisterBlock, Mode},
+    };
+    use embedded_hal::digital::{InputPin, OutputPin, StatefulOutputPin};
+
+    #[cfg(feature = "glb-v2")]
+    #[test]
+    fn function_check_function() {
+        let glb = MockRegisterBlock::new();
+        let io4 = Padv2::<4, _>::__from_glb(&glb).into_spi::<0>();
+        assert_eq!(io4.check_function(Function::Spi0), Ok(()));
+        // Another handle of the same pin reassigns it behind the typed pad.
+        let _ = Padv2::<4, _>::__from_glb(&glb).into_uart();
+        assert_eq!(glb.function_of(4), Function::Uart);
+        assert_eq!(
+            io4.check_function(Function::Spi0),
+            Err(super::PinConflict {
+                pin: 4,
+                function: Function::Uart,
+            })
+        );
+    }
+
+    #[test]
+    fn function_into_open_drain_output() {
+        let glb = MockRegisterBlock::new();
+        let mut io8: Padv2<'_, 8, Output<OpenDrain<PullUp>>> =
+            Padv2::<8, _>::__from_glb(&glb).into_pull_up_open_drain_output();
+        let config = glb.gpio_config[8].read();
+        assert!(config.is_input_enabled() && !config.is_output_enabled());
+        assert_eq!(config.pull(), Pull::Up);
+        assert_eq!(config.mode(), Mode::SetClear);
+        assert_eq!(glb.raw(0xaf4), 1 << 8);
+        assert!(io8.is_set_high().unwrap());
+        io8.set_low().unwrap();
+        assert!(glb.gpio_config[8].read().is_output_enabled());
+        assert!(io8.is_set_low().unwrap());
+        io8.set_high().unwrap();
+        assert!(!glb.gpio_config[8].read().is_output_enabled());
+        // Releasing never drives the line high.
+        assert_eq!(glb.raw(0xaec), 0);
+
+        let _ = Padv2::<40, _>::__from_glb(&glb).into_open_drain_output();
+        assert_eq!(glb.gpio_config[40].read().pull(), Pull::None);
+        assert_eq!(glb.raw(0xaf8), 1 << 8);
+    }
+
+    #[cfg(feature = "glb-v2")]
+    #[test]
+    fn function_open_drain_output_set_high() {
+        use crate::gpio::{self, Disabled, Floating, IntoPadv2};
+        let glb = MockRegisterBlock::new();
+        let io9 = Disabled::from(Padv2::<9, _>::__from_glb(&glb));
+        let mut io9: gpio::Output<'_, 9, OpenDrain<Floating>> = io9.into_open_drain_output();
+        io9.set_low().unwrap();
+        assert!(glb.gpio_config[9].read().is_output_enabled());
+        // Releasing disables output, and never drives the line high.
+        io9.set_high().unwrap();
+        assert!(!glb.gpio_config[9].read().is_output_enabled());
+        assert_eq!(glb.raw(0xaec), 0);
+    }
+
+    #[test]
+    fn function_into_analog() {
+        let glb = MockRegisterBlock::new();
+        let mut io17 = Padv2::<17, _>::__from_glb(&glb).into_pull_up_output();
+        io17.set_high().unwrap();
+        let before = glb.gpio_config[17]
+            .read()
+            .enable_schmitt()
+            .unmask_interrupt();
+        unsafe { glb.gpio_config[17].write(before) };
+        let analog = io17.into_analog();
+        let config = glb.gpio_config[17].read();
+        assert_eq!(config.function(), Function::Analog);
+        assert!(!config.is_input_enabled());
+        assert!(!config.is_output_enabled());
+        assert!(!config.is_schmitt_enabled());
+        assert!(config.is_interrupt_masked());
+        assert_eq!(config.pull(), Pull::None);
+
+        let mut io17 = analog.into_floating_input();
+        let config = glb.gpio_config[17].read();
+        assert_eq!(config.function(), Function::Gpio);
+        assert!(config.is_input_enabled() && !config.is_output_enabled());
+        glb.set_raw(0xac4, 1 << 17);
+        assert!(io17.is_high().unwrap());
+    }
+
+    #[test]
+    fn function_enable_schmitt() {
+        let glb = MockRegisterBlock::new();
+        let mut io0 = Padv2::<0, _>::__from_glb(&glb).into_pull_up_input();
+        io0.set_drive(Drive::Drive2);
+        let before = glb.gpio_config[0].read();
+        io0.enable_schmitt();
+        assert!(io0.is_schmitt_enabled());
+        assert!(glb.gpio_config[0].read().is_schmitt_enabled());
+        io0.disable_schmitt();
+        assert!(!io0.is_schmitt_enabled());
+        let after = glb.gpio_config[0].read();
+        assert!(!after.is_schmitt_enabled());
+        assert_eq!(after.pull(), Pull::Up);
+        assert_eq!(after.drive(), Drive::Drive2);
+        assert_eq!(after.enable_schmitt(), before.enable_schmitt());
+    }
+
+    #[test]
+    fn function_input_bank_and_bit() {
+        let glb = MockRegisterBlock::new();
+        let mut io0 = Padv2::<0, _>::__from_glb(&glb).into_floating_input();
+        let mut io31 = Padv2::<31, _>::__from_glb(&glb).into_pull_up_input();
+        let mut io32 = Padv2::<32, _>::__from_glb(&glb).into_pull_down_input();
+        let mut io45 = Padv2::<45, _>::__from_glb(&glb).into_floating_input();
+        let mut levels = || {
+            [
+                io0.is_high().unwrap(),
+                io31.is_high().unwrap(),
+                io32.is_high().unwrap(),
+                io45.is_high().unwrap(),
+            ]
+        };
+        assert_eq!(levels(), [false; 4]);
+        glb.set_raw(0xac4, 1 << 0);
+        assert_eq!(levels(), [true, false, false, false]);
+        glb.set_raw(0xac4, 1 << 31);
+        assert_eq!(levels(), [false, true, false, false]);
+        glb.set_raw(0xac4, 0);
+        glb.set_raw(0xac8, 1 << 0);
+        assert_eq!(levels(), [false, false, true, false]);
+        glb.set_raw(0xac8, 1 << 13);
+        assert_eq!(levels(), [false, false, false, true]);
+
+        // Output pads with input function enabled read back the pad level.
+        let mut pad = Padv2::<8, _>::__from_glb(&glb)
+            .into_floating_output()
+            .into_open_drain();
+        assert!(glb.gpio_config[8].read().is_input_enabled());
+        assert!(pad.is_low().unwrap());
+        glb.set_raw(0xac4, 1 << 8);
+        assert!(pad.is_high().unwrap());
+    }
+
+    #[test]
+    fn function_atomic_set_clear() {
+        let glb = MockRegisterBlock::new();
+        let mut io8 = Padv2::<8, _>::__from_glb(&glb).into_floating_output();
+        let _io9 = Padv2::<9, _>::__from_glb(&glb).into_floating_output();
+        // Output register records io9 high; io8 must never write it back.
+        glb.set_raw(0xae4, 1 << 9);
+        io8.set_high().unwrap();
+        assert_eq!(glb.raw(0xaec), 1 << 8);
+        assert_eq!(glb.raw(0xaf4), 0);
+        assert_eq!(glb.raw(0xae4), 1 << 9);
+        io8.set_low().unwrap();
+        assert_eq!(glb.raw(0xaf4), 1 << 8);
+        assert_eq!(glb.raw(0xae4), 1 << 9);
+        assert_eq!(glb.raw(0xaf0), 0);
+        assert_eq!(glb.raw(0xaf8), 0);
+    }
+
+    // Apply set and clear register writes of io8 to its output latch, like hardware does.
+    fn latch(glb: &MockRegisterBlock) {
+        let config = glb.gpio_config[8].read();
+        if glb.raw(0xaec) & (1 << 8) != 0 {
+            unsafe { glb.gpio_config[8].write(config.set_output(true)) };
+        }
+        if glb.raw(0xaf4) & (1 << 8) != 0 {
+            unsafe { glb.gpio_config[8].write(config.set_output(false)) };
+        }
+        glb.set_raw(0xaec, 0);
+        glb.set_raw(0xaf4, 0);
+    }
+
+    #[test]
+    fn function_toggle() {
+        let glb = MockRegisterBlock::new();
+        let mut pad = Padv2::<8, _>::__from_glb(&glb).into_floating_output();
+        assert_eq!(glb.gpio_config[8].read().mode(), Mode::SetClear);
+        assert!(pad.is_set_low().unwrap());
+        pad.toggle().unwrap();
+        assert_eq!(glb.raw(0xaec), 1 << 8);
+        latch(&glb);
+        assert!(pad.is_set_high().unwrap());
+        pad.toggle().unwrap();
+        assert_eq!(glb.raw(0xaf4), 1 << 8);
+        latch(&glb);
+        assert!(pad.is_set_low().unwrap());
+
+        // Normal mode toggles the output bit without set and clear registers.
+        let config = glb.gpio_config[8].read();
+        unsafe { glb.gpio_config[8].write(config.set_mode(Mode::Normal)) };
+        pad.toggle().unwrap();
+        assert!(pad.is_set_high().unwrap());
+        pad.toggle().unwrap();
+        assert!(pad.is_set_low().unwrap());
+        assert_eq!(glb.raw(0xaec) | glb.raw(0xaf4), 0);
+
+        // Open-drain output toggles between driving low and releasing the line.
+        unsafe { glb.gpio_config[8].write(config) };
+        let mut pad = pad.into_open_drain();
+        pad.set_low().unwrap();
+        pad.toggle().unwrap();
+        assert!(pad.is_set_high().unwrap());
+        pad.toggle().unwrap();
+        assert!(pad.is_set_low().unwrap());
+    }
+
+    #[test]
+    fn function_listen_interrupt() {
+        let glb = MockRegisterBlock::new();
+        let mut pad = Padv2::<0, _>::__from_glb(&glb).into_pull_up_input();
+        pad.listen(InterruptMode::AsyncFallingEdge);
+        let config = glb.gpio_config[0].read();
+        assert_eq!(config.interrupt_mode(), InterruptMode::AsyncFallingEdge);
+        assert!(!config.is_interrupt_masked());
+        assert_eq!(glb.raw(0x8c4) & (1 << 20), 0);
+        pad.listen(InterruptMode::SyncFallingEdge);
+        assert_eq!(
+            glb.gpio_config[0].read().interrupt_mode(),
+            InterruptMode::SyncFallingEdge
+        );
+
+        assert!(!pad.check_interrupt());
+        glb.set_raw(0x8c4, glb.raw(0x8c4) | (1 << 21));
+        assert!(pad.check_interrupt());
+        pad.clear_interrupt();
+        assert_eq!(glb.raw(0x8c4) & (1 << 20), 0);
+
+        pad.unlisten();
+        let config = glb.gpio_config[0].read();
+        assert!(config.is_interrupt_masked());
+        assert_eq!(config.interrupt_mode(), InterruptMode::SyncFallingEdge);
+    }
+
+    #[test]
+    fn function_buffered_set_clear() {
+        let glb = MockRegisterBlock::new();
+        let mut pad = Padv2::<8, _>::__from_glb(&glb).into_floating_output();
+        assert_eq!(glb.gpio_config[8].read().mode(), Mode::SetClear);
+        assert!(!pad.is_buffered_set_clear());
+
+        pad.enable_buffered_set_clear();
+        assert_eq!(glb.gpio_config[8].read().mode(), Mode::BufferedSetClear);
+        assert!(pad.is_buffered_set_clear());
+        pad.set_high().unwrap();
+        assert_eq!(glb.raw(0xaec), 1 << 8);
+        pad.set_low().unwrap();
+        assert_eq!(glb.raw(0xaf4), 1 << 8);
+
+        pad.disable_buffered_set_clear();
+        assert_eq!(glb.gpio_config[8].read().mode(), Mode::SetClear);
+    }
+
+    #[test]
+    fn function_into_open_drain() {
+        let glb = MockRegisterBlock::new();
+        let pad = Padv2::<8, _>::__from_glb(&glb).into_pull_up_output();
+        let push_pull = glb.gpio_config[8].read();
+        assert!(push_pull.is_output_enabled());
+        assert!(!push_pull.is_input_enabled());
+
+        // Switch while driven high: line is released instead of driven low.
+        let push_pull = push_pull.set_output(true);
+        unsafe { glb.gpio_config[8].write(push_pull) };
+        let mut pad = pad.into_open_drain();
+        let open_drain = glb.gpio_config[8].read();
+        assert!(open_drain.is_input_enabled());
+        assert!(!open_drain.is_output_enabled());
+        assert_eq!(open_drain.pull(), push_pull.pull());
+        assert_eq!(open_drain.function(), push_pull.function());
+        assert_eq!(glb.raw(0xaf4), 1 << 8);
+        assert!(pad.is_set_high().unwrap());
+
+        pad.set_low().unwrap();
+        assert!(glb.gpio_config[8].read().is_output_enabled());
+        pad.set_high().unwrap();
+        assert!(!glb.gpio_config[8].read().is_output_enabled());
+
+        // Released high line switches back to a push-pull high.
+        let pad = pad.into_push_pull();
+        assert_eq!(glb.gpio_config[8].read(), push_pull);
+        assert_eq!(glb.raw(0xaec), 1 << 8);
+
+        // Low level is kept driven, with only input function turned on.
+        let config = glb.gpio_config[8].read().set_output(false);
+        unsafe { glb.gpio_config[8].write(config) };
+        let _pad = pad.into_open_drain();
+        let open_drain = glb.gpio_config[8].read();
+        assert!(open_drain.is_input_enabled() && open_drain.is_output_enabled());
+    }
+
+    #[test]
+    fn function_set_drive_and_pull() {
+        let glb = MockRegisterBlock::new();
+        let mut pad = Padv2::<8, _>::__from_glb(&glb).into_floating_output();
+        let before = glb.gpio_config[8].read();
+        pad.set_drive(Drive::Drive3);
+        let after = glb.gpio_config[8].read();
+        assert_eq!(pad.drive(), Drive::Drive3);
+        assert_eq!(after.function(), Function::Gpio);
+        assert_eq!(after.mode(), before.mode());
+        assert_eq!(after.is_output_enabled(), before.is_output_enabled());
+        assert_eq!(after.is_input_enabled(), before.is_input_enabled());
+        assert_eq!(after.set_drive(before.drive()), before);
+
+        pad.set_pull(Pull::Up).set_drive(Drive::Drive1);
+        assert_eq!(pad.pull(), Pull::Up);
+        assert_eq!(pad.drive(), Drive::Drive1);
+        let mut pad = pad.into_floating_input();
+        pad.set_pull(Pull::Down);
+        assert_eq!(glb.gpio_config[8].read().pull(), Pull::Down);
+        assert!(glb.gpio_config[8].read().is_input_enabled());
+    }
+}
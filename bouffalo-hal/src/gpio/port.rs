@@ -0,0 +1,182 @@
+//! Grouped access to pins of one 32-bit GPIO register word.
+use super::pad_v2::PIN_COUNT;
+use crate::glb::{Pull, v2};
+use core::ops::Range;
+
+/// Contiguous range of GPIO pins in port `P`, accessed as a group.
+///
+/// Port 0 holds io0 to io31, port 1 holds io32 to io45. Levels are read from and
+/// written to `gpio_input`, `gpio_set` and `gpio_clear` registers by one access each.
+/// Bit `i` of every mask or value is pin `P * 32 + i`; bits outside the owned range
+/// are ignored on write and read as zero.
+pub struct Port<'a, const P: usize> {
+    base: &'a v2::RegisterBlock,
+    mask: u32,
+}
+
+/// Pins io0 to io31.
+pub type Port0<'a> = Port<'a, 0>;
+/// Pins io32 to io45.
+pub type Port1<'a> = Port<'a, 1>;
+
+impl<'a, const P: usize> Port<'a, P> {
+    /// Number of valid pins in this port.
+    const PIN_COUNT: usize = {
+        assert!(P < 2, "there are only GPIO ports 0 and 1");
+        let count = PIN_COUNT - P * 32;
+        if count > 32 { 32 } else { count }
+    };
+
+    /// Create a port handle owning pins in `pins`, numbered from zero in this port.
+    ///
+    /// Use `Pads::into_ports` on GLB v2 chips to take ports without `unsafe`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pins` falls outside this port, e.g. beyond io45 in port 1.
+    ///
+    /// # Safety
+    ///
+    /// Owned pins must not be used by any other pad structure at the same time.
+    #[inline]
+    pub unsafe fn new(base: &'a v2::RegisterBlock, pins: Range<usize>) -> Self {
+        assert!(
+            pins.start <= pins.end && pins.end <= Self::PIN_COUNT,
+            "pins out of GPIO port range"
+        );
+        let len = pins.end - pins.start;
+        let mask = if len == 32 {
+            u32::MAX
+        } else {
+            ((1u32 << len) - 1) << pins.start
+        };
+        Self { base, mask }
+    }
+    // Create a port handle owning every valid pin of this port.
+    #[cfg(feature = "glb-v2")]
+    #[inline]
+    pub(crate) fn from_all_pins(base: &'a v2::RegisterBlock) -> Self {
+        unsafe { Self::new(base, 0..Self::PIN_COUNT) }
+    }
+    /// Get mask of pins owned by this port handle.
+    #[inline]
+    pub fn mask(&self) -> u32 {
+        self.mask
+    }
+    /// Configure pins in `mask` as GPIO outputs with given pull.
+    #[inline]
+    pub fn set_output(&mut self, mask: u32, pull: Pull) {
+        self.modify_config(mask, |config| {
+            config
+                .set_function(v2::Function::Gpio)
+                .set_mode(v2::Mode::SetClear)
+                .disable_input()
+                .enable_output()
+                .set_pull(pull)
+        })
+    }
+    /// Configure pins in `mask` as GPIO inputs with given pull.
+    #[inline]
+    pub fn set_input(&mut self, mask: u32, pull: Pull) {
+        self.modify_config(mask, |config| {
+            config
+                .set_function(v2::Function::Gpio)
+                .set_mode(v2::Mode::SetClear)
+                .enable_input()
+                .disable_output()
+                .set_pull(pull)
+        })
+    }
+    /// Disable both input and output of pins in `mask`.
+    #[inline]
+    pub fn disable(&mut self, mask: u32) {
+        self.modify_config(mask, |config| config.disable_input().disable_output())
+    }
+    /// Read input levels of owned pins.
+    #[inline]
+    pub fn read(&self) -> u32 {
+        self.base.gpio_input[P].read() & self.mask
+    }
+    /// Drive owned pins to levels in `value`: bit set for high, clear for low.
+    #[inline]
+    pub fn write(&mut self, value: u32) {
+        unsafe {
+            self.base.gpio_set[P].write(value & self.mask);
+            self.base.gpio_clear[P].write(!value & self.mask);
+        }
+    }
+    /// Drive pins in `mask` high, leaving other pins unchanged.
+    #[inline]
+    pub fn set_high(&mut self, mask: u32) {
+        unsafe { self.base.gpio_set[P].write(mask & self.mask) };
+    }
+    /// Drive pins in `mask` low, leaving other pins unchanged.
+    #[inline]
+    pub fn set_low(&mut self, mask: u32) {
+        unsafe { self.base.gpio_clear[P].write(mask & self.mask) };
+    }
+    #[inline]
+    fn modify_config(&mut self, mask: u32, f: impl Fn(v2::GpioConfig) -> v2::GpioConfig) {
+        let mut mask = mask & self.mask;
+        while mask != 0 {
+            let i = mask.trailing_zeros() as usize;
+            let config = f(self.base.gpio_config[P * 32 + i].read());
+            unsafe { self.base.gpio_config[P * 32 + i].write(config) };
+            mask &= mask - 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Port0, Port1};
+    use crate::glb::{Pull, v2::MockRegisterBlock};
+
+    #[test]
+    fn struct_port_masks_valid_pins() {
+        let glb = MockRegisterBlock::new();
+        let mut port = unsafe { Port1::new(&glb, 8..14) };
+        // io40 to io45, the last valid pin.
+        assert_eq!(port.mask(), 0x3f00);
+        port.write(u32::MAX);
+        assert_eq!(glb.raw(0xaf0), 0x3f00);
+        assert_eq!(glb.raw(0xaf8), 0);
+        port.write(0x0001_0500);
+        assert_eq!(glb.raw(0xaf0), 0x0500);
+        assert_eq!(glb.raw(0xaf8), 0x3a00);
+        glb.set_raw(0xac8, u32::MAX);
+        assert_eq!(port.read(), 0x3f00);
+
+        let reset = glb.gpio_config[39].read();
+        port.set_output(u32::MAX, Pull::None);
+        assert!(glb.gpio_config[45].read().is_output_enabled());
+        assert!(glb.gpio_config[40].read().is_output_enabled());
+        assert_eq!(glb.gpio_config[39].read(), reset);
+
+        let mut port = unsafe { Port0::new(&glb, 0..32) };
+        assert_eq!(port.mask(), u32::MAX);
+        port.set_low(0x8000_0001);
+        assert_eq!(glb.raw(0xaf4), 0x8000_0001);
+    }
+
+    #[cfg(feature = "glb-v2")]
+    #[test]
+    fn function_into_ports() {
+        let glb = MockRegisterBlock::new();
+        let (mut port0, mut port1) = crate::gpio::Pads::__pads_from_glb(&glb).into_ports();
+        assert_eq!(port0.mask(), u32::MAX);
+        // io45 is the last pin of port 1.
+        assert_eq!(port1.mask(), 0x3fff);
+        port0.set_high(u32::MAX);
+        port1.set_high(u32::MAX);
+        assert_eq!(glb.raw(0xaec), u32::MAX);
+        assert_eq!(glb.raw(0xaf0), 0x3fff);
+    }
+
+    #[test]
+    #[should_panic]
+    fn struct_port_out_of_range() {
+        let glb = MockRegisterBlock::new();
+        let _ = unsafe { Port1::new(&glb, 8..15) };
+    }
+}
@@ -0,0 +1,137 @@
+//! Loopback self-test for wired pin pairs.
+use embedded_hal::digital::{InputPin, OutputPin, PinState};
+
+/// Number of spin loop iterations to wait before sampling the input pin.
+///
+/// Covers propagation delay of the external loopback wire and input synchronizer.
+const SETTLE_SPINS: usize = 64;
+
+/// Fault found by a loopback self-test.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SelfTestError {
+    /// Input always reads high, regardless of output level.
+    StuckHigh,
+    /// Input always reads low, regardless of output level.
+    StuckLow,
+    /// Input does not follow output level, e.g. loopback wire is not connected.
+    Open,
+    /// Output or input pin returned an error.
+    Pin,
+}
+
+/// Toggle `output` for `cycles` low-high periods and check that `input` follows it.
+///
+/// The input is sampled after a short settle delay following every output transition.
+/// It is expected that `output` and `input` are wired together outside the chip.
+pub fn loopback_test<O: OutputPin, I: InputPin>(
+    output: &mut O,
+    input: &mut I,
+    cycles: usize,
+) -> Result<(), SelfTestError> {
+    let mut seen_high = false;
+    let mut seen_low = false;
+    let mut mismatch = false;
+    for _ in 0..cycles {
+        for state in [PinState::Low, PinState::High] {
+            output.set_state(state).map_err(|_| SelfTestError::Pin)?;
+            for _ in 0..SETTLE_SPINS {
+                core::hint::spin_loop();
+            }
+            let high = input.is_high().map_err(|_| SelfTestError::Pin)?;
+            if high {
+                seen_high = true;
+            } else {
+                seen_low = true;
+            }
+            if high != (state == PinState::High) {
+                mismatch = true;
+            }
+        }
+    }
+    match (mismatch, seen_high, seen_low) {
+        (false, _, _) => Ok(()),
+        (true, true, false) => Err(SelfTestError::StuckHigh),
+        (true, false, true) => Err(SelfTestError::StuckLow),
+        (true, _, _) => Err(SelfTestError::Open),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SelfTestError, loopback_test};
+    use core::cell::Cell;
+    use embedded_hal::digital::{ErrorType, InputPin, OutputPin};
+
+    #[derive(Clone, Copy)]
+    enum Fault {
+        None,
+        StuckHigh,
+        StuckLow,
+        // Floating input reads a level unrelated to output.
+        Open,
+    }
+
+    struct Wire {
+        level: Cell<bool>,
+        fault: Fault,
+        samples: Cell<usize>,
+    }
+
+    struct MockOutput<'a>(&'a Wire);
+
+    struct MockInput<'a>(&'a Wire);
+
+    impl ErrorType for MockOutput<'_> {
+        type Error = core::convert::Infallible;
+    }
+
+    impl ErrorType for MockInput<'_> {
+        type Error = core::convert::Infallible;
+    }
+
+    impl OutputPin for MockOutput<'_> {
+        fn set_low(&mut self) -> Result<(), Self::Error> {
+            self.0.level.set(false);
+            Ok(())
+        }
+        fn set_high(&mut self) -> Result<(), Self::Error> {
+            self.0.level.set(true);
+            Ok(())
+        }
+    }
+
+    impl InputPin for MockInput<'_> {
+        fn is_high(&mut self) -> Result<bool, Self::Error> {
+            let samples = self.0.samples.get();
+            self.0.samples.set(samples + 1);
+            Ok(match self.0.fault {
+                Fault::None => self.0.level.get(),
+                Fault::StuckHigh => true,
+                Fault::StuckLow => false,
+                Fault::Open => samples.is_multiple_of(3),
+            })
+        }
+        fn is_low(&mut self) -> Result<bool, Self::Error> {
+            self.is_high().map(|high| !high)
+        }
+    }
+
+    fn run(fault: Fault) -> Result<(), SelfTestError> {
+        let wire = Wire {
+            level: Cell::new(false),
+            fault,
+            samples: Cell::new(0),
+        };
+        let ans = loopback_test(&mut MockOutput(&wire), &mut MockInput(&wire), 4);
+        assert_eq!(wire.samples.get(), 8);
+        ans
+    }
+
+    #[test]
+    fn function_loopback_test() {
+        assert_eq!(run(Fault::None), Ok(()));
+        assert_eq!(run(Fault::StuckHigh), Err(SelfTestError::StuckHigh));
+        assert_eq!(run(Fault::StuckLow), Err(SelfTestError::StuckLow));
+        assert_eq!(run(Fault::Open), Err(SelfTestError::Open));
+    }
+}
@@ -0,0 +1,261 @@
+//! Hardware-timed output patterns using the GPIO output sequencer.
+//!
+//! A pad in [`Mode::Programmable`](v2::Mode::Programmable) takes its level from the
+//! sequencer FIFO instead of the output register. A pattern is a slice of `(level, ticks)`
+//! steps, each tick lasting `tick_cycles` bus clock cycles; it plays back with cycle
+//! accuracy while the CPU is free to do other work. Step lengths are encoded in the
+//! length of FIFO entries: every step is split into entries of the longest length, up to
+//! [`SEQUENCER_ENTRY_MAX`] bus clock cycles, dividing all steps of the pattern. The whole
+//! pattern must fit into the FIFO of [`SEQUENCER_DEPTH`] entries; longer waveforms are
+//! refilled by software as the FIFO drains.
+//!
+//! ```no_run
+//! # use bouffalo_hal::gpio::{Disabled, IntoPadv2};
+//! # fn example(io8: Disabled<'static, 8>) {
+//! // Tick of 100 bus clock cycles.
+//! let mut pulse = io8.into_sequencer(100).ok().unwrap();
+//! // High for 3 ticks, low for 1 tick, high for 2 ticks.
+//! pulse.load(&[(true, 3), (false, 1), (true, 2)]).unwrap();
+//! while !pulse.is_idle() {}
+//! # }
+//! ```
+//!
+//! All pads in programmable mode share one sequencer, and thus one pattern.
+use super::guard::{PadGuard, Shared};
+use super::{Floating, alternate::Alternate, convert::IntoPad, output::Output, typestate};
+use crate::glb::v2;
+
+/// Number of entries the output sequencer FIFO holds.
+pub const SEQUENCER_DEPTH: usize = v2::GpioTxFifo::DEPTH;
+/// Longest output sequencer FIFO entry in bus clock cycles.
+pub const SEQUENCER_ENTRY_MAX: u16 = 0x1ff;
+
+/// Output sequencer configuration error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SequencerError {
+    /// Tick length is zero or longer than [`SEQUENCER_ENTRY_MAX`].
+    InvalidTick,
+    /// Steps are not whole multiples of the entry length of steps still queued.
+    TimingMismatch,
+    /// Pattern needs more entries than free in the sequencer FIFO.
+    PatternTooLong,
+}
+
+/// Pad `N` driven by the output sequencer.
+///
+/// The pad is restored to a floating output when this guard is dropped.
+pub struct SequencerPin<'a, const N: usize> {
+    guard: PadGuard<'a, N, typestate::Sequenced>,
+    tick_cycles: u16,
+}
+
+impl<'a, const N: usize> SequencerPin<'a, N> {
+    #[cfg(any(doc, feature = "glb-v2"))]
+    #[inline]
+    pub(crate) fn new(
+        base: &'a v2::RegisterBlock,
+        pad: Alternate<'a, N, typestate::Sequenced>,
+        tick_cycles: u16,
+    ) -> Self {
+        Self {
+            guard: PadGuard::new(base, pad),
+            tick_cycles,
+        }
+    }
+    /// Queue a pattern of `(level, ticks)` steps after the steps already queued.
+    ///
+    /// Returns [`SequencerError::PatternTooLong`] if the pattern does not fit into the
+    /// free FIFO entries, or [`SequencerError::TimingMismatch`] if steps are still
+    /// queued with an entry length that does not divide the new steps. Nothing is
+    /// queued on error.
+    #[inline]
+    pub fn load(&mut self, pattern: &[(bool, u16)]) -> Result<(), SequencerError> {
+        let base = self.guard.base;
+        let cycles = |ticks: u16| ticks as u32 * self.tick_cycles as u32;
+        let steps = pattern
+            .iter()
+            .fold(0, |g, &(_, ticks)| gcd(g, cycles(ticks)));
+        if steps == 0 {
+            return Ok(());
+        }
+        let queued = self.queued();
+        let config = base.gpio_tx_config.read();
+        let entry = if queued > 0 {
+            // Length of entries still queued cannot change under them.
+            let entry = config.total_time() as u32;
+            if entry == 0 || steps % entry != 0 {
+                return Err(SequencerError::TimingMismatch);
+            }
+            entry
+        } else {
+            (1..=SEQUENCER_ENTRY_MAX as u32)
+                .rev()
+                .find(|d| steps % d == 0)
+                .unwrap_or(1)
+        };
+        let entries: usize = pattern
+            .iter()
+            .map(|&(_, ticks)| (cycles(ticks) / entry) as usize)
+            .sum();
+        if entries > SEQUENCER_DEPTH - queued {
+            return Err(SequencerError::PatternTooLong);
+        }
+        if queued == 0 {
+            unsafe {
+                base.gpio_tx_config
+                    .write(config.set_total_time(entry as u16))
+            };
+        }
+        for &(level, ticks) in pattern {
+            for _ in 0..cycles(ticks) / entry {
+                unsafe { base.gpio_tx_data.write(level as u32) };
+            }
+        }
+        Ok(())
+    }
+    /// Number of entries still waiting in the sequencer FIFO.
+    #[inline]
+    pub fn queued(&self) -> usize {
+        self.guard
+            .base
+            .gpio_tx_fifo
+            .read()
+            .count()
+            .min(SEQUENCER_DEPTH)
+    }
+    /// Check if the whole pattern has been output.
+    #[inline]
+    pub fn is_idle(&self) -> bool {
+        self.queued() == 0
+    }
+    /// Discard steps not yet output.
+    #[inline]
+    pub fn stop(&mut self) {
+        unsafe { self.guard.base.gpio_tx_fifo.modify(|v| v.clear()) };
+    }
+    /// Length of one tick in bus clock cycles.
+    #[inline]
+    pub fn tick_cycles(&self) -> u16 {
+        self.tick_cycles
+    }
+    /// Stop the sequencer output, returning the pad as floating output.
+    #[inline]
+    pub fn free(self) -> Output<'a, N, Floating> {
+        self.guard.free()
+    }
+}
+
+/// Check tick of `tick_cycles` bus clock cycles and start the sequencer for pad `n`.
+#[cfg(any(doc, feature = "glb-v2"))]
+#[inline]
+pub(crate) fn configure(
+    base: &v2::RegisterBlock,
+    n: usize,
+    tick_cycles: u16,
+) -> Result<(), SequencerError> {
+    if tick_cycles == 0 || tick_cycles > SEQUENCER_ENTRY_MAX {
+        return Err(SequencerError::InvalidTick);
+    }
+    if super::guard::in_use::<typestate::Sequenced>(base, n) {
+        return Ok(());
+    }
+    // Entry `0` is high for no cycle, entry `1` with inverted code is low for no cycle,
+    // so each keeps its level for the whole entry.
+    let config = base
+        .gpio_tx_config
+        .read()
+        .set_total_time(tick_cycles)
+        .set_code0_high_time(0)
+        .disable_invert_code0()
+        .set_code1_high_time(0)
+        .enable_invert_code1()
+        .enable();
+    unsafe {
+        base.gpio_tx_fifo.modify(|v| v.clear());
+        base.gpio_tx_config.write(config);
+    }
+    Ok(())
+}
+
+#[inline]
+const fn gcd(mut a: u32, mut b: u32) -> u32 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+impl Shared for typestate::Sequenced {
+    type Released<'a, const N: usize> = Output<'a, N, Floating>;
+
+    #[inline]
+    fn shares(_: usize, _: usize, config: v2::GpioConfig) -> bool {
+        config.mode() == v2::Mode::Programmable
+    }
+    #[inline]
+    fn release<'a, const N: usize>(pad: Alternate<'a, N, Self>) -> Output<'a, N, Floating> {
+        pad.into_floating_output()
+    }
+    #[inline]
+    fn stop(base: &v2::RegisterBlock, _: usize) {
+        unsafe {
+            base.gpio_tx_config.modify(|v| v.disable());
+            base.gpio_tx_fifo.modify(|v| v.clear());
+        }
+    }
+}
+
+#[cfg(all(test, feature = "glb-v2"))]
+mod tests {
+    use super::SequencerError;
+    use crate::glb::v2::{MockRegisterBlock, Mode};
+    use crate::gpio::{Disabled, IntoPadv2, Padv2};
+
+    #[test]
+    fn struct_sequencer_pin() {
+        let glb = MockRegisterBlock::new();
+        let io8 = Disabled::from(Padv2::<8, _>::__from_glb(&glb));
+        let mut pulse = io8.into_sequencer(100).ok().unwrap();
+        assert_eq!(glb.gpio_config[8].read().mode(), Mode::Programmable);
+        assert_eq!(glb.raw(0xafc), 0x0000_3205);
+        assert_eq!(pulse.tick_cycles(), 100);
+
+        // Steps of 400 and 200 cycles take entries of 200 cycles each.
+        pulse.load(&[(false, 4), (true, 2)]).unwrap();
+        assert_eq!(glb.gpio_tx_config.read().total_time(), 200);
+        assert_eq!(glb.raw(0xb04), 1);
+        // 3 entries queued; 6 entries of steps 3 ticks long do not divide into them.
+        glb.set_raw(0xb00, 3 << 8);
+        assert_eq!(
+            pulse.load(&[(true, 3), (false, 3)]),
+            Err(SequencerError::TimingMismatch)
+        );
+        // FIFO holds 120 entries; 9 more entries would overflow it.
+        glb.set_raw(0xb00, 120 << 8);
+        assert_eq!(pulse.queued(), 120);
+        assert_eq!(
+            pulse.load(&[(true, 8), (false, 10)]),
+            Err(SequencerError::PatternTooLong)
+        );
+        assert!(pulse.load(&[(true, 8), (false, 8)]).is_ok());
+        assert_eq!(glb.raw(0xb04), 0);
+        // With the FIFO drained, long steps take few long entries.
+        glb.set_raw(0xb00, 0);
+        assert!(pulse.load(&[(true, 50), (false, 50)]).is_ok());
+        assert_eq!(glb.gpio_tx_config.read().total_time(), 500);
+
+        let io10 = Disabled::from(Padv2::<10, _>::__from_glb(&glb));
+        let Err((io10, e)) = io10.into_sequencer(512) else {
+            panic!("tick is too long");
+        };
+        assert_eq!(e, SequencerError::InvalidTick);
+        assert_ne!(glb.gpio_config[10].read().mode(), Mode::Programmable);
+        let shared = io10.into_sequencer(511).ok().unwrap();
+        assert_eq!(glb.gpio_tx_config.read().total_time(), 500);
+        let _ = pulse.free();
+        assert_eq!(glb.gpio_config[8].read().mode(), Mode::SetClear);
+        assert!(glb.gpio_tx_config.read().is_enabled());
+        drop(shared);
+        assert!(!glb.gpio_tx_config.read().is_enabled());
+    }
+}
@@ -11,6 +11,13 @@ pub struct Output<MODE> {
     _mode: PhantomData<MODE>,
 }
 
+/// Open-drain output with pull direction `MODE` (type state).
+///
+/// Used as `Output<OpenDrain<MODE>>`; `set_high` releases the line instead of driving it.
+pub struct OpenDrain<MODE> {
+    _mode: PhantomData<MODE>,
+}
+
 /// Disabled (type state).
 pub struct Disabled;
 
@@ -38,6 +45,36 @@ pub struct JtagM0;
 /// LP core JTAG mode (type state).
 pub struct JtagLp;
 
+/// Analog signal mode (type state).
+pub struct Analog;
+
+/// Inter-IC Sound mode (type state).
+pub struct I2s;
+
+/// Pulse Density Modulation microphone mode (type state).
+pub struct Pdm;
+
+/// Ethernet Media Access Control mode (type state).
+pub struct Emac;
+
+/// Camera interface mode (type state).
+pub struct Cam;
+
+/// Display bus interface type B mode (type state).
+pub struct DbiB;
+
+/// Display bus interface type C mode (type state).
+pub struct DbiC;
+
+/// Display pixel interface mode (type state).
+pub struct Dpi;
+
+/// Clock output mode (type state).
+pub struct ClockOut;
+
+/// Output sequencer mode (type state).
+pub struct Sequenced;
+
 /// Serial Peripheral Interface mode (type state).
 pub struct Spi<const F: usize>;
 
@@ -78,3 +115,31 @@ impl<const F: usize> Pwm<F> {
         _ => unreachable!(),
     };
 }
+
+/// Pad type states with a digital function, thus with pull and drive strength settings.
+///
+/// Not implemented by [`Analog`], where pull resistors and drivers would load the
+/// analog signal.
+pub trait Digital {}
+
+impl<MODE> Digital for Input<MODE> {}
+impl<MODE> Digital for Output<MODE> {}
+impl Digital for Disabled {}
+impl Digital for Uart {}
+impl Digital for MmUart {}
+impl Digital for JtagD0 {}
+impl Digital for JtagM0 {}
+impl Digital for JtagLp {}
+impl Digital for I2s {}
+impl Digital for Pdm {}
+impl Digital for Emac {}
+impl Digital for Cam {}
+impl Digital for DbiB {}
+impl Digital for DbiC {}
+impl Digital for Dpi {}
+impl Digital for ClockOut {}
+impl Digital for Sequenced {}
+impl<const F: usize> Digital for Spi<F> {}
+impl Digital for Sdh {}
+impl<const F: usize> Digital for I2c<F> {}
+impl<const F: usize> Digital for Pwm<F> {}
@@ -56,7 +56,11 @@ pub struct GpadcCommand(u32);
 
 impl GpadcCommand {
     const GLOBAL_ENABLE: u32 = 1 << 0;
+    const CONVERSION_START: u32 = 1 << 1;
     const SOFTWARE_RESET: u32 = 1 << 2;
+    const NEGATIVE_CHANNEL: u32 = 0x1f << 3;
+    const POSITIVE_CHANNEL: u32 = 0x1f << 8;
+    const NEGATIVE_GROUND: u32 = 1 << 13;
 
     /// Enable the Analog-to-Digital Converter.
     #[inline]
@@ -88,16 +92,195 @@ impl GpadcCommand {
     pub const fn is_software_reset_enabled(self) -> bool {
         self.0 & Self::SOFTWARE_RESET != 0
     }
+    /// Start conversion.
+    #[inline]
+    pub const fn start_conversion(self) -> Self {
+        Self(self.0 | Self::CONVERSION_START)
+    }
+    /// Stop conversion.
+    #[inline]
+    pub const fn stop_conversion(self) -> Self {
+        Self(self.0 & !Self::CONVERSION_START)
+    }
+    /// Check if conversion is started.
+    #[inline]
+    pub const fn is_conversion_started(self) -> bool {
+        self.0 & Self::CONVERSION_START != 0
+    }
+    /// Set negative input channel.
+    #[inline]
+    pub const fn set_negative_channel(self, val: u8) -> Self {
+        Self((self.0 & !Self::NEGATIVE_CHANNEL) | (((val as u32) << 3) & Self::NEGATIVE_CHANNEL))
+    }
+    /// Get negative input channel.
+    #[inline]
+    pub const fn negative_channel(self) -> u8 {
+        ((self.0 & Self::NEGATIVE_CHANNEL) >> 3) as u8
+    }
+    /// Set positive input channel.
+    #[inline]
+    pub const fn set_positive_channel(self, val: u8) -> Self {
+        Self((self.0 & !Self::POSITIVE_CHANNEL) | (((val as u32) << 8) & Self::POSITIVE_CHANNEL))
+    }
+    /// Get positive input channel.
+    #[inline]
+    pub const fn positive_channel(self) -> u8 {
+        ((self.0 & Self::POSITIVE_CHANNEL) >> 8) as u8
+    }
+    /// Connect negative input to ground.
+    #[inline]
+    pub const fn enable_negative_ground(self) -> Self {
+        Self(self.0 | Self::NEGATIVE_GROUND)
+    }
+    /// Disconnect negative input from ground.
+    #[inline]
+    pub const fn disable_negative_ground(self) -> Self {
+        Self(self.0 & !Self::NEGATIVE_GROUND)
+    }
+    /// Check if negative input is connected to ground.
+    #[inline]
+    pub const fn is_negative_ground_enabled(self) -> bool {
+        self.0 & Self::NEGATIVE_GROUND != 0
+    }
 }
 
+/// Generic Analog-to-Digital Converter conversion configuration register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct GpadcConfig1(u32);
 
+impl GpadcConfig1 {
+    const CONTINUOUS: u32 = 1 << 1;
+    const RESOLUTION: u32 = 0x7 << 2;
+    const CLOCK_DIVIDE: u32 = 0x7 << 18;
+
+    /// Enable continuous conversion.
+    #[inline]
+    pub const fn enable_continuous(self) -> Self {
+        Self(self.0 | Self::CONTINUOUS)
+    }
+    /// Disable continuous conversion.
+    #[inline]
+    pub const fn disable_continuous(self) -> Self {
+        Self(self.0 & !Self::CONTINUOUS)
+    }
+    /// Check if continuous conversion is enabled.
+    #[inline]
+    pub const fn is_continuous_enabled(self) -> bool {
+        self.0 & Self::CONTINUOUS != 0
+    }
+    /// Set conversion resolution.
+    #[inline]
+    pub const fn set_resolution(self, val: Resolution) -> Self {
+        Self((self.0 & !Self::RESOLUTION) | ((val as u32) << 2))
+    }
+    /// Get conversion resolution.
+    #[inline]
+    pub const fn resolution(self) -> Resolution {
+        match (self.0 & Self::RESOLUTION) >> 2 {
+            0 => Resolution::Bits12,
+            1 => Resolution::Bits14,
+            _ => Resolution::Bits16,
+        }
+    }
+    /// Set analog clock divide factor.
+    #[inline]
+    pub const fn set_clock_divide(self, val: u8) -> Self {
+        Self((self.0 & !Self::CLOCK_DIVIDE) | (((val as u32) << 18) & Self::CLOCK_DIVIDE))
+    }
+    /// Get analog clock divide factor.
+    #[inline]
+    pub const fn clock_divide(self) -> u8 {
+        ((self.0 & Self::CLOCK_DIVIDE) >> 18) as u8
+    }
+}
+
+/// Conversion resolution.
+///
+/// Resolutions over 12 bits are reached by averaging several 12-bit conversions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Resolution {
+    /// 12-bit result.
+    Bits12 = 0,
+    /// 14-bit result, average of 16 conversions.
+    Bits14 = 1,
+    /// 16-bit result, average of 64 conversions.
+    Bits16 = 2,
+}
+
+impl Resolution {
+    /// Number of bits in a result.
+    #[inline]
+    pub const fn bits(self) -> u32 {
+        match self {
+            Resolution::Bits12 => 12,
+            Resolution::Bits14 => 14,
+            Resolution::Bits16 => 16,
+        }
+    }
+}
+
+/// Generic Analog-to-Digital Converter analog configuration register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct GpadcConfig2(u32);
 
+impl GpadcConfig2 {
+    const DIFFERENTIAL: u32 = 1 << 2;
+    const REFERENCE: u32 = 1 << 3;
+
+    /// Enable differential input mode.
+    #[inline]
+    pub const fn enable_differential(self) -> Self {
+        Self(self.0 | Self::DIFFERENTIAL)
+    }
+    /// Disable differential input mode.
+    #[inline]
+    pub const fn disable_differential(self) -> Self {
+        Self(self.0 & !Self::DIFFERENTIAL)
+    }
+    /// Check if differential input mode is enabled.
+    #[inline]
+    pub const fn is_differential_enabled(self) -> bool {
+        self.0 & Self::DIFFERENTIAL != 0
+    }
+    /// Set reference voltage.
+    #[inline]
+    pub const fn set_reference(self, val: Reference) -> Self {
+        Self((self.0 & !Self::REFERENCE) | ((val as u32) << 3))
+    }
+    /// Get reference voltage.
+    #[inline]
+    pub const fn reference(self) -> Reference {
+        match (self.0 & Self::REFERENCE) >> 3 {
+            0 => Reference::Internal3V2,
+            _ => Reference::Internal2V0,
+        }
+    }
+}
+
+/// Reference voltage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Reference {
+    /// Internal 3.2 V reference.
+    Internal3V2 = 0,
+    /// Internal 2.0 V reference.
+    Internal2V0 = 1,
+}
+
+impl Reference {
+    /// Reference voltage in millivolts.
+    #[inline]
+    pub const fn millivolts(self) -> u32 {
+        match self {
+            Reference::Internal3V2 => 3200,
+            Reference::Internal2V0 => 2000,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct AdcConverationSequence1(u32);
@@ -114,18 +297,52 @@ pub struct AdcConverationSequence3(u32);
 #[repr(transparent)]
 pub struct AdcConverationSequence4(u32);
 
+/// Generic Analog-to-Digital Converter status register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct GpadcStatus(u32);
 
+impl GpadcStatus {
+    const DATA_READY: u32 = 1 << 0;
+
+    /// Check if a conversion result is ready.
+    #[inline]
+    pub const fn is_data_ready(self) -> bool {
+        self.0 & Self::DATA_READY != 0
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct GpadcInterruptState(u32);
 
+/// Generic Analog-to-Digital Converter result register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct GpadcResult(u32);
 
+impl GpadcResult {
+    const VALUE: u32 = 0xffff;
+    const NEGATIVE_CHANNEL: u32 = 0x1f << 16;
+    const POSITIVE_CHANNEL: u32 = 0x1f << 21;
+
+    /// Get conversion result, left aligned to 16 bits.
+    #[inline]
+    pub const fn value(self) -> u16 {
+        (self.0 & Self::VALUE) as u16
+    }
+    /// Get negative input channel of this result.
+    #[inline]
+    pub const fn negative_channel(self) -> u8 {
+        ((self.0 & Self::NEGATIVE_CHANNEL) >> 16) as u8
+    }
+    /// Get positive input channel of this result.
+    #[inline]
+    pub const fn positive_channel(self) -> u8 {
+        ((self.0 & Self::POSITIVE_CHANNEL) >> 21) as u8
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct GpadcRawResult(u32);
@@ -192,7 +409,10 @@ impl<ADC: Deref<Target = RegisterBlock>> Adc<ADC> {
 
 #[cfg(test)]
 mod tests {
-    use super::RegisterBlock;
+    use super::{
+        GpadcCommand, GpadcConfig1, GpadcConfig2, GpadcResult, GpadcStatus, Reference,
+        RegisterBlock, Resolution,
+    };
     use memoffset::offset_of;
 
     #[test]
@@ -221,4 +441,45 @@ mod tests {
         assert_eq!(offset_of!(RegisterBlock, gpadc_raw_result), 0x934);
         assert_eq!(offset_of!(RegisterBlock, gpadc_define), 0x938);
     }
+
+    #[test]
+    fn struct_gpadc_conversion_functions() {
+        let val = GpadcCommand(0x0).start_conversion();
+        assert_eq!(val.0, 0x0000_0002);
+        assert!(val.is_conversion_started());
+        assert!(!val.stop_conversion().is_conversion_started());
+        let val = GpadcCommand(0x0)
+            .set_positive_channel(17)
+            .set_negative_channel(23)
+            .enable_negative_ground();
+        assert_eq!(val.0, 0x0000_31b8);
+        assert_eq!(val.positive_channel(), 17);
+        assert_eq!(val.negative_channel(), 23);
+        assert!(val.is_negative_ground_enabled());
+        assert!(!val.disable_negative_ground().is_negative_ground_enabled());
+
+        let val = GpadcConfig1(0x0)
+            .enable_continuous()
+            .set_resolution(Resolution::Bits16)
+            .set_clock_divide(3);
+        assert_eq!(val.0, 0x000c_000a);
+        assert!(val.is_continuous_enabled());
+        assert_eq!(val.resolution(), Resolution::Bits16);
+        assert_eq!(val.clock_divide(), 3);
+        assert!(!val.disable_continuous().is_continuous_enabled());
+
+        let val = GpadcConfig2(0x0)
+            .enable_differential()
+            .set_reference(Reference::Internal2V0);
+        assert_eq!(val.0, 0x0000_000c);
+        assert!(val.is_differential_enabled());
+        assert_eq!(val.reference(), Reference::Internal2V0);
+
+        assert!(GpadcStatus(0x1).is_data_ready());
+        assert!(!GpadcStatus(0x0).is_data_ready());
+        let val = GpadcResult(0x0237_8ab0);
+        assert_eq!(val.value(), 0x8ab0);
+        assert_eq!(val.negative_channel(), 23);
+        assert_eq!(val.positive_channel(), 17);
+    }
 }
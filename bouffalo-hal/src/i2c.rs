@@ -2,9 +2,11 @@
 use core::ops::Deref;
 
 use crate::{
+    clocks::Clocks,
     glb::{self, v2::I2cClockSource},
-    gpio::{self, Alternate},
+    gpio::{self, Alternate, CheckFunctions, PinConflict},
 };
+use embedded_time::rate::Hertz;
 use volatile_register::{RO, RW, WO};
 
 /// Inter-integrated circuit registers.
@@ -498,6 +500,8 @@ impl FifoConfig1 {
 pub struct I2c<I2C, PADS> {
     i2c: I2C,
     pads: PADS,
+    /// Register polls to wait for each byte on the bus before timing out.
+    byte_timeout: u32,
 }
 
 impl<I2C: Deref<Target = RegisterBlock>, SCL, SDA> I2c<I2C, (SCL, SDA)> {
@@ -547,7 +551,34 @@ impl<I2C: Deref<Target = RegisterBlock>, SCL, SDA> I2c<I2C, (SCL, SDA)> {
             );
         }
 
-        Self { i2c, pads }
+        // Processor clock is not known yet; assume the fastest one.
+        let byte_timeout = byte_timeout(MAX_CLOCK_RATIO, 0xff, 0xff);
+        Self {
+            i2c,
+            pads,
+            byte_timeout,
+        }
+    }
+
+    /// Create a new Inter-Integrated Circuit instance, checking that no other function
+    /// claimed any of its pads.
+    ///
+    /// Returns the peripheral and pads along with the first [`PinConflict`] found,
+    /// without touching any register.
+    #[inline]
+    pub fn new_checked<const I: usize>(
+        i2c: I2C,
+        pads: (SCL, SDA),
+        glb: &glb::v2::RegisterBlock,
+    ) -> Result<Self, (I2C, (SCL, SDA), PinConflict)>
+    where
+        SCL: SclPin<I> + CheckFunctions,
+        SDA: SdaPin<I> + CheckFunctions,
+    {
+        match pads.check_functions() {
+            Ok(()) => Ok(Self::new(i2c, pads, glb)),
+            Err(e) => Err((i2c, pads, e)),
+        }
     }
 
     /// Release the I2C instance and return the pads.
@@ -560,6 +591,57 @@ impl<I2C: Deref<Target = RegisterBlock>, SCL, SDA> I2c<I2C, (SCL, SDA)> {
         (self.i2c, self.pads)
     }
 
+    /// Set bus clock frequency, returning the actual frequency.
+    ///
+    /// Typical values are 100 kHz for standard mode and 400 kHz for fast mode. The bus
+    /// clock is divided from I2C source clock in `clocks`; the actual frequency is equal
+    /// to or lower than `freq`. Returns [`Error::FrequencyTooLow`] without changing any
+    /// register if `freq` is below the slowest reachable bus clock.
+    ///
+    /// The I2C clock divider is a global configuration register shared by all I2C
+    /// controllers, thus this function also changes bus clock of other I2C controllers.
+    #[inline]
+    pub fn set_frequency(
+        &mut self,
+        freq: Hertz,
+        clocks: &Clocks,
+        glb: &glb::v2::RegisterBlock,
+    ) -> Result<Hertz, Error> {
+        let source = clocks.i2c_clock().ok_or(Error::ClockSource)?;
+        let (divide, phase, actual) = timing_for(source, freq).ok_or(Error::FrequencyTooLow)?;
+        let ratio = clocks.cpu_clock().0.div_ceil(source.0);
+        self.byte_timeout = byte_timeout(ratio, divide, phase);
+        unsafe {
+            glb.i2c_config.modify(|config| {
+                config
+                    .set_clock_source(I2cClockSource::Xclk)
+                    .set_clock_divide(divide)
+            });
+            self.i2c.period_start.write(
+                PeriodStart(0)
+                    .set_phase(0, phase)
+                    .set_phase(1, phase)
+                    .set_phase(2, phase)
+                    .set_phase(3, phase),
+            );
+            self.i2c.period_stop.write(
+                PeriodStop(0)
+                    .set_phase(0, phase)
+                    .set_phase(1, phase)
+                    .set_phase(2, phase)
+                    .set_phase(3, phase),
+            );
+            self.i2c.period_data.write(
+                PeriodData(0)
+                    .set_phase(0, phase)
+                    .set_phase(1, phase)
+                    .set_phase(2, phase)
+                    .set_phase(3, phase),
+            );
+        }
+        Ok(actual)
+    }
+
     /// Enable sub-address.
     #[inline]
     pub fn enable_sub_address(&mut self, sub_address: u8) {
@@ -585,19 +667,105 @@ impl<I2C: Deref<Target = RegisterBlock>, SCL, SDA> I2c<I2C, (SCL, SDA)> {
     }
 }
 
+/// Clock divider and phase register values, and actual frequency for bus clock `freq`.
+///
+/// One bus clock period consists of four phases of 1 to 256 cycles of I2C clock, which
+/// is divided from `source` by 1 to 256. Returns `None` if even the slowest timing is
+/// faster than `freq`.
+#[inline]
+fn timing_for(source: Hertz, freq: Hertz) -> Option<(u8, u8, Hertz)> {
+    // Round up so that the actual frequency does not exceed the requested one.
+    let cycles = source.0.div_ceil(freq.0.max(1));
+    let divide = cycles.div_ceil(4 * 256).clamp(1, 256);
+    let phase = cycles.div_ceil(4 * divide).clamp(1, 256);
+    let actual = Hertz(source.0 / (divide * phase * 4));
+    if actual.0 > freq.0 {
+        return None;
+    }
+    Some(((divide - 1) as u8, (phase - 1) as u8, actual))
+}
+
+/// Number of words the transmit queue holds.
+const TRANSMIT_FIFO_WORDS: u8 = 2;
+
+/// Largest number of bytes in one packet.
+const MAX_PACKET_LENGTH: usize = 256;
+
+/// Upper bound of processor clock over I2C source clock frequency.
+const MAX_CLOCK_RATIO: u32 = 16;
+
+/// Bus time in bytes a slave device may stretch the clock for each byte.
+const STRETCH_BYTES: u32 = 10;
+
+/// Register polls covering [`STRETCH_BYTES`] bytes on the bus at given timing.
+///
+/// One register poll takes at least one processor clock cycle, and `ratio` processor
+/// cycles pass in every I2C source clock cycle, thus the timeout never expires earlier.
+#[inline]
+const fn byte_timeout(ratio: u32, divide: u8, phase: u8) -> u32 {
+    // 9 bits per byte including acknowledge, 4 phases per bit.
+    let cycles = 9 * 4 * (phase as u32 + 1) * (divide as u32 + 1);
+    cycles.saturating_mul(ratio).saturating_mul(STRETCH_BYTES)
+}
+
+/// Check that `len` bytes fit in one packet.
+///
+/// The controller transfers at least one data byte in every packet, thus empty packets
+/// are not supported.
+#[inline]
+fn packet_length(len: usize) -> Result<usize, Error> {
+    match len {
+        0 => Err(Error::Unsupported),
+        1..=MAX_PACKET_LENGTH => Ok(len),
+        _ => Err(Error::InvalidLength),
+    }
+}
+
 /// I2C error.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum Error {
+    /// Slave device did not acknowledge its address.
+    AddressNack,
+    /// Slave device did not acknowledge a data byte.
+    DataNack,
+    /// Another master took over the bus.
+    ArbitrationLost,
+    /// Bus did not finish in time, e.g. slave device stretched the clock for too long.
+    Timeout,
+    /// Address does not fit in 7 bits.
+    InvalidAddress,
+    /// Buffer is longer than the 256 bytes one packet holds.
+    InvalidLength,
+    /// Transaction shape cannot be issued by the controller.
+    ///
+    /// The controller only issues a repeated start after a write of 1 to 4 bytes
+    /// followed by reads; other combinations would need a stop condition in between.
+    /// It also transfers at least one data byte after the address, thus transactions
+    /// without any data byte, e.g. address probes by an empty write, are not supported.
+    Unsupported,
+    /// Source clock is not available.
+    ClockSource,
+    /// Bus clock frequency is below the slowest reachable one.
+    FrequencyTooLow,
     Other,
 }
 
 impl embedded_hal::i2c::Error for Error {
     #[inline(always)]
     fn kind(&self) -> embedded_hal::i2c::ErrorKind {
-        use embedded_hal::i2c::ErrorKind;
+        use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
         match self {
-            Error::Other => ErrorKind::Other,
+            Error::AddressNack => ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address),
+            Error::DataNack => ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data),
+            Error::ArbitrationLost => ErrorKind::ArbitrationLoss,
+            Error::Timeout
+            | Error::InvalidAddress
+            | Error::InvalidLength
+            | Error::Unsupported
+            | Error::ClockSource
+            | Error::FrequencyTooLow
+            | Error::Other => ErrorKind::Other,
         }
     }
 }
@@ -606,55 +774,265 @@ impl<I2C: Deref<Target = RegisterBlock>, PADS> embedded_hal::i2c::ErrorType for
     type Error = Error;
 }
 
-impl<I2C: Deref<Target = RegisterBlock>, PADS> embedded_hal::i2c::I2c for I2c<I2C, PADS> {
+impl<I2C: Deref<Target = RegisterBlock>, PADS> I2c<I2C, PADS> {
+    /// Write one packet of `len` bytes to the slave device.
     #[inline]
-    fn transaction(
+    fn write_packet(
         &mut self,
         address: u8,
-        operations: &mut [embedded_hal::i2c::Operation<'_>],
-    ) -> Result<(), Self::Error> {
-        for op in operations {
-            match op {
-                embedded_hal::i2c::Operation::Write(_bytes) => {
-                    todo!()
+        len: usize,
+        bytes: impl Iterator<Item = u8>,
+    ) -> Result<(), Error> {
+        if address > 0x7f {
+            return Err(Error::InvalidAddress);
+        }
+        let len = packet_length(len)?;
+        unsafe {
+            self.i2c.config.modify(|config| {
+                config
+                    .set_write_direction()
+                    .disable_ten_bit_address()
+                    .set_slave_address(address as u16)
+                    .set_packet_length((len - 1) as u8)
+                    .enable_master()
+            })
+        };
+
+        // One more byte for the address.
+        let max_retry = self.byte_timeout.saturating_mul(len as u32 + 1);
+        let mut retry = 0;
+        let mut pushed = 0;
+        // Data phase has begun once any pushed word left the transmit queue.
+        let data_started = |this: &Self, pushed: u8| {
+            let queued =
+                TRANSMIT_FIFO_WORDS - this.i2c.fifo_config_1.read().transmit_available_bytes();
+            pushed > queued
+        };
+        let mut bytes = bytes.peekable();
+        while bytes.peek().is_some() {
+            while self.i2c.fifo_config_1.read().transmit_available_bytes() == 0 {
+                if let Some(error) = self.bus_error(data_started(self, pushed)) {
+                    return Err(error);
                 }
-                embedded_hal::i2c::Operation::Read(bytes) => {
-                    let len = bytes.len() as u8;
-                    unsafe {
-                        self.i2c.config.modify(|config| {
-                            config
-                                .set_read_direction()
-                                .set_slave_address(address as u16)
-                                .set_packet_length(len - 1)
-                                .enable_master()
-                        })
-                    };
-
-                    let mut i = 0;
-                    let max_retry = len * 100;
-                    let mut retry = 0;
-                    while i < len {
-                        while self.i2c.fifo_config_1.read().receive_available_bytes() == 0 {
-                            retry += 1;
-                            if retry >= max_retry {
-                                unsafe { self.i2c.config.modify(|config| config.disable_master()) };
-                                return Err(Error::Other);
-                            }
-                        }
-                        let word = self.i2c.fifo_read.read();
-                        let bytes_to_read = core::cmp::min(len - i, 4);
-                        for j in 0..bytes_to_read {
-                            bytes[i as usize] = (word >> (j * 8)) as u8;
-                            i += 1;
-                        }
-                    }
+                retry += 1;
+                if retry >= max_retry {
+                    unsafe { self.i2c.config.modify(|config| config.disable_master()) };
+                    return Err(Error::Timeout);
+                }
+            }
+            let word = (0..4)
+                .zip(&mut bytes)
+                .fold(0u32, |word, (j, byte)| word | ((byte as u32) << (j * 8)));
+            unsafe { self.i2c.fifo_write.write(word) };
+            pushed += 1;
+        }
+        // Wait until the last byte left the bus before stopping master.
+        while self.i2c.bus_busy.read().is_bus_busy() {
+            if let Some(error) = self.bus_error(data_started(self, pushed)) {
+                return Err(error);
+            }
+            retry += 1;
+            if retry >= max_retry {
+                unsafe { self.i2c.config.modify(|config| config.disable_master()) };
+                return Err(Error::Timeout);
+            }
+        }
+        if let Some(error) = self.bus_error(data_started(self, pushed)) {
+            return Err(error);
+        }
 
+        unsafe { self.i2c.config.modify(|config| config.disable_master()) };
+        Ok(())
+    }
+
+    /// Read one packet of `len` bytes from the slave device.
+    ///
+    /// If sub-address is enabled, the sub-address bytes are written first, followed by
+    /// a repeated start and the read phase in the same packet.
+    #[inline]
+    fn read_packet<'b>(
+        &mut self,
+        address: u8,
+        len: usize,
+        mut bytes: impl Iterator<Item = &'b mut u8>,
+    ) -> Result<(), Error> {
+        if address > 0x7f {
+            return Err(Error::InvalidAddress);
+        }
+        let len = packet_length(len)?;
+        unsafe {
+            self.i2c.config.modify(|config| {
+                config
+                    .set_read_direction()
+                    .disable_ten_bit_address()
+                    .set_slave_address(address as u16)
+                    .set_packet_length((len - 1) as u8)
+                    .enable_master()
+            })
+        };
+
+        let mut i = 0;
+        // One more byte for the address.
+        let max_retry = self.byte_timeout.saturating_mul(len as u32 + 1);
+        let mut retry = 0;
+        while i < len {
+            while self.i2c.fifo_config_1.read().receive_available_bytes() == 0 {
+                // Master acknowledges data bytes on reading, thus only the address
+                // (or sub-address) phase can be not acknowledged.
+                if let Some(error) = self.bus_error(false) {
+                    return Err(error);
+                }
+                retry += 1;
+                if retry >= max_retry {
                     unsafe { self.i2c.config.modify(|config| config.disable_master()) };
+                    return Err(Error::Timeout);
                 }
             }
+            let word = self.i2c.fifo_read.read();
+            for (j, slot) in (0..core::cmp::min(len - i, 4)).zip(&mut bytes) {
+                *slot = (word >> (j * 8)) as u8;
+                i += 1;
+            }
         }
+
+        unsafe { self.i2c.config.modify(|config| config.disable_master()) };
         Ok(())
     }
+
+    /// Check for bus errors, stopping master and clearing error state if any.
+    ///
+    /// A not acknowledged condition is reported as data error if `data_started`.
+    #[inline]
+    fn bus_error(&self, data_started: bool) -> Option<Error> {
+        let state = self.i2c.interrupt_state.read();
+        let error = if state.has_interrupt(Interrupt::ArbitrationLost) {
+            Error::ArbitrationLost
+        } else if state.has_interrupt(Interrupt::NackReceived) {
+            if data_started {
+                Error::DataNack
+            } else {
+                Error::AddressNack
+            }
+        } else {
+            return None;
+        };
+        unsafe {
+            self.i2c.interrupt_clear.write(
+                InterruptClear(0)
+                    .clear_interrupt(Interrupt::NackReceived)
+                    .clear_interrupt(Interrupt::ArbitrationLost),
+            );
+            self.i2c.config.modify(|config| config.disable_master());
+        }
+        Some(error)
+    }
+}
+
+impl<I2C: Deref<Target = RegisterBlock>, PADS> I2c<I2C, PADS> {
+    /// Write 1 to 4 bytes as the sub-address phase, then read after a repeated start.
+    #[inline]
+    fn write_read_packet<'b>(
+        &mut self,
+        address: u8,
+        write: (usize, impl Iterator<Item = u8>),
+        read: (usize, impl Iterator<Item = &'b mut u8>),
+    ) -> Result<(), Error> {
+        let count = match write.0 {
+            1 => SubAddressByteCount::One,
+            2 => SubAddressByteCount::Two,
+            3 => SubAddressByteCount::Three,
+            4 => SubAddressByteCount::Four,
+            _ => return Err(Error::Unsupported),
+        };
+        // Sub-address bytes are sent from the least significant byte.
+        let sub_address = (0..4).zip(write.1).fold(0u32, |sub_address, (j, byte)| {
+            sub_address | (byte as u32) << (j * 8)
+        });
+        let prev_config = self.i2c.config.read();
+        let prev_sub_address = self.i2c.sub_address.read();
+        unsafe {
+            self.i2c.sub_address.write(sub_address);
+            self.i2c.config.modify(|config| {
+                config
+                    .enable_sub_address()
+                    .set_sub_address_byte_count(count)
+            });
+        }
+        let ans = self.read_packet(address, read.0, read.1);
+        // Restore sub-address settings from `enable_sub_address`.
+        unsafe {
+            if prev_config.is_sub_address_enabled() {
+                self.i2c.sub_address.write(prev_sub_address);
+                self.i2c.config.modify(|config| {
+                    config
+                        .enable_sub_address()
+                        .set_sub_address_byte_count(prev_config.get_sub_address_byte_count())
+                });
+            } else {
+                self.i2c
+                    .config
+                    .modify(|config| config.disable_sub_address());
+            }
+        }
+        ans
+    }
+}
+
+impl<I2C: Deref<Target = RegisterBlock>, PADS> embedded_hal::i2c::I2c for I2c<I2C, PADS> {
+    /// Run operations in one bus transaction, with a stop condition only at the end.
+    ///
+    /// Adjacent operations of the same kind are merged into one packet. Writes of 1 to 4
+    /// bytes in total, typically a register address, followed by reads are sent as the
+    /// hardware sub-address phase, so the controller issues a repeated start between
+    /// writing and reading without releasing the bus.
+    ///
+    /// The following transactions return [`Error::Unsupported`] without starting on
+    /// the bus:
+    ///
+    /// - writes of more than 4 bytes in total followed by reads;
+    /// - reads followed by writes;
+    /// - transactions of no data byte at all, e.g. an empty `write` to probe whether a
+    ///   device acknowledges its address; probe with a one-byte read instead.
+    ///
+    /// Writes or reads of more than 256 bytes in total return [`Error::InvalidLength`].
+    #[inline]
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [embedded_hal::i2c::Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        use embedded_hal::i2c::Operation;
+        let split = operations
+            .iter()
+            .position(|op| matches!(op, Operation::Read(_)))
+            .unwrap_or(operations.len());
+        let (writes, reads) = operations.split_at_mut(split);
+        if reads.iter().any(|op| matches!(op, Operation::Write(_))) {
+            return Err(Error::Unsupported);
+        }
+        let len = |ops: &[Operation<'_>]| -> usize {
+            ops.iter()
+                .map(|op| match op {
+                    Operation::Write(bytes) => bytes.len(),
+                    Operation::Read(bytes) => bytes.len(),
+                })
+                .sum()
+        };
+        let (write_len, read_len) = (len(writes), len(reads));
+        let write_bytes = writes.iter().flat_map(|op| match op {
+            Operation::Write(bytes) => bytes.iter().copied(),
+            Operation::Read(_) => [].iter().copied(),
+        });
+        let read_bytes = reads.iter_mut().flat_map(|op| match op {
+            Operation::Read(bytes) => bytes.iter_mut(),
+            Operation::Write(_) => [].iter_mut(),
+        });
+        match (write_len, read_len) {
+            (_, 0) => self.write_packet(address, write_len, write_bytes),
+            (0, _) => self.read_packet(address, read_len, read_bytes),
+            _ => self.write_read_packet(address, (write_len, write_bytes), (read_len, read_bytes)),
+        }
+    }
 }
 
 pub trait SclPin<const I: usize> {}
@@ -705,8 +1083,10 @@ mod tests {
     use super::{
         BusBusy, Config, FifoConfig0, FifoConfig1, Interrupt, InterruptClear, InterruptEnable,
         InterruptMask, InterruptState, PeriodData, PeriodStart, PeriodStop, RegisterBlock,
-        SubAddressByteCount,
+        SubAddressByteCount, byte_timeout, timing_for,
     };
+    use crate::mock::MockRegisters;
+    use embedded_time::rate::Hertz;
     use memoffset::offset_of;
 
     #[test]
@@ -959,4 +1339,158 @@ mod tests {
         fifo_config = FifoConfig1(0x0);
         assert_eq!(fifo_config.receive_threshold(), 0x00);
     }
+
+    #[test]
+    fn function_i2c_write_read_repeated_start() {
+        use embedded_hal::i2c::I2c as _;
+        let mock = MockRegisters::<RegisterBlock>::new();
+        // Receive FIFO holds one word; transmit FIFO is never touched.
+        mock.set_raw(0x84, 1 << 8);
+        mock.set_raw(0x8c, 0x4433_2211);
+        mock.set_raw(0x88, 0xdead_beef);
+        let mut i2c = super::I2c {
+            i2c: mock.registers(),
+            pads: (),
+            byte_timeout: 100,
+        };
+        let mut buf = [0u8; 2];
+        i2c.write_read(0x50, &[0x12, 0x34], &mut buf).unwrap();
+        assert_eq!(buf, [0x11, 0x22]);
+        // Register address is sent as sub-address of the read packet, thus there is
+        // no separate write packet and no stop condition before the read phase.
+        assert_eq!(mock.raw(0x88), 0xdead_beef);
+        assert_eq!(mock.raw(0x08), 0x3412);
+        let config = mock.registers().config.read();
+        assert!(config.is_read_direction());
+        assert_eq!(config.get_slave_address(), 0x50);
+        assert_eq!(config.get_packet_length(), 1);
+        assert!(!config.is_master_enabled());
+        // Sub-address setting is restored after the transaction.
+        assert!(!config.is_sub_address_enabled());
+
+        // Sequences needing a stop in between are rejected before touching the bus.
+        use embedded_hal::i2c::Operation;
+        mock.set_raw(0x08, 0);
+        let ans = i2c.write_read(0x50, &[0; 5], &mut buf);
+        assert_eq!(ans, Err(super::Error::Unsupported));
+        let mut operations = [Operation::Read(&mut buf), Operation::Write(&[0x12])];
+        let ans = i2c.transaction(0x50, &mut operations);
+        assert_eq!(ans, Err(super::Error::Unsupported));
+        assert_eq!(mock.raw(0x08), 0);
+        assert_eq!(mock.raw(0x88), 0xdead_beef);
+
+        // Adjacent writes are merged into one packet.
+        mock.set_raw(0x84, 2);
+        let mut operations = [Operation::Write(&[0x11, 0x22]), Operation::Write(&[0x33])];
+        i2c.transaction(0x50, &mut operations).unwrap();
+        assert_eq!(mock.raw(0x88), 0x0033_2211);
+        let config = mock.registers().config.read();
+        assert!(!config.is_read_direction());
+        assert_eq!(config.get_packet_length(), 2);
+    }
+
+    #[test]
+    fn function_i2c_bus_errors() {
+        use super::Error;
+        use embedded_hal::i2c::{Error as _, ErrorKind, I2c as _, NoAcknowledgeSource};
+        let mock = MockRegisters::<RegisterBlock>::new();
+        let mut i2c = super::I2c {
+            i2c: mock.registers(),
+            pads: (),
+            byte_timeout: 100,
+        };
+        assert_eq!(i2c.write(0x80, &[0]), Err(Error::InvalidAddress));
+
+        // Address not acknowledged: nothing left the full transmit queue.
+        mock.set_raw(0x04, 1 << 3);
+        let ans = i2c.write(0x50, &[0; 12]);
+        assert_eq!(ans, Err(Error::AddressNack));
+        assert_eq!(
+            ans.unwrap_err().kind(),
+            ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)
+        );
+        assert_eq!((mock.raw(0x04) >> 16) as u8, (1 << 3) | (1 << 4));
+        assert!(!mock.registers().config.read().is_master_enabled());
+
+        // Data not acknowledged: queue has drained while bus is busy.
+        mock.set_raw(0x84, 2);
+        mock.set_raw(0x0c, 1);
+        assert_eq!(i2c.write(0x50, &[0; 4]), Err(Error::DataNack));
+        let mut buf = [0; 2];
+        assert_eq!(i2c.read(0x50, &mut buf), Err(Error::AddressNack));
+
+        mock.set_raw(0x04, 1 << 4);
+        assert_eq!(i2c.write(0x50, &[0; 4]), Err(Error::ArbitrationLost));
+        assert_eq!(Error::ArbitrationLost.kind(), ErrorKind::ArbitrationLoss);
+
+        // Slave stretches the clock forever.
+        mock.set_raw(0x04, 0);
+        assert_eq!(i2c.write(0x50, &[0; 4]), Err(Error::Timeout));
+        mock.set_raw(0x84, 0);
+        assert_eq!(i2c.read(0x50, &mut buf), Err(Error::Timeout));
+    }
+
+    #[test]
+    fn function_i2c_packet_length() {
+        use super::Error;
+        use embedded_hal::i2c::I2c as _;
+        let mock = MockRegisters::<RegisterBlock>::new();
+        // Transmit queue always has room and the bus is idle.
+        mock.set_raw(0x84, 2);
+        let mut i2c = super::I2c {
+            i2c: mock.registers(),
+            pads: (),
+            byte_timeout: 100,
+        };
+        i2c.write(0x50, &[0; 256]).unwrap();
+        assert_eq!(mock.registers().config.read().get_packet_length(), 255);
+        i2c.write(0x50, &[0; 1]).unwrap();
+        assert_eq!(mock.registers().config.read().get_packet_length(), 0);
+        assert_eq!(i2c.write(0x50, &[0; 257]), Err(Error::InvalidLength));
+        // Empty transactions, e.g. address probes, need a packet without data byte.
+        assert_eq!(i2c.write(0x50, &[]), Err(Error::Unsupported));
+        assert_eq!(i2c.read(0x50, &mut []), Err(Error::Unsupported));
+        assert_eq!(i2c.transaction(0x50, &mut []), Err(Error::Unsupported));
+        // Nothing was started by rejected packets.
+        assert_eq!(mock.registers().config.read().get_packet_length(), 0);
+    }
+
+    #[test]
+    fn function_byte_timeout() {
+        // 100 kHz from 40 MHz: 400 source cycles per bit, 3600 per byte.
+        assert_eq!(byte_timeout(8, 0, 99), 3600 * 8 * 10);
+        // Four times the cycles at a quarter of the bus frequency.
+        assert_eq!(byte_timeout(8, 3, 99), 4 * 3600 * 8 * 10);
+        assert_eq!(byte_timeout(u32::MAX, 0xff, 0xff), u32::MAX);
+    }
+
+    #[test]
+    fn function_timing_for() {
+        let xclk = Hertz(40_000_000);
+        assert_eq!(
+            timing_for(xclk, Hertz(100_000)),
+            Some((0, 99, Hertz(100_000)))
+        );
+        assert_eq!(
+            timing_for(xclk, Hertz(400_000)),
+            Some((0, 24, Hertz(400_000)))
+        );
+        // Slow clocks need the I2C clock divider.
+        assert_eq!(
+            timing_for(xclk, Hertz(10_000)),
+            Some((3, 249, Hertz(10_000)))
+        );
+        // Actual frequency never exceeds the requested one.
+        assert_eq!(
+            timing_for(xclk, Hertz(130_000)),
+            Some((0, 76, Hertz(129_870)))
+        );
+        assert_eq!(timing_for(xclk, Hertz(152)), Some((255, 255, Hertz(152))));
+        assert_eq!(timing_for(xclk, Hertz(151)), None);
+        assert_eq!(timing_for(xclk, Hertz(0)), None);
+        assert_eq!(
+            timing_for(xclk, Hertz(100_000_000)),
+            Some((0, 0, Hertz(10_000_000)))
+        );
+    }
 }
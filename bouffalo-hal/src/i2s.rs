@@ -1,5 +1,28 @@
 //! Inter-IC sound bus peripheral.
-
+//!
+//! The driver operates the bus as master transmitter in standard I2S (Philips) format:
+//! frame clock is low for the left channel and each slot starts one bit clock after a
+//! frame clock edge. Samples are written in whole left-right frames, so that the first
+//! sample after start always lands in the left slot.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use bouffalo_hal::{clocks::Clocks, gpio::{Alternate, I2s as I2sPin}, i2s::{Format, I2s}};
+//! # fn example(
+//! #     i2s: &'static bouffalo_hal::i2s::RegisterBlock,
+//! #     pads: (Alternate<'static, 0, I2sPin>, Alternate<'static, 1, I2sPin>, Alternate<'static, 3, I2sPin>),
+//! #     clocks: &Clocks,
+//! # ) {
+//! let mut i2s = I2s::new(i2s, pads, Format::stereo_16bit(48_000), clocks).unwrap();
+//! let frames: [[i16; 2]; 4] = [[0, 0], [1000, -1000], [2000, -2000], [1000, -1000]];
+//! i2s.write(&frames).ok();
+//! # }
+//! ```
+use crate::clocks::Clocks;
+use crate::gpio::{self, Alternate};
+use core::ops::Deref;
+use embedded_time::rate::Hertz;
 use volatile_register::{RO, RW, WO};
 
 /// Inter-IC sound bus peripheral registers.
@@ -30,6 +53,216 @@ pub struct RegisterBlock {
 #[repr(transparent)]
 pub struct Config(u32);
 
+/// Width of a channel slot or sample data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum DataSize {
+    /// 8 bits.
+    Bits8 = 0,
+    /// 16 bits.
+    Bits16 = 1,
+    /// 24 bits.
+    Bits24 = 2,
+    /// 32 bits.
+    Bits32 = 3,
+}
+
+impl DataSize {
+    /// Number of bits of this width.
+    #[inline]
+    pub const fn bits(self) -> u32 {
+        match self {
+            DataSize::Bits8 => 8,
+            DataSize::Bits16 => 16,
+            DataSize::Bits24 => 24,
+            DataSize::Bits32 => 32,
+        }
+    }
+    #[inline]
+    const fn from_bits(val: u32) -> Self {
+        match val & 0x3 {
+            0 => DataSize::Bits8,
+            1 => DataSize::Bits16,
+            2 => DataSize::Bits24,
+            _ => DataSize::Bits32,
+        }
+    }
+}
+
+/// Data alignment inside a channel slot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Alignment {
+    /// Data starts at the beginning of slot.
+    LeftJustified = 0,
+    /// Data ends at the end of slot.
+    RightJustified = 1,
+    /// Digital signal processor mode with a short frame pulse.
+    Dsp = 2,
+}
+
+impl Config {
+    const MASTER_ENABLE: u32 = 1 << 0;
+    const SLAVE_ENABLE: u32 = 1 << 1;
+    const TRANSMIT_ENABLE: u32 = 1 << 2;
+    const RECEIVE_ENABLE: u32 = 1 << 3;
+    const MONO: u32 = 1 << 4;
+    const MUTE: u32 = 1 << 5;
+    const FRAME_SIZE: u32 = 0x3 << 12;
+    const DATA_SIZE: u32 = 0x3 << 14;
+    const ALIGNMENT: u32 = 0x3 << 16;
+    const OFFSET_COUNT: u32 = 0x1f << 20;
+    const OFFSET_ENABLE: u32 = 1 << 25;
+
+    /// Enable master mode.
+    #[inline]
+    pub const fn enable_master(self) -> Self {
+        Self(self.0 | Self::MASTER_ENABLE)
+    }
+    /// Disable master mode.
+    #[inline]
+    pub const fn disable_master(self) -> Self {
+        Self(self.0 & !Self::MASTER_ENABLE)
+    }
+    /// Check if master mode is enabled.
+    #[inline]
+    pub const fn is_master_enabled(self) -> bool {
+        self.0 & Self::MASTER_ENABLE != 0
+    }
+    /// Enable slave mode.
+    #[inline]
+    pub const fn enable_slave(self) -> Self {
+        Self(self.0 | Self::SLAVE_ENABLE)
+    }
+    /// Disable slave mode.
+    #[inline]
+    pub const fn disable_slave(self) -> Self {
+        Self(self.0 & !Self::SLAVE_ENABLE)
+    }
+    /// Check if slave mode is enabled.
+    #[inline]
+    pub const fn is_slave_enabled(self) -> bool {
+        self.0 & Self::SLAVE_ENABLE != 0
+    }
+    /// Enable data transmission.
+    #[inline]
+    pub const fn enable_transmit(self) -> Self {
+        Self(self.0 | Self::TRANSMIT_ENABLE)
+    }
+    /// Disable data transmission.
+    #[inline]
+    pub const fn disable_transmit(self) -> Self {
+        Self(self.0 & !Self::TRANSMIT_ENABLE)
+    }
+    /// Check if data transmission is enabled.
+    #[inline]
+    pub const fn is_transmit_enabled(self) -> bool {
+        self.0 & Self::TRANSMIT_ENABLE != 0
+    }
+    /// Enable data reception.
+    #[inline]
+    pub const fn enable_receive(self) -> Self {
+        Self(self.0 | Self::RECEIVE_ENABLE)
+    }
+    /// Disable data reception.
+    #[inline]
+    pub const fn disable_receive(self) -> Self {
+        Self(self.0 & !Self::RECEIVE_ENABLE)
+    }
+    /// Check if data reception is enabled.
+    #[inline]
+    pub const fn is_receive_enabled(self) -> bool {
+        self.0 & Self::RECEIVE_ENABLE != 0
+    }
+    /// Enable mono mode.
+    #[inline]
+    pub const fn enable_mono(self) -> Self {
+        Self(self.0 | Self::MONO)
+    }
+    /// Disable mono mode.
+    #[inline]
+    pub const fn disable_mono(self) -> Self {
+        Self(self.0 & !Self::MONO)
+    }
+    /// Check if mono mode is enabled.
+    #[inline]
+    pub const fn is_mono_enabled(self) -> bool {
+        self.0 & Self::MONO != 0
+    }
+    /// Enable mute mode.
+    #[inline]
+    pub const fn enable_mute(self) -> Self {
+        Self(self.0 | Self::MUTE)
+    }
+    /// Disable mute mode.
+    #[inline]
+    pub const fn disable_mute(self) -> Self {
+        Self(self.0 & !Self::MUTE)
+    }
+    /// Check if mute mode is enabled.
+    #[inline]
+    pub const fn is_mute_enabled(self) -> bool {
+        self.0 & Self::MUTE != 0
+    }
+    /// Set width of a channel slot.
+    #[inline]
+    pub const fn set_frame_size(self, size: DataSize) -> Self {
+        Self((self.0 & !Self::FRAME_SIZE) | ((size as u32) << 12))
+    }
+    /// Get width of a channel slot.
+    #[inline]
+    pub const fn frame_size(self) -> DataSize {
+        DataSize::from_bits((self.0 & Self::FRAME_SIZE) >> 12)
+    }
+    /// Set width of sample data.
+    #[inline]
+    pub const fn set_data_size(self, size: DataSize) -> Self {
+        Self((self.0 & !Self::DATA_SIZE) | ((size as u32) << 14))
+    }
+    /// Get width of sample data.
+    #[inline]
+    pub const fn data_size(self) -> DataSize {
+        DataSize::from_bits((self.0 & Self::DATA_SIZE) >> 14)
+    }
+    /// Set data alignment inside a channel slot.
+    #[inline]
+    pub const fn set_alignment(self, alignment: Alignment) -> Self {
+        Self((self.0 & !Self::ALIGNMENT) | ((alignment as u32) << 16))
+    }
+    /// Get data alignment inside a channel slot.
+    #[inline]
+    pub const fn alignment(self) -> Alignment {
+        match (self.0 & Self::ALIGNMENT) >> 16 {
+            0 => Alignment::LeftJustified,
+            1 => Alignment::RightJustified,
+            _ => Alignment::Dsp,
+        }
+    }
+    /// Delay data against frame clock edge by `count + 1` bit clocks.
+    #[inline]
+    pub const fn enable_offset(self, count: u8) -> Self {
+        Self(
+            (self.0 & !Self::OFFSET_COUNT)
+                | (((count as u32) << 20) & Self::OFFSET_COUNT)
+                | Self::OFFSET_ENABLE,
+        )
+    }
+    /// Align data with frame clock edge.
+    #[inline]
+    pub const fn disable_offset(self) -> Self {
+        Self(self.0 & !Self::OFFSET_ENABLE)
+    }
+    /// Get data delay in bit clocks against frame clock edge.
+    #[inline]
+    pub const fn offset(self) -> Option<u8> {
+        if self.0 & Self::OFFSET_ENABLE != 0 {
+            Some(((self.0 & Self::OFFSET_COUNT) >> 20) as u8 + 1)
+        } else {
+            None
+        }
+    }
+}
+
 /// Interrupt configuration and state register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
@@ -40,24 +273,500 @@ pub struct InterruptConfig(u32);
 #[repr(transparent)]
 pub struct BclkConfig(u32);
 
+impl BclkConfig {
+    const LOW: u32 = 0xfff;
+    const HIGH: u32 = 0xfff << 16;
+
+    /// Set bit clock low phase in source clock cycles minus one.
+    #[inline]
+    pub const fn set_low(self, val: u16) -> Self {
+        Self((self.0 & !Self::LOW) | (val as u32 & Self::LOW))
+    }
+    /// Get bit clock low phase in source clock cycles minus one.
+    #[inline]
+    pub const fn low(self) -> u16 {
+        (self.0 & Self::LOW) as u16
+    }
+    /// Set bit clock high phase in source clock cycles minus one.
+    #[inline]
+    pub const fn set_high(self, val: u16) -> Self {
+        Self((self.0 & !Self::HIGH) | (((val as u32) << 16) & Self::HIGH))
+    }
+    /// Get bit clock high phase in source clock cycles minus one.
+    #[inline]
+    pub const fn high(self) -> u16 {
+        ((self.0 & Self::HIGH) >> 16) as u16
+    }
+}
+
 /// First-in first-out queue configuration register 0.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct FifoConfig0(u32);
 
+impl FifoConfig0 {
+    const DMA_TRANSMIT_ENABLE: u32 = 1 << 0;
+    const DMA_RECEIVE_ENABLE: u32 = 1 << 1;
+    const TRANSMIT_FIFO_CLEAR: u32 = 1 << 2;
+    const RECEIVE_FIFO_CLEAR: u32 = 1 << 3;
+    const TRANSMIT_FIFO_UNDERFLOW: u32 = 1 << 5;
+    const LR_MERGE: u32 = 1 << 8;
+    const LR_EXCHANGE: u32 = 1 << 9;
+
+    /// Enable DMA transmit feature.
+    #[inline]
+    pub const fn enable_dma_transmit(self) -> Self {
+        Self(self.0 | Self::DMA_TRANSMIT_ENABLE)
+    }
+    /// Disable DMA transmit feature.
+    #[inline]
+    pub const fn disable_dma_transmit(self) -> Self {
+        Self(self.0 & !Self::DMA_TRANSMIT_ENABLE)
+    }
+    /// Check if DMA transmit feature is enabled.
+    #[inline]
+    pub const fn is_dma_transmit_enabled(self) -> bool {
+        self.0 & Self::DMA_TRANSMIT_ENABLE != 0
+    }
+    /// Enable DMA receive feature.
+    #[inline]
+    pub const fn enable_dma_receive(self) -> Self {
+        Self(self.0 | Self::DMA_RECEIVE_ENABLE)
+    }
+    /// Disable DMA receive feature.
+    #[inline]
+    pub const fn disable_dma_receive(self) -> Self {
+        Self(self.0 & !Self::DMA_RECEIVE_ENABLE)
+    }
+    /// Check if DMA receive feature is enabled.
+    #[inline]
+    pub const fn is_dma_receive_enabled(self) -> bool {
+        self.0 & Self::DMA_RECEIVE_ENABLE != 0
+    }
+    /// Clear transmit first-in first-out queue.
+    #[inline]
+    pub const fn clear_transmit_fifo(self) -> Self {
+        Self(self.0 | Self::TRANSMIT_FIFO_CLEAR)
+    }
+    /// Clear receive first-in first-out queue.
+    #[inline]
+    pub const fn clear_receive_fifo(self) -> Self {
+        Self(self.0 | Self::RECEIVE_FIFO_CLEAR)
+    }
+    /// Check if transmit first-in first-out queue has underflowed.
+    #[inline]
+    pub const fn transmit_underflow(self) -> bool {
+        self.0 & Self::TRANSMIT_FIFO_UNDERFLOW != 0
+    }
+    /// Pack left and right samples into one queue entry.
+    #[inline]
+    pub const fn enable_lr_merge(self) -> Self {
+        Self(self.0 | Self::LR_MERGE)
+    }
+    /// Use one queue entry for each sample.
+    #[inline]
+    pub const fn disable_lr_merge(self) -> Self {
+        Self(self.0 & !Self::LR_MERGE)
+    }
+    /// Check if left and right samples are packed into one queue entry.
+    #[inline]
+    pub const fn is_lr_merge_enabled(self) -> bool {
+        self.0 & Self::LR_MERGE != 0
+    }
+    /// Swap left and right samples.
+    #[inline]
+    pub const fn enable_lr_exchange(self) -> Self {
+        Self(self.0 | Self::LR_EXCHANGE)
+    }
+    /// Keep left and right samples in queue order.
+    #[inline]
+    pub const fn disable_lr_exchange(self) -> Self {
+        Self(self.0 & !Self::LR_EXCHANGE)
+    }
+    /// Check if left and right samples are swapped.
+    #[inline]
+    pub const fn is_lr_exchange_enabled(self) -> bool {
+        self.0 & Self::LR_EXCHANGE != 0
+    }
+}
+
 /// First-in first-out queue configuration register 1.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct FifoConfig1(u32);
 
+impl FifoConfig1 {
+    const TRANSMIT_COUNT: u32 = 0x3f;
+    const RECEIVE_COUNT: u32 = 0x3f << 8;
+    const TRANSMIT_THRESHOLD: u32 = 0xf << 16;
+
+    /// Get number of empty entries remained in transmit FIFO queue.
+    #[inline]
+    pub const fn transmit_available_words(self) -> u8 {
+        (self.0 & Self::TRANSMIT_COUNT) as u8
+    }
+    /// Get number of entries received in receive FIFO queue.
+    #[inline]
+    pub const fn receive_available_words(self) -> u8 {
+        ((self.0 & Self::RECEIVE_COUNT) >> 8) as u8
+    }
+    /// Set transmit FIFO threshold for DMA requests.
+    #[inline]
+    pub const fn set_transmit_threshold(self, val: u8) -> Self {
+        Self(
+            (self.0 & !Self::TRANSMIT_THRESHOLD)
+                | (((val as u32) << 16) & Self::TRANSMIT_THRESHOLD),
+        )
+    }
+    /// Get transmit FIFO threshold for DMA requests.
+    #[inline]
+    pub const fn transmit_threshold(self) -> u8 {
+        ((self.0 & Self::TRANSMIT_THRESHOLD) >> 16) as u8
+    }
+}
+
 /// Input/output signal configuration register.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(transparent)]
 pub struct IoConfig(u32);
 
+/// Stereo audio sample format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Format {
+    /// Frame rate in frames per second.
+    pub sample_rate: Hertz,
+    /// Width of each sample.
+    pub data_size: DataSize,
+}
+
+impl Format {
+    /// Stereo 16-bit samples at `rate` frames per second.
+    #[inline]
+    pub const fn stereo_16bit(rate: u32) -> Self {
+        Self {
+            sample_rate: Hertz(rate),
+            data_size: DataSize::Bits16,
+        }
+    }
+    /// Stereo 24-bit samples in 32-bit slots at `rate` frames per second.
+    #[inline]
+    pub const fn stereo_24bit(rate: u32) -> Self {
+        Self {
+            sample_rate: Hertz(rate),
+            data_size: DataSize::Bits24,
+        }
+    }
+    /// Width of a channel slot on the bus.
+    #[inline]
+    pub const fn slot_size(&self) -> DataSize {
+        match self.data_size {
+            DataSize::Bits24 => DataSize::Bits32,
+            size => size,
+        }
+    }
+}
+
+/// Audio sample which fits into one transmit queue entry.
+pub trait Sample: Copy {
+    /// Width of this sample.
+    const SIZE: DataSize;
+    /// Queue entry of this sample.
+    fn to_word(self) -> u32;
+}
+
+impl Sample for i16 {
+    const SIZE: DataSize = DataSize::Bits16;
+    #[inline]
+    fn to_word(self) -> u32 {
+        self as u16 as u32
+    }
+}
+
+/// 24-bit samples are held in the lower bits of `i32`.
+impl Sample for i32 {
+    const SIZE: DataSize = DataSize::Bits24;
+    #[inline]
+    fn to_word(self) -> u32 {
+        self as u32 & 0x00ff_ffff
+    }
+}
+
+/// Inter-IC sound bus error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// Sample width differs from configured format.
+    SampleSize,
+    /// Source clock is not configured.
+    ClockSource,
+    /// Sample rate cannot be derived from source clock within tolerance.
+    SampleRate,
+}
+
+/// Managed Inter-IC sound bus peripheral.
+pub struct I2s<I2S, PADS> {
+    i2s: I2S,
+    pads: PADS,
+    format: Format,
+    sample_rate: Hertz,
+}
+
+impl<I2S: Deref<Target = RegisterBlock>, PADS> I2s<I2S, PADS> {
+    /// Create a master transmit instance of `format` using source clock from `clocks`.
+    ///
+    /// Returns an error if the source clock is not configured, or the sample rate cannot
+    /// be derived from it within [`SAMPLE_RATE_TOLERANCE`] per mille.
+    #[inline]
+    pub fn new(i2s: I2S, pads: PADS, format: Format, clocks: &Clocks) -> Result<Self, Error>
+    where
+        PADS: Pads,
+    {
+        let source = clocks.i2s_clock().ok_or(Error::ClockSource)?;
+        let (low, high, sample_rate) =
+            bclk_divide_for(source, format.sample_rate, format.slot_size().bits() * 2)
+                .ok_or(Error::SampleRate)?;
+        let config = Config(0)
+            .set_frame_size(format.slot_size())
+            .set_data_size(format.data_size)
+            .set_alignment(Alignment::LeftJustified)
+            // Standard I2S format delays data by one bit clock.
+            .enable_offset(0);
+        unsafe {
+            i2s.config.write(config);
+            i2s.bclk_config
+                .write(BclkConfig(0).set_low(low).set_high(high));
+            i2s.fifo_config_0.write(
+                FifoConfig0(0)
+                    .disable_lr_merge()
+                    .disable_lr_exchange()
+                    .clear_transmit_fifo()
+                    .clear_receive_fifo(),
+            );
+        }
+        Ok(Self {
+            i2s,
+            pads,
+            format,
+            sample_rate,
+        })
+    }
+    /// Get actual frame rate after clock division.
+    #[inline]
+    pub fn sample_rate(&self) -> Hertz {
+        self.sample_rate
+    }
+    /// Write left-right frames, blocking until all are queued.
+    ///
+    /// Transmission starts on the first write after creation or [`stop`](Self::stop);
+    /// the queue is filled with whole frames before clocks run, so the first sample goes
+    /// out in the left slot.
+    #[inline]
+    pub fn write<S: Sample>(&mut self, frames: &[[S; 2]]) -> Result<(), Error> {
+        if S::SIZE != self.format.data_size {
+            return Err(Error::SampleSize);
+        }
+        let mut frames = frames.iter();
+        if !self.i2s.config.read().is_transmit_enabled() {
+            // Prime the queue before clocking out the first frame.
+            unsafe {
+                self.i2s
+                    .fifo_config_0
+                    .modify(|config| config.clear_transmit_fifo())
+            };
+            while self.i2s.fifo_config_1.read().transmit_available_words() >= 2 {
+                match frames.next() {
+                    Some(frame) => self.write_frame(frame),
+                    None => break,
+                }
+            }
+            unsafe {
+                self.i2s
+                    .config
+                    .modify(|config| config.enable_master().enable_transmit())
+            };
+        }
+        for frame in frames {
+            while self.i2s.fifo_config_1.read().transmit_available_words() < 2 {
+                core::hint::spin_loop();
+            }
+            self.write_frame(frame);
+        }
+        Ok(())
+    }
+    /// Stop transmission after queued frames are sent.
+    #[inline]
+    pub fn stop(&mut self) {
+        while self.i2s.fifo_config_1.read().transmit_available_words() < TRANSMIT_FIFO_WORDS {
+            core::hint::spin_loop();
+        }
+        unsafe {
+            self.i2s
+                .config
+                .modify(|config| config.disable_transmit().disable_master())
+        };
+    }
+    /// Release the peripheral and return its registers and pads.
+    #[inline]
+    pub fn free(self) -> (I2S, PADS) {
+        unsafe {
+            self.i2s
+                .config
+                .modify(|config| config.disable_transmit().disable_master())
+        };
+        (self.i2s, self.pads)
+    }
+    #[inline]
+    fn write_frame<S: Sample>(&self, [left, right]: &[S; 2]) {
+        unsafe {
+            self.i2s.fifo_write.write(left.to_word());
+            self.i2s.fifo_write.write(right.to_word());
+        }
+    }
+}
+
+/// Transmit queue depth in entries.
+const TRANSMIT_FIFO_WORDS: u8 = 16;
+
+/// DMA transmits interleaved left and right samples, one queue entry each. Buffers
+/// should hold whole frames; transmission restarts at a left slot on every transfer.
+#[cfg(feature = "dma")]
+unsafe impl<I2S: Deref<Target = RegisterBlock>, PADS> crate::dma::TransmitTarget
+    for I2s<I2S, PADS>
+{
+    type Word = u32;
+    const REQUEST: crate::dma::Request =
+        crate::dma::Request::Dma01(crate::dma::Periph4DMA01::I2sTx);
+    #[inline]
+    fn transmit_address(&self) -> u32 {
+        &self.i2s.fifo_write as *const _ as u32
+    }
+    #[inline]
+    fn enable_transmit_dma(&mut self) {
+        unsafe {
+            self.i2s
+                .config
+                .modify(|config| config.disable_transmit().disable_master());
+            self.i2s
+                .fifo_config_0
+                .modify(|config| config.clear_transmit_fifo().enable_dma_transmit());
+            self.i2s
+                .fifo_config_1
+                .modify(|config| config.set_transmit_threshold(TRANSMIT_FIFO_WORDS / 2 - 1));
+            // Let DMA prime the queue before clocks run.
+            while self.i2s.fifo_config_1.read().transmit_available_words() > TRANSMIT_FIFO_WORDS / 2
+            {
+                core::hint::spin_loop();
+            }
+            self.i2s
+                .config
+                .modify(|config| config.enable_master().enable_transmit());
+        }
+    }
+    #[inline]
+    fn disable_transmit_dma(&mut self) {
+        self.stop();
+        unsafe {
+            self.i2s
+                .fifo_config_0
+                .modify(|config| config.disable_dma_transmit())
+        };
+    }
+}
+
+/// Largest deviation of actual frame rate from requested rate, in per mille.
+pub const SAMPLE_RATE_TOLERANCE: u32 = 10;
+
+/// Bit clock phases and actual frame rate for `rate` with `frame_bits` per frame.
+///
+/// Returns `None` if the bit clock divide factor is out of range, or the actual frame
+/// rate deviates from `rate` by more than [`SAMPLE_RATE_TOLERANCE`] per mille.
+#[inline]
+pub fn bclk_divide_for(source: Hertz, rate: Hertz, frame_bits: u32) -> Option<(u16, u16, Hertz)> {
+    let bclk = rate.0.checked_mul(frame_bits)?;
+    if bclk == 0 {
+        return None;
+    }
+    // Round to the nearest divide factor.
+    let divide = (source.0 + bclk / 2) / bclk;
+    if !(2..=0x2000).contains(&divide) {
+        return None;
+    }
+    let actual = source.0 / divide / frame_bits;
+    if actual.abs_diff(rate.0) as u64 * 1000 > rate.0 as u64 * SAMPLE_RATE_TOLERANCE as u64 {
+        return None;
+    }
+    let low = divide / 2;
+    let high = divide - low;
+    Some(((low - 1) as u16, (high - 1) as u16, Hertz(actual)))
+}
+
+/// Valid I2S pads for master transmit.
+#[diagnostic::on_unimplemented(
+    message = "the I2S peripheral needs bit clock, frame clock and data output pads"
+)]
+pub trait Pads {}
+
+/// Bit clock, frame clock and data output.
+impl<'a, 'b, 'c, const N1: usize, const N2: usize, const N3: usize> Pads
+    for (
+        Alternate<'a, N1, gpio::I2s>,
+        Alternate<'b, N2, gpio::I2s>,
+        Alternate<'c, N3, gpio::I2s>,
+    )
+where
+    Alternate<'a, N1, gpio::I2s>: HasBclkSignal,
+    Alternate<'b, N2, gpio::I2s>: HasFsSignal,
+    Alternate<'c, N3, gpio::I2s>: HasDoSignal,
+{
+}
+
+/// Bit clock, frame clock, data output and data input.
+impl<'a, 'b, 'c, 'd, const N1: usize, const N2: usize, const N3: usize, const N4: usize> Pads
+    for (
+        Alternate<'a, N1, gpio::I2s>,
+        Alternate<'b, N2, gpio::I2s>,
+        Alternate<'c, N3, gpio::I2s>,
+        Alternate<'d, N4, gpio::I2s>,
+    )
+where
+    Alternate<'a, N1, gpio::I2s>: HasBclkSignal,
+    Alternate<'b, N2, gpio::I2s>: HasFsSignal,
+    Alternate<'c, N3, gpio::I2s>: HasDoSignal,
+    Alternate<'d, N4, gpio::I2s>: HasDiSignal,
+{
+}
+
+/// Check if target gpio `Pin` is internally connected to I2S bit clock signal.
+pub trait HasBclkSignal {}
+
+/// Check if target gpio `Pin` is internally connected to I2S frame clock signal.
+pub trait HasFsSignal {}
+
+/// Check if target gpio `Pin` is internally connected to I2S data input signal.
+pub trait HasDiSignal {}
+
+/// Check if target gpio `Pin` is internally connected to I2S data output signal.
+pub trait HasDoSignal {}
+
+macro_rules! i2s_signals {
+    ($signal: ident: $($pad: expr),+) => {
+        $(
+            impl<'a> $signal for Alternate<'a, $pad, gpio::I2s> {}
+        )+
+    };
+}
+
+i2s_signals!(HasBclkSignal: 0, 4, 8, 12, 16, 20, 24, 28, 32, 36, 40, 44);
+i2s_signals!(HasFsSignal: 1, 5, 9, 13, 17, 21, 25, 29, 33, 37, 41, 45);
+i2s_signals!(HasDiSignal: 2, 6, 10, 14, 18, 22, 26, 30, 34, 38, 42);
+i2s_signals!(HasDoSignal: 3, 7, 11, 15, 19, 23, 27, 31, 35, 39, 43);
+
 #[cfg(test)]
 mod tests {
-    use super::RegisterBlock;
+    use super::{
+        Alignment, BclkConfig, Config, DataSize, FifoConfig0, FifoConfig1, Format, RegisterBlock,
+        bclk_divide_for,
+    };
+    use embedded_time::rate::Hertz;
     use memoffset::offset_of;
 
     #[test]
@@ -71,4 +780,65 @@ mod tests {
         assert_eq!(offset_of!(RegisterBlock, fifo_read), 0x8c);
         assert_eq!(offset_of!(RegisterBlock, io_config), 0xfc);
     }
+
+    #[test]
+    fn struct_config_functions() {
+        let config = Config(0)
+            .enable_master()
+            .enable_transmit()
+            .set_frame_size(DataSize::Bits32)
+            .set_data_size(DataSize::Bits24)
+            .set_alignment(Alignment::LeftJustified)
+            .enable_offset(0);
+        assert_eq!(config.0, 0x0200_b005);
+        assert!(config.is_master_enabled() && config.is_transmit_enabled());
+        assert!(!config.is_slave_enabled() && !config.is_receive_enabled());
+        assert_eq!(config.frame_size(), DataSize::Bits32);
+        assert_eq!(config.data_size(), DataSize::Bits24);
+        assert_eq!(config.alignment(), Alignment::LeftJustified);
+        assert_eq!(config.offset(), Some(1));
+        let config = config.disable_offset().set_alignment(Alignment::Dsp);
+        assert_eq!(config.offset(), None);
+        assert_eq!(config.alignment(), Alignment::Dsp);
+
+        let bclk = BclkConfig(0).set_low(7).set_high(8);
+        assert_eq!(bclk.0, 0x0008_0007);
+        assert_eq!((bclk.low(), bclk.high()), (7, 8));
+
+        let fifo = FifoConfig0(0).enable_dma_transmit().enable_lr_merge();
+        assert_eq!(fifo.0, 0x101);
+        assert!(fifo.is_dma_transmit_enabled() && !fifo.is_lr_exchange_enabled());
+        let fifo = FifoConfig1(0x0003_0510).set_transmit_threshold(7);
+        assert_eq!(fifo.transmit_available_words(), 16);
+        assert_eq!(fifo.receive_available_words(), 5);
+        assert_eq!(fifo.transmit_threshold(), 7);
+    }
+
+    #[test]
+    fn function_bclk_divide_for() {
+        let source = Hertz(24_576_000);
+        // 48 kHz 16-bit stereo: 1.536 MHz bit clock from 16 source cycles.
+        let format = Format::stereo_16bit(48_000);
+        let frame_bits = format.slot_size().bits() * 2;
+        assert_eq!(frame_bits, 32);
+        assert_eq!(
+            bclk_divide_for(source, format.sample_rate, frame_bits),
+            Some((7, 7, Hertz(48_000)))
+        );
+        // 24-bit samples are sent in 32-bit slots.
+        let format = Format::stereo_24bit(48_000);
+        assert_eq!(
+            bclk_divide_for(source, format.sample_rate, format.slot_size().bits() * 2),
+            Some((3, 3, Hertz(48_000)))
+        );
+        // Rates close to a divide factor are approximated.
+        assert_eq!(
+            bclk_divide_for(source, Hertz(48_200), 32),
+            Some((7, 7, Hertz(48_000)))
+        );
+        // 44.1 kHz would come out as 45,176 Hz, too far off from this source clock.
+        assert_eq!(bclk_divide_for(source, Hertz(44_100), 32), None);
+        assert_eq!(bclk_divide_for(source, Hertz(0), 32), None);
+        assert_eq!(bclk_divide_for(source, Hertz(1_000_000), 32), None);
+    }
 }
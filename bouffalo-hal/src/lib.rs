@@ -10,10 +10,15 @@
 
 pub mod clocks;
 
+pub mod adc;
 pub mod audio;
+pub mod cam;
 pub mod dbi;
+pub mod display;
 pub mod dma;
+pub mod dpi;
 pub mod emac;
+pub mod flash;
 pub mod glb;
 pub mod gpio;
 pub mod gpip;
@@ -22,24 +27,38 @@ pub mod i2c;
 pub mod i2s;
 pub mod ir;
 pub mod lz4d;
+#[cfg(test)]
+mod mock;
+pub mod onewire;
+pub mod pdm;
+pub mod profiling;
 pub mod psram;
 pub mod pwm;
+#[cfg(feature = "rtt")]
+pub mod rtt;
 pub mod sdio;
 pub mod sec;
+pub mod shared;
+pub mod shared_bus;
 pub mod spi;
 pub mod timer;
 pub mod uart;
 pub mod usb;
+#[cfg(feature = "vectored")]
+pub mod vectored;
 
 #[doc(hidden)]
 pub mod prelude {
     pub use crate::gpio::{IntoPad as _, IntoPadv2 as _};
     pub use crate::lz4d::Lz4dExt as _;
     pub use crate::uart::UartExt as _;
-    pub use embedded_hal::digital::{InputPin as _, OutputPin as _, PinState};
+    pub use embedded_hal::digital::{
+        InputPin as _, OutputPin as _, PinState, StatefulOutputPin as _,
+    };
     pub use embedded_hal::i2c::I2c as _;
     pub use embedded_hal::pwm::SetDutyCycle as _;
     pub use embedded_io::{Read as _, Write as _};
+    #[cfg(feature = "async")]
     pub use embedded_io_async::{Read as _, Write as _};
 }
 
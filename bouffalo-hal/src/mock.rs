@@ -0,0 +1,48 @@
+//! Register blocks backed by ordinary memory, for driver unit tests.
+use core::{cell::UnsafeCell, mem::MaybeUninit, ops::Deref};
+
+/// Register block `T` backed by ordinary memory.
+///
+/// Registers keep the last value written, regardless of hardware side effects; tests
+/// set status bits with [`set_raw`](Self::set_raw) to model the peripheral.
+pub(crate) struct MockRegisters<T>(UnsafeCell<MaybeUninit<T>>);
+
+impl<T> MockRegisters<T> {
+    /// Create a register block with all registers zeroed.
+    pub(crate) fn new() -> Self {
+        Self(UnsafeCell::new(MaybeUninit::zeroed()))
+    }
+    /// Get the register block for use by drivers.
+    pub(crate) fn registers(&self) -> &T {
+        // SAFETY: register blocks consist of integer registers, valid when zeroed.
+        unsafe { &*(self.0.get() as *const T) }
+    }
+    /// Read raw register value at offset, including write-only registers.
+    pub(crate) fn raw(&self, offset: usize) -> u32 {
+        assert!(offset + 4 <= size_of::<T>(), "offset out of register block");
+        unsafe {
+            (self.0.get() as *const u8)
+                .add(offset)
+                .cast::<u32>()
+                .read_volatile()
+        }
+    }
+    /// Write raw register value at offset, including read-only registers.
+    pub(crate) fn set_raw(&self, offset: usize, val: u32) {
+        assert!(offset + 4 <= size_of::<T>(), "offset out of register block");
+        unsafe {
+            (self.0.get() as *mut u8)
+                .add(offset)
+                .cast::<u32>()
+                .write_volatile(val)
+        }
+    }
+}
+
+impl<T> Deref for MockRegisters<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.registers()
+    }
+}
@@ -0,0 +1,429 @@
+//! Dallas 1-Wire bus master on an open-drain pin.
+//!
+//! The bus is driven by one GPIO pad in open-drain output mode with a pull-up, e.g. by
+//! [`into_pull_up_open_drain_output`](crate::gpio::IntoPadv2::into_pull_up_open_drain_output);
+//! long buses need an external pull-up of about 4.7 kΩ. Releasing the pad lets the bus
+//! float high, thus devices and the master can both pull it low.
+//!
+//! Slot timings follow the standard speed of the 1-Wire protocol. They are kept by a
+//! busy-wait delay function counting processor core cycles, e.g. `riscv::asm::delay`,
+//! and converted from microseconds to cycles using the processor clock, so they stay
+//! correct under any clock configuration. Interrupts during a slot stretch it and may
+//! corrupt the transfer; run time critical transfers in a critical section.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use bouffalo_hal::{clocks::Clocks, gpio::{OpenDrain, Output, PullUp}, onewire::OneWire};
+//! # fn example(io0: Output<'static, 0, OpenDrain<PullUp>>, clocks: &Clocks, delay: fn(u32)) {
+//! let mut bus = OneWire::new(io0, delay, clocks);
+//! for rom in bus.search() {
+//!     let rom = rom.unwrap();
+//!     // Address device `rom`.
+//! }
+//! # }
+//! ```
+use crate::clocks::Clocks;
+use embedded_hal::digital::{InputPin, OutputPin};
+
+/// Match ROM command, followed by 64-bit ROM code.
+pub const MATCH_ROM: u8 = 0x55;
+/// Skip ROM command, addresses all devices on the bus.
+pub const SKIP_ROM: u8 = 0xCC;
+/// Search ROM command.
+pub const SEARCH_ROM: u8 = 0xF0;
+
+/// 1-Wire bus error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error<E> {
+    /// Pin operation failed.
+    Pin(E),
+    /// No device answered reset pulse or search slot.
+    NoPresence,
+    /// Cyclic redundancy check of received data failed.
+    Crc,
+}
+
+impl<E> From<E> for Error<E> {
+    #[inline]
+    fn from(value: E) -> Self {
+        Error::Pin(value)
+    }
+}
+
+/// Slot timings in processor core cycles.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Timing {
+    /// Low time of reset pulse.
+    pub reset_low: u32,
+    /// Delay from reset release to presence sample.
+    pub presence_sample: u32,
+    /// Remaining reset time slot after presence sample.
+    pub reset_recovery: u32,
+    /// Low time of write one slot, and initial low time of read slot.
+    pub write_one_low: u32,
+    /// Remaining time slot after writing one.
+    pub write_one_recovery: u32,
+    /// Low time of write zero slot.
+    pub write_zero_low: u32,
+    /// Remaining time slot after writing zero.
+    pub write_zero_recovery: u32,
+    /// Delay from read slot release to sample.
+    pub read_sample: u32,
+    /// Remaining time slot after read sample.
+    pub read_recovery: u32,
+}
+
+impl Timing {
+    /// Standard speed timings under processor clock frequency of `clocks`.
+    #[inline]
+    pub fn new(clocks: &Clocks) -> Self {
+        Self::from_cpu_hz(clocks.cpu_clock().0)
+    }
+    /// Standard speed timings under processor clock frequency of `hz`.
+    #[inline]
+    pub const fn from_cpu_hz(hz: u32) -> Self {
+        Self {
+            reset_low: us(480, hz),
+            presence_sample: us(70, hz),
+            reset_recovery: us(410, hz),
+            write_one_low: us(6, hz),
+            write_one_recovery: us(64, hz),
+            write_zero_low: us(60, hz),
+            write_zero_recovery: us(10, hz),
+            read_sample: us(9, hz),
+            read_recovery: us(55, hz),
+        }
+    }
+}
+
+/// Convert `n` microseconds to cycles under `hz`, rounding up.
+#[inline]
+const fn us(n: u64, hz: u32) -> u32 {
+    (n * hz as u64).div_ceil(1_000_000) as u32
+}
+
+/// 1-Wire bus master.
+pub struct OneWire<P, D> {
+    pin: P,
+    delay: D,
+    timing: Timing,
+}
+
+impl<P: InputPin + OutputPin, D: FnMut(u32)> OneWire<P, D> {
+    /// Create a bus master on open-drain `pin`, waiting with cycle `delay` function.
+    #[inline]
+    pub fn new(pin: P, delay: D, clocks: &Clocks) -> Self {
+        Self::with_timing(pin, delay, Timing::new(clocks))
+    }
+    /// Create a bus master with custom slot timings.
+    #[inline]
+    pub fn with_timing(pin: P, delay: D, timing: Timing) -> Self {
+        Self { pin, delay, timing }
+    }
+    /// Send reset pulse, returning whether any device answered with presence pulse.
+    #[inline]
+    pub fn reset(&mut self) -> Result<bool, P::Error> {
+        self.pin.set_low()?;
+        (self.delay)(self.timing.reset_low);
+        self.pin.set_high()?;
+        (self.delay)(self.timing.presence_sample);
+        let presence = self.pin.is_low()?;
+        (self.delay)(self.timing.reset_recovery);
+        Ok(presence)
+    }
+    /// Write one bit in a time slot.
+    #[inline]
+    pub fn write_bit(&mut self, bit: bool) -> Result<(), P::Error> {
+        let (low, recovery) = match bit {
+            true => (self.timing.write_one_low, self.timing.write_one_recovery),
+            false => (self.timing.write_zero_low, self.timing.write_zero_recovery),
+        };
+        self.pin.set_low()?;
+        (self.delay)(low);
+        self.pin.set_high()?;
+        (self.delay)(recovery);
+        Ok(())
+    }
+    /// Read one bit in a time slot.
+    #[inline]
+    pub fn read_bit(&mut self) -> Result<bool, P::Error> {
+        self.pin.set_low()?;
+        (self.delay)(self.timing.write_one_low);
+        self.pin.set_high()?;
+        (self.delay)(self.timing.read_sample);
+        let bit = self.pin.is_high()?;
+        (self.delay)(self.timing.read_recovery);
+        Ok(bit)
+    }
+    /// Write one byte, least significant bit first.
+    #[inline]
+    pub fn write_byte(&mut self, byte: u8) -> Result<(), P::Error> {
+        for i in 0..8 {
+            self.write_bit(byte & (1 << i) != 0)?;
+        }
+        Ok(())
+    }
+    /// Read one byte, least significant bit first.
+    #[inline]
+    pub fn read_byte(&mut self) -> Result<u8, P::Error> {
+        let mut byte = 0;
+        for i in 0..8 {
+            byte |= (self.read_bit()? as u8) << i;
+        }
+        Ok(byte)
+    }
+    /// Reset the bus and address the device with `rom` code.
+    #[inline]
+    pub fn select(&mut self, rom: u64) -> Result<(), Error<P::Error>> {
+        if !self.reset()? {
+            return Err(Error::NoPresence);
+        }
+        self.write_byte(MATCH_ROM)?;
+        for byte in rom.to_le_bytes() {
+            self.write_byte(byte)?;
+        }
+        Ok(())
+    }
+    /// Reset the bus and address all devices at once.
+    #[inline]
+    pub fn skip(&mut self) -> Result<(), Error<P::Error>> {
+        if !self.reset()? {
+            return Err(Error::NoPresence);
+        }
+        self.write_byte(SKIP_ROM)?;
+        Ok(())
+    }
+    /// Enumerate ROM codes of all devices on the bus.
+    #[inline]
+    pub fn search(&mut self) -> Search<'_, P, D> {
+        Search {
+            bus: self,
+            rom: 0,
+            last_discrepancy: 0,
+            done: false,
+        }
+    }
+    /// Release the bus pin and delay function.
+    #[inline]
+    pub fn free(self) -> (P, D) {
+        (self.pin, self.delay)
+    }
+}
+
+/// Iterator over ROM codes of devices on a 1-Wire bus.
+///
+/// ROM codes are returned as little-endian integers; low byte is the family code, e.g.
+/// `0x28` for DS18B20 temperature sensors. Iteration stops after the first error.
+pub struct Search<'a, P, D> {
+    bus: &'a mut OneWire<P, D>,
+    rom: u64,
+    last_discrepancy: u32,
+    done: bool,
+}
+
+impl<P: InputPin + OutputPin, D: FnMut(u32)> Search<'_, P, D> {
+    #[inline]
+    fn step(&mut self) -> Result<u64, Error<P::Error>> {
+        if !self.bus.reset()? {
+            return Err(Error::NoPresence);
+        }
+        self.bus.write_byte(SEARCH_ROM)?;
+        let mut last_zero = 0;
+        for n in 1..=64 {
+            let bit = self.bus.read_bit()?;
+            let complement = self.bus.read_bit()?;
+            let direction = match (bit, complement) {
+                (true, true) => return Err(Error::NoPresence),
+                (bit, complement) if bit != complement => bit,
+                // Devices differ at this bit; take the zero branch first.
+                _ => match n.cmp(&self.last_discrepancy) {
+                    core::cmp::Ordering::Less => self.rom & (1 << (n - 1)) != 0,
+                    core::cmp::Ordering::Equal => true,
+                    core::cmp::Ordering::Greater => false,
+                },
+            };
+            if bit == complement && !direction {
+                last_zero = n;
+            }
+            self.rom = (self.rom & !(1 << (n - 1))) | ((direction as u64) << (n - 1));
+            self.bus.write_bit(direction)?;
+        }
+        self.last_discrepancy = last_zero;
+        if crc8(&self.rom.to_le_bytes()) != 0 {
+            return Err(Error::Crc);
+        }
+        Ok(self.rom)
+    }
+}
+
+impl<P: InputPin + OutputPin, D: FnMut(u32)> Iterator for Search<'_, P, D> {
+    type Item = Result<u64, Error<P::Error>>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let ans = self.step();
+        self.done = ans.is_err() || self.last_discrepancy == 0;
+        Some(ans)
+    }
+}
+
+/// Dallas/Maxim 8-bit cyclic redundancy check used by 1-Wire ROM codes and data.
+///
+/// Data followed by its own check value results in zero.
+#[inline]
+pub const fn crc8(data: &[u8]) -> u8 {
+    let mut crc = 0u8;
+    let mut i = 0;
+    while i < data.len() {
+        crc ^= data[i];
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0x8c
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        i += 1;
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, OneWire, Timing, crc8};
+    use core::convert::Infallible;
+    use embedded_hal::digital::{ErrorType, InputPin, OutputPin};
+
+    /// Pin sampling scripted bus levels, recording levels driven by the master.
+    struct MockPin<'a> {
+        samples: core::slice::Iter<'a, bool>,
+        driven: &'a mut [bool],
+        count: usize,
+    }
+
+    impl ErrorType for MockPin<'_> {
+        type Error = Infallible;
+    }
+
+    impl InputPin for MockPin<'_> {
+        fn is_high(&mut self) -> Result<bool, Infallible> {
+            Ok(*self.samples.next().unwrap())
+        }
+        fn is_low(&mut self) -> Result<bool, Infallible> {
+            self.is_high().map(|high| !high)
+        }
+    }
+
+    impl OutputPin for MockPin<'_> {
+        fn set_low(&mut self) -> Result<(), Infallible> {
+            self.driven[self.count] = false;
+            self.count += 1;
+            Ok(())
+        }
+        fn set_high(&mut self) -> Result<(), Infallible> {
+            self.driven[self.count] = true;
+            self.count += 1;
+            Ok(())
+        }
+    }
+
+    const TIMING: Timing = Timing::from_cpu_hz(1_000_000);
+
+    #[test]
+    fn struct_timing() {
+        assert_eq!(TIMING.reset_low, 480);
+        let timing = Timing::from_cpu_hz(320_000_000);
+        assert_eq!(timing.reset_low, 153_600);
+        assert_eq!(timing.write_one_low, 1_920);
+        assert_eq!(Timing::from_cpu_hz(1_500_000).read_sample, 14);
+    }
+
+    #[test]
+    fn function_crc8() {
+        // Example ROM code of Maxim application note 27, check value in the last byte.
+        let rom = [0x02, 0x1c, 0xb8, 0x01, 0x00, 0x00, 0x00, 0xa2];
+        assert_eq!(crc8(&rom[..7]), 0xa2);
+        assert_eq!(crc8(&rom), 0);
+        assert_eq!(crc8(&[]), 0);
+    }
+
+    #[test]
+    fn struct_one_wire_transfer() {
+        let samples = [false, true, false, true, true, false, false, true, false];
+        let mut driven = [true; 64];
+        let mut waited = 0;
+        let pin = MockPin {
+            samples: samples.iter(),
+            driven: &mut driven,
+            count: 0,
+        };
+        let mut bus = OneWire::with_timing(pin, |cycles| waited += cycles, TIMING);
+        assert!(bus.reset().unwrap());
+        assert_eq!(bus.read_byte().unwrap(), 0b0100_1101);
+        bus.write_byte(0xa5).unwrap();
+        let (pin, _) = bus.free();
+        // Every slot pulls the bus low, then releases it.
+        assert_eq!(pin.count, 2 + 16 + 16);
+        assert!(pin.driven[..34].chunks(2).all(|s| s == [false, true]));
+        assert_eq!(waited, 960 + 8 * 70 + 4 * 70 + 4 * 70);
+    }
+
+    /// Bus levels of one search pass following `rom`, devices differing at `conflicts`.
+    fn search_samples(rom: u64, conflicts: u64) -> impl Iterator<Item = bool> {
+        core::iter::once(false).chain((0..64).flat_map(move |i| {
+            let bit = rom & (1 << i) != 0;
+            match conflicts & (1 << i) != 0 {
+                true => [false, false],
+                false => [bit, !bit],
+            }
+        }))
+    }
+
+    #[test]
+    fn struct_search() {
+        let first = u64::from_le_bytes([0x02, 0x1c, 0xb8, 0x01, 0x00, 0x00, 0x00, 0xa2]);
+        let mut second = first ^ (1 << 8);
+        let crc = crc8(&second.to_le_bytes()[..7]);
+        second = (second & !(0xff << 56)) | ((crc as u64) << 56);
+        // Devices share bits below 8, and only the selected device answers above.
+        let mask = (1 << 8) - 1;
+        let mut samples = [false; 2 * 129];
+        for (slot, level) in samples.iter_mut().zip(
+            search_samples(first, 1 << 8)
+                .take(129)
+                .chain(search_samples(second, 1 << 8)),
+        ) {
+            *slot = level;
+        }
+        assert!(first & mask == second & mask);
+        let mut driven = [true; 2 * (2 + 16 + 64 * 6)];
+        let pin = MockPin {
+            samples: samples.iter(),
+            driven: &mut driven,
+            count: 0,
+        };
+        let mut bus = OneWire::with_timing(pin, |_| {}, TIMING);
+        let mut search = bus.search();
+        assert_eq!(search.next(), Some(Ok(first)));
+        assert_eq!(search.next(), Some(Ok(second)));
+        assert_eq!(search.next(), None);
+
+        let samples = [true];
+        let mut driven = [true; 2];
+        let pin = MockPin {
+            samples: samples.iter(),
+            driven: &mut driven,
+            count: 0,
+        };
+        let mut bus = OneWire::with_timing(pin, |_| {}, TIMING);
+        let mut search = bus.search();
+        assert_eq!(search.next(), Some(Err(Error::NoPresence)));
+        assert_eq!(search.next(), None);
+    }
+}
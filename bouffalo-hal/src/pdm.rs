@@ -0,0 +1,552 @@
+//! Pulse Density Modulation microphone interface.
+//!
+//! The interface clocks a digital microphone and collects its one-bit data stream into
+//! 32-bit words, most significant bit first. Users may take these words as the raw
+//! bitstream, or decimate them into PCM samples with the [`Cic`] filter in this module.
+//!
+//! # Decimation filter
+//!
+//! [`Cic`] is a cascaded integrator-comb filter of `ORDER` stages with differential
+//! delay of one. Its magnitude response at frequency `f` of output rate `fs` is
+//! `|sin(pi f) / (R sin(pi f / R))|^ORDER` for decimation ratio `R`, with nulls on
+//! every multiple of `fs`. For 4 stages and ratio 64 the response droops by 0.6 dB at
+//! `0.1 fs` and 2.3 dB at `0.2 fs`, falling to -3 dB at about `0.23 fs`. Choose an
+//! output rate of at least four times the highest frequency of interest, or apply a
+//! compensation filter afterwards.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use bouffalo_hal::{clocks::Clocks, gpio::{Alternate, Pdm as PdmPin}, pdm::{Format, Pdm}};
+//! # fn example(
+//! #     pdm: &'static bouffalo_hal::pdm::RegisterBlock,
+//! #     pads: (Alternate<'static, 6, PdmPin>, Alternate<'static, 7, PdmPin>),
+//! #     clocks: &Clocks,
+//! # ) {
+//! let mut pdm = Pdm::new(pdm, pads, Format::new(16_000, 64), clocks);
+//! let mut samples = [0i16; 160];
+//! pdm.read(&mut samples);
+//! # }
+//! ```
+use crate::clocks::Clocks;
+use crate::gpio::{self, Alternate};
+use core::convert::Infallible;
+use core::ops::Deref;
+use embedded_time::rate::Hertz;
+use volatile_register::{RO, RW};
+
+/// Pulse Density Modulation interface registers.
+#[repr(C)]
+pub struct RegisterBlock {
+    /// Peripheral configuration register.
+    pub config: RW<Config>,
+    _reserved0: [u8; 0x7c],
+    /// First-in first-out queue configuration register 0.
+    pub fifo_config_0: RW<FifoConfig0>,
+    /// First-in first-out queue configuration register 1.
+    pub fifo_config_1: RW<FifoConfig1>,
+    _reserved1: [u8; 0x4],
+    /// First-in first-out queue read data register.
+    pub fifo_read: RO<u32>,
+}
+
+/// Peripheral configuration register.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Config(u32);
+
+/// Clock edge on which microphone data is sampled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Edge {
+    /// Sample on rising edge, for microphones selected as left channel.
+    Rising,
+    /// Sample on falling edge, for microphones selected as right channel.
+    Falling,
+}
+
+impl Config {
+    const ENABLE: u32 = 1 << 0;
+    const FALLING_EDGE: u32 = 1 << 4;
+    const CLOCK_DIVIDE: u32 = 0xfff << 8;
+
+    /// Enable the interface.
+    #[inline]
+    pub const fn enable(self) -> Self {
+        Self(self.0 | Self::ENABLE)
+    }
+    /// Disable the interface.
+    #[inline]
+    pub const fn disable(self) -> Self {
+        Self(self.0 & !Self::ENABLE)
+    }
+    /// Check if the interface is enabled.
+    #[inline]
+    pub const fn is_enabled(self) -> bool {
+        self.0 & Self::ENABLE != 0
+    }
+    /// Set sampling clock edge.
+    #[inline]
+    pub const fn set_edge(self, edge: Edge) -> Self {
+        match edge {
+            Edge::Rising => Self(self.0 & !Self::FALLING_EDGE),
+            Edge::Falling => Self(self.0 | Self::FALLING_EDGE),
+        }
+    }
+    /// Get sampling clock edge.
+    #[inline]
+    pub const fn edge(self) -> Edge {
+        if self.0 & Self::FALLING_EDGE != 0 {
+            Edge::Falling
+        } else {
+            Edge::Rising
+        }
+    }
+    /// Set microphone clock divide factor minus one.
+    #[inline]
+    pub const fn set_clock_divide(self, val: u16) -> Self {
+        Self((self.0 & !Self::CLOCK_DIVIDE) | (((val as u32) << 8) & Self::CLOCK_DIVIDE))
+    }
+    /// Get microphone clock divide factor minus one.
+    #[inline]
+    pub const fn clock_divide(self) -> u16 {
+        ((self.0 & Self::CLOCK_DIVIDE) >> 8) as u16
+    }
+}
+
+/// First-in first-out queue configuration register 0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct FifoConfig0(u32);
+
+impl FifoConfig0 {
+    const DMA_RECEIVE_ENABLE: u32 = 1 << 1;
+    const RECEIVE_FIFO_CLEAR: u32 = 1 << 3;
+    const RECEIVE_FIFO_OVERFLOW: u32 = 1 << 6;
+
+    /// Enable DMA receive feature.
+    #[inline]
+    pub const fn enable_dma_receive(self) -> Self {
+        Self(self.0 | Self::DMA_RECEIVE_ENABLE)
+    }
+    /// Disable DMA receive feature.
+    #[inline]
+    pub const fn disable_dma_receive(self) -> Self {
+        Self(self.0 & !Self::DMA_RECEIVE_ENABLE)
+    }
+    /// Check if DMA receive feature is enabled.
+    #[inline]
+    pub const fn is_dma_receive_enabled(self) -> bool {
+        self.0 & Self::DMA_RECEIVE_ENABLE != 0
+    }
+    /// Clear receive first-in first-out queue.
+    #[inline]
+    pub const fn clear_receive_fifo(self) -> Self {
+        Self(self.0 | Self::RECEIVE_FIFO_CLEAR)
+    }
+    /// Check if receive first-in first-out queue has overflowed.
+    #[inline]
+    pub const fn receive_overflow(self) -> bool {
+        self.0 & Self::RECEIVE_FIFO_OVERFLOW != 0
+    }
+}
+
+/// First-in first-out queue configuration register 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct FifoConfig1(u32);
+
+impl FifoConfig1 {
+    const RECEIVE_COUNT: u32 = 0x3f << 8;
+
+    /// Get number of words received in receive FIFO queue.
+    #[inline]
+    pub const fn receive_available_words(self) -> u8 {
+        ((self.0 & Self::RECEIVE_COUNT) >> 8) as u8
+    }
+}
+
+/// Cascaded integrator-comb decimation filter of `ORDER` stages.
+///
+/// Input bits are taken as `+1` for one and `-1` for zero. Output samples lie within
+/// `±ratio^ORDER`; [`gain`](Self::gain) gives this full scale value.
+#[derive(Clone, Debug)]
+pub struct Cic<const ORDER: usize> {
+    ratio: u32,
+    phase: u32,
+    integrators: [i32; ORDER],
+    combs: [i32; ORDER],
+    pending: Option<(u32, u8)>,
+}
+
+impl<const ORDER: usize> Cic<ORDER> {
+    /// Create a filter decimating by `ratio`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ratio` is zero, or full scale `ratio^ORDER` does not fit into `i32`.
+    #[inline]
+    pub const fn new(ratio: u32) -> Self {
+        if ratio == 0 {
+            panic!("decimation ratio must not be zero");
+        }
+        match (ratio as i64).checked_pow(ORDER as u32) {
+            Some(gain) if gain <= i32::MAX as i64 => {}
+            _ => panic!("decimation ratio too large for filter order"),
+        }
+        Self {
+            ratio,
+            phase: 0,
+            integrators: [0; ORDER],
+            combs: [0; ORDER],
+            pending: None,
+        }
+    }
+    /// Decimation ratio of this filter.
+    #[inline]
+    pub const fn ratio(&self) -> u32 {
+        self.ratio
+    }
+    /// Full scale output value.
+    #[inline]
+    pub const fn gain(&self) -> i32 {
+        self.ratio.pow(ORDER as u32) as i32
+    }
+    /// Feed one input bit, returning a sample on every `ratio`-th bit.
+    #[inline]
+    pub fn push(&mut self, bit: bool) -> Option<i32> {
+        // Integrators wrap around; combs undo the wrapping as long as the output
+        // stays within range of `i32`.
+        let mut acc = if bit { 1 } else { -1 };
+        for integrator in self.integrators.iter_mut() {
+            *integrator = integrator.wrapping_add(acc);
+            acc = *integrator;
+        }
+        self.phase += 1;
+        if self.phase < self.ratio {
+            return None;
+        }
+        self.phase = 0;
+        for comb in self.combs.iter_mut() {
+            let delayed = *comb;
+            *comb = acc;
+            acc = acc.wrapping_sub(delayed);
+        }
+        Some(acc)
+    }
+    /// Decimate raw bitstream `words` into 16-bit samples in `out`.
+    ///
+    /// Words are consumed most significant bit first. Returns numbers of words consumed
+    /// and samples written. If `out` fills in the middle of a word, that word counts as
+    /// consumed and its remaining bits are kept in the filter, to be decimated first on
+    /// the next call.
+    #[inline]
+    pub fn process(&mut self, words: &[u32], out: &mut [i16]) -> (usize, usize) {
+        let mut written = match self.pending.take() {
+            Some((word, bits)) => self.decimate(word, bits, out),
+            None => 0,
+        };
+        let mut consumed = 0;
+        while written < out.len() && consumed < words.len() {
+            written += self.decimate(words[consumed], 32, &mut out[written..]);
+            consumed += 1;
+        }
+        (consumed, written)
+    }
+    /// Decimate lowest `bits` bits of `word`, keeping bits left over once `out` is full.
+    #[inline]
+    fn decimate(&mut self, word: u32, bits: u8, out: &mut [i16]) -> usize {
+        let gain = self.gain() as i64;
+        let mut written = 0;
+        for bit in (0..bits).rev() {
+            if written == out.len() {
+                self.pending = Some((word, bit + 1));
+                break;
+            }
+            if let Some(sample) = self.push(word & (1 << bit) != 0) {
+                out[written] = scale(sample, gain);
+                written += 1;
+            }
+        }
+        written
+    }
+}
+
+/// Scale a filter output of full scale `gain` to 16 bits.
+#[inline]
+fn scale(sample: i32, gain: i64) -> i16 {
+    (sample as i64 * i16::MAX as i64 / gain) as i16
+}
+
+/// Order of decimation filter used by [`Pdm::read`].
+pub const DEFAULT_ORDER: usize = 4;
+
+/// Microphone capture format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Format {
+    /// Output PCM sample rate.
+    pub sample_rate: Hertz,
+    /// Microphone clock cycles per output sample.
+    pub decimation: u32,
+    /// Sampling clock edge.
+    pub edge: Edge,
+}
+
+impl Format {
+    /// Capture at `rate` samples per second, decimating by `decimation`.
+    ///
+    /// Microphone clock runs at `rate * decimation`, e.g. 1.024 MHz for 16 kHz output
+    /// decimated by 64.
+    #[inline]
+    pub const fn new(rate: u32, decimation: u32) -> Self {
+        Self {
+            sample_rate: Hertz(rate),
+            decimation,
+            edge: Edge::Rising,
+        }
+    }
+    /// Set sampling clock edge.
+    #[inline]
+    pub const fn set_edge(self, edge: Edge) -> Self {
+        Self { edge, ..self }
+    }
+}
+
+/// Managed Pulse Density Modulation microphone interface.
+pub struct Pdm<PDM, PADS> {
+    pdm: PDM,
+    pads: PADS,
+    filter: Cic<DEFAULT_ORDER>,
+    sample_rate: Hertz,
+}
+
+impl<PDM: Deref<Target = RegisterBlock>, PADS> Pdm<PDM, PADS> {
+    /// Create and start a microphone interface using source clock from `clocks`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if microphone clock is impossible under current source clock.
+    #[inline]
+    pub fn new(pdm: PDM, pads: PADS, format: Format, clocks: &Clocks) -> Self
+    where
+        PADS: Pads,
+    {
+        let source = clocks
+            .pdm_clock()
+            .expect("PDM source clock is not configured");
+        let (divide, sample_rate) = clock_divide_for(source, format.sample_rate, format.decimation)
+            .expect("impossible microphone clock");
+        unsafe {
+            pdm.config.write(
+                Config(0)
+                    .set_clock_divide(divide)
+                    .set_edge(format.edge)
+                    .disable(),
+            );
+            pdm.fifo_config_0
+                .write(FifoConfig0(0).disable_dma_receive().clear_receive_fifo());
+            pdm.config.modify(|config| config.enable());
+        }
+        Self {
+            pdm,
+            pads,
+            filter: Cic::new(format.decimation),
+            sample_rate,
+        }
+    }
+    /// Get actual output sample rate after clock division.
+    #[inline]
+    pub fn sample_rate(&self) -> Hertz {
+        self.sample_rate
+    }
+    /// Read one word of raw bitstream, most significant bit first.
+    #[inline]
+    pub fn read_raw(&mut self) -> nb::Result<u32, Infallible> {
+        if self.pdm.fifo_config_1.read().receive_available_words() == 0 {
+            return Err(nb::Error::WouldBlock);
+        }
+        Ok(self.pdm.fifo_read.read())
+    }
+    /// Fill `out` with decimated samples, blocking until all are captured.
+    #[inline]
+    pub fn read(&mut self, out: &mut [i16]) {
+        // Bits left over from the previous read are decimated first.
+        let mut written = self.filter.process(&[], out).1;
+        while written < out.len() {
+            let Ok(word) = nb::block!(self.read_raw());
+            written += self.filter.process(&[word], &mut out[written..]).1;
+        }
+    }
+    /// Stop the interface and return its registers and pads.
+    #[inline]
+    pub fn free(self) -> (PDM, PADS) {
+        unsafe { self.pdm.config.modify(|config| config.disable()) };
+        (self.pdm, self.pads)
+    }
+}
+
+/// DMA receives raw bitstream words; decimate them with [`Cic::process`].
+#[cfg(feature = "dma")]
+unsafe impl<PDM: Deref<Target = RegisterBlock>, PADS> crate::dma::ReceiveTarget for Pdm<PDM, PADS> {
+    type Word = u32;
+    const REQUEST: crate::dma::Request =
+        crate::dma::Request::Dma01(crate::dma::Periph4DMA01::PdmRx);
+    #[inline]
+    fn receive_address(&self) -> u32 {
+        &self.pdm.fifo_read as *const _ as u32
+    }
+    #[inline]
+    fn enable_receive_dma(&mut self) {
+        unsafe {
+            self.pdm
+                .fifo_config_0
+                .modify(|config| config.clear_receive_fifo().enable_dma_receive())
+        };
+    }
+    #[inline]
+    fn disable_receive_dma(&mut self) {
+        unsafe {
+            self.pdm
+                .fifo_config_0
+                .modify(|config| config.disable_dma_receive())
+        };
+    }
+}
+
+/// Microphone clock divide factor minus one and actual output rate.
+///
+/// Returns `None` if the divide factor is out of range.
+#[inline]
+pub fn clock_divide_for(source: Hertz, rate: Hertz, decimation: u32) -> Option<(u16, Hertz)> {
+    let clock = rate.0.checked_mul(decimation)?;
+    if clock == 0 {
+        return None;
+    }
+    // Round to the nearest divide factor.
+    let divide = (source.0 + clock / 2) / clock;
+    if !(1..=0x1000).contains(&divide) {
+        return None;
+    }
+    Some(((divide - 1) as u16, Hertz(source.0 / divide / decimation)))
+}
+
+/// Valid microphone clock and data pads.
+#[diagnostic::on_unimplemented(
+    message = "the PDM interface needs a clock pad and a data pad in PDM mode"
+)]
+pub trait Pads {}
+
+/// Microphone clock and data.
+impl<'a, 'b, const N1: usize, const N2: usize> Pads
+    for (Alternate<'a, N1, gpio::Pdm>, Alternate<'b, N2, gpio::Pdm>)
+where
+    Alternate<'a, N1, gpio::Pdm>: HasClkSignal,
+    Alternate<'b, N2, gpio::Pdm>: HasDataSignal,
+{
+}
+
+/// Check if target gpio `Pin` is internally connected to PDM clock signal.
+pub trait HasClkSignal {}
+
+/// Check if target gpio `Pin` is internally connected to PDM data input signal.
+pub trait HasDataSignal {}
+
+macro_rules! pdm_signals {
+    ($signal: ident: $($pad: expr),+) => {
+        $(
+            impl<'a> $signal for Alternate<'a, $pad, gpio::Pdm> {}
+        )+
+    };
+}
+
+pdm_signals!(HasClkSignal: 0, 2, 4, 6, 8, 10, 12, 14, 16, 18, 20, 22, 24, 26, 28, 30, 32, 34, 36);
+pdm_signals!(HasClkSignal: 38, 40, 42, 44);
+pdm_signals!(HasDataSignal: 1, 3, 5, 7, 9, 11, 13, 15, 17, 19, 21, 23, 25, 27, 29, 31, 33, 35, 37);
+pdm_signals!(HasDataSignal: 39, 41, 43, 45);
+
+#[cfg(test)]
+mod tests {
+    use super::{Cic, Config, Edge, FifoConfig0, RegisterBlock, clock_divide_for};
+    use embedded_time::rate::Hertz;
+    use memoffset::offset_of;
+
+    #[test]
+    fn struct_register_block_offset() {
+        assert_eq!(offset_of!(RegisterBlock, config), 0x0);
+        assert_eq!(offset_of!(RegisterBlock, fifo_config_0), 0x80);
+        assert_eq!(offset_of!(RegisterBlock, fifo_config_1), 0x84);
+        assert_eq!(offset_of!(RegisterBlock, fifo_read), 0x8c);
+    }
+
+    #[test]
+    fn struct_config_functions() {
+        let config = Config(0)
+            .set_clock_divide(23)
+            .set_edge(Edge::Falling)
+            .enable();
+        assert_eq!(config.0, 0x1711);
+        assert_eq!(config.clock_divide(), 23);
+        assert_eq!(config.edge(), Edge::Falling);
+        assert!(config.is_enabled());
+        assert!(!config.disable().is_enabled());
+        let fifo = FifoConfig0(0).enable_dma_receive().clear_receive_fifo();
+        assert_eq!(fifo.0, 0xa);
+        assert!(!fifo.disable_dma_receive().is_dma_receive_enabled());
+    }
+
+    #[test]
+    fn function_cic_decimation() {
+        // All ones and all zeros settle at positive and negative full scale.
+        let mut cic = Cic::<4>::new(8);
+        assert_eq!(cic.gain(), 4096);
+        let mut out = [0i16; 8];
+        assert_eq!(cic.process(&[u32::MAX; 2], &mut out), (2, 8));
+        assert_eq!(out[7], i16::MAX);
+        let mut cic = Cic::<4>::new(8);
+        cic.process(&[0; 2], &mut out);
+        assert_eq!(out[7], -i16::MAX);
+
+        // Alternating bits average to zero after the filter settles.
+        let mut cic = Cic::<3>::new(16);
+        let mut out = [0i16; 16];
+        assert_eq!(cic.process(&[0xaaaa_aaaa; 8], &mut out), (8, 16));
+        assert!(out[4..].iter().all(|&s| s == 0));
+
+        // Density of three ones in four bits decodes to half scale.
+        let mut cic = Cic::<2>::new(32);
+        let mut out = [0i16; 8];
+        cic.process(&[0xeeee_eeee; 8], &mut out);
+        assert_eq!(out[7], i16::MAX / 2);
+
+        // Filter stops once output is full, keeping the rest of the last word.
+        let words = [0x1234_5678, 0x9abc_def0, 0x0f0f_f0f0, 0xcccc_3333];
+        let mut whole = [0i16; 8];
+        assert_eq!(Cic::<4>::new(16).process(&words, &mut whole), (4, 8));
+        let mut cic = Cic::<4>::new(16);
+        let mut out = [0i16; 8];
+        assert_eq!(cic.process(&words, &mut out[..3]), (2, 3));
+        assert_eq!(cic.process(&words[2..], &mut out[3..3]), (0, 0));
+        assert_eq!(cic.process(&words[2..], &mut out[3..]), (2, 5));
+        assert_eq!(out, whole);
+    }
+
+    #[test]
+    #[should_panic(expected = "decimation ratio too large for filter order")]
+    fn function_cic_overflow() {
+        Cic::<5>::new(128);
+    }
+
+    #[test]
+    fn function_clock_divide_for() {
+        let source = Hertz(24_576_000);
+        assert_eq!(
+            clock_divide_for(source, Hertz(16_000), 64),
+            Some((23, Hertz(16_000)))
+        );
+        assert_eq!(
+            clock_divide_for(source, Hertz(48_000), 64),
+            Some((7, Hertz(48_000)))
+        );
+        assert_eq!(clock_divide_for(source, Hertz(0), 64), None);
+        assert_eq!(clock_divide_for(source, Hertz(1_000_000), 64), None);
+    }
+}
@@ -0,0 +1,173 @@
+//! Cycle-counter based profiling.
+//!
+//! [`Stopwatch`] measures code paths in processor core cycles by reading the `mcycle`
+//! machine mode counter, thus it should be used from machine mode. On 32-bit cores the
+//! 64-bit counter is read as two halves without tearing.
+//!
+//! Reading the counter takes a few cycles itself, which are included in every
+//! measurement. Use [`Stopwatch::overhead`] to get this cost on current core and
+//! subtract it when measuring very short code paths.
+//!
+//! The counter is read by RISC-V instructions, thus [`Stopwatch`] is only available
+//! when compiling for RISC-V.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+//! # fn main() {
+//! use bouffalo_hal::profiling::Stopwatch;
+//!
+//! let overhead = Stopwatch::overhead();
+//! let stopwatch = Stopwatch::start();
+//! // ... code to be measured ...
+//! let cycles = stopwatch.elapsed().saturating_sub(overhead);
+//! # }
+//! # #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+//! # fn main() {}
+//! ```
+#[cfg(any(doc, target_arch = "riscv32", target_arch = "riscv64"))]
+use crate::clocks::Clocks;
+#[cfg(any(test, doc, target_arch = "riscv32", target_arch = "riscv64"))]
+use core::time::Duration;
+
+/// Cycle counter stopwatch.
+#[cfg(any(doc, target_arch = "riscv32", target_arch = "riscv64"))]
+#[derive(Clone, Copy, Debug)]
+pub struct Stopwatch {
+    start: u64,
+}
+
+#[cfg(any(doc, target_arch = "riscv32", target_arch = "riscv64"))]
+impl Stopwatch {
+    /// Start a new stopwatch from current cycle count.
+    #[inline]
+    pub fn start() -> Self {
+        Self {
+            start: read_mcycle(),
+        }
+    }
+    /// Restart this stopwatch, returning cycles elapsed before restart.
+    #[inline]
+    pub fn restart(&mut self) -> u64 {
+        let now = read_mcycle();
+        let ans = elapsed_cycles(self.start, now);
+        self.start = now;
+        ans
+    }
+    /// Get cycles elapsed since this stopwatch started.
+    #[inline]
+    pub fn elapsed(&self) -> u64 {
+        elapsed_cycles(self.start, read_mcycle())
+    }
+    /// Get time elapsed since this stopwatch started under given clock settings.
+    #[inline]
+    pub fn elapsed_duration(&self, clocks: &Clocks) -> Duration {
+        cycles_to_duration(self.elapsed(), clocks.cpu_clock().0)
+    }
+    /// Measure cycles of an empty code path, i.e. the cost of the stopwatch itself.
+    ///
+    /// Minimum of several measurements is taken to exclude interrupts and cache misses.
+    #[inline]
+    pub fn overhead() -> u64 {
+        (0..8).map(|_| Self::start().elapsed()).min().unwrap_or(0)
+    }
+}
+
+#[cfg(any(test, doc, target_arch = "riscv32", target_arch = "riscv64"))]
+#[inline]
+const fn elapsed_cycles(start: u64, now: u64) -> u64 {
+    now.wrapping_sub(start)
+}
+
+#[cfg(any(test, doc, target_arch = "riscv32", target_arch = "riscv64"))]
+#[inline]
+fn cycles_to_duration(cycles: u64, hz: u32) -> Duration {
+    let nanos = cycles as u128 * 1_000_000_000 / hz as u128;
+    Duration::from_nanos(nanos as u64)
+}
+
+/// Read a 64-bit counter from its high and low halves without tearing.
+///
+/// If low half overflows between reads, high half changes and the read is retried.
+#[cfg(any(test, target_arch = "riscv32"))]
+#[inline]
+fn read_split(mut high: impl FnMut() -> u32, mut low: impl FnMut() -> u32) -> u64 {
+    loop {
+        let hi = high();
+        let lo = low();
+        if high() == hi {
+            return ((hi as u64) << 32) | lo as u64;
+        }
+    }
+}
+
+#[cfg(any(doc, target_arch = "riscv32", target_arch = "riscv64"))]
+#[inline]
+fn read_mcycle() -> u64 {
+    cfg_if::cfg_if! {
+        if #[cfg(target_arch = "riscv32")] {
+            read_split(
+                || {
+                    let ans: u32;
+                    unsafe { core::arch::asm!("csrr {}, mcycleh", out(reg) ans) };
+                    ans
+                },
+                || {
+                    let ans: u32;
+                    unsafe { core::arch::asm!("csrr {}, mcycle", out(reg) ans) };
+                    ans
+                },
+            )
+        } else {
+            let ans: u64;
+            unsafe { core::arch::asm!("csrr {}, mcycle", out(reg) ans) };
+            ans
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cycles_to_duration, elapsed_cycles, read_split};
+    use core::cell::Cell;
+    use core::time::Duration;
+
+    #[test]
+    fn function_elapsed_cycles() {
+        assert_eq!(elapsed_cycles(100, 350), 250);
+        assert_eq!(elapsed_cycles(u64::MAX - 9, 10), 20);
+        assert_eq!(
+            cycles_to_duration(320_000_000, 320_000_000),
+            Duration::from_secs(1)
+        );
+        assert_eq!(
+            cycles_to_duration(480, 320_000_000),
+            Duration::from_nanos(1500)
+        );
+        assert_eq!(
+            cycles_to_duration(u64::MAX, 1_000_000_000),
+            Duration::from_nanos(u64::MAX)
+        );
+    }
+
+    #[test]
+    fn function_read_split_tear_free() {
+        // Counter at 0x1_ffff_ffff; low half wraps to zero right after the first read
+        // of high half.
+        let counter = Cell::new(0x1_ffff_ffffu64);
+        let high_reads = Cell::new(0);
+        let high = || {
+            let ans = (counter.get() >> 32) as u32;
+            high_reads.set(high_reads.get() + 1);
+            if high_reads.get() == 1 {
+                counter.set(counter.get() + 1);
+            }
+            ans
+        };
+        let low = || counter.get() as u32;
+        // Torn read would give 0x1_0000_0000.
+        assert_eq!(read_split(high, low), 0x2_0000_0000);
+        assert_eq!(high_reads.get(), 4);
+    }
+}
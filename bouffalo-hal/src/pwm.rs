@@ -723,6 +723,40 @@ impl<PWM: Deref<Target = RegisterBlock>, S, const I: usize> Channels<PWM, S, I>
             })
         };
     }
+    /// Configure output frequency for this PWM group, returning the actual frequency.
+    ///
+    /// The clock divider and period are chosen so that the period is as long as possible,
+    /// giving the finest duty cycle resolution; after this call, `set_duty_cycle` on the
+    /// channels ranges from 0 to the new `max_duty_cycle`. All the channels of the group
+    /// share this timebase.
+    ///
+    /// Output frequency is `source / (divide * period)` with integer divide and period,
+    /// thus not every frequency can be represented exactly. The pair with the least error
+    /// is chosen, and the returned frequency is rounded down to whole hertz; a request
+    /// above the source clock frequency gets the source clock frequency.
+    ///
+    /// Returns an error without changing any register if `source` is not available in
+    /// `clocks`, or `frequency` is zero or too low to be reached with the maximum divider.
+    #[inline]
+    pub fn set_frequency(
+        &mut self,
+        frequency: Hertz,
+        source: ClockSource,
+        clocks: &Clocks,
+    ) -> Result<Hertz, ConfigError> {
+        let source_freq = source_clock(source, clocks).ok_or(ConfigError::ClockSource)?;
+        let (divide, period, actual) =
+            timebase_for(source_freq, frequency).ok_or(ConfigError::FrequencyTooLow)?;
+        unsafe {
+            self.pwm.group[I]
+                .group_config
+                .modify(|val| val.set_clock_source(source).set_clock_divide(divide));
+            self.pwm.group[I]
+                .period_config
+                .modify(|val| val.set_period(period));
+        }
+        Ok(actual)
+    }
     /// Configure maximum duty cycle for this PWM group.
     #[inline]
     pub fn set_max_duty_cycle(&mut self, duty: u16) {
@@ -758,6 +792,63 @@ impl<PWM: Deref<Target = RegisterBlock>, S, const I: usize> Channels<PWM, S, I>
     }
 }
 
+/// Error of PWM group clock configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigError {
+    /// Impossibly low frequency for current clock source, or zero.
+    FrequencyTooLow,
+    /// Clock source unavailable.
+    ClockSource,
+}
+
+/// Frequency of PWM clock `source`, if described by `clocks`.
+#[inline]
+fn source_clock(source: ClockSource, clocks: &Clocks) -> Option<Hertz> {
+    match source {
+        ClockSource::Xclk => Some(clocks.xclk()),
+        ClockSource::Bclk => Some(clocks.bus_clock()),
+        // todo: 32-kHz clock is not described by `Clocks` yet
+        ClockSource::F32kClk => None,
+    }
+}
+
+/// Clock divider, period and actual frequency giving PWM output `frequency`.
+///
+/// Dividers from the smallest one reaching `frequency` to twice of it are searched, so
+/// that the period keeps at least half of its finest resolution; the pair with the least
+/// frequency error wins, preferring longer periods on ties.
+#[inline]
+fn timebase_for(source: Hertz, frequency: Hertz) -> Option<(u16, u16, Hertz)> {
+    if frequency.0 == 0 {
+        return None;
+    }
+    let (source, frequency) = (source.0 as u64, frequency.0 as u64);
+    let cycles = ((source + frequency / 2) / frequency).max(1);
+    let min_divide = cycles.div_ceil(u16::MAX as u64);
+    if min_divide > u16::MAX as u64 {
+        return None;
+    }
+    let max_divide = (2 * min_divide - 1).min(u16::MAX as u64);
+    let mut best: Option<(u64, u64, u64)> = None;
+    for divide in min_divide..=max_divide {
+        let step = frequency * divide;
+        let period = ((source + step / 2) / step).clamp(1, u16::MAX as u64);
+        // Frequency error is `|source - frequency * ticks| / ticks`.
+        let ticks = divide * period;
+        let error = source.abs_diff(frequency * ticks);
+        let better = match best {
+            Some((d, p, e)) => (error as u128) * ((d * p) as u128) < (e as u128) * (ticks as u128),
+            None => true,
+        };
+        if better {
+            best = Some((divide, period, error));
+        }
+    }
+    let (divide, period, _) = best?;
+    let actual = Hertz((source / (divide * period)) as u32);
+    Some((divide as u16, period as u16, actual))
+}
+
 /// Pulse Width Modulation channel.
 pub struct Channel<PWM, S, const I: usize, const J: usize> {
     pwm: PWM,
@@ -1188,8 +1279,9 @@ mod tests {
     use super::{
         AdcTriggerSource, ChannelConfig, ClockSource, DeadTime, ElectricLevel, Group, GroupConfig,
         Interrupt, InterruptClear, InterruptConfig, InterruptEnable, InterruptMask, InterruptState,
-        PeriodConfig, Polarity, RegisterBlock, StopMode, Threshold,
+        PeriodConfig, Polarity, RegisterBlock, StopMode, Threshold, timebase_for,
     };
+    use embedded_time::rate::Hertz;
     use memoffset::offset_of;
 
     #[test]
@@ -1525,4 +1617,25 @@ mod tests {
             assert_eq!(val.0, 0x00000000 << idx);
         }
     }
+
+    #[test]
+    fn function_timebase_for() {
+        let xclk = Hertz(40_000_000);
+        assert_eq!(
+            timebase_for(xclk, Hertz(1_000)),
+            Some((1, 40000, Hertz(1_000)))
+        );
+        // Long periods need the clock divider; the divider with least error is chosen.
+        assert_eq!(timebase_for(xclk, Hertz(100)), Some((8, 50000, Hertz(100))));
+        assert_eq!(timebase_for(xclk, Hertz(1)), Some((625, 64000, Hertz(1))));
+        assert_eq!(timebase_for(xclk, Hertz(64)), Some((10, 62500, Hertz(64))));
+        // 13.33 cycles rounds to 13, giving a slightly higher frequency.
+        assert_eq!(
+            timebase_for(xclk, Hertz(3_000_000)),
+            Some((1, 13, Hertz(3_076_923)))
+        );
+        assert_eq!(timebase_for(xclk, Hertz(80_000_000)), Some((1, 1, xclk)));
+        assert_eq!(timebase_for(xclk, Hertz(0)), None);
+        assert_eq!(timebase_for(Hertz(u32::MAX), Hertz(1)), None);
+    }
 }
@@ -0,0 +1,200 @@
+//! Real-Time Transfer console over the debug probe.
+//!
+//! RTT places a control block in RAM which the debug probe finds by scanning for its
+//! identifier, and exchanges data through ring buffers in target memory while the core
+//! keeps running. This module provides one up (target to host) channel, usually shown
+//! as a terminal by host tools like probe-rs or J-Link RTT Viewer.
+//!
+//! Writes never block. If no debugger is attached, or the host does not read fast
+//! enough, bytes that do not fit into the ring buffer are dropped.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use bouffalo_hal::rtt::Rtt;
+//! use core::fmt::Write;
+//!
+//! static RTT: Rtt<1024> = Rtt::new();
+//!
+//! let mut console = RTT.init().unwrap();
+//! writeln!(console, "Hello from RTT!").ok();
+//! ```
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering, compiler_fence};
+
+/// Name of the up channel shown by host tools.
+const CHANNEL_NAME: &[u8] = b"Terminal\0";
+
+/// Channel operating mode: skip or trim data that does not fit, never block.
+const MODE_NO_BLOCK_TRIM: u32 = 1;
+
+/// RTT control block and ring buffer storage of `SIZE` bytes.
+pub struct Rtt<const SIZE: usize> {
+    control: UnsafeCell<ControlBlock>,
+    buffer: UnsafeCell<[u8; SIZE]>,
+    taken: AtomicBool,
+}
+
+// SAFETY: the control block is only written by the first `init` call, and the ring
+// buffer is written by the one `Writer` instance while the debug probe reads it.
+unsafe impl<const SIZE: usize> Sync for Rtt<SIZE> {}
+
+/// RTT control block, layout defined by SEGGER.
+#[repr(C)]
+struct ControlBlock {
+    id: [u8; 16],
+    max_up_buffers: i32,
+    max_down_buffers: i32,
+    up: BufferDescriptor,
+}
+
+/// RTT ring buffer descriptor, layout defined by SEGGER.
+#[repr(C)]
+struct BufferDescriptor {
+    name: *const u8,
+    buffer: *mut u8,
+    size: u32,
+    write: AtomicU32,
+    read: AtomicU32,
+    flags: u32,
+}
+
+impl<const SIZE: usize> Rtt<SIZE> {
+    /// Create an uninitialized RTT control block.
+    ///
+    /// It should be placed in a `static` so that the debug probe can find it in RAM.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            control: UnsafeCell::new(ControlBlock {
+                id: [0; 16],
+                max_up_buffers: 1,
+                max_down_buffers: 0,
+                up: BufferDescriptor {
+                    name: core::ptr::null(),
+                    buffer: core::ptr::null_mut(),
+                    size: 0,
+                    write: AtomicU32::new(0),
+                    read: AtomicU32::new(0),
+                    flags: 0,
+                },
+            }),
+            buffer: UnsafeCell::new([0; SIZE]),
+            taken: AtomicBool::new(false),
+        }
+    }
+    /// Initialize the control block and get the console writer.
+    ///
+    /// Returns `None` if the writer was already taken by an earlier call.
+    pub fn init(&self) -> Option<Writer<'_>> {
+        if self.taken.swap(true, Ordering::Acquire) {
+            return None;
+        }
+        let control = self.control.get();
+        unsafe {
+            (*control).up.name = CHANNEL_NAME.as_ptr();
+            (*control).up.buffer = self.buffer.get() as *mut u8;
+            (*control).up.size = SIZE as u32;
+            (*control).up.write.store(0, Ordering::Relaxed);
+            (*control).up.read.store(0, Ordering::Relaxed);
+            (*control).up.flags = MODE_NO_BLOCK_TRIM;
+        }
+        // The identifier is written last, so that the probe never sees a half
+        // initialized control block. It is built at runtime to prevent the probe from
+        // finding a copy of it in read-only data.
+        compiler_fence(Ordering::SeqCst);
+        let mut id = *b"TTR REGGES\0\0\0\0\0\0";
+        id[..10].reverse();
+        unsafe { core::ptr::write_volatile(&raw mut (*control).id, id) };
+        Some(Writer {
+            up: unsafe { &(*control).up },
+        })
+    }
+}
+
+/// Non-blocking writer into the RTT up channel.
+pub struct Writer<'a> {
+    up: &'a BufferDescriptor,
+}
+
+impl Writer<'_> {
+    /// Write bytes into the ring buffer, returning the number of bytes accepted.
+    ///
+    /// Bytes that do not fit into the free space of the ring buffer are dropped.
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> usize {
+        let up = self.up;
+        let size = up.size as usize;
+        let read = up.read.load(Ordering::Acquire) as usize;
+        let mut write = up.write.load(Ordering::Relaxed) as usize;
+        // One byte is always kept free to distinguish a full buffer from an empty one.
+        let free = if read > write {
+            read - write - 1
+        } else {
+            size - write + read - 1
+        };
+        let len = core::cmp::min(free, bytes.len());
+        for &byte in &bytes[..len] {
+            unsafe { up.buffer.add(write).write_volatile(byte) };
+            write += 1;
+            if write == size {
+                write = 0;
+            }
+        }
+        up.write.store(write as u32, Ordering::Release);
+        len
+    }
+}
+
+impl<const SIZE: usize> Default for Rtt<SIZE> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl core::fmt::Write for Writer<'_> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.write_bytes(s.as_bytes());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rtt;
+    use core::fmt::Write;
+    use core::sync::atomic::Ordering;
+
+    #[test]
+    fn struct_rtt_control_block() {
+        let rtt = Rtt::<16>::new();
+        let _writer = rtt.init().unwrap();
+        assert!(rtt.init().is_none());
+        let control = unsafe { &*rtt.control.get() };
+        assert_eq!(&control.id, b"SEGGER RTT\0\0\0\0\0\0");
+        assert_eq!(control.up.size, 16);
+        assert_eq!(control.up.buffer, rtt.buffer.get() as *mut u8);
+    }
+
+    #[test]
+    fn struct_rtt_writer_drops_when_full() {
+        let rtt = Rtt::<16>::new();
+        let mut writer = rtt.init().unwrap();
+        // Host is not reading; the buffer fills up and the rest is dropped.
+        assert!(writer.write_str("0123456789abcdefghij").is_ok());
+        assert_eq!(writer.write_bytes(b"klmn"), 0);
+        let up = unsafe { &(*rtt.control.get()).up };
+        assert_eq!(up.write.load(Ordering::Relaxed), 15);
+        let buffer = unsafe { &*rtt.buffer.get() };
+        assert_eq!(&buffer[..15], b"0123456789abcde");
+
+        // Host reads 4 bytes, making room for new data wrapping around.
+        up.read.store(4, Ordering::Relaxed);
+        assert_eq!(writer.write_bytes(b"xyz!?"), 4);
+        assert_eq!(up.write.load(Ordering::Relaxed), 3);
+        let buffer = unsafe { &*rtt.buffer.get() };
+        assert_eq!(buffer[15], b'x');
+        assert_eq!(&buffer[..3], b"yz!");
+    }
+}
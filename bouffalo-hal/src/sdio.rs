@@ -4,8 +4,8 @@ use crate::glb;
 use crate::gpio::{self, Alternate};
 use core::arch::asm;
 use core::ops::Deref;
-use embedded_io::Write;
 use embedded_sdmmc::{Block, BlockDevice, BlockIdx};
+use embedded_time::rate::Hertz;
 use volatile_register::{RO, RW, WO};
 
 /// Secure Digital Input/Output peripheral registers.
@@ -3337,6 +3337,22 @@ fn sleep_ms(n: u32) {
     }
 }
 
+/// Clock of SD host controller, 96 MHz PLL divided by 8 in global register.
+const BASE_CLOCK: Hertz = Hertz(12_000_000);
+
+/// Bus clock during card identification.
+const IDENTIFICATION_CLOCK: Hertz = Hertz(400_000);
+
+/// Number of 1 ms attempts to wait for card power up.
+const POWER_UP_ATTEMPTS: u32 = 1000;
+
+/// Supported voltage window, 3.2 V to 3.4 V.
+const OCR_VOLTAGE: u32 = 0x0030_0000;
+/// Card power up status bit.
+const OCR_BUSY: u32 = 1 << 31;
+/// Card capacity status bit, or host capacity support bit in ACMD41 argument.
+const OCR_CCS: u32 = 1 << 30;
+
 /// SDH config.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Config {
@@ -3344,6 +3360,7 @@ pub struct Config {
     transfer_width: TransferWidth,
     speed_mode: SpeedMode,
     dma_mode: DmaMode,
+    clock: Hertz,
     // TODO: implment more configurations if necessary.
 }
 
@@ -3356,6 +3373,7 @@ impl Config {
             transfer_width: TransferWidth::OneBitMode,
             speed_mode: SpeedMode::HighSpeed,
             dma_mode: DmaMode::None,
+            clock: BASE_CLOCK,
         }
     }
     /// Set bus width mode.
@@ -3365,6 +3383,8 @@ impl Config {
         self
     }
     /// Set transfer width.
+    ///
+    /// 4-bit mode needs pads of all four data lines.
     #[inline]
     pub const fn transfer_width(mut self, transfer_width: TransferWidth) -> Self {
         self.transfer_width = transfer_width;
@@ -3382,6 +3402,47 @@ impl Config {
         self.dma_mode = dma_mode;
         self
     }
+    /// Set bus clock after card initialization.
+    ///
+    /// Actual clock is the fastest one no higher than `clock` that the controller can
+    /// divide down to, up to 12 MHz.
+    #[inline]
+    pub const fn clock(mut self, clock: Hertz) -> Self {
+        self.clock = clock;
+        self
+    }
+}
+
+/// SD card error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Error {
+    /// No card answers on the bus.
+    NoCard,
+    /// Card is not initialized by [`Sdh::init`].
+    Uninitialized,
+    /// Card gives an unexpected response, or does not support host voltage.
+    UnsupportedCard,
+    /// Card does not respond to command of given index.
+    CommandTimeout(u8),
+    /// Response to command of given index fails CRC, end bit or index check.
+    CommandError(u8),
+    /// Card does not finish data transfer in time.
+    DataTimeout,
+    /// Data block fails CRC or end bit check.
+    DataError,
+}
+
+/// Initialized SD card.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Card {
+    /// Relative card address.
+    pub rca: u16,
+    /// Card is high or extended capacity (SDHC or SDXC), addressed by block.
+    ///
+    /// Standard capacity cards (SDSC) are addressed by byte.
+    pub high_capacity: bool,
+    /// Number of 512-byte blocks.
+    pub block_count: u32,
 }
 
 /// Managed Secure Digital Host Controller peripheral.
@@ -3390,16 +3451,25 @@ pub struct Sdh<SDH, PADS, const I: usize> {
     sdh: SDH,
     pads: PADS,
     config: Config,
-    block_count: u32,
+    card: Option<Card>,
 }
 
 impl<SDH: Deref<Target = RegisterBlock>, PADS, const I: usize> Sdh<SDH, PADS, I> {
     /// Create a new instance of the SDH peripheral.
+    ///
+    /// # Panics
+    ///
+    /// Panics if 4-bit transfer width is configured on pads without data lines 1 to 3.
     #[inline]
     pub fn new(sdh: SDH, pads: PADS, config: Config, glb: &glb::v2::RegisterBlock) -> Self
     where
         PADS: Pads<I>,
     {
+        assert!(
+            !(matches!(config.transfer_width, TransferWidth::FourBitMode)
+                && matches!(PADS::TRANSFER_WIDTH, TransferWidth::OneBitMode)),
+            "4-bit SDH transfer width needs data 1 to 3 pads"
+        );
         // Reset SDH peripheral.
         unsafe {
             sdh.software_reset.modify(|val| val.reset_all());
@@ -3414,17 +3484,8 @@ impl<SDH: Deref<Target = RegisterBlock>, PADS, const I: usize> Sdh<SDH, PADS, I>
                     .set_sdh_clk_div_len(7) // GLB_REG_SDH_CLK_DIV.
                     .enable_sdh_clk() // GLB_REG_SDH_CLK_EN.
             });
-            sdh.clock_control.modify(|val| {
-                val.set_sd_clk_freq(0) // SDH_SD_FREQ_SEL_LO.
-                    .set_sd_clk_freq_upper(0) // SDH_SD_FREQ_SEL_HI.
-                    .set_clk_gen_mode(ClkGenMode::DividedClk) // SDH_CLK_GEN_SEL.
-                    .enable_internal_clk() // SDH_INT_CLK_EN.
-                    .enable_sd_clk() // SDH_SD_CLK_EN.
-            });
-        }
-        while !sdh.clock_control.read().is_sd_clk_enabled() {
-            core::hint::spin_loop()
         }
+        set_bus_clock(&sdh, IDENTIFICATION_CLOCK);
         // Miscellaneous settings.
         unsafe {
             // SDH_DMA_EN.
@@ -3445,9 +3506,10 @@ impl<SDH: Deref<Target = RegisterBlock>, PADS, const I: usize> Sdh<SDH, PADS, I>
                     }
                 }
             }
+            // Card identification always starts on a 1-bit bus.
             sdh.host_control_1.modify(|val| {
                 val.set_bus_width(config.bus_width_mode) // SDH_EX_DATA_WIDTH.
-                    .set_transfer_width(config.transfer_width) // SDH_DATA_WIDTH.
+                    .set_transfer_width(TransferWidth::OneBitMode) // SDH_DATA_WIDTH.
                     .set_speed_mode(config.speed_mode) // SDH_HI_SPEED_EN.
                     .set_dma_mode(config.dma_mode)
             });
@@ -3457,8 +3519,21 @@ impl<SDH: Deref<Target = RegisterBlock>, PADS, const I: usize> Sdh<SDH, PADS, I>
             // SDH_TX_INT_CLK_SEL.
             sdh.tx_configuration.modify(|val| val.set_tx_int_clk_sel(1));
             // SDH enable interrupt.
-            sdh.normal_interrupt_status_enable
-                .modify(|val| val.enable_buffer_read_ready());
+            sdh.normal_interrupt_status_enable.modify(|val| {
+                val.enable_cmd_complete()
+                    .enable_transfer_complete()
+                    .enable_buffer_read_ready()
+                    .enable_buffer_write_ready()
+            });
+            sdh.error_interrupt_status_enable.modify(|val| {
+                val.enable_cmd_timeout_err()
+                    .enable_cmd_crc_err()
+                    .enable_cmd_end_bit_err()
+                    .enable_cmd_index_err()
+                    .enable_data_timeout_err()
+                    .enable_data_crc_err()
+                    .enable_data_end_bit_err()
+            });
             // SDH_Set_Timeout.
             sdh.timeout_control.modify(|val| val.set_timeout_val(0x0e));
             // SDH_Powon.
@@ -3468,124 +3543,116 @@ impl<SDH: Deref<Target = RegisterBlock>, PADS, const I: usize> Sdh<SDH, PADS, I>
             sdh,
             pads,
             config,
-            block_count: 0,
+            card: None,
         }
     }
 
-    /// Initialize the SDH peripheral (enable debug to print card info).
-    // TODO a more proper abstraction
+    /// Initialize the inserted card, switching to configured transfer width and clock.
+    ///
+    /// Returns [`Error::NoCard`] if no card answers on the bus.
     #[inline]
-    pub fn init<W: Write>(&mut self, w: &mut W, debug: bool) {
-        // Sdcard idle.
-        loop {
-            self.send_command(SdhResp::None, CmdType::Normal, 0, 0, false);
-            sleep_ms(100);
-
-            // Send CMD8.
-            self.send_command(SdhResp::R7, CmdType::Normal, 8, 0x1AA, false);
-            sleep_ms(100);
-            let data = self.get_resp();
-            if data != 0x1AA {
-                writeln!(
-                    *w,
-                    "unexpected response to CMD8: {:#010X}, expected 0x1AA",
-                    data
-                )
-                .ok();
+    pub fn init(&mut self) -> Result<Card, Error> {
+        self.card = None;
+        set_bus_clock(&self.sdh, IDENTIFICATION_CLOCK);
+        unsafe {
+            self.sdh
+                .host_control_1
+                .modify(|val| val.set_transfer_width(TransferWidth::OneBitMode))
+        };
+        // At least 74 clock cycles before the first command.
+        sleep_ms(1);
+
+        // CMD0, go idle.
+        self.send_command(SdhResp::None, CmdType::Normal, 0, 0, false)?;
+        // CMD8, check interface condition; version 1.x cards do not respond.
+        let version_2 = match self.send_command(SdhResp::R7, CmdType::Normal, 8, 0x1AA, false) {
+            Ok(()) => {
+                if self.get_resp() as u32 & 0xFFF != 0x1AA {
+                    return Err(Error::UnsupportedCard);
+                }
+                true
+            }
+            Err(Error::CommandTimeout(_)) => false,
+            Err(e) => return Err(e),
+        };
+        // ACMD41, wait for power up.
+        let mut attempts = 0;
+        let ocr = loop {
+            match self.send_command(SdhResp::R1, CmdType::Normal, 55, 0, false) {
+                Err(Error::CommandTimeout(_)) if !version_2 => return Err(Error::NoCard),
+                ans => ans?,
+            }
+            let argument = if version_2 {
+                OCR_VOLTAGE | OCR_CCS
             } else {
-                break;
+                OCR_VOLTAGE
+            };
+            self.send_command(SdhResp::R3, CmdType::Normal, 41, argument, false)?;
+            let ocr = self.get_resp() as u32;
+            if ocr & OCR_BUSY != 0 {
+                break ocr;
             }
-            sleep_ms(1000);
-        }
-
-        loop {
-            const OCR_NBUSY: u32 = 0x80000000;
-            const OCR_VOLTAGE_MASK: u32 = 0x007FFF80;
-            const OCR_HCS: u32 = 0x40000000;
-            self.send_command(SdhResp::R1, CmdType::Normal, 55, 0, false);
-            sleep_ms(100);
-            self.send_command(
-                SdhResp::R3,
-                CmdType::Normal,
-                41,
-                OCR_VOLTAGE_MASK & 0x00ff8000 | OCR_HCS,
-                false,
-            );
-            sleep_ms(100);
-            let ocr = self.get_resp();
-            if (ocr as u32 & OCR_NBUSY) == OCR_NBUSY {
-                break;
+            attempts += 1;
+            if attempts == POWER_UP_ATTEMPTS {
+                return Err(Error::UnsupportedCard);
             }
-            sleep_ms(100);
-        }
-
-        // Send CMD2 to get CID.
-        self.send_command(SdhResp::R2, CmdType::Normal, 2, 0, false);
-        sleep_ms(100);
-        let cid = self.get_resp();
-        if debug {
-            writeln!(*w, "cid: {:#034X}", cid).ok();
-        }
-
-        // Send CMD3 to get RCA.
-        self.send_command(SdhResp::R6, CmdType::Normal, 3, 0, false);
-        sleep_ms(100);
-        let rca = self.get_resp() as u32 >> 16;
-        if debug {
-            writeln!(*w, "rca: {:#010X}", rca).ok();
-        }
-
-        // Send CMD9 to get CSD.
-        self.send_command(SdhResp::R2, CmdType::Normal, 9, rca << 16, false);
-        sleep_ms(100);
-        let csd_raw = self.get_resp();
-        let (csd_structure, c_size) = parse_csd_v2(csd_raw);
-        if csd_structure != 1 {
-            writeln!(*w, "unexpected CSD: {:#034X}", csd_raw).ok();
-            loop {}
+            sleep_ms(1);
+        };
+        let high_capacity = ocr & OCR_CCS != 0;
+
+        // CMD2, get CID.
+        self.send_command(SdhResp::R2, CmdType::Normal, 2, 0, false)?;
+        // CMD3, get RCA.
+        self.send_command(SdhResp::R6, CmdType::Normal, 3, 0, false)?;
+        let rca = (self.get_resp() as u32 >> 16) as u16;
+        // CMD9, get CSD.
+        self.send_command(SdhResp::R2, CmdType::Normal, 9, (rca as u32) << 16, false)?;
+        let block_count = block_count_from_csd(self.get_resp()).ok_or(Error::UnsupportedCard)?;
+        // CMD7, select card.
+        self.send_command(SdhResp::R1B, CmdType::Normal, 7, (rca as u32) << 16, false)?;
+        if !high_capacity {
+            // CMD16, set block length of byte addressed cards.
+            self.send_command(SdhResp::R1, CmdType::Normal, 16, Block::LEN as u32, false)?;
         }
-        if debug {
-            writeln!(*w, "csd: {:#034X}, c_size: {}", csd_raw, c_size).ok();
-        }
-
-        let block_size = 512;
-        self.block_count = (c_size + 1) * 1024;
-
-        // Send CMD7 to select card.
-        self.send_command(SdhResp::R1B, CmdType::Normal, 7, rca << 16, false);
-        sleep_ms(100);
-
-        // Set 1 data len, CMD55 -> ACMD6.
-        self.send_command(SdhResp::R1, CmdType::Normal, 55, rca << 16, false);
-        sleep_ms(100);
-        self.send_command(SdhResp::R1, CmdType::Normal, 6, 0x0, false);
-        sleep_ms(100);
+        // ACMD6, set bus width.
+        self.send_command(SdhResp::R1, CmdType::Normal, 55, (rca as u32) << 16, false)?;
+        let bus_width = match self.config.transfer_width {
+            TransferWidth::OneBitMode => 0,
+            TransferWidth::FourBitMode => 2,
+        };
+        self.send_command(SdhResp::R1, CmdType::Normal, 6, bus_width, false)?;
+        unsafe {
+            self.sdh
+                .host_control_1
+                .modify(|val| val.set_transfer_width(self.config.transfer_width))
+        };
+        set_bus_clock(&self.sdh, self.config.clock);
 
-        let kb_size = (self.block_count as f64) * (block_size as f64) / 1024.0;
-        let mb_size = kb_size / 1024.0;
-        let gb_size = mb_size / 1024.0;
+        let card = Card {
+            rca,
+            high_capacity,
+            block_count,
+        };
+        self.card = Some(card);
+        Ok(card)
+    }
 
-        if debug {
-            if kb_size < 1024.0 {
-                writeln!(*w, "sdcard init done, size: {:.2} KB", kb_size).ok();
-            } else if mb_size < 1024.0 {
-                writeln!(*w, "sdcard init done, size: {:.2} MB", mb_size).ok();
-            } else {
-                writeln!(*w, "sdcard init done, size: {:.2} GB", gb_size).ok();
-            }
-        }
+    /// Get the card initialized by [`init`](Self::init).
+    #[inline]
+    pub fn card(&self) -> Option<Card> {
+        self.card
     }
 
-    /// Send command to sdcard.
+    /// Send command to sdcard and wait for its response.
     #[inline]
     fn send_command(
         &self,
         resp_type: SdhResp,
         cmd_type: CmdType,
-        cmd_idx: u32,
+        cmd_idx: u8,
         argument: u32,
         has_data: bool,
-    ) {
+    ) -> Result<(), Error> {
         let mut flag = SdhTransFlag::None as u32;
         if has_data {
             flag |= SdhTransFlag::DataPresent as u32;
@@ -3610,6 +3677,9 @@ impl<SDH: Deref<Target = RegisterBlock>, PADS, const I: usize> Sdh<SDH, PADS, I>
             }
         }
 
+        while self.sdh.present_state.read().is_cmd_line_busy() {
+            core::hint::spin_loop()
+        }
         unsafe {
             self.sdh.argument.write(Argument(argument));
             self.sdh.command.write(
@@ -3618,6 +3688,36 @@ impl<SDH: Deref<Target = RegisterBlock>, PADS, const I: usize> Sdh<SDH, PADS, I>
                     .set_cmd_idx(cmd_idx as u16),
             )
         }
+        loop {
+            let error = self.sdh.error_interrupt_status.read();
+            if error.if_cmd_timeout_err_occurs()
+                || error.if_cmd_crc_err_occurs()
+                || error.if_cmd_end_bit_err_occurs()
+                || error.if_cmd_index_err_occurs()
+            {
+                unsafe {
+                    self.sdh.error_interrupt_status.write(error);
+                    self.sdh.software_reset.modify(|val| val.reset_cmd());
+                }
+                while !self.sdh.software_reset.read().is_reset_cmd_finished() {
+                    core::hint::spin_loop()
+                }
+                return Err(if error.if_cmd_timeout_err_occurs() {
+                    Error::CommandTimeout(cmd_idx)
+                } else {
+                    Error::CommandError(cmd_idx)
+                });
+            }
+            if self.sdh.normal_interrupt_status.read().is_cmd_completed() {
+                unsafe {
+                    self.sdh
+                        .normal_interrupt_status
+                        .write(NormalInterruptStatus(0).clear_cmd_completed())
+                };
+                return Ok(());
+            }
+            core::hint::spin_loop()
+        }
     }
 
     /// Get response from sdcard.
@@ -3626,47 +3726,95 @@ impl<SDH: Deref<Target = RegisterBlock>, PADS, const I: usize> Sdh<SDH, PADS, I>
         self.sdh.response.read().response()
     }
 
-    /// Read block from sdcard.
+    /// Prepare a single block transfer and send its command.
     #[inline]
-    fn read_block(&self, block: &mut Block, block_idx: u32) {
+    fn start_block(
+        &self,
+        direction: DataTransferMode,
+        cmd_idx: u8,
+        block_idx: u32,
+    ) -> Result<(), Error> {
+        let card = self.card.ok_or(Error::Uninitialized)?;
+        let address = if card.high_capacity {
+            block_idx
+        } else {
+            block_idx * Block::LEN as u32
+        };
         unsafe {
             // SDH_SD_TRANSFER_MODE.
             self.sdh.transfer_mode.modify(|val| {
-                val.set_data_transfer_mode(DataTransferMode::MISO) // SDH_TO_HOST_DIR.
+                val.set_data_transfer_mode(direction) // SDH_TO_HOST_DIR.
+                    .set_block_mode(BlockMode::Other)
                     .set_auto_cmd_mode(AutoCMDMode::None) // SDH_AUTO_CMD_EN.
             });
-
             // Block_size.
             self.sdh
                 .block_size
-                .modify(|val| val.set_transfer_block(512));
-
+                .modify(|val| val.set_transfer_block(Block::LEN as u16));
             // Block_count.
             self.sdh.block_count.modify(|val| val.set_blocks_count(1));
-
-            // SDH_ClearIntStatus(SDH_INT_BUFFER_READ_READY).
-            self.sdh
-                .normal_interrupt_status
-                .modify(|val| val.clear_buffer_read_ready());
         }
-        self.send_command(SdhResp::R1, CmdType::Normal, 17, block_idx, true);
-        while !self
-            .sdh
-            .normal_interrupt_status
-            .read()
-            .is_buffer_read_ready()
-        {
-            // SDH_INT_BUFFER_READ_READY.
-            // Wait for buffer read ready.
+        self.send_command(SdhResp::R1, CmdType::Normal, cmd_idx, address, true)
+    }
+
+    /// Wait for a data transfer event, failing on data errors.
+    #[inline]
+    fn wait_data(&self, event: fn(NormalInterruptStatus) -> bool) -> Result<(), Error> {
+        loop {
+            let error = self.sdh.error_interrupt_status.read();
+            if error.if_data_timeout_err_occurs()
+                || error.if_data_crc_err_occurs()
+                || error.if_data_end_bit_err_occurs()
+            {
+                unsafe {
+                    self.sdh.error_interrupt_status.write(error);
+                    self.sdh.software_reset.modify(|val| val.reset_dat());
+                }
+                while !self.sdh.software_reset.read().is_reset_dat_finished() {
+                    core::hint::spin_loop()
+                }
+                return Err(if error.if_data_timeout_err_occurs() {
+                    Error::DataTimeout
+                } else {
+                    Error::DataError
+                });
+            }
+            let status = self.sdh.normal_interrupt_status.read();
+            if event(status) {
+                unsafe { self.sdh.normal_interrupt_status.write(status) };
+                return Ok(());
+            }
             core::hint::spin_loop()
         }
-        for j in 0..Block::LEN / 4 {
+    }
+
+    /// Read block from sdcard.
+    #[inline]
+    fn read_block(&self, block: &mut Block, block_idx: u32) -> Result<(), Error> {
+        self.start_block(DataTransferMode::MISO, 17, block_idx)?;
+        self.wait_data(NormalInterruptStatus::is_buffer_read_ready)?;
+        for chunk in block.contents.chunks_exact_mut(4) {
             let val = self.sdh.buffer_data_port.read().buffer_data();
-            block[j * 4 + 0] = (val >> 0) as u8;
-            block[j * 4 + 1] = (val >> 8) as u8;
-            block[j * 4 + 2] = (val >> 16) as u8;
-            block[j * 4 + 3] = (val >> 24) as u8;
+            chunk.copy_from_slice(&val.to_le_bytes());
         }
+        self.wait_data(NormalInterruptStatus::is_transfer_completed)
+    }
+
+    /// Write block to sdcard.
+    #[inline]
+    fn write_block(&self, block: &Block, block_idx: u32) -> Result<(), Error> {
+        self.start_block(DataTransferMode::Other, 24, block_idx)?;
+        self.wait_data(NormalInterruptStatus::is_buffer_write_ready)?;
+        for chunk in block.contents.chunks_exact(4) {
+            let val = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+            unsafe {
+                self.sdh
+                    .buffer_data_port
+                    .write(BufferDataPort(0).set_buffer_data(val))
+            };
+        }
+        // Transfer completes after the card releases its busy signal.
+        self.wait_data(NormalInterruptStatus::is_transfer_completed)
     }
 
     /// Release the SDH instance and return the pads and configs.
@@ -3677,7 +3825,7 @@ impl<SDH: Deref<Target = RegisterBlock>, PADS, const I: usize> Sdh<SDH, PADS, I>
 }
 
 impl<SDH: Deref<Target = RegisterBlock>, PADS, const I: usize> BlockDevice for Sdh<SDH, PADS, I> {
-    type Error = core::convert::Infallible;
+    type Error = Error;
 
     #[inline]
     fn read(
@@ -3687,33 +3835,104 @@ impl<SDH: Deref<Target = RegisterBlock>, PADS, const I: usize> BlockDevice for S
         _reason: &str,
     ) -> Result<(), Self::Error> {
         for (i, block) in blocks.iter_mut().enumerate() {
-            self.read_block(block, start_block_idx.0 + i as u32);
+            self.read_block(block, start_block_idx.0 + i as u32)?;
         }
         Ok(())
     }
 
     #[inline]
-    fn write(&self, _blocks: &[Block], _start_block_idx: BlockIdx) -> Result<(), Self::Error> {
-        todo!();
+    fn write(&self, blocks: &[Block], start_block_idx: BlockIdx) -> Result<(), Self::Error> {
+        for (i, block) in blocks.iter().enumerate() {
+            self.write_block(block, start_block_idx.0 + i as u32)?;
+        }
+        Ok(())
     }
 
     #[inline]
     fn num_blocks(&self) -> Result<embedded_sdmmc::BlockCount, Self::Error> {
-        Ok(embedded_sdmmc::BlockCount(self.block_count))
+        let card = self.card.ok_or(Error::Uninitialized)?;
+        Ok(embedded_sdmmc::BlockCount(card.block_count))
+    }
+}
+
+/// Set bus clock to the fastest one no higher than `clock`.
+#[inline]
+fn set_bus_clock(sdh: &RegisterBlock, clock: Hertz) {
+    let divide = clock_divide_for(BASE_CLOCK, clock);
+    unsafe {
+        sdh.clock_control.modify(|val| val.disable_sd_clk());
+        sdh.clock_control.modify(|val| {
+            val.set_sd_clk_freq(divide as u8) // SDH_SD_FREQ_SEL_LO.
+                .set_sd_clk_freq_upper((divide >> 8) as u8) // SDH_SD_FREQ_SEL_HI.
+                .set_clk_gen_mode(ClkGenMode::DividedClk) // SDH_CLK_GEN_SEL.
+                .enable_internal_clk() // SDH_INT_CLK_EN.
+        });
+    }
+    while !sdh.clock_control.read().is_internal_clk_stable() {
+        core::hint::spin_loop()
+    }
+    unsafe { sdh.clock_control.modify(|val| val.enable_sd_clk()) }; // SDH_SD_CLK_EN.
+}
+
+/// 10-bit divided clock factor `N` giving `base / 2N` no higher than `clock`.
+///
+/// Factor 0 selects the base clock itself.
+#[inline]
+fn clock_divide_for(base: Hertz, clock: Hertz) -> u16 {
+    if clock.0 >= base.0 {
+        return 0;
     }
+    let divide = base.0.div_ceil(2 * clock.0.max(1));
+    divide.min(0x3FF) as u16
 }
 
-/// Parse CSD version 2.0.
+/// Number of 512-byte blocks described by card specific data.
+///
+/// `csd` is the 136-bit response without its CRC, as held in response register.
 #[inline]
-fn parse_csd_v2(csd: u128) -> (u32, u32) {
-    let csd_structure = (((csd >> (32 * 3)) & 0xC00000) >> 22) as u32;
-    let c_size = (((csd >> 32) & 0x3FFFFF00) >> 8) as u32;
-    (csd_structure, c_size)
+fn block_count_from_csd(csd: u128) -> Option<u32> {
+    match (csd >> 118) & 0x3 {
+        // CSD version 1.0: (C_SIZE + 1) * 2 ^ (C_SIZE_MULT + 2) blocks of 2 ^ READ_BL_LEN bytes.
+        0 => {
+            let read_bl_len = ((csd >> 72) & 0xF) as u32;
+            let c_size = ((csd >> 54) & 0xFFF) as u32;
+            let c_size_mult = ((csd >> 39) & 0x7) as u32;
+            if !(9..=11).contains(&read_bl_len) {
+                return None;
+            }
+            Some((c_size + 1) << (c_size_mult + 2 + read_bl_len - 9))
+        }
+        // CSD version 2.0: (C_SIZE + 1) * 512 KiB.
+        1 => {
+            let c_size = ((csd >> 40) & 0x3F_FFFF) as u32;
+            Some((c_size + 1) * 1024)
+        }
+        _ => None,
+    }
 }
 
 /// Valid SDH pads.
-pub trait Pads<const I: usize> {}
+pub trait Pads<const I: usize> {
+    /// Widest transfer width these pads support.
+    const TRANSFER_WIDTH: TransferWidth;
+}
 
+/// Clock, command and data 0 pads for 1-bit transfer width.
+impl<'a, 'b, 'c, const N1: usize, const N2: usize, const N3: usize> Pads<1>
+    for (
+        Alternate<'a, N1, gpio::Sdh>,
+        Alternate<'b, N2, gpio::Sdh>,
+        Alternate<'c, N3, gpio::Sdh>,
+    )
+where
+    Alternate<'a, N1, gpio::Sdh>: HasClkSignal,
+    Alternate<'b, N2, gpio::Sdh>: HasCmdSignal,
+    Alternate<'c, N3, gpio::Sdh>: HasDat0Signal,
+{
+    const TRANSFER_WIDTH: TransferWidth = TransferWidth::OneBitMode;
+}
+
+/// Clock, command and data 0 to 3 pads for 1-bit or 4-bit transfer width.
 impl<
     'a,
     'b,
@@ -3744,6 +3963,7 @@ where
     Alternate<'e, N5, gpio::Sdh>: HasDat2Signal,
     Alternate<'f, N6, gpio::Sdh>: HasDat3Signal,
 {
+    const TRANSFER_WIDTH: TransferWidth = TransferWidth::FourBitMode;
 }
 
 /// Check if target gpio `Pin` is internally connected to SDH clock signal.
@@ -3790,7 +4010,9 @@ mod tests {
         PresetValue, Response, ResponseType, SDExtraParameters, SharedBusControl,
         SlotInterruptStatus, SlotType, SoftwareReset, SpecificVersion, SpeedMode, SpiMode,
         SystemAddress, TimeoutControl, TransferMode, TransferWidth, TxConfiguration, WakeupControl,
+        block_count_from_csd, clock_divide_for,
     };
+    use embedded_time::rate::Hertz;
     use memoffset::offset_of;
 
     #[test]
@@ -5243,4 +5465,30 @@ mod tests {
     fn struct_tuning_configuration_functions() {
         // TODO
     }
+
+    #[test]
+    fn function_clock_divide_for() {
+        let base = Hertz(12_000_000);
+        assert_eq!(clock_divide_for(base, Hertz(400_000)), 15);
+        assert_eq!(clock_divide_for(base, Hertz(5_000_000)), 2);
+        assert_eq!(clock_divide_for(base, Hertz(6_000_000)), 1);
+        assert_eq!(clock_divide_for(base, Hertz(12_000_000)), 0);
+        assert_eq!(clock_divide_for(base, Hertz(50_000_000)), 0);
+        assert_eq!(clock_divide_for(base, Hertz(1_000)), 0x3FF);
+    }
+
+    #[test]
+    fn function_block_count_from_csd() {
+        // Version 2.0, C_SIZE 15159: about 7.4 GiB.
+        let csd = (1 << 118) | (15159 << 40);
+        assert_eq!(block_count_from_csd(csd), Some(15160 * 1024));
+        // Version 1.0, READ_BL_LEN 10, C_SIZE 4095, C_SIZE_MULT 7: 2 GiB.
+        let csd = (10 << 72) | (4095 << 54) | (7 << 39);
+        assert_eq!(block_count_from_csd(csd), Some(4096 * 1024));
+        // Version 1.0, READ_BL_LEN 9, C_SIZE 1000, C_SIZE_MULT 3.
+        let csd = (9 << 72) | (1000 << 54) | (3 << 39);
+        assert_eq!(block_count_from_csd(csd), Some(1001 * 32));
+        assert_eq!(block_count_from_csd(3 << 118), None);
+        assert_eq!(block_count_from_csd(12 << 72), None);
+    }
 }
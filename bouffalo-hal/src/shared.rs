@@ -0,0 +1,104 @@
+//! Peripheral sharing between main code and interrupt handlers.
+//!
+//! [`Shared`] wraps the common `Mutex<RefCell<Option<T>>>` pattern. A peripheral or pin
+//! is moved in once after it is configured, and then accessed from both main code and
+//! interrupt handlers in a critical section. The value must be `Send`; on BL808 and
+//! BL616 this includes GPIO pads.
+//!
+//! Critical sections are provided by the `critical-section` crate; the application or
+//! runtime must link in an implementation for the target, e.g. one that disables
+//! machine mode interrupts.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use bouffalo_hal::{gpio::{Floating, Output}, shared::Shared};
+//! # use embedded_hal::digital::OutputPin;
+//! static LED: Shared<Output<'static, 8, Floating>> = Shared::new();
+//!
+//! # fn init(led: Output<'static, 8, Floating>) {
+//! LED.put(led);
+//! # }
+//! // In the interrupt handler:
+//! # fn handler() {
+//! LED.with(|led| led.set_high().ok());
+//! # }
+//! ```
+//!
+//! # Nested access
+//!
+//! Critical sections are re-entrant, thus calling [`Shared::with`] from inside another
+//! `with` closure does not deadlock. However, accessing the *same* `Shared` value in
+//! nested closures panics, because the value is already mutably borrowed. Finish one
+//! access before starting the next, or pass the borrowed value down instead.
+use core::cell::RefCell;
+use critical_section::Mutex;
+
+/// Value shared between main code and interrupt handlers.
+pub struct Shared<T> {
+    inner: Mutex<RefCell<Option<T>>>,
+}
+
+impl<T> Shared<T> {
+    /// Create an empty shared value slot.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            inner: Mutex::new(RefCell::new(None)),
+        }
+    }
+    /// Move a value into this slot, returning the previous value if any.
+    #[inline]
+    pub fn put(&self, val: T) -> Option<T> {
+        critical_section::with(|cs| self.inner.borrow_ref_mut(cs).replace(val))
+    }
+    /// Take the value out of this slot, leaving it empty.
+    #[inline]
+    pub fn take(&self) -> Option<T> {
+        critical_section::with(|cs| self.inner.borrow_ref_mut(cs).take())
+    }
+    /// Access the value in a critical section.
+    ///
+    /// Returns `None` without calling `f` if the slot is empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from inside another `with` closure of the same slot.
+    #[inline]
+    pub fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        critical_section::with(|cs| self.inner.borrow_ref_mut(cs).as_mut().map(f))
+    }
+}
+
+impl<T> Default for Shared<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Shared;
+
+    #[test]
+    fn struct_shared_with() {
+        let shared = Shared::<u32>::new();
+        assert_eq!(shared.with(|val| *val), None);
+        assert_eq!(shared.put(1), None);
+        assert_eq!(shared.with(|val| *val += 1), Some(()));
+        // Borrow is released after `with` returns, so that it can be accessed again.
+        assert_eq!(shared.with(|val| *val), Some(2));
+        assert_eq!(shared.put(5), Some(2));
+        assert_eq!(shared.take(), Some(5));
+        assert_eq!(shared.take(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn struct_shared_nested_access_panics() {
+        let shared = Shared::new();
+        shared.put(0u32);
+        shared.with(|_| shared.with(|_| ()));
+    }
+}
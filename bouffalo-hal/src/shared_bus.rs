@@ -0,0 +1,311 @@
+//! Bus sharing between several device drivers.
+//!
+//! `embedded-hal` device drivers take ownership of an [`SpiDevice`] or [`I2c`] value,
+//! thus one bus driver cannot be handed to several of them directly. [`SharedBus`]
+//! owns the bus and hands out cheap device proxies borrowing it; each SPI proxy drives
+//! its own chip select pin around transactions.
+//!
+//! A transaction holds the bus until it completes. If another transaction on the same
+//! bus is attempted meanwhile, e.g. from an interrupt handler, it fails with
+//! [`SharedBusError::Busy`] without touching the bus, so that the ongoing transfer is
+//! never corrupted. Interrupts stay enabled during transactions; only claiming and
+//! releasing the bus run in short critical sections.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use bouffalo_hal::{shared_bus::SharedBus, spi::CsPolarity};
+//! # use embedded_hal::{digital::OutputPin, spi::{SpiBus, SpiDevice}};
+//! # fn example(spi: impl SpiBus, cs0: impl OutputPin, cs1: impl OutputPin) {
+//! let bus = SharedBus::new(spi);
+//! let mut flash = bus.spi_device(cs0, CsPolarity::ActiveLow).unwrap();
+//! let mut display = bus.spi_device(cs1, CsPolarity::ActiveLow).unwrap();
+//! flash.write(&[0x9f]).ok();
+//! display.write(&[0x2c]).ok();
+//! # }
+//! ```
+//!
+//! [`SpiDevice`]: embedded_hal::spi::SpiDevice
+//! [`I2c`]: embedded_hal::i2c::I2c
+use crate::spi::{CsPolarity, DeviceError, bus_operations, cs_held};
+use core::cell::{Cell, UnsafeCell};
+use critical_section::Mutex;
+use embedded_hal::digital::OutputPin;
+
+/// Bus driver shared by several device proxies.
+pub struct SharedBus<BUS> {
+    bus: UnsafeCell<BUS>,
+    busy: Mutex<Cell<bool>>,
+}
+
+// SAFETY: the bus is only accessed by the context that claimed the busy flag.
+unsafe impl<BUS: Send> Sync for SharedBus<BUS> {}
+
+impl<BUS> SharedBus<BUS> {
+    /// Share `bus` among device proxies.
+    #[inline]
+    pub const fn new(bus: BUS) -> Self {
+        Self {
+            bus: UnsafeCell::new(bus),
+            busy: Mutex::new(Cell::new(false)),
+        }
+    }
+    /// Access the bus driver directly.
+    ///
+    /// Returns `None` without calling `f` if a transaction is in progress.
+    #[inline]
+    pub fn lock<R>(&self, f: impl FnOnce(&mut BUS) -> R) -> Option<R> {
+        let claimed = critical_section::with(|cs| !self.busy.borrow(cs).replace(true));
+        if !claimed {
+            return None;
+        }
+        let _guard = Release(&self.busy);
+        // SAFETY: the busy flag is claimed, thus no other reference to the bus exists.
+        Some(f(unsafe { &mut *self.bus.get() }))
+    }
+    /// Create an SPI device proxy with chip select pin `cs`, driving it idle.
+    #[inline]
+    pub fn spi_device<CS: OutputPin>(
+        &self,
+        mut cs: CS,
+        polarity: CsPolarity,
+    ) -> Result<SharedSpiDevice<'_, BUS, CS>, CS::Error> {
+        polarity.deassert(&mut cs)?;
+        Ok(SharedSpiDevice {
+            bus: self,
+            cs,
+            polarity,
+        })
+    }
+    /// Create an I2C device proxy.
+    #[inline]
+    pub fn i2c_device(&self) -> SharedI2cDevice<'_, BUS> {
+        SharedI2cDevice { bus: self }
+    }
+    /// Release the bus driver.
+    #[inline]
+    pub fn into_inner(self) -> BUS {
+        self.bus.into_inner()
+    }
+}
+
+/// Busy flag released on drop, also when the transaction panics.
+struct Release<'a>(&'a Mutex<Cell<bool>>);
+
+impl Drop for Release<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        critical_section::with(|cs| self.0.borrow(cs).set(false));
+    }
+}
+
+/// Error of device proxy on a shared bus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SharedBusError<E> {
+    /// Device error.
+    Device(E),
+    /// Another transaction on this bus is in progress.
+    Busy,
+}
+
+impl<E: embedded_hal::spi::Error> embedded_hal::spi::Error for SharedBusError<E> {
+    #[inline]
+    fn kind(&self) -> embedded_hal::spi::ErrorKind {
+        match self {
+            SharedBusError::Device(e) => e.kind(),
+            SharedBusError::Busy => embedded_hal::spi::ErrorKind::Other,
+        }
+    }
+}
+
+impl<E: embedded_hal::i2c::Error> embedded_hal::i2c::Error for SharedBusError<E> {
+    #[inline]
+    fn kind(&self) -> embedded_hal::i2c::ErrorKind {
+        match self {
+            SharedBusError::Device(e) => e.kind(),
+            SharedBusError::Busy => embedded_hal::i2c::ErrorKind::Other,
+        }
+    }
+}
+
+/// SPI device on a shared bus, with a software managed chip select pin.
+pub struct SharedSpiDevice<'a, BUS, CS> {
+    bus: &'a SharedBus<BUS>,
+    cs: CS,
+    polarity: CsPolarity,
+}
+
+impl<BUS, CS> SharedSpiDevice<'_, BUS, CS> {
+    /// Release the chip select pin.
+    #[inline]
+    pub fn free(self) -> CS {
+        self.cs
+    }
+}
+
+impl<BUS: embedded_hal::spi::ErrorType, CS: OutputPin> embedded_hal::spi::ErrorType
+    for SharedSpiDevice<'_, BUS, CS>
+{
+    type Error = SharedBusError<DeviceError<BUS::Error, CS::Error>>;
+}
+
+impl<BUS: embedded_hal::spi::SpiBus, CS: OutputPin> embedded_hal::spi::SpiDevice
+    for SharedSpiDevice<'_, BUS, CS>
+{
+    fn transaction(
+        &mut self,
+        operations: &mut [embedded_hal::spi::Operation<'_, u8>],
+    ) -> Result<(), Self::Error> {
+        let (cs, polarity) = (&mut self.cs, self.polarity);
+        self.bus
+            .lock(|bus| cs_held(bus, cs, polarity, |bus| bus_operations(bus, operations)))
+            .ok_or(SharedBusError::Busy)?
+            .map_err(SharedBusError::Device)
+    }
+}
+
+/// I2C device on a shared bus.
+pub struct SharedI2cDevice<'a, BUS> {
+    bus: &'a SharedBus<BUS>,
+}
+
+impl<BUS: embedded_hal::i2c::ErrorType> embedded_hal::i2c::ErrorType for SharedI2cDevice<'_, BUS> {
+    type Error = SharedBusError<BUS::Error>;
+}
+
+impl<BUS: embedded_hal::i2c::I2c> embedded_hal::i2c::I2c for SharedI2cDevice<'_, BUS> {
+    #[inline]
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [embedded_hal::i2c::Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.bus
+            .lock(|bus| bus.transaction(address, operations))
+            .ok_or(SharedBusError::Busy)?
+            .map_err(SharedBusError::Device)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SharedBus, SharedBusError};
+    use crate::spi::CsPolarity;
+    use core::cell::RefCell;
+    use core::convert::Infallible;
+    use embedded_hal::{
+        digital::{ErrorType, OutputPin},
+        i2c::{self, I2c},
+        spi::{self, SpiBus, SpiDevice},
+    };
+
+    /// Bus and pins recording events as `(device, byte)`; device 0 is the bus itself.
+    struct Log<'a>(&'a RefCell<[(u8, u8); 16]>, &'a RefCell<usize>);
+
+    impl Log<'_> {
+        fn push(&self, event: (u8, u8)) {
+            let mut len = self.1.borrow_mut();
+            self.0.borrow_mut()[*len] = event;
+            *len += 1;
+        }
+    }
+
+    struct MockBus<'a>(Log<'a>);
+
+    impl spi::ErrorType for MockBus<'_> {
+        type Error = Infallible;
+    }
+
+    impl SpiBus for MockBus<'_> {
+        fn read(&mut self, _: &mut [u8]) -> Result<(), Infallible> {
+            Ok(())
+        }
+        fn write(&mut self, words: &[u8]) -> Result<(), Infallible> {
+            words.iter().for_each(|&w| self.0.push((0, w)));
+            Ok(())
+        }
+        fn transfer(&mut self, _: &mut [u8], _: &[u8]) -> Result<(), Infallible> {
+            Ok(())
+        }
+        fn transfer_in_place(&mut self, _: &mut [u8]) -> Result<(), Infallible> {
+            Ok(())
+        }
+        fn flush(&mut self) -> Result<(), Infallible> {
+            Ok(())
+        }
+    }
+
+    impl i2c::ErrorType for MockBus<'_> {
+        type Error = Infallible;
+    }
+
+    impl I2c for MockBus<'_> {
+        fn transaction(
+            &mut self,
+            address: u8,
+            _: &mut [i2c::Operation<'_>],
+        ) -> Result<(), Infallible> {
+            self.0.push((0, address));
+            Ok(())
+        }
+    }
+
+    struct MockCs<'a>(u8, Log<'a>);
+
+    impl ErrorType for MockCs<'_> {
+        type Error = Infallible;
+    }
+
+    impl OutputPin for MockCs<'_> {
+        fn set_low(&mut self) -> Result<(), Infallible> {
+            self.1.push((self.0, 0));
+            Ok(())
+        }
+        fn set_high(&mut self) -> Result<(), Infallible> {
+            self.1.push((self.0, 1));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn struct_shared_bus_devices() {
+        let (events, len) = (RefCell::new([(0, 0); 16]), RefCell::new(0));
+        let log = || Log(&events, &len);
+        let bus = SharedBus::new(MockBus(log()));
+        let mut first = bus
+            .spi_device(MockCs(1, log()), CsPolarity::ActiveLow)
+            .unwrap();
+        let mut second = bus
+            .spi_device(MockCs(2, log()), CsPolarity::ActiveHigh)
+            .unwrap();
+        first.write(&[0xaa]).unwrap();
+        second.write(&[0x55]).unwrap();
+        let mut sensor = bus.i2c_device();
+        sensor.write(0x48, &[0]).unwrap();
+        let expected = [
+            (1, 1),
+            (2, 0),
+            (1, 0),
+            (0, 0xaa),
+            (1, 1),
+            (2, 1),
+            (0, 0x55),
+            (2, 0),
+            (0, 0x48),
+        ];
+        assert_eq!(events.borrow()[..*len.borrow()], expected);
+
+        // Transactions while the bus is held fail instead of interleaving.
+        assert_eq!(
+            bus.lock(|_| first.write(&[0x11])),
+            Some(Err(SharedBusError::Busy))
+        );
+        assert_eq!(
+            bus.lock(|_| sensor.write(0x48, &[0])),
+            Some(Err(SharedBusError::Busy))
+        );
+        assert_eq!(bus.lock(|_| bus.lock(|_| ())), Some(None));
+        assert_eq!(*len.borrow(), expected.len());
+        assert!(first.write(&[0x11]).is_ok());
+    }
+}
@@ -1,10 +1,13 @@
 //! Serial Peripheral Interface peripheral.
 
+use crate::clocks::Clocks;
 use crate::glb::{self, v2::SpiMode};
-use crate::gpio::{self, Alternate};
+use crate::gpio::{self, Alternate, CheckFunctions, PinConflict};
 use core::cmp::max;
 use core::ops::Deref;
+use embedded_hal::digital::OutputPin;
 use embedded_hal::spi::Mode;
+use embedded_time::rate::Hertz;
 use volatile_register::{RO, RW, WO};
 
 /// Serial Peripheral Interface registers.
@@ -30,9 +33,8 @@ pub struct RegisterBlock {
     pub fifo_config_0: RW<FifoConfig0>,
     /// First-in first-out queue configuration register 1.
     pub fifo_config_1: RW<FifoConfig1>,
-    /// First-in first-out queue write data register.
-    pub fifo_write: WO<u8>,
-    _reserved2: [u8; 0x3],
+    /// First-in first-out queue write data register, one frame per write.
+    pub fifo_write: WO<u32>,
     /// First-in first-out queue read data register.
     pub fifo_read: RO<u8>,
 }
@@ -259,6 +261,19 @@ pub enum FrameSize {
     ThirtyTwo,
 }
 
+impl FrameSize {
+    /// Number of bytes in one frame.
+    #[inline]
+    const fn bytes(self) -> usize {
+        match self {
+            FrameSize::Eight => 1,
+            FrameSize::Sixteen => 2,
+            FrameSize::TwentyFour => 3,
+            FrameSize::ThirtyTwo => 4,
+        }
+    }
+}
+
 /// Clock polarity settings.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Polarity {
@@ -623,6 +638,7 @@ impl FifoConfig1 {
 pub struct Spi<SPI, PADS, const I: usize> {
     spi: SPI,
     pads: PADS,
+    dummy: u8,
 }
 
 impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> Spi<SPI, PADS, I> {
@@ -642,20 +658,7 @@ impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> Spi<SPI, PADS, I>
             .set_frame_size(FrameSize::Eight)
             .disable_master();
 
-        config = match mode.phase {
-            embedded_hal::spi::Phase::CaptureOnFirstTransition => {
-                config.set_clock_phase(Phase::CaptureOnFirstTransition)
-            }
-
-            embedded_hal::spi::Phase::CaptureOnSecondTransition => {
-                config.set_clock_phase(Phase::CaptureOnSecondTransition)
-            }
-        };
-
-        config = match mode.polarity {
-            embedded_hal::spi::Polarity::IdleHigh => config.set_clock_polarity(Polarity::IdleHigh),
-            embedded_hal::spi::Polarity::IdleLow => config.set_clock_polarity(Polarity::IdleLow),
-        };
+        config = config_mode(config, mode);
 
         unsafe {
             glb.param_config
@@ -679,7 +682,76 @@ impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> Spi<SPI, PADS, I>
             spi.period_interval
                 .write(PeriodInterval(0).set_frame_interval(4));
         }
-        Spi { spi, pads }
+        Spi {
+            spi,
+            pads,
+            dummy: 0x00,
+        }
+    }
+    /// Create a new Serial Peripheral Interface instance, checking that no other function
+    /// claimed any of its pads.
+    ///
+    /// Returns the peripheral and pads along with the first [`PinConflict`] found,
+    /// without touching any register.
+    #[inline]
+    pub fn new_checked<GLB>(
+        spi: SPI,
+        pads: PADS,
+        mode: Mode,
+        glb: &GLB,
+    ) -> Result<Self, (SPI, PADS, PinConflict)>
+    where
+        PADS: Pads<I> + CheckFunctions,
+        GLB: Deref<Target = glb::v2::RegisterBlock>,
+    {
+        match pads.check_functions() {
+            Ok(()) => Ok(Self::new(spi, pads, mode, glb)),
+            Err(e) => Err((spi, pads, e)),
+        }
+    }
+    /// Set clock polarity and phase.
+    #[inline]
+    pub fn set_mode(&mut self, mode: Mode) {
+        unsafe { self.spi.config.modify(|config| config_mode(config, mode)) };
+    }
+    /// Set serial clock frequency, returning the actual frequency.
+    ///
+    /// The serial clock is divided from SPI source clock in `clocks`, each clock period
+    /// consists of two data phases with 1 to 256 source clock cycles. Frequencies out of
+    /// reachable range are clamped to the nearest reachable value; the actual frequency
+    /// is equal to or lower than `freq` if it is reachable.
+    ///
+    /// Returns [`Error::ClockSource`] without changing the frequency if the source clock
+    /// is not available, e.g. PLL is disabled.
+    #[inline]
+    pub fn set_frequency(&mut self, freq: Hertz, clocks: &Clocks) -> Result<Hertz, Error> {
+        let source = clocks.spi_clock::<I>().ok_or(Error::ClockSource)?;
+        let (phase_0, phase_1, actual) = period_for(source, freq);
+        unsafe {
+            self.spi.period_signal.write(
+                PeriodSignal(0)
+                    .set_data_phase_0(phase_0)
+                    .set_data_phase_1(phase_1)
+                    .set_start_condition(phase_0)
+                    .set_stop_condition(phase_1),
+            );
+            self.spi
+                .period_interval
+                .write(PeriodInterval(0).set_frame_interval(phase_0));
+        }
+        Ok(actual)
+    }
+    /// Set byte sent while reading beyond the end of write buffer in `transfer`.
+    ///
+    /// Defaults to `0x00`; many devices expect `0xFF` instead.
+    #[inline]
+    pub fn set_dummy_byte(&mut self, byte: u8) {
+        self.dummy = byte;
+    }
+    /// Get byte sent while reading beyond the end of write buffer in `transfer`.
+    #[inline]
+    pub fn dummy_byte(&self) -> u8 {
+        self.dummy
     }
 
     /// Release the SPI instance and return the pads.
@@ -687,12 +759,240 @@ impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> Spi<SPI, PADS, I>
     pub fn free(self) -> (SPI, PADS) {
         (self.spi, self.pads)
     }
+    /// Keep chip select asserted while running several bus operations in `f`.
+    ///
+    /// Chip select is asserted before `f` is called and deasserted after it returns,
+    /// including when `f` returns an error or panics, so that the device is never left
+    /// selected. This allows logically separate writes to form one device transaction.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use bouffalo_hal::spi::{CsPolarity, Spi};
+    /// # use embedded_hal::{digital::OutputPin, spi::SpiBus};
+    /// # fn f<SPI, PADS, CS>(spi: &mut Spi<SPI, PADS, 1>, cs: &mut CS)
+    /// # where SPI: core::ops::Deref<Target = bouffalo_hal::spi::RegisterBlock>, CS: OutputPin {
+    /// spi.with_cs_held(cs, CsPolarity::ActiveLow, |bus| {
+    ///     bus.write(&[0x02, 0x00, 0x10])?;
+    ///     bus.write(&[0xde, 0xad, 0xbe, 0xef])
+    /// })
+    /// .ok();
+    /// # }
+    /// ```
+    #[inline]
+    pub fn with_cs_held<CS: OutputPin, R>(
+        &mut self,
+        cs: &mut CS,
+        polarity: CsPolarity,
+        f: impl FnOnce(&mut Self) -> Result<R, Error>,
+    ) -> Result<R, DeviceError<Error, CS::Error>> {
+        cs_held(self, cs, polarity, f)
+    }
+    /// Write 16-bit words with the most significant byte first.
+    ///
+    /// See [`Spi::write_u16`] for how words are placed on the wire.
+    #[inline]
+    pub fn write_u16_be(&mut self, words: &[u16]) -> Result<(), Error> {
+        self.write_u16(words, ByteOrder::BigEndian)
+    }
+    /// Write 16-bit words with the least significant byte first.
+    ///
+    /// See [`Spi::write_u16`] for how words are placed on the wire.
+    #[inline]
+    pub fn write_u16_le(&mut self, words: &[u16]) -> Result<(), Error> {
+        self.write_u16(words, ByteOrder::LittleEndian)
+    }
+    /// Write 16-bit words with bytes on the wire in given order.
+    ///
+    /// Byte order on the wire is `order` under any frame size and byte inverse setting.
+    /// Each frame is written into the transmit queue as one word, whose bytes are sent
+    /// from the least significant one, or from the most significant one of the frame if
+    /// byte inverse is enabled; frame words are arranged to compensate this. Bit order
+    /// inside every byte follows bit inverse setting: most significant bit first if
+    /// disabled, least significant bit first if enabled.
+    ///
+    /// Total byte count of `words` must be a multiple of frame size, e.g. 8-bit frames
+    /// send each word as two frames, 16-bit frames send each word as one frame, while
+    /// 24-bit frames require a multiple of 3 words. Returns an error without writing
+    /// anything otherwise.
+    #[inline]
+    pub fn write_u16(&mut self, words: &[u16], order: ByteOrder) -> Result<(), Error> {
+        let frames = u16_fifo_frames(words, order, self.spi.config.read())?;
+        self.write_fifo(frames);
+        Ok(())
+    }
+    #[inline]
+    fn write_fifo(&mut self, frames: impl Iterator<Item = u32>) {
+        unsafe { self.spi.config.modify(|config| config.enable_master()) };
+
+        frames.for_each(|word| {
+            while self.spi.fifo_config_1.read().transmit_available_bytes() == 0 {
+                core::hint::spin_loop();
+            }
+            unsafe { self.spi.fifo_write.write(word) }
+            _ = self.spi.fifo_read.read();
+        });
+
+        unsafe { self.spi.config.modify(|config| config.disable_master()) };
+    }
+}
+
+/// Capacity of transmit first-in first-out queue in bytes.
+#[cfg(feature = "dma")]
+const TRANSMIT_FIFO_BYTES: u8 = 32;
+
+/// SPI0 transmit requests are routed to DMA0 and DMA1.
+#[cfg(feature = "dma")]
+unsafe impl<SPI: Deref<Target = RegisterBlock>, PADS> crate::dma::TransmitTarget
+    for Spi<SPI, PADS, 0>
+{
+    type Word = u8;
+    const REQUEST: crate::dma::Request =
+        crate::dma::Request::Dma01(crate::dma::Periph4DMA01::Spi0Tx);
+    #[inline]
+    fn transmit_address(&self) -> u32 {
+        &self.spi.fifo_write as *const _ as u32
+    }
+    #[inline]
+    fn enable_transmit_dma(&mut self) {
+        self.enable_dma_transmit()
+    }
+    #[inline]
+    fn disable_transmit_dma(&mut self) {
+        self.disable_dma_transmit()
+    }
+}
+
+/// SPI1 transmit requests are routed to DMA2 only.
+#[cfg(feature = "dma")]
+unsafe impl<SPI: Deref<Target = RegisterBlock>, PADS> crate::dma::TransmitTarget
+    for Spi<SPI, PADS, 1>
+{
+    type Word = u8;
+    const REQUEST: crate::dma::Request = crate::dma::Request::Dma2(crate::dma::Periph4DMA2::Spi1Tx);
+    #[inline]
+    fn transmit_address(&self) -> u32 {
+        &self.spi.fifo_write as *const _ as u32
+    }
+    #[inline]
+    fn enable_transmit_dma(&mut self) {
+        self.enable_dma_transmit()
+    }
+    #[inline]
+    fn disable_transmit_dma(&mut self) {
+        self.disable_dma_transmit()
+    }
+}
+
+#[cfg(feature = "dma")]
+impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> Spi<SPI, PADS, I> {
+    /// Let transmit queue request data from DMA, and start clocking out frames.
+    ///
+    /// Received frames are discarded while DMA transmit is enabled.
+    #[inline]
+    fn enable_dma_transmit(&mut self) {
+        unsafe {
+            self.spi.fifo_config_0.modify(|config| {
+                config
+                    .clear_transmit_fifo()
+                    .clear_receive_fifo()
+                    .enable_dma_transmit()
+            });
+            self.spi.config.modify(|config| config.enable_master());
+        }
+    }
+    /// Wait until the last frame is sent, then stop DMA requests and the bus.
+    #[inline]
+    fn disable_dma_transmit(&mut self) {
+        while self.spi.fifo_config_1.read().transmit_available_bytes() < TRANSMIT_FIFO_BYTES
+            || self.spi.bus_busy.read().is_bus_busy()
+        {
+            core::hint::spin_loop();
+        }
+        unsafe {
+            self.spi
+                .fifo_config_0
+                .modify(|config| config.disable_dma_transmit().clear_receive_fifo());
+            self.spi.config.modify(|config| config.disable_master());
+        }
+    }
+}
+
+/// Apply clock polarity and phase of `mode` onto `config`.
+#[inline]
+fn config_mode(config: Config, mode: Mode) -> Config {
+    let config = match mode.phase {
+        embedded_hal::spi::Phase::CaptureOnFirstTransition => {
+            config.set_clock_phase(Phase::CaptureOnFirstTransition)
+        }
+        embedded_hal::spi::Phase::CaptureOnSecondTransition => {
+            config.set_clock_phase(Phase::CaptureOnSecondTransition)
+        }
+    };
+    match mode.polarity {
+        embedded_hal::spi::Polarity::IdleHigh => config.set_clock_polarity(Polarity::IdleHigh),
+        embedded_hal::spi::Polarity::IdleLow => config.set_clock_polarity(Polarity::IdleLow),
+    }
+}
+
+/// Data phase register values and actual frequency for `freq` from `source` clock.
+#[inline]
+fn period_for(source: Hertz, freq: Hertz) -> (u8, u8, Hertz) {
+    // Round divider up so that the actual frequency does not exceed the requested one.
+    let divide = source.0.div_ceil(freq.0.max(1)).clamp(2, 512);
+    let phase_0 = divide / 2;
+    let phase_1 = divide - phase_0;
+    (
+        (phase_0 - 1) as u8,
+        (phase_1 - 1) as u8,
+        Hertz(source.0 / divide),
+    )
+}
+
+/// Byte order of multi-byte words on the wire.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ByteOrder {
+    /// Most significant byte first.
+    BigEndian,
+    /// Least significant byte first.
+    LittleEndian,
+}
+
+/// Frame words to push into transmit queue so that `words` are sent in `order`.
+#[inline]
+fn u16_fifo_frames(
+    words: &[u16],
+    order: ByteOrder,
+    config: Config,
+) -> Result<impl Iterator<Item = u32> + '_, Error> {
+    let frame = config.frame_size().bytes();
+    let len = words.len() * 2;
+    if !len.is_multiple_of(frame) {
+        return Err(Error::Other);
+    }
+    let byte_inverse = config.is_byte_inverse_enabled();
+    let wire_byte = move |wire: usize| {
+        let [msb, lsb] = words[wire / 2].to_be_bytes();
+        match (order, wire % 2) {
+            (ByteOrder::BigEndian, 0) | (ByteOrder::LittleEndian, 1) => msb,
+            _ => lsb,
+        }
+    };
+    Ok((0..len).step_by(frame).map(move |start| {
+        // Byte `k` of frame word is sent `k`-th, or last but `k` with byte inverse.
+        (0..frame).fold(0, |word, k| {
+            let wire = if byte_inverse { frame - 1 - k } else { k };
+            word | (wire_byte(start + wire) as u32) << (8 * k)
+        })
+    }))
 }
 
 /// SPI error.
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum Error {
+    /// Source clock is not available.
+    ClockSource,
     Other,
 }
 
@@ -701,7 +1001,7 @@ impl embedded_hal::spi::Error for Error {
     fn kind(&self) -> embedded_hal::spi::ErrorKind {
         use embedded_hal::spi::ErrorKind;
         match self {
-            Error::Other => ErrorKind::Other,
+            Error::ClockSource | Error::Other => ErrorKind::Other,
         }
     }
 }
@@ -731,17 +1031,7 @@ impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> embedded_hal::spi
     }
     #[inline]
     fn write(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
-        unsafe { self.spi.config.modify(|config| config.enable_master()) };
-
-        buf.iter().for_each(|&word| {
-            while self.spi.fifo_config_1.read().transmit_available_bytes() == 0 {
-                core::hint::spin_loop();
-            }
-            unsafe { self.spi.fifo_write.write(word) }
-            _ = self.spi.fifo_read.read();
-        });
-
-        unsafe { self.spi.config.modify(|config| config.disable_master()) };
+        self.write_fifo(buf.iter().map(|&byte| byte as u32));
         Ok(())
     }
     #[inline]
@@ -749,25 +1039,31 @@ impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> embedded_hal::spi
         const MAX_RETRY: usize = 1000;
         unsafe { self.spi.config.modify(|config| config.enable_master()) };
 
+        // Shorter buffer is padded: dummy bytes are written, extra received bytes dropped.
+        let len = max(write.len(), read.len());
         let (mut tx, mut rx) = (0, 0);
         let mut fifo_config = self.spi.fifo_config_1.read();
         let mut retry = 0;
-        while tx < write.len() || rx < read.len() {
+        while tx < len || rx < len {
             while fifo_config.receive_available_bytes() == 0
                 && fifo_config.transmit_available_bytes() == 0
             {
                 fifo_config = self.spi.fifo_config_1.read();
             }
-            if fifo_config.transmit_available_bytes() != 0 && tx < write.len() {
-                unsafe { self.spi.fifo_write.write(write[tx]) }
+            if fifo_config.transmit_available_bytes() != 0 && tx < len {
+                let byte = write.get(tx).copied().unwrap_or(self.dummy);
+                unsafe { self.spi.fifo_write.write(byte as u32) }
                 tx += 1;
             }
-            if fifo_config.receive_available_bytes() != 0 && rx < read.len() {
-                read[rx] = self.spi.fifo_read.read();
+            if fifo_config.receive_available_bytes() != 0 && rx < len {
+                let byte = self.spi.fifo_read.read();
+                if let Some(slot) = read.get_mut(rx) {
+                    *slot = byte;
+                }
                 rx += 1;
             }
             retry += 1;
-            if retry > MAX_RETRY * max(write.len(), read.len()) {
+            if retry > MAX_RETRY * len {
                 return Err(Error::Other);
             }
         }
@@ -790,7 +1086,7 @@ impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> embedded_hal::spi
                 fifo_config = self.spi.fifo_config_1.read();
             }
             if fifo_config.transmit_available_bytes() != 0 && tx < words.len() {
-                unsafe { self.spi.fifo_write.write(words[tx]) }
+                unsafe { self.spi.fifo_write.write(words[tx] as u32) }
                 tx += 1;
             }
             if fifo_config.receive_available_bytes() != 0 && rx < tx {
@@ -851,6 +1147,170 @@ impl<SPI: Deref<Target = RegisterBlock>, PADS, const I: usize> embedded_hal::spi
     }
 }
 
+/// Chip select signal polarity.
+///
+/// Hardware managed chip select signal of SPI peripheral is always active-low and has no
+/// polarity setting. Devices with active-high chip select should use [`ExclusiveDevice`]
+/// or [`Spi::with_cs_held`] with a GPIO output pad.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CsPolarity {
+    /// Chip select is low during transaction and high when idle.
+    ActiveLow,
+    /// Chip select is high during transaction and low when idle.
+    ActiveHigh,
+}
+
+impl CsPolarity {
+    /// Drive chip select pin to its active level.
+    #[inline]
+    pub fn assert<CS: OutputPin>(self, cs: &mut CS) -> Result<(), CS::Error> {
+        match self {
+            CsPolarity::ActiveLow => cs.set_low(),
+            CsPolarity::ActiveHigh => cs.set_high(),
+        }
+    }
+    /// Drive chip select pin to its idle level.
+    #[inline]
+    pub fn deassert<CS: OutputPin>(self, cs: &mut CS) -> Result<(), CS::Error> {
+        match self {
+            CsPolarity::ActiveLow => cs.set_high(),
+            CsPolarity::ActiveHigh => cs.set_low(),
+        }
+    }
+}
+
+/// SPI device with exclusive access to the bus and a software managed chip select pin.
+pub struct ExclusiveDevice<BUS, CS> {
+    bus: BUS,
+    cs: CS,
+    polarity: CsPolarity,
+}
+
+impl<BUS, CS: OutputPin> ExclusiveDevice<BUS, CS> {
+    /// Create a new SPI device, driving chip select pin to its idle level.
+    #[inline]
+    pub fn new(bus: BUS, mut cs: CS, polarity: CsPolarity) -> Result<Self, CS::Error> {
+        polarity.deassert(&mut cs)?;
+        Ok(Self { bus, cs, polarity })
+    }
+    /// Get chip select polarity of this device.
+    #[inline]
+    pub fn cs_polarity(&self) -> CsPolarity {
+        self.polarity
+    }
+    /// Release the SPI device and return the bus and chip select pin.
+    #[inline]
+    pub fn free(self) -> (BUS, CS) {
+        (self.bus, self.cs)
+    }
+}
+
+/// Error of SPI device with software managed chip select.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceError<BUS, CS> {
+    /// SPI bus error.
+    Spi(BUS),
+    /// Chip select pin error.
+    Cs(CS),
+}
+
+impl<BUS: embedded_hal::spi::Error, CS: core::fmt::Debug> embedded_hal::spi::Error
+    for DeviceError<BUS, CS>
+{
+    #[inline]
+    fn kind(&self) -> embedded_hal::spi::ErrorKind {
+        match self {
+            DeviceError::Spi(e) => e.kind(),
+            DeviceError::Cs(_) => embedded_hal::spi::ErrorKind::ChipSelectFault,
+        }
+    }
+}
+
+impl<BUS: embedded_hal::spi::ErrorType, CS: OutputPin> embedded_hal::spi::ErrorType
+    for ExclusiveDevice<BUS, CS>
+{
+    type Error = DeviceError<BUS::Error, CS::Error>;
+}
+
+impl<BUS: embedded_hal::spi::SpiBus, CS: OutputPin> embedded_hal::spi::SpiDevice
+    for ExclusiveDevice<BUS, CS>
+{
+    fn transaction(
+        &mut self,
+        operations: &mut [embedded_hal::spi::Operation<'_, u8>],
+    ) -> Result<(), Self::Error> {
+        cs_held(&mut self.bus, &mut self.cs, self.polarity, |bus| {
+            bus_operations(bus, operations)
+        })
+    }
+}
+
+/// Chip select deasserted on drop.
+struct CsGuard<'a, CS: OutputPin> {
+    cs: &'a mut CS,
+    polarity: CsPolarity,
+    armed: bool,
+}
+
+impl<CS: OutputPin> CsGuard<'_, CS> {
+    #[inline]
+    fn release(mut self) -> Result<(), CS::Error> {
+        self.armed = false;
+        self.polarity.deassert(self.cs)
+    }
+}
+
+impl<CS: OutputPin> Drop for CsGuard<'_, CS> {
+    #[inline]
+    fn drop(&mut self) {
+        if self.armed {
+            let _ = self.polarity.deassert(self.cs);
+        }
+    }
+}
+
+#[inline]
+pub(crate) fn cs_held<BUS, CS: OutputPin, R, E>(
+    bus: &mut BUS,
+    cs: &mut CS,
+    polarity: CsPolarity,
+    f: impl FnOnce(&mut BUS) -> Result<R, E>,
+) -> Result<R, DeviceError<E, CS::Error>> {
+    polarity.assert(cs).map_err(DeviceError::Cs)?;
+    let guard = CsGuard {
+        cs,
+        polarity,
+        armed: true,
+    };
+    let ans = f(bus);
+    let cs_ans = guard.release();
+    let ans = ans.map_err(DeviceError::Spi)?;
+    cs_ans.map_err(DeviceError::Cs)?;
+    Ok(ans)
+}
+
+#[inline]
+pub(crate) fn bus_operations<BUS: embedded_hal::spi::SpiBus>(
+    bus: &mut BUS,
+    operations: &mut [embedded_hal::spi::Operation<'_, u8>],
+) -> Result<(), BUS::Error> {
+    for op in operations {
+        match op {
+            embedded_hal::spi::Operation::Read(buf) => bus.read(buf)?,
+            embedded_hal::spi::Operation::Write(buf) => bus.write(buf)?,
+            embedded_hal::spi::Operation::Transfer(read, write) => bus.transfer(read, write)?,
+            embedded_hal::spi::Operation::TransferInPlace(buf) => bus.transfer_in_place(buf)?,
+            embedded_hal::spi::Operation::DelayNs(_delay) => {
+                for _ in 0..*_delay {
+                    // TODO: more accurate delay
+                    core::hint::spin_loop();
+                }
+            }
+        }
+    }
+    bus.flush()
+}
+
 // This part of implementation using `embedded_hal_027` is designed for backward compatibility of
 // ecosystem crates, as some of them depends on embedded-hal v0.2.7 traits.
 // We encourage ecosystem developers to use embedded-hal v1.0.0 traits; after that, this part of code
@@ -880,6 +1340,17 @@ impl<SPI: Deref<Target = RegisterBlock>, PINS, const I: usize>
 /// Valid SPI pads.
 pub trait Pads<const I: usize> {}
 
+impl<'a, 'b, const N1: usize, const N2: usize> Pads<1>
+    for (
+        Alternate<'a, N1, gpio::Spi<1>>,
+        Alternate<'b, N2, gpio::Spi<1>>,
+    )
+where
+    Alternate<'a, N1, gpio::Spi<1>>: HasClkSignal,
+    Alternate<'b, N2, gpio::Spi<1>>: HasMosiSignal,
+{
+}
+
 impl<'a, 'b, 'c, const N1: usize, const N2: usize, const N3: usize> Pads<1>
     for (
         Alternate<'a, N1, gpio::Spi<1>>,
@@ -973,9 +1444,22 @@ impl<'a> HasCsSignal for Alternate<'a, 44, gpio::Spi<1>> {}
 #[cfg(test)]
 mod tests {
     use super::{
-        BusBusy, Config, FifoConfig0, FifoConfig1, FrameSize, Interrupt, InterruptConfig,
-        PeriodInterval, PeriodSignal, Phase, Polarity, ReceiveIgnore, RegisterBlock, SlaveTimeout,
+        BusBusy, ByteOrder, Config, CsPolarity, Error, ExclusiveDevice, FifoConfig0, FifoConfig1,
+        FrameSize, Interrupt, InterruptConfig, PeriodInterval, PeriodSignal, Phase, Polarity,
+        ReceiveIgnore, RegisterBlock, SlaveTimeout, Spi, cs_held, period_for, u16_fifo_frames,
+    };
+    use crate::{clocks::ClockConfig, mock::MockRegisters};
+    #[cfg(feature = "glb-v2")]
+    use crate::{
+        glb::v2::{Function, MockRegisterBlock},
+        gpio::{Alternate, Padv2, PinConflict},
     };
+    use core::cell::Cell;
+    use embedded_hal::{
+        digital,
+        spi::{SpiBus, SpiDevice},
+    };
+    use embedded_time::rate::Hertz;
     use memoffset::offset_of;
 
     #[test]
@@ -1261,4 +1745,334 @@ mod tests {
         assert_eq!(config.0, 0x1f000000);
         assert_eq!(config.receive_threshold(), 0x1f);
     }
+
+    struct MockCs<'a>(&'a Cell<bool>);
+
+    impl digital::ErrorType for MockCs<'_> {
+        type Error = core::convert::Infallible;
+    }
+
+    impl digital::OutputPin for MockCs<'_> {
+        fn set_low(&mut self) -> Result<(), Self::Error> {
+            self.0.set(false);
+            Ok(())
+        }
+        fn set_high(&mut self) -> Result<(), Self::Error> {
+            self.0.set(true);
+            Ok(())
+        }
+    }
+
+    // Records chip select level seen by every written byte.
+    struct MockBus<'a> {
+        cs: &'a Cell<bool>,
+        seen: [Option<bool>; 4],
+        len: usize,
+    }
+
+    impl embedded_hal::spi::ErrorType for MockBus<'_> {
+        type Error = embedded_hal::spi::ErrorKind;
+    }
+
+    impl embedded_hal::spi::SpiBus for MockBus<'_> {
+        fn read(&mut self, _: &mut [u8]) -> Result<(), Self::Error> {
+            Err(embedded_hal::spi::ErrorKind::Overrun)
+        }
+        fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+            for _ in words {
+                self.seen[self.len] = Some(self.cs.get());
+                self.len += 1;
+            }
+            Ok(())
+        }
+        fn transfer(&mut self, _: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
+            self.write(write)
+        }
+        fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+            self.write(words)
+        }
+        fn flush(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn struct_exclusive_device_cs_polarity() {
+        for (polarity, active) in [
+            (CsPolarity::ActiveHigh, true),
+            (CsPolarity::ActiveLow, false),
+        ] {
+            let cs = Cell::new(active);
+            let bus = MockBus {
+                cs: &cs,
+                seen: [None; 4],
+                len: 0,
+            };
+            let mut device = ExclusiveDevice::new(bus, MockCs(&cs), polarity).unwrap();
+            assert_eq!(cs.get(), !active);
+            device.write(&[0x9f, 0x00]).unwrap();
+            assert_eq!(cs.get(), !active);
+            // Chip select is deasserted on error as well.
+            assert!(device.read(&mut [0; 2]).is_err());
+            assert_eq!(cs.get(), !active);
+            let (bus, _) = device.free();
+            assert_eq!(bus.seen, [Some(active), Some(active), None, None]);
+        }
+    }
+
+    // Wire bit sequence sent by hardware for frame words pushed into transmit queue.
+    fn wire_bits(fifo: impl Iterator<Item = u32>, config: Config) -> ([bool; 96], usize) {
+        let frame = config.frame_size().bytes();
+        let (mut bits, mut n) = ([false; 96], 0);
+        for word in fifo {
+            assert_eq!(word as u64 >> (8 * frame), 0, "frame word wider than frame");
+            for k in 0..frame {
+                let k = if config.is_byte_inverse_enabled() {
+                    frame - 1 - k
+                } else {
+                    k
+                };
+                let byte = (word >> (8 * k)) as u8;
+                for b in 0..8 {
+                    let shift = if config.is_bit_inverse_enabled() {
+                        b
+                    } else {
+                        7 - b
+                    };
+                    bits[n] = byte & (1 << shift) != 0;
+                    n += 1;
+                }
+            }
+        }
+        (bits, n)
+    }
+
+    #[test]
+    fn function_u16_fifo_frames_matrix() {
+        let words = [0x1234, 0xabcd, 0x00ff, 0x8001, 0x5a5a, 0xf00f];
+        let frames = [
+            FrameSize::Eight,
+            FrameSize::Sixteen,
+            FrameSize::TwentyFour,
+            FrameSize::ThirtyTwo,
+        ];
+        for lsb_first in [false, true] {
+            for order in [ByteOrder::BigEndian, ByteOrder::LittleEndian] {
+                for frame in frames {
+                    for byte_inverse in [false, true] {
+                        let mut config = Config(0).set_frame_size(frame);
+                        if lsb_first {
+                            config = config.enable_bit_inverse();
+                        }
+                        if byte_inverse {
+                            config = config.enable_byte_inverse();
+                        }
+                        let fifo = u16_fifo_frames(&words, order, config).unwrap();
+                        let (bits, n) = wire_bits(fifo, config);
+                        assert_eq!(n, 96);
+                        let mut i = 0;
+                        for word in words {
+                            let bytes = match order {
+                                ByteOrder::BigEndian => word.to_be_bytes(),
+                                ByteOrder::LittleEndian => word.to_le_bytes(),
+                            };
+                            for byte in bytes {
+                                for b in 0..8 {
+                                    let shift = if lsb_first { b } else { 7 - b };
+                                    assert_eq!(
+                                        bits[i],
+                                        byte & (1 << shift) != 0,
+                                        "{:?} {:?} lsb_first {} byte_inverse {}",
+                                        order,
+                                        frame,
+                                        lsb_first,
+                                        byte_inverse
+                                    );
+                                    i += 1;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn function_u16_fifo_frames_frame_size() {
+        // 8-bit frame mode sends a 16-bit value as two frames.
+        let config = Config(0).set_frame_size(FrameSize::Eight);
+        let mut fifo = u16_fifo_frames(&[0x1234], ByteOrder::BigEndian, config).unwrap();
+        assert_eq!(
+            (fifo.next(), fifo.next(), fifo.next()),
+            (Some(0x12), Some(0x34), None)
+        );
+        let mut fifo = u16_fifo_frames(&[0x1234], ByteOrder::LittleEndian, config).unwrap();
+        assert_eq!(
+            (fifo.next(), fifo.next(), fifo.next()),
+            (Some(0x34), Some(0x12), None)
+        );
+        // 16-bit frame mode sends a 16-bit value as one frame word.
+        let config = Config(0).set_frame_size(FrameSize::Sixteen);
+        let mut fifo = u16_fifo_frames(&[0x1234], ByteOrder::BigEndian, config).unwrap();
+        assert_eq!((fifo.next(), fifo.next()), (Some(0x3412), None));
+        let mut fifo = u16_fifo_frames(&[0x1234], ByteOrder::LittleEndian, config).unwrap();
+        assert_eq!((fifo.next(), fifo.next()), (Some(0x1234), None));
+        let config = config.enable_byte_inverse();
+        let mut fifo = u16_fifo_frames(&[0x1234], ByteOrder::BigEndian, config).unwrap();
+        assert_eq!((fifo.next(), fifo.next()), (Some(0x1234), None));
+        // Partial frames are rejected.
+        let config = Config(0).set_frame_size(FrameSize::ThirtyTwo);
+        assert!(u16_fifo_frames(&[0x1234], ByteOrder::BigEndian, config).is_err());
+        let config = Config(0).set_frame_size(FrameSize::TwentyFour);
+        assert!(u16_fifo_frames(&[0x1234, 0x5678], ByteOrder::BigEndian, config).is_err());
+    }
+
+    #[test]
+    fn function_period_for() {
+        let source = Hertz(160_000_000);
+        assert_eq!(
+            period_for(source, Hertz(40_000_000)),
+            (1, 1, Hertz(40_000_000))
+        );
+        assert_eq!(
+            period_for(source, Hertz(16_000_000)),
+            (4, 4, Hertz(16_000_000))
+        );
+        // Odd dividers put the extra cycle into phase 1.
+        assert_eq!(
+            period_for(source, Hertz(32_000_000)),
+            (1, 2, Hertz(32_000_000))
+        );
+        // Not reachable exactly: round down the frequency.
+        assert_eq!(
+            period_for(source, Hertz(30_000_000)),
+            (2, 2, Hertz(26_666_666))
+        );
+        // Out of range: clamp to the fastest and slowest clocks.
+        assert_eq!(
+            period_for(source, Hertz(200_000_000)),
+            (0, 0, Hertz(80_000_000))
+        );
+        assert_eq!(period_for(source, Hertz(1_000)), (255, 255, Hertz(312_500)));
+        assert_eq!(period_for(source, Hertz(0)), (255, 255, Hertz(312_500)));
+    }
+
+    #[cfg(feature = "glb-v2")]
+    #[test]
+    fn function_spi_new_checked() {
+        let glb = MockRegisterBlock::new();
+        let mock = MockRegisters::<RegisterBlock>::new();
+        let pads = (
+            Alternate::from(Padv2::<3, _>::__from_glb(&glb).into_spi::<1>()),
+            Alternate::from(Padv2::<1, _>::__from_glb(&glb).into_spi::<1>()),
+            Alternate::from(Padv2::<0, _>::__from_glb(&glb).into_spi::<1>()),
+        );
+        // Chip select pin taken over by I2C: nothing is configured.
+        let _ = Padv2::<0, _>::__from_glb(&glb).into_i2c::<0>();
+        let Err((spi, pads, conflict)) =
+            Spi::new_checked(mock.registers(), pads, embedded_hal::spi::MODE_0, &glb)
+        else {
+            panic!("pin conflict is not detected");
+        };
+        assert_eq!(
+            conflict,
+            PinConflict {
+                pin: 0,
+                function: Function::I2c0,
+            }
+        );
+        assert_eq!(mock.raw(offset_of!(RegisterBlock, period_signal)), 0);
+
+        let (clk, mosi, _) = pads;
+        let cs = Alternate::from(Padv2::<0, _>::__from_glb(&glb).into_spi::<1>());
+        assert!(Spi::new_checked(spi, (clk, mosi, cs), embedded_hal::spi::MODE_0, &glb).is_ok());
+        assert_ne!(mock.raw(offset_of!(RegisterBlock, period_signal)), 0);
+    }
+
+    #[test]
+    fn function_transfer_pads_buffers() {
+        // FIFOs always show one free and one received byte.
+        let mock = MockRegisters::<RegisterBlock>::new();
+        mock.set_raw(offset_of!(RegisterBlock, fifo_config_1), 0x0101);
+        mock.set_raw(offset_of!(RegisterBlock, fifo_read), 0xa5);
+        let mut spi = Spi::<_, (), 1> {
+            spi: mock.registers(),
+            pads: (),
+            dummy: 0,
+        };
+        assert_eq!(spi.dummy_byte(), 0x00);
+        spi.set_dummy_byte(0xff);
+
+        // Read is longer: the rest is clocked out with dummy bytes.
+        let mut read = [0; 4];
+        SpiBus::transfer(&mut spi, &mut read, &[0x12, 0x34]).unwrap();
+        assert_eq!(read, [0xa5; 4]);
+        assert_eq!(mock.raw(offset_of!(RegisterBlock, fifo_write)), 0xff);
+
+        // Write is longer: extra received bytes are dropped.
+        let mut read = [0; 1];
+        SpiBus::transfer(&mut spi, &mut read, &[0x12, 0x34, 0x56]).unwrap();
+        assert_eq!(read, [0xa5]);
+        assert_eq!(mock.raw(offset_of!(RegisterBlock, fifo_write)), 0x56);
+
+        // 16-bit frames take one whole queue word per frame, sent from byte 0.
+        unsafe {
+            spi.spi
+                .config
+                .write(Config(0).set_frame_size(FrameSize::Sixteen))
+        };
+        spi.write_u16_be(&[0xbeef]).unwrap();
+        assert_eq!(mock.raw(offset_of!(RegisterBlock, fifo_write)), 0xefbe);
+
+        // No source clock without PLL; timing registers stay untouched.
+        let clocks = ClockConfig::new(Hertz(40_000_000)).freeze();
+        let ans = spi.set_frequency(Hertz(1_000_000), &clocks);
+        assert!(matches!(ans, Err(Error::ClockSource)));
+        assert_eq!(mock.raw(offset_of!(RegisterBlock, period_signal)), 0);
+    }
+
+    #[test]
+    fn function_cs_held() {
+        extern crate std;
+        use embedded_hal::spi::SpiBus;
+        let cs = Cell::new(true);
+        let mut bus = MockBus {
+            cs: &cs,
+            seen: [None; 4],
+            len: 0,
+        };
+        let ans = cs_held(&mut bus, &mut MockCs(&cs), CsPolarity::ActiveLow, |bus| {
+            bus.write(&[0x02, 0x00])?;
+            bus.write(&[0x10])?;
+            Ok::<_, embedded_hal::spi::ErrorKind>(3)
+        });
+        assert!(matches!(ans, Ok(3)));
+        assert!(cs.get());
+        // Chip select stays low across separate writes.
+        assert_eq!(bus.seen, [Some(false), Some(false), Some(false), None]);
+
+        let ans = cs_held(&mut bus, &mut MockCs(&cs), CsPolarity::ActiveLow, |bus| {
+            bus.write(&[0x03])?;
+            bus.read(&mut [0; 1])
+        });
+        assert!(ans.is_err());
+        assert!(cs.get());
+        assert_eq!(bus.seen[3], Some(false));
+
+        let mut pin = MockCs(&cs);
+        let ans = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _ = cs_held(
+                &mut (),
+                &mut pin,
+                CsPolarity::ActiveLow,
+                |_| -> Result<(), ()> {
+                    assert!(!cs.get());
+                    panic!("error inside transaction");
+                },
+            );
+        }));
+        assert!(ans.is_err());
+        assert!(cs.get());
+    }
 }
@@ -1,4 +1,9 @@
 //! Universal Asynchronous Receiver/Transmitter.
+//!
+//! Pads are routed to UART peripherals by signal multiplexers; pad `ioN` carries
+//! multiplexer signal `N % 12`. Transmit and receive pads are configured independently,
+//! thus they may be placed in different GPIO banks, e.g. transmit on `io14` through
+//! `sig2` and receive on `io40` through `sig4`.
 use crate::clocks::Clocks;
 use core::ops::Deref;
 
@@ -14,7 +19,9 @@ mod error;
 pub use error::*;
 mod blocking;
 pub use blocking::*;
+#[cfg(feature = "async")]
 mod asynch;
+#[cfg(feature = "async")]
 pub use asynch::*;
 
 /// Extend constructor to owned UART register blocks.
@@ -29,6 +36,7 @@ pub trait UartExt<PADS>: Sized {
     where
         PADS: Pads<I>;
     /// Creates an interrupt driven async/await serial instance without DMA configurations.
+    #[cfg(feature = "async")]
     fn with_interrupt<const I: usize>(
         self,
         config: Config,
@@ -53,6 +61,7 @@ impl<UART: Deref<Target = RegisterBlock>, PADS> UartExt<PADS> for UART {
     {
         BlockingSerial::freerun(self, config, pads, clocks)
     }
+    #[cfg(feature = "async")]
     #[inline]
     fn with_interrupt<const I: usize>(
         self,
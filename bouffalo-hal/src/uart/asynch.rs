@@ -44,6 +44,13 @@ impl<UART: Deref<Target = RegisterBlock>, PADS> AsyncSerial<UART, PADS> {
         unsafe { uart.transmit_config.write(transmit_config.enable_freerun()) };
         // Configure receive feature.
         unsafe { uart.receive_config.write(receive_config) };
+        // Keep ready interrupts masked until a transfer waits on them.
+        unsafe {
+            uart.interrupt_mask.modify(|val| {
+                val.mask_interrupt(Interrupt::TransmitFifoReady)
+                    .mask_interrupt(Interrupt::ReceiveFifoReady)
+            })
+        };
 
         state
             .ref_to_serial
@@ -78,6 +85,9 @@ impl SerialState {
         }
     }
     /// Use this waker set to handle interrupt.
+    ///
+    /// Ready interrupts are level triggered; each one is masked here after waking its
+    /// task, and unmasked again by the next transfer which has to wait.
     #[inline]
     pub fn on_interrupt(&self) {
         let uart =
@@ -88,16 +98,22 @@ impl SerialState {
             (Interrupt::TransmitFifoReady, &self.transmit_ready),
         ] {
             if state.has_interrupt(interrupt) {
-                waker.wake();
                 unsafe {
+                    uart.interrupt_mask
+                        .modify(|val| val.mask_interrupt(interrupt));
                     uart.interrupt_clear
                         .write(InterruptClear::default().clear_interrupt(interrupt))
                 };
+                waker.wake();
             }
         }
     }
 }
 
+/// Wait until transmit queue has free space or receive queue has data.
+///
+/// The ready interrupt is armed only while the future is pending, and masked again
+/// once it resolves or is dropped, so a cancelled transfer leaves no interrupt armed.
 struct WaitForInterrupt<'r> {
     uart: &'r RegisterBlock,
     interrupt: Interrupt,
@@ -117,6 +133,22 @@ impl<'r> WaitForInterrupt<'r> {
             registry,
         }
     }
+    #[inline]
+    fn is_ready(&self) -> bool {
+        let fifo = self.uart.fifo_config_1.read();
+        match self.interrupt {
+            Interrupt::TransmitFifoReady => fifo.transmit_available_bytes() != 0,
+            _ => fifo.receive_available_bytes() != 0,
+        }
+    }
+    #[inline]
+    fn disarm(&self) {
+        unsafe {
+            self.uart
+                .interrupt_mask
+                .modify(|val| val.mask_interrupt(self.interrupt))
+        };
+    }
 }
 
 impl Future for WaitForInterrupt<'_> {
@@ -124,20 +156,36 @@ impl Future for WaitForInterrupt<'_> {
 
     #[inline]
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        if self
-            .uart
-            .interrupt_state
-            .read()
-            .has_interrupt(self.interrupt)
-        {
+        if self.is_ready() {
+            self.disarm();
+            return Poll::Ready(());
+        }
+        self.registry.register(cx.waker());
+        unsafe {
+            self.uart
+                .interrupt_enable
+                .modify(|val| val.enable_interrupt(self.interrupt));
+            self.uart
+                .interrupt_mask
+                .modify(|val| val.unmask_interrupt(self.interrupt));
+        }
+        // Queue may have become ready before the interrupt is armed.
+        if self.is_ready() {
+            self.disarm();
             Poll::Ready(())
         } else {
-            self.registry.register(cx.waker());
             Poll::Pending
         }
     }
 }
 
+impl Drop for WaitForInterrupt<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        self.disarm();
+    }
+}
+
 #[inline]
 async fn uart_write_async(
     uart: &RegisterBlock,
@@ -148,11 +196,9 @@ async fn uart_write_async(
         0 => return Ok(0),
         _ => buf,
     };
-    unsafe {
-        uart.interrupt_enable
-            .modify(|val| val.enable_interrupt(Interrupt::TransmitFifoReady))
-    };
     WaitForInterrupt::new(uart, Interrupt::TransmitFifoReady, registry).await;
+    // Nothing is written before the wait resolves, thus dropping this future at any
+    // await point sends either no byte or a whole chunk.
     let len = core::cmp::min(
         uart.fifo_config_1.read().transmit_available_bytes() as usize,
         buf.len(),
@@ -173,11 +219,8 @@ async fn uart_read_async(
         0 => return Ok(0),
         _ => buf,
     };
-    unsafe {
-        uart.interrupt_enable
-            .modify(|val| val.enable_interrupt(Interrupt::ReceiveFifoReady))
-    };
     WaitForInterrupt::new(uart, Interrupt::ReceiveFifoReady, registry).await;
+    // Received bytes stay in queue if this future is dropped while waiting.
     let len = core::cmp::min(
         uart.fifo_config_1.read().receive_available_bytes() as usize,
         buf.len(),
@@ -209,3 +252,71 @@ impl<UART: Deref<Target = RegisterBlock>, PADS> embedded_io_async::Read
         uart_read_async(&self.uart, buf, &self.state.receive_ready).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Interrupt, RegisterBlock, SerialState, WaitForInterrupt};
+    use crate::mock::MockRegisters;
+    use core::{
+        future::Future,
+        pin::pin,
+        sync::atomic::Ordering,
+        task::{Context, Poll, Waker},
+    };
+
+    #[test]
+    fn function_wait_for_interrupt() {
+        let mock = MockRegisters::<RegisterBlock>::new();
+        let uart = mock.registers();
+        let state = SerialState::new();
+        state
+            .ref_to_serial
+            .store(uart as *const _ as usize, Ordering::Release);
+        let mut cx = Context::from_waker(Waker::noop());
+        mock.set_raw(0x24, 0xfff);
+
+        // Idle receive queue arms the interrupt and parks.
+        let fut = WaitForInterrupt::new(uart, Interrupt::ReceiveFifoReady, &state.receive_ready);
+        let mut fut = pin!(fut);
+        assert_eq!(fut.as_mut().poll(&mut cx), Poll::Pending);
+        let mask = uart.interrupt_mask.read();
+        assert!(!mask.is_interrupt_masked(Interrupt::ReceiveFifoReady));
+        assert!(mask.is_interrupt_masked(Interrupt::TransmitFifoReady));
+        assert!(
+            uart.interrupt_enable
+                .read()
+                .is_interrupt_enabled(Interrupt::ReceiveFifoReady)
+        );
+
+        // Interrupt handler masks the level triggered interrupt.
+        mock.set_raw(0x20, 1 << Interrupt::ReceiveFifoReady as u32);
+        state.on_interrupt();
+        assert!(
+            uart.interrupt_mask
+                .read()
+                .is_interrupt_masked(Interrupt::ReceiveFifoReady)
+        );
+        mock.set_raw(0x84, 3 << 8);
+        assert_eq!(fut.as_mut().poll(&mut cx), Poll::Ready(()));
+
+        // Cancelled wait leaves the interrupt masked.
+        mock.set_raw(0x84, 0);
+        {
+            let fut =
+                WaitForInterrupt::new(uart, Interrupt::TransmitFifoReady, &state.transmit_ready);
+            let mut fut = pin!(fut);
+            assert_eq!(fut.as_mut().poll(&mut cx), Poll::Pending);
+            assert!(
+                !uart
+                    .interrupt_mask
+                    .read()
+                    .is_interrupt_masked(Interrupt::TransmitFifoReady)
+            );
+        }
+        assert!(
+            uart.interrupt_mask
+                .read()
+                .is_interrupt_masked(Interrupt::TransmitFifoReady)
+        );
+    }
+}
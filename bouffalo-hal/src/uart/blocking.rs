@@ -1,5 +1,9 @@
-use super::{Config, ConfigError, Error, Pads, RegisterBlock, uart_config};
+use super::{
+    BitPeriod, Config, ConfigError, DataConfig, Error, Pads, ReceiveConfig, RegisterBlock,
+    TransmitConfig, uart_config,
+};
 use crate::clocks::Clocks;
+use crate::gpio::CheckFunctions;
 use core::ops::Deref;
 
 /// Managed blocking serial peripheral.
@@ -21,9 +25,45 @@ impl<UART: Deref<Target = RegisterBlock>, PADS> BlockingSerial<UART, PADS> {
         PADS: Pads<I>,
     {
         // Calculate transmit interval and register values from configuration.
-        let (bit_period, data_config, transmit_config, receive_config) =
-            uart_config::<I, PADS>(config, &clocks)?;
+        let registers = uart_config::<I, PADS>(config, &clocks)?;
+        Ok(Self::init(uart, pads, registers))
+    }
+
+    /// Creates a polling serial instance, checking that no other function claimed any of
+    /// its pads.
+    ///
+    /// Returns the peripheral and pads along with [`ConfigError::PinConflict`] or any
+    /// other configuration error, without touching any register.
+    #[inline]
+    pub fn freerun_checked<const I: usize>(
+        uart: UART,
+        config: Config,
+        pads: PADS,
+        clocks: &Clocks,
+    ) -> Result<Self, (UART, PADS, ConfigError)>
+    where
+        PADS: Pads<I> + CheckFunctions,
+    {
+        if let Err(e) = pads.check_functions() {
+            return Err((uart, pads, ConfigError::PinConflict(e)));
+        }
+        match uart_config::<I, PADS>(config, clocks) {
+            Ok(registers) => Ok(Self::init(uart, pads, registers)),
+            Err(e) => Err((uart, pads, e)),
+        }
+    }
 
+    #[inline]
+    fn init(
+        uart: UART,
+        pads: PADS,
+        (bit_period, data_config, transmit_config, receive_config): (
+            BitPeriod,
+            DataConfig,
+            TransmitConfig,
+            ReceiveConfig,
+        ),
+    ) -> Self {
         // Write bit period.
         unsafe { uart.bit_period.write(bit_period) };
         // Write the bit-order.
@@ -36,7 +76,7 @@ impl<UART: Deref<Target = RegisterBlock>, PADS> BlockingSerial<UART, PADS> {
         // Configure receive feature.
         unsafe { uart.receive_config.write(receive_config) };
 
-        Ok(Self { uart, pads })
+        Self { uart, pads }
     }
 
     /// Release serial instance and return its peripheral and pads.
@@ -53,6 +93,34 @@ impl<UART: Deref<Target = RegisterBlock>, PADS> BlockingSerial<UART, PADS> {
     {
         self.pads.split(self.uart)
     }
+
+    /// Discard all bytes pending in the receive FIFO.
+    ///
+    /// An active receive DMA transfer would keep refilling the queue, thus it is
+    /// aborted before the FIFO is cleared.
+    #[inline]
+    pub fn flush_rx(&mut self) {
+        uart_flush_rx(&self.uart)
+    }
+
+    /// Wait until all bytes in the transmit FIFO have been sent onto the UART bus.
+    ///
+    /// An active transmit DMA transfer is aborted first, so that this function only
+    /// waits for bytes already queued in the FIFO. Bytes the DMA channel has not yet
+    /// moved into the FIFO are not sent, and the DMA channel itself is left running;
+    /// stop or reconfigure it, then enable transmit DMA again to resume transfers.
+    #[inline]
+    pub fn flush_tx(&mut self) {
+        uart_flush_tx(&self.uart)
+    }
+
+    /// Reset both transmit and receive FIFOs, discarding all pending data.
+    ///
+    /// Active DMA transfers on either direction are aborted before clearing.
+    #[inline]
+    pub fn reset_fifos(&mut self) {
+        uart_reset_fifos(&self.uart)
+    }
 }
 
 /// Transmit half from splitted serial structure.
@@ -67,6 +135,71 @@ pub struct BlockingReceiveHalf<UART, PADS> {
     pub(crate) _pads: PADS,
 }
 
+impl<UART: Deref<Target = RegisterBlock>, PADS> BlockingTransmitHalf<UART, PADS> {
+    /// Wait until all bytes in the transmit FIFO have been sent onto the UART bus.
+    ///
+    /// An active transmit DMA transfer is aborted first; see
+    /// [`BlockingSerial::flush_tx`] for what happens to bytes not yet in the FIFO.
+    #[inline]
+    pub fn flush_tx(&mut self) {
+        uart_flush_tx(&self.uart)
+    }
+}
+
+impl<UART: Deref<Target = RegisterBlock>, PADS> BlockingReceiveHalf<UART, PADS> {
+    /// Discard all bytes pending in the receive FIFO.
+    ///
+    /// An active receive DMA transfer is aborted first.
+    #[inline]
+    pub fn flush_rx(&mut self) {
+        uart_flush_rx(&self.uart)
+    }
+}
+
+#[inline]
+fn uart_flush_rx(uart: &RegisterBlock) {
+    // Stop DMA from pushing new bytes into the queue, then drop everything in it.
+    unsafe {
+        uart.fifo_config_0
+            .modify(|val| val.disable_receive_dma().clear_receive_fifo())
+    };
+}
+
+/// Depth of the transmit FIFO; it is empty when this many bytes are available.
+const TRANSMIT_FIFO_DEPTH: u8 = 32;
+
+#[inline]
+fn uart_flush_tx(uart: &RegisterBlock) {
+    uart_flush_tx_with(uart, core::hint::spin_loop)
+}
+
+#[inline]
+fn uart_flush_tx_with(uart: &RegisterBlock, mut spin: impl FnMut()) {
+    // Stop the DMA channel from refilling the FIFO while we wait for it to drain.
+    if uart.fifo_config_0.read().is_transmit_dma_enabled() {
+        unsafe { uart.fifo_config_0.modify(|val| val.disable_transmit_dma()) };
+    }
+    while uart.fifo_config_1.read().transmit_available_bytes() != TRANSMIT_FIFO_DEPTH {
+        spin();
+    }
+    // The last byte may still be shifting out after it left the FIFO.
+    while uart.bus_state.read().transmit_busy() {
+        spin();
+    }
+}
+
+#[inline]
+fn uart_reset_fifos(uart: &RegisterBlock) {
+    unsafe {
+        uart.fifo_config_0.modify(|val| {
+            val.disable_transmit_dma()
+                .disable_receive_dma()
+                .clear_transmit_fifo()
+                .clear_receive_fifo()
+        })
+    };
+}
+
 #[inline]
 fn uart_write(uart: &RegisterBlock, buf: &[u8]) -> Result<usize, Error> {
     while uart.fifo_config_1.read().transmit_available_bytes() == 0 {
@@ -93,9 +226,9 @@ fn uart_write_nb(uart: &RegisterBlock, word: u8) -> nb::Result<(), Error> {
 
 #[inline]
 fn uart_flush(uart: &RegisterBlock) -> Result<(), Error> {
-    // There are maximum 32 bytes in transmit FIFO queue, wait until all bytes are available,
-    // meaning that all data in queue has been sent into UART bus.
-    while uart.fifo_config_1.read().transmit_available_bytes() != 32 {
+    // Wait until the whole transmit FIFO is available, meaning that all data in queue
+    // has been sent into UART bus.
+    while uart.fifo_config_1.read().transmit_available_bytes() != TRANSMIT_FIFO_DEPTH {
         core::hint::spin_loop();
     }
     Ok(())
@@ -103,7 +236,7 @@ fn uart_flush(uart: &RegisterBlock) -> Result<(), Error> {
 
 #[inline]
 fn uart_flush_nb(uart: &RegisterBlock) -> nb::Result<(), Error> {
-    if uart.fifo_config_1.read().transmit_available_bytes() != 32 {
+    if uart.fifo_config_1.read().transmit_available_bytes() != TRANSMIT_FIFO_DEPTH {
         return Err(nb::Error::WouldBlock);
     }
     Ok(())
@@ -240,3 +373,65 @@ impl<UART: Deref<Target = RegisterBlock>, PADS> embedded_hal_nb::serial::Read
         uart_read_nb(&self.uart)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        RegisterBlock, TRANSMIT_FIFO_DEPTH, uart_flush_rx, uart_flush_tx, uart_flush_tx_with,
+        uart_reset_fifos,
+    };
+    use crate::mock::MockRegisters;
+
+    #[test]
+    fn function_uart_flush_rx() {
+        let mock = MockRegisters::<RegisterBlock>::new();
+        // Receive DMA enabled, transmit DMA enabled.
+        mock.set_raw(0x80, 0b11);
+        uart_flush_rx(mock.registers());
+        // Receive DMA is aborted and receive FIFO is cleared; transmit side untouched.
+        assert_eq!(mock.raw(0x80), 0b1001);
+    }
+
+    #[test]
+    fn function_uart_flush_tx() {
+        let mock = MockRegisters::<RegisterBlock>::new();
+        mock.set_raw(0x80, 0b11);
+        // Transmit FIFO is empty, all 32 bytes available.
+        mock.set_raw(0x84, 32);
+        uart_flush_tx(mock.registers());
+        assert_eq!(mock.raw(0x80), 0b10);
+    }
+
+    #[test]
+    fn function_uart_flush_tx_waits_for_fifo_and_bus() {
+        let mock = MockRegisters::<RegisterBlock>::new();
+        mock.set_raw(0x80, 0b11);
+        // Eight bytes still queued, and the transmitter is busy.
+        mock.set_raw(0x84, (TRANSMIT_FIFO_DEPTH - 8) as u32);
+        mock.set_raw(0x30, 1);
+        let mut spins = 0;
+        uart_flush_tx_with(mock.registers(), || {
+            spins += 1;
+            // Transmit DMA must be aborted before waiting starts.
+            assert_eq!(mock.raw(0x80) & 0b1, 0);
+            let available = mock.raw(0x84);
+            if available < TRANSMIT_FIFO_DEPTH as u32 {
+                // One byte leaves the FIFO per spin.
+                mock.set_raw(0x84, available + 1);
+            } else {
+                // Last byte has been shifted out.
+                mock.set_raw(0x30, 0);
+            }
+        });
+        assert_eq!(spins, 9);
+        assert_eq!(mock.raw(0x80), 0b10);
+    }
+
+    #[test]
+    fn function_uart_reset_fifos() {
+        let mock = MockRegisters::<RegisterBlock>::new();
+        mock.set_raw(0x80, 0b11);
+        uart_reset_fifos(mock.registers());
+        assert_eq!(mock.raw(0x80), 0b1100);
+    }
+}
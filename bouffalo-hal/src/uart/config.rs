@@ -134,6 +134,8 @@ pub enum ConfigError {
     ReceiveBaudrateTooLow,
     /// Clock source unavailable.
     ClockSource,
+    /// A pad is claimed by another function; returned by checked constructors only.
+    PinConflict(crate::gpio::PinConflict),
 }
 
 /// Order of the bits transmitted and received on the wire.
@@ -69,6 +69,14 @@ pub struct UartMux<'a, const N: usize, M> {
     _mode: PhantomData<M>,
 }
 
+/// Signal multiplexers have no pin function to check.
+impl<'a, const N: usize, M> crate::gpio::CheckFunctions for UartMux<'a, N, M> {
+    #[inline]
+    fn check_functions(&self) -> Result<(), crate::gpio::PinConflict> {
+        Ok(())
+    }
+}
+
 impl<'a, const N: usize, M> UartMux<'a, N, M> {
     /// Configure the internal UART signal to Request-to-Send (RTS).
     #[inline]
@@ -321,3 +321,36 @@ where
         ()
     }
 }
+
+#[cfg(all(test, feature = "glb-v2"))]
+mod tests {
+    use super::Pads;
+    use crate::glb::v2::{Function, MockRegisterBlock, UartSignal};
+    use crate::gpio::{Disabled, IntoPadv2, Padv2};
+    use crate::uart::{MuxRts, UartMux};
+
+    fn signals<P: Pads<0>>(_: &P) -> (bool, bool) {
+        (P::TXD, P::RXD)
+    }
+
+    #[test]
+    fn trait_pads_across_banks() {
+        // Transmit on io14 in bank 0, receive on io40 in bank 1.
+        let glb = MockRegisterBlock::new();
+        let tx = (
+            Disabled::from(Padv2::<14, _>::__from_glb(&glb)).into_uart(),
+            UartMux::<2, MuxRts<0>>::__from_glb(&glb).into_transmit::<0>(),
+        );
+        let rx = (
+            Disabled::from(Padv2::<40, _>::__from_glb(&glb)).into_uart(),
+            UartMux::<4, MuxRts<0>>::__from_glb(&glb).into_receive::<0>(),
+        );
+        let pads = (tx, rx);
+        assert_eq!(signals(&pads), (true, true));
+        assert_eq!(glb.gpio_config[14].read().function(), Function::Uart);
+        assert_eq!(glb.gpio_config[40].read().function(), Function::Uart);
+        let group = glb.uart_mux_group[0].read();
+        assert_eq!(group.signal(2), UartSignal::Txd0);
+        assert_eq!(group.signal(4), UartSignal::Rxd0);
+    }
+}
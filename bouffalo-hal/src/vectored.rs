@@ -0,0 +1,310 @@
+//! Direct vectored interrupt entries in RAM.
+//!
+//! In RISC-V vectored trap mode (`mtvec` mode 1), an interrupt with cause `c` jumps
+//! straight to `mtvec.BASE + 4 * c`. [`VectorTable`] is a writable copy of the vector
+//! table, where each entry is a `j` instruction; installing a handler for one cause
+//! makes that interrupt reach the handler without going through generic trap handler
+//! and its dispatch code, which reduces latency for cases like emergency stop or
+//! high-speed GPIO capture.
+//!
+//! # Integration with `bouffalo-rt`
+//!
+//! On BL808 cores, `bouffalo-rt` sets `mtvec` to its own table in standard vectored
+//! mode at startup. To coexist with it, create the RAM table by
+//! [`VectorTable::inherit`] from current `mtvec` so every entry first points to the
+//! runtime handler of the same cause, then override the wanted entries with
+//! [`VectorTable::install`] and switch to the new table using
+//! [`VectorTable::activate`]. Entries which are not overridden keep runtime behavior.
+//!
+//! On the DSP core, sources of the platform-level interrupt controller (PLIC) arrive
+//! through the machine external interrupt cause (11), which is shared by all of them.
+//! An installed handler for this cause replaces PLIC dispatch in the runtime, thus it
+//! must claim and complete interrupt sources itself, or jump to
+//! [`VectorTable::previous`] for sources it does not serve.
+//!
+//! Core-local interrupt controller (CLIC) mode, i.e. `mtvec` mode 3 as set up by vendor
+//! runtimes on the MCU core, dispatches interrupts through a separate table of handler
+//! addresses rather than jump instructions. It is not supported: inheriting from such
+//! `mtvec` fails with [`VectorError::UnsupportedMode`], so that activating the table
+//! never silently switches the core out of CLIC mode.
+//!
+//! Installed handlers are trap entries: they must save and restore all registers they
+//! use and return by `mret`, e.g. written as naked functions.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+//! # fn main() {
+//! use bouffalo_hal::vectored::{VectorTable, read_mtvec};
+//!
+//! static mut VECTORS: VectorTable<32> = VectorTable::new();
+//!
+//! unsafe extern "C" {
+//!     fn gpio_trap_entry() -> !;
+//! }
+//!
+//! let table = unsafe { &mut *core::ptr::addr_of_mut!(VECTORS) };
+//! table.inherit(read_mtvec()).unwrap();
+//! table.install(11, gpio_trap_entry).unwrap();
+//! unsafe { table.activate() };
+//! # }
+//! # #[cfg(not(any(target_arch = "riscv32", target_arch = "riscv64")))]
+//! # fn main() {}
+//! ```
+//!
+//! [`VectorTable::activate`] and [`read_mtvec`] access control and status registers,
+//! thus they are only available when compiling for RISC-V.
+
+/// Cause number of machine external interrupt.
+pub const MACHINE_EXTERNAL: usize = 11;
+
+/// Vectored trap table with `N` entries, placed in writable memory.
+///
+/// The table must not be moved after entries are written, since jump offsets are
+/// relative to entry addresses; it is usually placed in a `static`.
+#[repr(C, align(256))]
+pub struct VectorTable<const N: usize> {
+    entries: [u32; N],
+    previous: usize,
+}
+
+/// Error on installing a vectored trap entry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VectorError {
+    /// Cause number is not less than table size.
+    CauseOutOfRange,
+    /// Handler is too far away to be reached by one jump instruction.
+    OutOfReach,
+    /// Handler address is not aligned to 2 bytes.
+    Misaligned,
+    /// Trap mode in `mtvec` is neither direct nor standard vectored mode.
+    UnsupportedMode,
+}
+
+/// Opcode of `jal` instruction; `rd` of zero makes it `j`.
+const OPCODE_JAL: u32 = 0x6f;
+/// Encoding of `1: j 1b`, an endless loop.
+const JUMP_SELF: u32 = OPCODE_JAL;
+
+impl<const N: usize> VectorTable<N> {
+    /// Create a table where every entry loops forever.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            entries: [JUMP_SELF; N],
+            previous: 0,
+        }
+    }
+    /// Point every entry to handler of the same cause in the table given by `mtvec`.
+    ///
+    /// If `mtvec` is in direct mode, every entry points to its base address. If any
+    /// handler is out of reach of a jump instruction, returns the error and leaves the
+    /// table unchanged; the table should then be placed closer to the runtime handlers.
+    /// Other trap modes, e.g. CLIC mode, are rejected with
+    /// [`VectorError::UnsupportedMode`].
+    pub fn inherit(&mut self, mtvec: usize) -> Result<(), VectorError> {
+        let base = mtvec & !0x3;
+        let vectored = match mtvec & 0x3 {
+            0 => false,
+            1 => true,
+            _ => return Err(VectorError::UnsupportedMode),
+        };
+        let mut entries = [JUMP_SELF; N];
+        for (cause, instruction) in entries.iter_mut().enumerate() {
+            let target = if vectored { base + 4 * cause } else { base };
+            let entry = self.entries.as_ptr() as usize + 4 * cause;
+            *instruction = encode_j((target as isize).wrapping_sub(entry as isize))?;
+        }
+        for (cause, instruction) in entries.into_iter().enumerate() {
+            unsafe { (&raw mut self.entries[cause]).write_volatile(instruction) };
+        }
+        self.previous = mtvec;
+        Ok(())
+    }
+    /// Point the entry of interrupt `cause` to `handler`.
+    #[inline]
+    pub fn install(
+        &mut self,
+        cause: usize,
+        handler: unsafe extern "C" fn() -> !,
+    ) -> Result<(), VectorError> {
+        self.install_address(cause, handler as usize)
+    }
+    /// Get address of handler for `cause` in the table inherited from, if any.
+    #[inline]
+    pub fn previous(&self, cause: usize) -> Option<usize> {
+        let base = self.previous & !0x3;
+        match self.previous & 0x3 {
+            _ if self.previous == 0 => None,
+            0 => Some(base),
+            _ => Some(base + 4 * cause),
+        }
+    }
+    /// Get the `mtvec` value selecting this table in vectored mode.
+    #[inline]
+    pub fn mtvec(&self) -> usize {
+        self.entries.as_ptr() as usize | 1
+    }
+    /// Switch the current core to this vector table.
+    ///
+    /// # Safety
+    ///
+    /// Every entry that may be taken must point to a valid trap handler.
+    #[cfg(any(doc, target_arch = "riscv32", target_arch = "riscv64"))]
+    #[inline]
+    pub unsafe fn activate(&self) {
+        unsafe {
+            // Make written entries visible to instruction fetch.
+            core::arch::asm!("fence.i");
+            core::arch::asm!("csrw mtvec, {}", in(reg) self.mtvec());
+        }
+    }
+    #[inline]
+    fn install_address(&mut self, cause: usize, target: usize) -> Result<(), VectorError> {
+        if cause >= N {
+            return Err(VectorError::CauseOutOfRange);
+        }
+        let entry = &raw mut self.entries[cause];
+        let offset = (target as isize).wrapping_sub(entry as isize);
+        let instruction = encode_j(offset)?;
+        unsafe { entry.write_volatile(instruction) };
+        Ok(())
+    }
+}
+
+impl<const N: usize> Default for VectorTable<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Read current `mtvec` value.
+#[cfg(any(doc, target_arch = "riscv32", target_arch = "riscv64"))]
+#[inline]
+pub fn read_mtvec() -> usize {
+    let ans: usize;
+    unsafe { core::arch::asm!("csrr {}, mtvec", out(reg) ans) };
+    ans
+}
+
+/// Encode `j offset`, i.e. `jal x0, offset`.
+#[inline]
+fn encode_j(offset: isize) -> Result<u32, VectorError> {
+    if offset & 1 != 0 {
+        return Err(VectorError::Misaligned);
+    }
+    if !(-(1 << 20)..(1 << 20)).contains(&offset) {
+        return Err(VectorError::OutOfReach);
+    }
+    let imm = offset as u32;
+    let bits = ((imm >> 20) & 0x1) << 31
+        | ((imm >> 1) & 0x3ff) << 21
+        | ((imm >> 11) & 0x1) << 20
+        | ((imm >> 12) & 0xff) << 12;
+    Ok(bits | OPCODE_JAL)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{VectorError, VectorTable, encode_j};
+
+    // Decode jump offset of a `j` instruction.
+    fn decode_j(instruction: u32) -> isize {
+        let imm = ((instruction >> 31) & 0x1) << 20
+            | ((instruction >> 21) & 0x3ff) << 1
+            | ((instruction >> 20) & 0x1) << 11
+            | ((instruction >> 12) & 0xff) << 12;
+        // Sign extend from bit 20.
+        ((imm << 11) as i32 >> 11) as isize
+    }
+
+    #[test]
+    fn function_encode_j() {
+        assert_eq!(encode_j(0), Ok(0x0000_006f));
+        assert_eq!(encode_j(8), Ok(0x0080_006f));
+        assert_eq!(encode_j(-4), Ok(0xffdf_f06f));
+        assert_eq!(encode_j(0x800), Ok(0x0010_006f));
+        assert_eq!(encode_j(3), Err(VectorError::Misaligned));
+        assert_eq!(encode_j(1 << 20), Err(VectorError::OutOfReach));
+        assert_eq!(encode_j(-(1 << 20) - 2), Err(VectorError::OutOfReach));
+        for offset in [2, -2, 0x7fe, -0x800, 0x1000, 0xf_fffe, -(1 << 20)] {
+            assert_eq!(decode_j(encode_j(offset).unwrap()), offset);
+        }
+    }
+
+    #[test]
+    fn struct_vector_table_install() {
+        let mut table = VectorTable::<16>::new();
+        let base = table.entries.as_ptr() as usize;
+        assert_eq!(table.mtvec(), base | 1);
+        assert_eq!(table.previous(11), None);
+
+        // Inherit from a vectored table placed 64 KiB after this table.
+        let old = base + 0x1_0000;
+        table.inherit(old | 1).unwrap();
+        for cause in 0..16 {
+            let entry = base + 4 * cause;
+            let target = entry as isize + decode_j(table.entries[cause]);
+            assert_eq!(target as usize, old + 4 * cause);
+        }
+        assert_eq!(table.previous(11), Some(old + 44));
+
+        let handler = base - 0x2000;
+        table.install_address(11, handler).unwrap();
+        let target = (base + 44) as isize + decode_j(table.entries[11]);
+        assert_eq!(target as usize, handler);
+        // Other entries are untouched.
+        let target = (base + 40) as isize + decode_j(table.entries[10]);
+        assert_eq!(target as usize, old + 40);
+        assert_eq!(
+            table.install_address(16, handler),
+            Err(VectorError::CauseOutOfRange)
+        );
+
+        // Runtime handlers out of reach fail the whole inheritance.
+        let far = base + (1 << 20);
+        assert_eq!(table.inherit(far | 1), Err(VectorError::OutOfReach));
+        assert_eq!(table.previous(11), Some(old + 44));
+        let target = (base + 40) as isize + decode_j(table.entries[10]);
+        assert_eq!(target as usize, old + 40);
+        // Direct mode points every entry to the same handler.
+        let near = far - 0x20;
+        table.inherit(near).unwrap();
+        let target = (base + 60) as isize + decode_j(table.entries[15]);
+        assert_eq!(target as usize, near);
+    }
+
+    #[test]
+    fn function_inherit_runtime_trap_setup() {
+        // Runtime vectored table with exception handler at cause 0 and PLIC dispatch at
+        // machine external cause, placed 4 KiB before this table.
+        let mut table = VectorTable::<16>::new();
+        let base = table.entries.as_ptr() as usize;
+        let runtime = base - 0x1000;
+        table.inherit(runtime | 1).unwrap();
+        table.install_address(11, base + 0x2000).unwrap();
+        let target = |table: &VectorTable<16>, cause: usize| {
+            ((base + 4 * cause) as isize + decode_j(table.entries[cause])) as usize
+        };
+        // Exceptions and other interrupts still reach the runtime.
+        for cause in (0..16).filter(|&c| c != 11) {
+            assert_eq!(target(&table, cause), runtime + 4 * cause);
+        }
+        // Installed handler chains to runtime PLIC dispatch for other sources.
+        assert_eq!(target(&table, 11), base + 0x2000);
+        assert_eq!(table.previous(11), Some(runtime + 44));
+        assert_eq!(table.mtvec() & 0x3, 1);
+
+        // CLIC mode and reserved mode are rejected without touching the table.
+        for mode in [2, 3] {
+            assert_eq!(
+                table.inherit(runtime | mode),
+                Err(VectorError::UnsupportedMode)
+            );
+            assert_eq!(target(&table, 11), base + 0x2000);
+            assert_eq!(table.previous(11), Some(runtime + 44));
+        }
+    }
+}
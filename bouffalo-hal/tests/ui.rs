@@ -0,0 +1,10 @@
+//! Misuse rejected at compile time, checked against expected compiler diagnostics.
+
+#[cfg(feature = "glb-v2")]
+#[test]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+    // Some checks fail only when code is generated, which passing cases turn on.
+    t.pass("tests/ui/pass/*.rs");
+}
@@ -0,0 +1,11 @@
+use bouffalo_hal::{
+    glb::Pull,
+    gpio::{Alternate, Analog},
+};
+
+// Pull resistors would load the analog signal.
+fn bias(pot: &mut Alternate<'static, 17, Analog>) {
+    pot.set_pull(Pull::Up);
+}
+
+fn main() {}
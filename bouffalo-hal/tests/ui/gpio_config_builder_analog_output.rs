@@ -0,0 +1,9 @@
+use bouffalo_hal::glb::v2::{Function, GpioConfig, GpioConfigBuilder};
+
+// Analog pads must have their digital output driver disabled.
+const ADC_IN: GpioConfig = GpioConfigBuilder::new()
+    .set_function(Function::Analog)
+    .enable_output()
+    .build();
+
+fn main() {}
@@ -0,0 +1,7 @@
+use bouffalo_hal::{glb::v2::RegisterBlock, gpio::Padv2};
+
+fn main() {
+    let glb = unsafe { &*(0x2000_0000 as *const RegisterBlock) };
+    // There is no pad beyond io45.
+    let _ = Padv2::<46, _>::__from_glb(glb);
+}
@@ -0,0 +1,7 @@
+use bouffalo_hal::{glb::v2::RegisterBlock, gpio::Padv2};
+
+fn main() {
+    let glb: Box<RegisterBlock> = Box::new(unsafe { core::mem::zeroed() });
+    // The last pad is io45.
+    let _ = Padv2::<45, _>::__from_glb(&glb);
+}
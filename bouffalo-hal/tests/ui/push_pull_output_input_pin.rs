@@ -0,0 +1,9 @@
+use bouffalo_hal::gpio::{Floating, Output};
+use embedded_hal::digital::InputPin;
+
+// Push-pull outputs disable the input buffer, thus cannot be read back.
+fn read(mut led: Output<'static, 8, Floating>) -> bool {
+    led.is_high().unwrap()
+}
+
+fn main() {}
@@ -69,6 +69,7 @@ SECTIONS {
     } > FLASH
     .data : ALIGN(4) {
         sdata = .;
+        *(.ram_code .ram_code.*)
         *(.data .data.*)
         *(.sdata .sdata.*)
         . = ALIGN(4);
@@ -139,6 +140,7 @@ SECTIONS {
     } > FLASH
     .data : ALIGN(4) {
         sdata = .;
+        *(.ram_code .ram_code.*)
         *(.data .data.*)
         *(.sdata .sdata.*)
         . = ALIGN(4);
@@ -212,6 +214,7 @@ SECTIONS {
     } > FLASH
     .data : ALIGN(8) {
         sdata = .;
+        *(.ram_code .ram_code.*)
         *(.data .data.*)
         *(.sdata .sdata.*)
         . = ALIGN(8);
@@ -353,6 +356,7 @@ SECTIONS {
     } > FLASH
     .data : ALIGN(4) {
         sdata = .;
+        *(.ram_code .ram_code.*)
         *(.data .data.*)
         *(.sdata .sdata.*)
         . = ALIGN(4);
@@ -413,6 +417,7 @@ SECTIONS {
     } > XIP
     .data : ALIGN(4) {
         sdata = .;
+        *(.ram_code .ram_code.*)
         *(.data .data.*)
         *(.sdata .sdata.*)
         . = ALIGN(4);
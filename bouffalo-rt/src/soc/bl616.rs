@@ -260,6 +260,7 @@ soc! {
 }
 
 pub use bouffalo_hal::clocks::Clocks;
+use bouffalo_hal::clocks::{ClockConfig, Pll, RootClock};
 
 // Used by macros only.
 #[allow(unused)]
@@ -285,9 +286,14 @@ pub fn __rom_init_params(xtal_hz: u32) -> (Peripherals<'static>, Clocks) {
         hbn: HBN { _private: () },
         emac: EMAC { _private: () },
     };
-    let clocks = Clocks {
-        xtal: Hertz(xtal_hz),
-    };
+    // As requested by `CLOCK_CONFIG` in the image header, boot ROM locks the WiFi PLL to
+    // 960 MHz, runs the processor on its 320-MHz tap and divides peripheral bus clock by four.
+    let xtal = Hertz(xtal_hz);
+    let wifi_pll = Pll::for_output(xtal, Hertz(960_000_000)).expect("unsupported crystal");
+    let clocks = ClockConfig::with_pll(xtal, wifi_pll)
+        .set_root(RootClock::Pll)
+        .set_bus_divide(4)
+        .freeze();
     (peripherals, clocks)
 }
 
@@ -213,6 +213,7 @@ soc! {
     pub struct USBv1 => 0x4000D800, bouffalo_hal::usb::v1::RegisterBlock;
 }
 
+use bouffalo_hal::clocks::ClockConfig;
 pub use bouffalo_hal::clocks::Clocks;
 
 // TODO: BL702 clock tree configuration.
@@ -233,9 +234,9 @@ pub fn __rom_init_params(xtal_hz: u32) -> (Peripherals, Clocks) {
         hbn: HBN { _private: () },
         usb: USBv1 { _private: () },
     };
-    let clocks = Clocks {
-        xtal: Hertz(xtal_hz),
-    };
+    // TODO: describe the 144-MHz PLL clock boot ROM selects from `CLOCK_CONFIG`; only the
+    // crystal oscillator is described for now.
+    let clocks = ClockConfig::new(Hertz(xtal_hz)).freeze();
     (peripherals, clocks)
 }
 
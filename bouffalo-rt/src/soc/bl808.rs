@@ -834,14 +834,22 @@ pub struct Peripherals<'a> {
     pub i2c0: I2C0,
     /// Pulse Width Modulation peripheral.
     pub pwm: PWM,
+    /// Direct Memory Access peripheral 0.
+    pub dma0: DMA0,
     /// Inter-Integrated Circuit bus peripheral 1.
     pub i2c1: I2C1,
     /// Universal Asynchronous Receiver/Transmitter peripheral 2.
     pub uart2: UART2,
+    /// Inter-IC Sound peripheral.
+    pub i2s: I2S,
     /// Hardware LZ4 Decompressor.
     pub lz4d: LZ4D,
+    /// Serial flash controller.
+    pub flash: FLASH,
     /// Hibernation control peripheral.
     pub hbn: HBN,
+    /// Generic Analog-to-Digital Converter.
+    pub adc: ADC,
     /// Ethernet Media Access Control peripheral.
     pub emac: EMAC,
     /// Universal Asynchronous Receiver/Transmitter peripheral 3.
@@ -860,6 +868,10 @@ pub struct Peripherals<'a> {
     pub psram: PSRAM,
     /// Secure Digital High Capacity peripheral.
     pub sdh: SDH,
+    /// Camera interface peripheral.
+    pub cam: CAM,
+    /// Display Pixel Interface peripheral.
+    pub dpi: DPI,
 }
 
 soc! {
@@ -875,14 +887,24 @@ soc! {
     pub struct I2C0 => 0x2000A300, bouffalo_hal::i2c::RegisterBlock;
     /// Pulse Width Modulation peripheral.
     pub struct PWM => 0x2000A400, bouffalo_hal::pwm::RegisterBlock;
+    /// Display Bus Interface peripheral.
+    pub struct DBI => 0x2000A800, bouffalo_hal::dbi::RegisterBlock;
     /// Inter-Integrated Circuit bus 1 with fixed base address.
     pub struct I2C1 => 0x2000A900, bouffalo_hal::i2c::RegisterBlock;
     /// Universal Asynchronous Receiver/Transmitter 2 with fixed base address.
     pub struct UART2 => 0x2000AA00, bouffalo_hal::uart::RegisterBlock;
+    /// Inter-IC Sound peripheral.
+    pub struct I2S => 0x2000AB00, bouffalo_hal::i2s::RegisterBlock;
     /// Hardware LZ4 Decompressor.
     pub struct LZ4D => 0x2000AD00, bouffalo_hal::lz4d::RegisterBlock;
+    /// Serial flash controller.
+    pub struct FLASH => 0x2000B000, bouffalo_hal::flash::RegisterBlock;
+    /// Direct Memory Access peripheral 0.
+    pub struct DMA0 => 0x2000C000, bouffalo_hal::dma::RegisterBlock;
     /// Hibernation control peripheral.
     pub struct HBN => 0x2000F000, bouffalo_hal::hbn::RegisterBlock;
+    /// Generic Analog-to-Digital Converter in the always-on domain.
+    pub struct ADC => 0x2000F90C, bouffalo_hal::adc::RegisterBlock;
     /// Secure Digital High Capacity peripheral.
     pub struct SDH => 0x20060000, bouffalo_hal::sdio::RegisterBlock;
     /// Ethernet Media Access Control peripheral.
@@ -899,11 +921,16 @@ soc! {
     pub struct SPI1 => 0x30008000, bouffalo_hal::spi::RegisterBlock;
     /// Pseudo Static Random Access Memory controller.
     pub struct PSRAM => 0x3000F000, bouffalo_hal::psram::RegisterBlock;
+    /// Camera interface, the first DVP-to-AXI capture channel of multi-media subsystem.
+    pub struct CAM => 0x30010000, bouffalo_hal::cam::RegisterBlock;
+    /// Display Pixel Interface of multi-media subsystem.
+    pub struct DPI => 0x3001A000, bouffalo_hal::dpi::RegisterBlock;
     /// Platform-local Interrupt Controller.
     pub struct PLIC => 0xE0000000, xuantie_riscv::peripheral::plic::Plic;
 }
 
 pub use bouffalo_hal::clocks::Clocks;
+use bouffalo_hal::clocks::{ClockConfig, Pll, RootClock};
 
 // Used by macros only.
 #[allow(unused)]
@@ -925,10 +952,14 @@ pub fn __rom_init_params(xtal_hz: u32) -> (Peripherals<'static>, Clocks) {
         spi0: SPI0 { _private: () },
         i2c0: I2C0 { _private: () },
         pwm: PWM { _private: () },
+        dma0: DMA0 { _private: () },
         i2c1: I2C1 { _private: () },
         uart2: UART2 { _private: () },
+        i2s: I2S { _private: () },
         lz4d: LZ4D { _private: () },
+        flash: FLASH { _private: () },
         hbn: HBN { _private: () },
+        adc: ADC { _private: () },
         emac: EMAC { _private: () },
         uart3: UART3 { _private: () },
         i2c2: I2C2 { _private: () },
@@ -938,10 +969,17 @@ pub fn __rom_init_params(xtal_hz: u32) -> (Peripherals<'static>, Clocks) {
         mmglb: MMGLB { _private: () },
         psram: PSRAM { _private: () },
         sdh: SDH { _private: () },
+        cam: CAM { _private: () },
+        dpi: DPI { _private: () },
     };
-    let clocks = Clocks {
-        xtal: Hertz(xtal_hz),
-    };
+    // As requested by `CLOCK_CONFIG` in the image header, boot ROM locks the WiFi PLL to
+    // 960 MHz, runs the MCU core on its 320-MHz tap and divides peripheral bus clock by four.
+    let xtal = Hertz(xtal_hz);
+    let wifi_pll = Pll::for_output(xtal, Hertz(960_000_000)).expect("unsupported crystal");
+    let clocks = ClockConfig::with_pll(xtal, wifi_pll)
+        .set_root(RootClock::Pll)
+        .set_bus_divide(4)
+        .freeze();
     (peripherals, clocks)
 }
 
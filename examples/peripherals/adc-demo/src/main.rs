@@ -0,0 +1,34 @@
+#![no_std]
+#![no_main]
+
+use bouffalo_hal::{
+    adc::{self, Adc},
+    prelude::*,
+    uart::Config,
+};
+use bouffalo_rt::{Clocks, Peripherals, entry};
+use embedded_time::rate::*;
+use panic_halt as _;
+
+#[entry]
+fn main(p: Peripherals, c: Clocks) -> ! {
+    let tx = p.gpio.io14.into_uart();
+    let rx = p.gpio.io15.into_uart();
+    let sig2 = p.uart_muxes.sig2.into_transmit::<0>();
+    let sig3 = p.uart_muxes.sig3.into_receive::<0>();
+    let pads = ((tx, sig2), (rx, sig3));
+
+    let config = Config::default().set_baudrate(2000000.Bd());
+    let mut serial = p.uart0.freerun(config, pads, &c).unwrap();
+
+    let pot = p.gpio.io17.into_analog();
+    let mut adc = Adc::new(p.adc, adc::Config::default());
+
+    writeln!(serial, "Potentiometer reading example by bouffalo-hal🦀!").ok();
+
+    loop {
+        let raw = adc.convert(&pot);
+        writeln!(serial, "raw {:4}, {:4} mV", raw, adc.millivolts(raw)).ok();
+        riscv::asm::delay(10_000_000);
+    }
+}
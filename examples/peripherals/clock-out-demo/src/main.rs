@@ -0,0 +1,20 @@
+#![no_std]
+#![no_main]
+
+use bouffalo_hal::{gpio::ClockOutSource, prelude::*};
+use bouffalo_rt::{Clocks, Peripherals, entry};
+use panic_halt as _;
+
+#[entry]
+fn main(p: Peripherals, _c: Clocks) -> ! {
+    // Measure 32 MHz on io8; the pad is restored if `clock_out` is dropped.
+    let _clock_out = p
+        .gpio
+        .io8
+        .into_clock_out(ClockOutSource::Rc32m, 1)
+        .map_err(|(_, e)| e)
+        .unwrap();
+    loop {
+        core::hint::spin_loop();
+    }
+}
@@ -0,0 +1,76 @@
+#![no_std]
+#![no_main]
+
+use bouffalo_hal::{
+    emac::{Buffers, Ethernet},
+    prelude::*,
+    uart::Config,
+};
+use bouffalo_rt::{Clocks, Peripherals, entry};
+use embedded_time::rate::*;
+use panic_halt as _;
+use smoltcp::{
+    iface::{self, Interface, SocketSet, SocketStorage},
+    time::Instant,
+    wire::{EthernetAddress, HardwareAddress, IpAddress, IpCidr},
+};
+
+const MAC_ADDRESS: [u8; 6] = [0x18, 0xb9, 0x05, 0x00, 0x00, 0x01];
+const PHY_ADDRESS: u8 = 0;
+
+static mut BUFFERS: Buffers<4, 4> = Buffers::new();
+
+#[entry]
+fn main(p: Peripherals, c: Clocks) -> ! {
+    let tx = p.gpio.io14.into_uart();
+    let rx = p.gpio.io15.into_uart();
+    let sig2 = p.uart_muxes.sig2.into_transmit::<0>();
+    let sig3 = p.uart_muxes.sig3.into_receive::<0>();
+    let pads = ((tx, sig2), (rx, sig3));
+
+    let config = Config::default().set_baudrate(2000000.Bd());
+    let mut serial = p.uart0.freerun(config, pads, &c).unwrap();
+
+    let rmii = (
+        p.gpio.io24.into_emac(),
+        p.gpio.io25.into_emac(),
+        p.gpio.io26.into_emac(),
+        p.gpio.io27.into_emac(),
+        p.gpio.io28.into_emac(),
+        p.gpio.io29.into_emac(),
+        p.gpio.io30.into_emac(),
+        p.gpio.io31.into_emac(),
+        p.gpio.io32.into_emac(),
+    );
+    let buffers = unsafe { &mut *core::ptr::addr_of_mut!(BUFFERS) };
+    let mut ethernet = Ethernet::new(p.emac, rmii, MAC_ADDRESS, buffers);
+
+    writeln!(serial, "Ethernet ping example by bouffalo-hal🦀!").ok();
+    let link = loop {
+        if let Some(link) = ethernet.link_status(PHY_ADDRESS) {
+            break link;
+        }
+    };
+    writeln!(serial, "Link up: {:?}, {:?}", link.speed, link.duplex).ok();
+
+    let config = iface::Config::new(HardwareAddress::Ethernet(EthernetAddress(MAC_ADDRESS)));
+    let mut iface = Interface::new(config, &mut ethernet, now(&c));
+    iface.update_ip_addrs(|addresses| {
+        addresses
+            .push(IpCidr::new(IpAddress::v4(192, 168, 1, 50), 24))
+            .ok();
+    });
+    // Echo requests are answered by the interface itself; no sockets are needed.
+    let mut storage = [SocketStorage::EMPTY; 1];
+    let mut sockets = SocketSet::new(&mut storage[..]);
+
+    loop {
+        iface.poll(now(&c), &mut ethernet, &mut sockets);
+    }
+}
+
+/// Time since reset, counted in core clock cycles.
+fn now(c: &Clocks) -> Instant {
+    let cycles_per_micro = (c.cpu_clock().0 / 1_000_000) as u64;
+    Instant::from_micros((riscv::register::mcycle::read64() / cycles_per_micro) as i64)
+}
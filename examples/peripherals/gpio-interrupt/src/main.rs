@@ -0,0 +1,22 @@
+#![no_std]
+#![no_main]
+
+use bouffalo_hal::{glb::v2::InterruptMode, prelude::*};
+use bouffalo_rt::{Clocks, Peripherals, entry};
+use panic_halt as _;
+
+#[entry]
+fn main(p: Peripherals, _c: Clocks) -> ! {
+    let mut led = p.gpio.io8.into_floating_output();
+    let mut button = p.gpio.io0.into_pull_up_input();
+    button.enable_schmitt();
+    button.listen(InterruptMode::AsyncFallingEdge);
+    loop {
+        if button.check_interrupt() {
+            // Clear the flag, or the interrupt fires again.
+            button.clear_interrupt();
+            led.toggle().ok();
+        }
+        riscv::asm::delay(10_000)
+    }
+}
@@ -0,0 +1,29 @@
+#![no_std]
+#![no_main]
+
+use bouffalo_hal::{gpio::SEQUENCER_ENTRY_MAX, prelude::*};
+use bouffalo_rt::{Clocks, Peripherals, entry};
+use panic_halt as _;
+
+const CARRIER_HZ: u32 = 38_000;
+
+#[entry]
+fn main(p: Peripherals, c: Clocks) -> ! {
+    // Split each carrier half period into ticks no longer than one sequencer entry.
+    let half_period = c.bus_clock().0 / (2 * CARRIER_HZ);
+    let ticks = half_period.div_ceil(SEQUENCER_ENTRY_MAX as u32);
+    let tick_cycles = (half_period / ticks) as u16;
+    let carrier = [(true, ticks as u16), (false, ticks as u16)];
+
+    let mut ir = p
+        .gpio
+        .io8
+        .into_sequencer(tick_cycles)
+        .map_err(|(_, e)| e)
+        .unwrap();
+    loop {
+        // Keep the sequencer FIFO topped up; the hardware times every edge.
+        while ir.load(&carrier).is_ok() {}
+        core::hint::spin_loop();
+    }
+}
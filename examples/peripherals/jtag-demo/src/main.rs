@@ -1,17 +1,14 @@
 #![no_std]
 #![no_main]
 
-use bouffalo_hal::prelude::*;
+use bouffalo_hal::{gpio::JtagD0Pins, prelude::*};
 use bouffalo_rt::{Clocks, Peripherals, entry};
 use panic_halt as _;
 
 #[entry]
 fn main(p: Peripherals, _c: Clocks) -> ! {
     // enable jtag
-    p.gpio.io0.into_jtag_d0();
-    p.gpio.io1.into_jtag_d0();
-    p.gpio.io2.into_jtag_d0();
-    p.gpio.io3.into_jtag_d0();
+    let _jtag = JtagD0Pins::new(p.gpio.io0, p.gpio.io1, p.gpio.io2, p.gpio.io3);
 
     let mut led = p.gpio.io8.into_floating_output();
     loop {
@@ -0,0 +1,79 @@
+#![no_std]
+#![no_main]
+
+use bouffalo_hal::{
+    gpio::{Output, PullUp},
+    onewire::{Error, OneWire, crc8},
+    prelude::*,
+    uart::Config,
+};
+use bouffalo_rt::{Clocks, Peripherals, entry};
+use core::convert::Infallible;
+use embedded_time::rate::*;
+use panic_halt as _;
+
+const CONVERT_T: u8 = 0x44;
+const READ_SCRATCHPAD: u8 = 0xBE;
+
+type Bus = OneWire<Output<'static, 0, PullUp>, fn(u32)>;
+
+/// Read temperature in 1/16 degrees Celsius from the only sensor on the bus.
+fn read_temperature(bus: &mut Bus) -> Result<i16, Error<Infallible>> {
+    bus.skip()?;
+    bus.write_byte(CONVERT_T)?;
+    // Sensor holds the bus low until conversion completes.
+    while !bus.read_bit()? {}
+    bus.skip()?;
+    bus.write_byte(READ_SCRATCHPAD)?;
+    let mut scratchpad = [0u8; 9];
+    for byte in &mut scratchpad {
+        *byte = bus.read_byte()?;
+    }
+    if crc8(&scratchpad) != 0 {
+        return Err(Error::Crc);
+    }
+    Ok(i16::from_le_bytes([scratchpad[0], scratchpad[1]]))
+}
+
+#[entry]
+fn main(p: Peripherals, c: Clocks) -> ! {
+    let tx = p.gpio.io14.into_uart();
+    let rx = p.gpio.io15.into_uart();
+    let sig2 = p.uart_muxes.sig2.into_transmit::<0>();
+    let sig3 = p.uart_muxes.sig3.into_receive::<0>();
+    let pads = ((tx, sig2), (rx, sig3));
+
+    let config = Config::default().set_baudrate(2000000.Bd());
+    let mut serial = p.uart0.freerun(config, pads, &c).unwrap();
+
+    let pin = p.gpio.io0.into_pull_up_open_drain_output();
+    let mut bus: Bus = OneWire::new(pin, riscv::asm::delay as fn(u32), &c);
+
+    writeln!(serial, "Welcome to 1-Wire DS18B20 demo").ok();
+    for rom in bus.search() {
+        match rom {
+            Ok(rom) => writeln!(serial, "found device {:016x}", rom).ok(),
+            Err(e) => writeln!(serial, "search error: {:?}", e).ok(),
+        };
+    }
+    loop {
+        match read_temperature(&mut bus) {
+            Ok(raw) => {
+                let sign = if raw < 0 { "-" } else { "" };
+                let ten_thousandths = (raw as i32).abs() * 625;
+                writeln!(
+                    serial,
+                    "temperature: {}{}.{:04} °C",
+                    sign,
+                    ten_thousandths / 10000,
+                    ten_thousandths % 10000
+                )
+                .ok();
+            }
+            Err(e) => {
+                writeln!(serial, "read error: {:?}", e).ok();
+            }
+        }
+        riscv::asm::delay(100_000_000);
+    }
+}
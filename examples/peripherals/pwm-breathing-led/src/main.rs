@@ -0,0 +1,48 @@
+#![no_std]
+#![no_main]
+
+use bouffalo_hal::{
+    prelude::*,
+    pwm::{ClockSource::Xclk, Pwm, SingleEnd},
+};
+use bouffalo_rt::{Clocks, Peripherals, entry};
+use embedded_time::rate::units::Extensions;
+use panic_halt as _;
+
+/// Steps in one breath, rising and falling.
+const STEPS: u32 = 200;
+
+/// Brightness at `step` of a breath, from 0 to 100 percent.
+fn brightness(step: u32) -> u8 {
+    let step = step % STEPS;
+    let half = STEPS / 2;
+    let level = if step < half { step } else { STEPS - step };
+    // Square the level so that brightness looks linear to human eyes.
+    (level * level * 100 / (half * half)) as u8
+}
+
+#[entry]
+fn main(p: Peripherals, c: Clocks) -> ! {
+    let red = p.gpio.io8.into_pull_down_pwm::<0>();
+    let green = p.gpio.io9.into_pull_down_pwm::<0>();
+    let blue = p.gpio.io10.into_pull_down_pwm::<0>();
+
+    let mut pwm = Pwm::new(p.pwm, SingleEnd, SingleEnd, &p.glb);
+    pwm.group0.set_frequency(1_000.Hz(), Xclk, &c).unwrap();
+    pwm.group0.start();
+    let mut red = pwm.group0.channel0.positive_signal_pin(red);
+    let mut green = pwm.group0.channel1.positive_signal_pin(green);
+    let mut blue = pwm.group0.channel2.positive_signal_pin(blue);
+
+    let mut step = 0;
+    loop {
+        red.set_duty_cycle_percent(brightness(step)).ok();
+        green
+            .set_duty_cycle_percent(brightness(step + STEPS / 3))
+            .ok();
+        blue.set_duty_cycle_percent(brightness(step + STEPS * 2 / 3))
+            .ok();
+        step = (step + 1) % STEPS;
+        riscv::asm::delay(100_000);
+    }
+}
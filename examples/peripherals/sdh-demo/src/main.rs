@@ -3,11 +3,11 @@
 
 use bouffalo_hal::{
     prelude::*,
-    sdio::{Config as SdhConfig, Sdh},
+    sdio::{Config as SdhConfig, Sdh, TransferWidth},
     uart::Config as UartConfig,
 };
 use bouffalo_rt::{Clocks, Peripherals, entry};
-use embedded_sdmmc::VolumeManager;
+use embedded_sdmmc::{Block, BlockDevice, BlockIdx, VolumeManager};
 use embedded_time::rate::*;
 use panic_halt as _;
 
@@ -51,9 +51,58 @@ fn main(p: Peripherals, c: Clocks) -> ! {
     let pads = (sdh_clk, sdh_cmd, sdh_d0, sdh_d1, sdh_d2, sdh_d3);
 
     // Sdh init.
-    let config = SdhConfig::default();
+    let config = SdhConfig::default()
+        .transfer_width(TransferWidth::FourBitMode)
+        .clock(12_000_000.Hz());
     let mut sdcard = Sdh::new(p.sdh, pads, config, &p.glb);
-    sdcard.init(&mut serial, true);
+    let card = match sdcard.init() {
+        Ok(card) => card,
+        Err(e) => {
+            writeln!(serial, "Failed to initialize sdcard: {:?}", e).ok();
+            loop {}
+        }
+    };
+    writeln!(
+        serial,
+        "sdcard init done, {} MiB, {}",
+        card.block_count / 2048,
+        if card.high_capacity {
+            "SDHC/SDXC"
+        } else {
+            "SDSC"
+        }
+    )
+    .ok();
+
+    // Read sector 0 and print its partition table.
+    let mut blocks = [Block::new()];
+    if let Err(e) = sdcard.read(&mut blocks, BlockIdx(0), "mbr") {
+        writeln!(serial, "Failed to read sector 0: {:?}", e).ok();
+        loop {}
+    }
+    let mbr = &blocks[0].contents;
+    if mbr[510..512] != [0x55, 0xAA] {
+        writeln!(serial, "No partition table on sector 0").ok();
+    }
+    for (i, entry) in mbr[446..510].chunks_exact(16).enumerate() {
+        let kind = entry[4];
+        if kind == 0 {
+            continue;
+        }
+        let start = u32::from_le_bytes([entry[8], entry[9], entry[10], entry[11]]);
+        let sectors = u32::from_le_bytes([entry[12], entry[13], entry[14], entry[15]]);
+        writeln!(
+            serial,
+            "Partition {}: type {:#04X}, start {}, {} sectors{}",
+            i,
+            kind,
+            start,
+            sectors,
+            if entry[0] == 0x80 { ", bootable" } else { "" }
+        )
+        .ok();
+    }
+
     let time_source = MyTimeSource {};
     let mut volume_mgr = VolumeManager::new(sdcard, time_source);
     let volume_res = volume_mgr.open_raw_volume(embedded_sdmmc::VolumeIdx(0));
@@ -0,0 +1,24 @@
+#![no_std]
+#![no_main]
+
+use bouffalo_hal::{prelude::*, spi::Spi};
+use bouffalo_rt::{Clocks, Peripherals, entry};
+use embedded_hal::spi::{MODE_0, SpiBus};
+use embedded_time::rate::*;
+use panic_halt as _;
+
+#[entry]
+fn main(p: Peripherals, c: Clocks) -> ! {
+    let spi_clk = p.gpio.io19.into_spi::<1>();
+    let spi_mosi = p.gpio.io25.into_spi::<1>();
+    let spi_latch = p.gpio.io12.into_spi::<1>();
+    let mut spi = Spi::new(p.spi1, (spi_clk, spi_mosi, spi_latch), MODE_0, &p.glb);
+    spi.set_frequency(1_000_000.Hz(), &c).unwrap();
+
+    let mut count = 0u8;
+    loop {
+        spi.write(&[count]).ok();
+        count = count.wrapping_add(1);
+        riscv::asm::delay(10_000_000);
+    }
+}
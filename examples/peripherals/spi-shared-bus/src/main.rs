@@ -0,0 +1,34 @@
+#![no_std]
+#![no_main]
+
+use bouffalo_hal::{
+    prelude::*,
+    shared_bus::SharedBus,
+    spi::{CsPolarity, Spi},
+};
+use bouffalo_rt::{Clocks, Peripherals, entry};
+use embedded_hal::spi::{MODE_0, SpiDevice};
+use embedded_time::rate::*;
+use panic_halt as _;
+
+#[entry]
+fn main(p: Peripherals, c: Clocks) -> ! {
+    let spi_clk = p.gpio.io19.into_spi::<1>();
+    let spi_mosi = p.gpio.io25.into_spi::<1>();
+    let mut spi = Spi::new(p.spi1, (spi_clk, spi_mosi), MODE_0, &p.glb);
+    spi.set_frequency(1_000_000.Hz(), &c).unwrap();
+
+    let bus = SharedBus::new(spi);
+    let latch_up = p.gpio.io16.into_floating_output();
+    let latch_down = p.gpio.io17.into_floating_output();
+    let mut counter_up = bus.spi_device(latch_up, CsPolarity::ActiveLow).unwrap();
+    let mut counter_down = bus.spi_device(latch_down, CsPolarity::ActiveLow).unwrap();
+
+    let mut count = 0u8;
+    loop {
+        counter_up.write(&[count]).ok();
+        counter_down.write(&[!count]).ok();
+        count = count.wrapping_add(1);
+        riscv::asm::delay(10_000_000);
+    }
+}